@@ -9,14 +9,34 @@
 //! - Receiving responses
 //! - Managing configuration
 
-use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, Weak};
+use std::time::Duration;
 use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio_util::sync::CancellationToken;
+use zeroclaw::agent::TurnUsage;
+use zeroclaw::gateway::drain::RequestDrain;
 
 uniffi::setup_scaffolding!();
 
 /// Global runtime for async operations
 static RUNTIME: OnceLock<Runtime> = OnceLock::new();
 
+/// Guards the one-time tracing subscriber install
+static LOGGING: OnceLock<()> = OnceLock::new();
+
+/// Conversation history file, relative to `data_dir`
+const HISTORY_FILE: &str = "history.json";
+
+/// `SendResult::error` / `on_error` message for a request stopped by `cancel()`
+const CANCELLED: &str = "cancelled";
+
 fn runtime() -> &'static Runtime {
     RUNTIME.get_or_init(|| {
         tokio::runtime::Builder::new_multi_thread()
@@ -45,6 +65,23 @@ pub struct ZeroClawConfig {
     pub model: String,
     pub api_key: String,
     pub system_prompt: Option<String>,
+    /// Secondary provider retried once when the primary fails transiently
+    pub fallback_provider: Option<String>,
+    /// Model for the fallback provider (defaults to `model`)
+    pub fallback_model: Option<String>,
+    /// API key for the fallback provider
+    pub fallback_api_key: Option<String>,
+    /// Log output format: "text" (default) or "json"
+    pub log_format: Option<String>,
+    /// Return only the final answer in `SendResult::reply`, without tool
+    /// narration; `get_messages` still keeps the full transcript. Streaming
+    /// then sends the final answer as one chunk once the reply is complete
+    pub final_answer_only: bool,
+    /// Start the gateway from the constructor when the config has an API
+    /// key, so the app does not need to call `start()`
+    pub auto_start: bool,
+    /// Gateway listen port (defaults to `gateway.port`; 0 picks a free one)
+    pub gateway_port: Option<u16>,
 }
 
 impl Default for ZeroClawConfig {
@@ -55,17 +92,377 @@ impl Default for ZeroClawConfig {
             model: "claude-sonnet-4-5".to_string(),
             api_key: String::new(),
             system_prompt: None,
+            fallback_provider: None,
+            fallback_model: None,
+            fallback_api_key: None,
+            log_format: None,
+            final_answer_only: false,
+            auto_start: false,
+            gateway_port: None,
+        }
+    }
+}
+
+impl ZeroClawConfig {
+    /// Primary provider target
+    fn primary_target(&self) -> ProviderTarget {
+        ProviderTarget {
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            api_key: self.api_key.clone(),
+        }
+    }
+
+    /// Fallback provider target, if one is configured
+    fn fallback_target(&self) -> Option<ProviderTarget> {
+        let provider = self
+            .fallback_provider
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())?;
+        Some(ProviderTarget {
+            provider: provider.to_string(),
+            model: self
+                .fallback_model
+                .clone()
+                .unwrap_or_else(|| self.model.clone()),
+            api_key: self.fallback_api_key.clone().unwrap_or_default(),
+        })
+    }
+}
+
+/// Output format for the controller's tracing subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    /// Parse a `log_format` value; `None` and blank mean text
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim).filter(|v| !v.is_empty()) {
+            None => Ok(Self::Text),
+            Some(v) if v.eq_ignore_ascii_case("text") => Ok(Self::Text),
+            Some(v) if v.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            Some(v) => Err(v.to_string()),
+        }
+    }
+}
+
+/// Install the tracing subscriber once per process.
+/// Unknown formats fall back to text with a warning.
+fn init_logging(log_format: Option<&str>) {
+    LOGGING.get_or_init(|| {
+        let (format, invalid) = match LogFormat::parse(log_format) {
+            Ok(format) => (format, None),
+            Err(value) => (LogFormat::Text, Some(value)),
+        };
+
+        let builder = tracing_subscriber::fmt().with_env_filter("zeroclaw=info");
+        let _ = match format {
+            LogFormat::Json => builder.json().try_init(),
+            LogFormat::Text => builder.try_init(),
+        };
+
+        if let Some(value) = invalid {
+            tracing::warn!("Unknown log_format '{}', falling back to text", value);
+        }
+    });
+}
+
+/// Provider/model/key triple a reply is requested from
+#[derive(Debug, Clone)]
+struct ProviderTarget {
+    provider: String,
+    model: String,
+    api_key: String,
+}
+
+/// Failure from a provider call, classified for fallback decisions
+#[derive(Debug, Clone)]
+enum ProviderCallError {
+    /// Network/timeout/5xx/429 failure; worth retrying elsewhere
+    Transient(String),
+    /// Rejected credentials; retrying another provider would hide the problem
+    Auth(String),
+    /// Any other failure (bad request, context window overflow, turn
+    /// budget, tool or config error). Another provider would fail the same
+    /// way, and rerunning the turn would repeat its tool calls
+    Rejected(String),
+    /// Too many agent loops in flight (`agent.max_concurrent_loops`) and the
+    /// wait queue is full; another provider would be just as busy
+    Busy,
+}
+
+impl std::fmt::Display for ProviderCallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transient(message) => write!(f, "Provider unavailable: {}", message),
+            Self::Auth(message) => write!(f, "Provider auth failed: {}", message),
+            Self::Rejected(message) => write!(f, "Provider request failed: {}", message),
+            Self::Busy => write!(f, "{}", zeroclaw::agent::loop_limit::ServerBusy),
+        }
+    }
+}
+
+/// Error text that marks a rejected credential rather than an outage
+const AUTH_ERROR_HINTS: &[&str] = &[
+    "api error (401",
+    "api error (403",
+    "unauthorized",
+    "invalid api key",
+    "authentication",
+];
+
+impl ProviderCallError {
+    /// Classify an agent loop failure, scrubbing secrets from the message.
+    /// Only provider outages (see `is_provider_outage`) are `Transient`.
+    fn from_agent_error(err: &anyhow::Error) -> Self {
+        let message = zeroclaw::providers::sanitize_api_error(&format!("{err:#}"));
+        let lower = message.to_ascii_lowercase();
+        if zeroclaw::providers::reliable::is_provider_outage(err) {
+            Self::Transient(message)
+        } else if AUTH_ERROR_HINTS.iter().any(|hint| lower.contains(hint)) {
+            Self::Auth(message)
+        } else {
+            Self::Rejected(message)
+        }
+    }
+}
+
+/// Core config and system prompt the running gateway was started with
+struct GatewaySession {
+    config: zeroclaw::Config,
+    system_prompt: Option<String>,
+    /// Gateway tool registry, so final answers strip tool-call JSON the way
+    /// gateway replies do; empty until `load_tools`
+    tools: Vec<Box<dyn zeroclaw::tools::Tool>>,
+}
+
+impl GatewaySession {
+    /// Build a core config rooted at `data_dir` for the bridge settings
+    fn from_config(config: &ZeroClawConfig) -> Self {
+        let data_dir = PathBuf::from(&config.data_dir);
+        let mut core = zeroclaw::Config {
+            workspace_dir: data_dir.join("workspace"),
+            config_path: data_dir.join("config.toml"),
+            api_key: Some(config.api_key.clone()).filter(|key| !key.is_empty()),
+            default_provider: Some(config.provider.clone()),
+            default_model: Some(config.model.clone()),
+            ..zeroclaw::Config::default()
+        };
+        if let Some(port) = config.gateway_port {
+            core.gateway.port = port;
+        }
+        Self {
+            config: core,
+            system_prompt: config.system_prompt.clone(),
+            tools: Vec::new(),
+        }
+    }
+
+    /// Build the gateway tool registry for this config
+    fn load_tools(&mut self) -> Result<(), ZeroClawError> {
+        let config_error = |e: anyhow::Error| ZeroClawError::ConfigError {
+            message: format!("cannot build gateway tools: {e:#}"),
+        };
+        let memory = zeroclaw::memory::create_memory_with_storage(
+            &self.config.memory,
+            Some(&self.config.storage.provider.config),
+            &self.config.workspace_dir,
+            self.config.api_key.as_deref(),
+        )
+        .map_err(config_error)?;
+        self.tools = zeroclaw::gateway::gateway_tools(&self.config, Arc::from(memory))
+            .map_err(config_error)?;
+        Ok(())
+    }
+}
+
+/// Gateway started by `start()`
+struct GatewayHandle {
+    session: Arc<GatewaySession>,
+    /// Server task; `None` when no HTTP listener was spawned
+    server: Option<JoinHandle<()>>,
+    /// In-flight HTTP requests, drained by `stop()`
+    requests: Arc<RequestDrain>,
+}
+
+impl GatewayHandle {
+    /// `gateway.shutdown_grace_secs` of the running gateway
+    fn shutdown_grace(&self) -> Duration {
+        Duration::from_secs(self.session.config.gateway.shutdown_grace_secs)
+    }
+
+    fn shutdown(self) {
+        if let Some(server) = self.server {
+            server.abort();
+        }
+    }
+}
+
+/// Assistant reply with the token usage the backend reported for it
+#[derive(Debug, Clone)]
+struct ProviderReply {
+    text: String,
+    usage: TurnUsage,
+}
+
+impl From<String> for ProviderReply {
+    /// A reply whose usage is unknown
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            usage: TurnUsage::default(),
         }
     }
 }
 
+/// Reply produced asynchronously by [`ChatProvider::reply_streaming`]
+type ReplyFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ProviderReply, ProviderCallError>> + Send + 'a>>;
+
+/// Backend that produces an assistant reply for a user message
+///
+/// `cancel` fires when the app calls `cancel()`; backends should stop at the
+/// next agent-loop boundary and return an error.
+trait ChatProvider: Send + Sync {
+    fn reply(
+        &self,
+        session: &GatewaySession,
+        target: &ProviderTarget,
+        content: &str,
+        cancel: &CancellationToken,
+    ) -> Result<ProviderReply, ProviderCallError>;
+
+    /// Send the reply to `on_token` as it is produced and return it in full.
+    /// The default sends the whole reply as a single chunk.
+    fn reply_streaming<'a>(
+        &'a self,
+        session: &'a GatewaySession,
+        target: &'a ProviderTarget,
+        content: &'a str,
+        cancel: &'a CancellationToken,
+        on_token: mpsc::Sender<String>,
+    ) -> ReplyFuture<'a> {
+        Box::pin(async move {
+            let reply = self.reply(session, target, content, cancel)?;
+            let _ = on_token.send(reply.text.clone()).await;
+            Ok(reply)
+        })
+    }
+}
+
+/// Runs the message through the agent loop, the same path the gateway's
+/// `run_gateway_chat_with_tools` takes, bounded by
+/// `gateway.max_turn_duration_secs`
+struct AgentLoopProvider;
+
+impl AgentLoopProvider {
+    /// Core config for `target`, plus the turn budget it sets
+    fn core_config(
+        session: &GatewaySession,
+        target: &ProviderTarget,
+    ) -> (zeroclaw::Config, Option<Duration>) {
+        let mut config = session.config.clone();
+        config.default_provider = Some(target.provider.clone());
+        config.default_model = Some(target.model.clone());
+        config.api_key = Some(target.api_key.clone()).filter(|key| !key.is_empty());
+        let max_turn_duration = Some(config.gateway.max_turn_duration_secs)
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+        (config, max_turn_duration)
+    }
+}
+
+impl ChatProvider for AgentLoopProvider {
+    fn reply(
+        &self,
+        session: &GatewaySession,
+        target: &ProviderTarget,
+        content: &str,
+        cancel: &CancellationToken,
+    ) -> Result<ProviderReply, ProviderCallError> {
+        let (config, max_turn_duration) = Self::core_config(session, target);
+        runtime().block_on(async {
+            let _permit = zeroclaw::agent::loop_limit::acquire_agent_loop()
+                .await
+                .map_err(|_| ProviderCallError::Busy)?;
+            zeroclaw::agent::process_message_with_turn_budget(
+                config,
+                content,
+                None,
+                session.system_prompt.as_deref(),
+                max_turn_duration,
+                Some(cancel.clone()),
+            )
+            .await
+            .map(|(text, usage)| ProviderReply { text, usage })
+            .map_err(|e| ProviderCallError::from_agent_error(&e))
+        })
+    }
+
+    fn reply_streaming<'a>(
+        &'a self,
+        session: &'a GatewaySession,
+        target: &'a ProviderTarget,
+        content: &'a str,
+        cancel: &'a CancellationToken,
+        on_token: mpsc::Sender<String>,
+    ) -> ReplyFuture<'a> {
+        let (config, max_turn_duration) = Self::core_config(session, target);
+        Box::pin(async move {
+            let _permit = zeroclaw::agent::loop_limit::acquire_agent_loop()
+                .await
+                .map_err(|_| ProviderCallError::Busy)?;
+            zeroclaw::agent::process_message_streaming(
+                config,
+                content,
+                None,
+                session.system_prompt.as_deref(),
+                max_turn_duration,
+                Some(cancel.clone()),
+                on_token,
+            )
+            .await
+            .map(|(text, usage)| ProviderReply { text, usage })
+            .map_err(|e| ProviderCallError::from_agent_error(&e))
+        })
+    }
+}
+
+/// Receives a streamed reply. Called from a bridge runtime thread, never
+/// the thread that called `send_message_streaming`.
+#[uniffi::export(callback_interface)]
+pub trait ResponseCallback: Send + Sync {
+    /// Next chunk of the assistant reply
+    fn on_token(&self, chunk: String);
+    /// The reply is complete; `message_id` is the user message it answers
+    fn on_complete(&self, message_id: String);
+    /// The request failed; no further callbacks follow
+    fn on_error(&self, message: String);
+}
+
 /// A message in the conversation
-#[derive(Debug, Clone, uniffi::Record)]
+#[derive(Debug, Clone, uniffi::Record, serde::Serialize, serde::Deserialize)]
 pub struct ChatMessage {
     pub id: String,
     pub content: String,
     pub role: String, // "user" | "assistant" | "system"
     pub timestamp_ms: i64,
+    /// Provider that produced an assistant reply; `None` for user messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model that produced an assistant reply; `None` for user messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tokens used for the reply, when the backend reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    /// Whether this message reports a failure rather than a reply
+    #[serde(default)]
+    pub is_error: bool,
 }
 
 /// Response from sending a message
@@ -74,16 +471,101 @@ pub struct SendResult {
     pub success: bool,
     pub message_id: Option<String>,
     pub error: Option<String>,
+    /// Provider that served the reply (primary or fallback)
+    pub provider_used: Option<String>,
+    /// Assistant reply, reduced to the final answer when `final_answer_only` is set
+    pub reply: Option<String>,
+    /// Prompt tokens the reply used; `None` when the provider did not report usage
+    pub input_tokens: Option<u32>,
+    /// Completion tokens the reply used; `None` when the provider did not report usage
+    pub output_tokens: Option<u32>,
+    /// Cost of the reply in USD, priced from the core `[cost].prices` table;
+    /// `None` when usage is unknown
+    pub cost_usd: Option<f64>,
+}
+
+/// Token usage and cost summed over the controller's session. A field stays
+/// `None` until some reply reports it, so "unknown" is not shown as "free".
+#[derive(Debug, Clone, Default, PartialEq, uniffi::Record)]
+pub struct UsageStats {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageStats {
+    fn add(&mut self, usage: &TurnUsage) {
+        fn sum(total: Option<u64>, more: Option<u64>) -> Option<u64> {
+            match (total, more) {
+                (Some(total), Some(more)) => Some(total.saturating_add(more)),
+                (total, more) => total.or(more),
+            }
+        }
+        self.input_tokens = sum(self.input_tokens, usage.input_tokens);
+        self.output_tokens = sum(self.output_tokens, usage.output_tokens);
+        self.cost_usd = match (self.cost_usd, usage.cost_usd) {
+            (Some(total), Some(more)) => Some(total + more),
+            (total, more) => total.or(more),
+        };
+    }
+}
+
+/// Hardware connected to the device, from the same scan as `zeroclaw hardware discover`
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct DiscoveredDevice {
+    /// Board name, or `vid:pid` when the board is not recognised
+    pub name: String,
+    /// Product string reported by the device
+    pub detail: Option<String>,
+    pub device_path: Option<String>,
+    /// `"native"`, `"serial"`, `"probe"` or `"none"`
+    pub transport: String,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+    /// Board architecture, a hint at what the board can do
+    pub capabilities_hint: Option<String>,
+}
+
+impl From<zeroclaw::DiscoveredDevice> for DiscoveredDevice {
+    fn from(device: zeroclaw::DiscoveredDevice) -> Self {
+        Self {
+            name: device.name,
+            detail: device.detail,
+            device_path: device.device_path,
+            transport: device.transport.to_string(),
+            vid: device.vid,
+            pid: device.pid,
+            serial_number: device.serial_number,
+            capabilities_hint: device.capabilities_hint,
+        }
+    }
+}
+
+/// Run `scan` and convert its devices for the Kotlin side.
+fn devices_from_scan(
+    scan: impl FnOnce() -> Vec<zeroclaw::DiscoveredDevice>,
+) -> Vec<DiscoveredDevice> {
+    scan().into_iter().map(DiscoveredDevice::from).collect()
 }
 
 /// Main ZeroClaw controller exposed to Android
 #[derive(uniffi::Object)]
 pub struct ZeroClawController {
     config: Mutex<ZeroClawConfig>,
-    status: Mutex<AgentStatus>,
+    /// Shared with the server task, which reports a failed gateway
+    status: Arc<Mutex<AgentStatus>>,
     messages: Mutex<Vec<ChatMessage>>,
-    // TODO: Add actual gateway handle
-    // gateway: Mutex<Option<GatewayHandle>>,
+    provider: Arc<dyn ChatProvider>,
+    gateway: Mutex<Option<GatewayHandle>>,
+    /// Streaming requests still running, aborted when the controller drops
+    streams: Mutex<Vec<AbortHandle>>,
+    /// Cancellation token of each in-flight request, keyed by message id
+    in_flight: Mutex<HashMap<String, CancellationToken>>,
+    /// Signalled when `in_flight` becomes empty
+    idle: Condvar,
+    /// Usage of every reply since the controller was created
+    usage: Mutex<UsageStats>,
 }
 
 #[uniffi::export]
@@ -91,16 +573,7 @@ impl ZeroClawController {
     /// Create a new controller with the given config
     #[uniffi::constructor]
     pub fn new(config: ZeroClawConfig) -> Arc<Self> {
-        // Initialize logging
-        let _ = tracing_subscriber::fmt()
-            .with_env_filter("zeroclaw=info")
-            .try_init();
-
-        Arc::new(Self {
-            config: Mutex::new(config),
-            status: Mutex::new(AgentStatus::Stopped),
-            messages: Mutex::new(Vec::new()),
-        })
+        Self::with_provider(config, Arc::new(AgentLoopProvider))
     }
 
     /// Create with default config
@@ -115,38 +588,44 @@ impl ZeroClawController {
     pub fn start(&self) -> Result<(), ZeroClawError> {
         let mut status = self.status.lock().map_err(|_| ZeroClawError::LockError)?;
 
-        if matches!(*status, AgentStatus::Running | AgentStatus::Starting) {
+        if matches!(
+            *status,
+            AgentStatus::Running | AgentStatus::Starting | AgentStatus::Thinking
+        ) {
             return Ok(());
         }
 
         *status = AgentStatus::Starting;
         drop(status);
 
-        // TODO: Actually start the gateway
-        // runtime().spawn(async move {
-        //     let config = zeroclaw::Config::load()?;
-        //     let gateway = zeroclaw::Gateway::new(config).await?;
-        //     gateway.run().await
-        // });
-
-        // For now, simulate successful start
-        let mut status = self.status.lock().map_err(|_| ZeroClawError::LockError)?;
-        *status = AgentStatus::Running;
-
-        tracing::info!("ZeroClaw gateway started");
-        Ok(())
+        runtime().block_on(self.finish_start())
     }
 
-    /// Stop the gateway
+    /// Stop the gateway, draining in-flight requests the same way
+    /// `zeroclaw gateway` does on Ctrl+C: new requests are refused at once,
+    /// running ones get up to `gateway.shutdown_grace_secs` to finish and
+    /// are cancelled after that.
     pub fn stop(&self) -> Result<(), ZeroClawError> {
-        let mut status = self.status.lock().map_err(|_| ZeroClawError::LockError)?;
+        let gateway = self
+            .gateway
+            .lock()
+            .map_err(|_| ZeroClawError::LockError)?
+            .take();
 
-        // TODO: Actually stop the gateway
-        // if let Some(gateway) = self.gateway.lock()?.take() {
-        //     gateway.shutdown();
-        // }
+        if let Some(gateway) = gateway {
+            let grace = gateway.shutdown_grace();
+            let requests = Arc::clone(&gateway.requests);
+            let http = runtime().spawn(async move { requests.drain(grace).await });
+            let cancelled = self.drain_in_flight(grace) + runtime().block_on(http).unwrap_or(0);
+            if cancelled > 0 {
+                tracing::warn!(
+                    "Cancelled {cancelled} request(s) still running after the shutdown grace period"
+                );
+            }
+            gateway.shutdown();
+        }
 
-        *status = AgentStatus::Stopped;
+        self.set_status(AgentStatus::Stopped);
         tracing::info!("ZeroClaw gateway stopped");
         Ok(())
     }
@@ -164,33 +643,157 @@ impl ZeroClawController {
     /// Send a message to the agent
     pub fn send_message(&self, content: String) -> SendResult {
         let msg_id = uuid_v4();
+        let failed = |message_id: Option<String>, error: String| SendResult {
+            success: false,
+            message_id,
+            error: Some(error),
+            provider_used: None,
+            reply: None,
+            input_tokens: None,
+            output_tokens: None,
+            cost_usd: None,
+        };
+        let (config, session, cancel) = match self.begin_request(&msg_id, &content) {
+            Ok(prepared) => prepared,
+            Err(e) => return failed(None, e),
+        };
 
-        // Add user message
-        if let Ok(mut messages) = self.messages.lock() {
-            messages.push(ChatMessage {
-                id: msg_id.clone(),
-                content: content.clone(),
-                role: "user".to_string(),
-                timestamp_ms: current_timestamp_ms(),
-            });
-        }
+        let outcome = self.reply_with_fallback(&session, &config, &content, &cancel);
+        self.finish_request(&msg_id);
 
-        // TODO: Actually send to gateway and get response
-        // For now, echo back
-        if let Ok(mut messages) = self.messages.lock() {
-            messages.push(ChatMessage {
-                id: uuid_v4(),
-                content: format!("Echo: {}", content),
-                role: "assistant".to_string(),
-                timestamp_ms: current_timestamp_ms(),
-            });
-        }
+        let (reply, served_by) = match outcome {
+            _ if cancel.is_cancelled() => return failed(Some(msg_id), CANCELLED.to_string()),
+            Ok(served) => served,
+            Err((e, target)) => {
+                tracing::warn!("send_message failed: {}", e);
+                self.record_error(&e, &target);
+                return failed(Some(msg_id), e.to_string());
+            }
+        };
+
+        let visible = if config.final_answer_only {
+            final_answer(&reply.text, &session)
+        } else {
+            reply.text.clone()
+        };
+        let usage = reply.usage;
+
+        self.record_reply(reply, &served_by);
 
         SendResult {
             success: true,
             message_id: Some(msg_id),
             error: None,
+            provider_used: Some(served_by.provider),
+            reply: Some(visible),
+            input_tokens: usage.input_tokens.map(saturating_u32),
+            output_tokens: usage.output_tokens.map(saturating_u32),
+            cost_usd: usage.cost_usd,
+        }
+    }
+
+    /// Send a message and stream the reply to `callback` from a runtime
+    /// thread instead of blocking until it is complete. With
+    /// `final_answer_only` the reply is buffered and only its final answer
+    /// is sent. Dropping the controller cancels the stream.
+    pub fn send_message_streaming(
+        self: Arc<Self>,
+        content: String,
+        callback: Box<dyn ResponseCallback>,
+    ) {
+        let msg_id = uuid_v4();
+        let prepared = self.begin_request(&msg_id, &content);
+        // The task only holds a weak reference so dropping the controller
+        // still runs `Drop`, which aborts it.
+        let controller = Arc::downgrade(&self);
+        let provider = Arc::clone(&self.provider);
+
+        let task = runtime().spawn(async move {
+            let (config, session, cancel) = match prepared {
+                Ok(prepared) => prepared,
+                Err(message) => {
+                    callback.on_error(message);
+                    return;
+                }
+            };
+
+            let (token_tx, mut token_rx) = mpsc::channel(64);
+            let forward = async {
+                while let Some(chunk) = token_rx.recv().await {
+                    if !config.final_answer_only {
+                        callback.on_token(chunk);
+                    }
+                }
+            };
+            let (outcome, ()) = tokio::join!(
+                stream_with_fallback(
+                    provider.as_ref(),
+                    &session,
+                    &config,
+                    &content,
+                    &cancel,
+                    token_tx,
+                ),
+                forward,
+            );
+
+            let cancelled = cancel.is_cancelled();
+            if let Some(controller) = Weak::upgrade(&controller) {
+                controller.finish_request(&msg_id);
+                match &outcome {
+                    _ if cancelled => {}
+                    Ok((reply, served_by)) => controller.record_reply(reply.clone(), served_by),
+                    Err((e, target)) => controller.record_error(e, target),
+                }
+            }
+            match outcome {
+                _ if cancelled => callback.on_error(CANCELLED.to_string()),
+                Ok((reply, _)) => {
+                    if config.final_answer_only {
+                        callback.on_token(final_answer(&reply.text, &session));
+                    }
+                    callback.on_complete(msg_id);
+                }
+                Err((e, _)) => {
+                    tracing::warn!("send_message_streaming failed: {}", e);
+                    callback.on_error(e.to_string());
+                }
+            }
+        });
+
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.retain(|stream| !stream.is_finished());
+            streams.push(task.abort_handle());
+        }
+    }
+
+    /// Cancel every in-flight request and return to `Running`. The agent
+    /// stops at its next provider or tool boundary, and the request reports
+    /// a `"cancelled"` error. A no-op when nothing is in flight.
+    pub fn cancel(&self) -> Result<(), ZeroClawError> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|_| ZeroClawError::LockError)?;
+        let cancelled: Vec<_> = in_flight.drain().map(|(_, token)| token).collect();
+        if cancelled.is_empty() {
+            return Ok(());
+        }
+        self.idle.notify_all();
+        self.finish_thinking();
+        drop(in_flight);
+
+        for token in &cancelled {
+            token.cancel();
         }
+        tracing::info!("Cancelled {} in-flight request(s)", cancelled.len());
+        Ok(())
+    }
+
+    /// Token usage and cost summed over every reply since the controller
+    /// was created
+    pub fn get_usage_total(&self) -> UsageStats {
+        self.usage.lock().map(|u| u.clone()).unwrap_or_default()
     }
 
     /// Get conversation history
@@ -205,7 +808,27 @@ impl ZeroClawController {
     pub fn clear_messages(&self) {
         if let Ok(mut messages) = self.messages.lock() {
             messages.clear();
+            self.save_history(&messages);
+        }
+    }
+
+    /// Reload conversation history from `data_dir/history.json`, replacing
+    /// the in-memory copy, and return it
+    pub fn load_messages(&self) -> Vec<ChatMessage> {
+        let loaded = self
+            .history_path()
+            .map(|path| load_history(&path))
+            .unwrap_or_default();
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.clone_from(&loaded);
         }
+        loaded
+    }
+
+    /// Conversation history as JSON, for the app to back up
+    pub fn export_history(&self) -> String {
+        let messages = self.get_messages();
+        serde_json::to_string_pretty(&messages).unwrap_or_else(|_| "[]".to_string())
     }
 
     /// Update configuration
@@ -230,6 +853,426 @@ impl ZeroClawController {
             .map(|c| !c.api_key.is_empty())
             .unwrap_or(false)
     }
+
+    /// List connected hardware (USB boards) so the app can show it
+    pub fn discover_devices(&self) -> Vec<DiscoveredDevice> {
+        devices_from_scan(zeroclaw::discover_hardware)
+    }
+}
+
+impl ZeroClawController {
+    fn with_provider(config: ZeroClawConfig, provider: Arc<dyn ChatProvider>) -> Arc<Self> {
+        init_logging(config.log_format.as_deref());
+        let history = history_path(&config)
+            .map(|path| load_history(&path))
+            .unwrap_or_default();
+
+        let auto_start = config.auto_start;
+        let controller = Arc::new(Self {
+            config: Mutex::new(config),
+            status: Arc::new(Mutex::new(AgentStatus::Stopped)),
+            messages: Mutex::new(history),
+            provider,
+            gateway: Mutex::new(None),
+            streams: Mutex::new(Vec::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            idle: Condvar::new(),
+            usage: Mutex::new(UsageStats::default()),
+        });
+
+        if auto_start && controller.is_configured() {
+            controller.set_status(AgentStatus::Starting);
+            let weak = Arc::downgrade(&controller);
+            runtime().spawn(async move {
+                // Skip if the controller was dropped or stopped meanwhile.
+                let Some(controller) = weak.upgrade() else {
+                    return;
+                };
+                if matches!(controller.get_status(), AgentStatus::Starting) {
+                    let _ = controller.finish_start().await;
+                }
+            });
+        }
+
+        controller
+    }
+
+    /// Bind and attach the gateway once the status is `Starting`, moving to
+    /// `Error` on failure
+    async fn finish_start(&self) -> Result<(), ZeroClawError> {
+        match self.spawn_gateway().await {
+            Ok(handle) => self.attach_gateway(handle),
+            Err(e) => {
+                self.set_status(AgentStatus::Error {
+                    message: e.to_string(),
+                });
+                Err(e)
+            }
+        }
+    }
+
+    /// Snapshot the config and gateway session, record the user message,
+    /// register the request's cancellation token and mark the agent
+    /// `Thinking`. Fails without recording anything when the gateway is not
+    /// running.
+    fn begin_request(
+        &self,
+        msg_id: &str,
+        content: &str,
+    ) -> Result<(ZeroClawConfig, Arc<GatewaySession>, CancellationToken), String> {
+        let config = self
+            .config
+            .lock()
+            .map_err(|_| ZeroClawError::LockError.to_string())?
+            .clone();
+        let session = self
+            .gateway
+            .lock()
+            .map_err(|_| ZeroClawError::LockError.to_string())?
+            .as_ref()
+            .map(|handle| Arc::clone(&handle.session))
+            .ok_or_else(|| "gateway not started".to_string())?;
+
+        self.append_message(ChatMessage {
+            id: msg_id.to_string(),
+            content: content.to_string(),
+            role: "user".to_string(),
+            timestamp_ms: current_timestamp_ms(),
+            provider: None,
+            model: None,
+            tokens: None,
+            is_error: false,
+        });
+
+        let cancel = CancellationToken::new();
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|_| ZeroClawError::LockError.to_string())?;
+        in_flight.insert(msg_id.to_string(), cancel.clone());
+        self.set_status(AgentStatus::Thinking);
+        drop(in_flight);
+        Ok((config, session, cancel))
+    }
+
+    /// Forget the request's cancellation token, leaving `Thinking` once no
+    /// other request is in flight
+    fn finish_request(&self, msg_id: &str) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.remove(msg_id);
+            if in_flight.is_empty() {
+                self.idle.notify_all();
+                self.finish_thinking();
+            }
+        }
+    }
+
+    /// Wait up to `grace` for in-flight requests to finish, then cancel the
+    /// rest. Returns how many were cancelled.
+    fn drain_in_flight(&self, grace: Duration) -> usize {
+        let Ok(in_flight) = self.in_flight.lock() else {
+            return 0;
+        };
+        let Ok((mut in_flight, _)) = self
+            .idle
+            .wait_timeout_while(in_flight, grace, |pending| !pending.is_empty())
+        else {
+            return 0;
+        };
+        let leftover: Vec<_> = in_flight.drain().map(|(_, token)| token).collect();
+        drop(in_flight);
+        for token in &leftover {
+            token.cancel();
+        }
+        leftover.len()
+    }
+
+    /// Append an assistant reply and add its usage to the session total;
+    /// history keeps the full transcript, narration included
+    fn record_reply(&self, reply: ProviderReply, served_by: &ProviderTarget) {
+        if let Ok(mut usage) = self.usage.lock() {
+            usage.add(&reply.usage);
+        }
+        self.append_message(ChatMessage {
+            id: uuid_v4(),
+            content: reply.text,
+            role: "assistant".to_string(),
+            timestamp_ms: current_timestamp_ms(),
+            provider: Some(served_by.provider.clone()),
+            model: Some(served_by.model.clone()),
+            tokens: reply.usage.total_tokens(),
+            is_error: false,
+        });
+    }
+
+    /// Append an assistant message reporting a failed request, so the
+    /// history shows why no reply follows the user message
+    fn record_error(&self, error: &ProviderCallError, target: &ProviderTarget) {
+        self.append_message(ChatMessage {
+            id: uuid_v4(),
+            content: error.to_string(),
+            role: "assistant".to_string(),
+            timestamp_ms: current_timestamp_ms(),
+            provider: Some(target.provider.clone()),
+            model: Some(target.model.clone()),
+            tokens: None,
+            is_error: true,
+        });
+    }
+
+    /// Append to the history and write it through to disk
+    fn append_message(&self, message: ChatMessage) {
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.push(message);
+            self.save_history(&messages);
+        }
+    }
+
+    fn history_path(&self) -> Option<PathBuf> {
+        self.config.lock().ok().and_then(|c| history_path(&c))
+    }
+
+    /// Write `messages` to the history file; failures are logged, since the
+    /// in-memory conversation is still usable
+    fn save_history(&self, messages: &[ChatMessage]) {
+        let Some(path) = self.history_path() else {
+            return;
+        };
+        if let Err(e) = write_history(&path, messages) {
+            tracing::warn!("Failed to save conversation history: {}", e);
+        }
+    }
+
+    /// Bind the HTTP gateway and serve it on the bridge runtime. Returns
+    /// once the listener is bound, so a taken port fails the start instead
+    /// of the server task.
+    async fn spawn_gateway(&self) -> Result<GatewayHandle, ZeroClawError> {
+        let config = self
+            .config
+            .lock()
+            .map_err(|_| ZeroClawError::LockError)?
+            .clone();
+        let mut session = GatewaySession::from_config(&config);
+        // Also applied by `run_gateway`, but messages may arrive before the
+        // server task gets that far.
+        zeroclaw::agent::loop_limit::configure(&session.config.agent);
+        std::fs::create_dir_all(&session.config.workspace_dir).map_err(|e| {
+            ZeroClawError::ConfigError {
+                message: format!("cannot create workspace: {e}"),
+            }
+        })?;
+        session.load_tools()?;
+
+        let gateway_config = session.config.clone();
+        let host = gateway_config.gateway.host.clone();
+        let listener =
+            zeroclaw::gateway::bind_gateway(&host, gateway_config.gateway.port, &gateway_config)
+                .await
+                .map_err(|e| ZeroClawError::GatewayError {
+                    message: format!("{e:#}"),
+                })?;
+
+        let requests = RequestDrain::new();
+        let server_requests = Arc::clone(&requests);
+        let status = Arc::clone(&self.status);
+        let server = runtime().spawn(async move {
+            // Runs until `stop()` drains `server_requests` and aborts it.
+            let served = zeroclaw::gateway::serve_gateway(
+                &host,
+                listener,
+                gateway_config,
+                server_requests,
+                std::future::pending(),
+            )
+            .await;
+            if let Err(e) = served {
+                tracing::error!("ZeroClaw gateway exited: {:#}", e);
+                if let Ok(mut status) = status.lock() {
+                    *status = AgentStatus::Error {
+                        message: format!("{e:#}"),
+                    };
+                }
+            }
+        });
+
+        Ok(GatewayHandle {
+            session: Arc::new(session),
+            server: Some(server),
+            requests,
+        })
+    }
+
+    /// Install a started gateway and mark the agent running
+    fn attach_gateway(&self, handle: GatewayHandle) -> Result<(), ZeroClawError> {
+        let mut gateway = self.gateway.lock().map_err(|_| ZeroClawError::LockError)?;
+        if let Some(previous) = gateway.replace(handle) {
+            previous.shutdown();
+        }
+        drop(gateway);
+
+        self.set_status(AgentStatus::Running);
+        tracing::info!("ZeroClaw gateway started");
+        Ok(())
+    }
+
+    fn set_status(&self, next: AgentStatus) {
+        if let Ok(mut status) = self.status.lock() {
+            *status = next;
+        }
+    }
+
+    /// Return to `Running` after the last in-flight request, unless the
+    /// gateway was stopped meanwhile. Called with `in_flight` locked and
+    /// empty, so a request starting concurrently cannot be overwritten.
+    fn finish_thinking(&self) {
+        if let Ok(mut status) = self.status.lock() {
+            if matches!(*status, AgentStatus::Thinking) {
+                *status = AgentStatus::Running;
+            }
+        }
+    }
+
+    /// Ask the primary provider for a reply, retrying once against the
+    /// fallback provider on transient failures. Auth failures are returned
+    /// as-is so a bad key is not masked by the fallback; other rejected
+    /// requests are too, since rerunning the turn would repeat its tool
+    /// calls. A cancelled request is never retried.
+    ///
+    /// Returns the reply and the provider target that served it, or the
+    /// error and the target that failed last.
+    fn reply_with_fallback(
+        &self,
+        session: &GatewaySession,
+        config: &ZeroClawConfig,
+        content: &str,
+        cancel: &CancellationToken,
+    ) -> Result<(ProviderReply, ProviderTarget), (ProviderCallError, ProviderTarget)> {
+        let primary = config.primary_target();
+        match self.provider.reply(session, &primary, content, cancel) {
+            Ok(reply) => Ok((reply, primary)),
+            Err(ProviderCallError::Transient(message)) if !cancel.is_cancelled() => {
+                let Some(fallback) = config.fallback_target() else {
+                    return Err((ProviderCallError::Transient(message), primary));
+                };
+                tracing::warn!(
+                    "Primary provider {} failed ({}), retrying with fallback {}",
+                    primary.provider,
+                    message,
+                    fallback.provider
+                );
+                match self.provider.reply(session, &fallback, content, cancel) {
+                    Ok(reply) => Ok((reply, fallback)),
+                    Err(e) => Err((e, fallback)),
+                }
+            }
+            Err(e) => Err((e, primary)),
+        }
+    }
+}
+
+impl Drop for ZeroClawController {
+    fn drop(&mut self) {
+        if let Ok(streams) = self.streams.get_mut() {
+            for stream in streams.drain(..) {
+                stream.abort();
+            }
+        }
+    }
+}
+
+/// Streaming counterpart of [`ZeroClawController::reply_with_fallback`].
+/// The fallback only runs when the primary failed before sending any
+/// tokens; otherwise the caller would get two replies spliced together.
+async fn stream_with_fallback(
+    provider: &dyn ChatProvider,
+    session: &GatewaySession,
+    config: &ZeroClawConfig,
+    content: &str,
+    cancel: &CancellationToken,
+    on_token: mpsc::Sender<String>,
+) -> Result<(ProviderReply, ProviderTarget), (ProviderCallError, ProviderTarget)> {
+    let primary = config.primary_target();
+    let (outcome, sent_tokens) =
+        stream_attempt(provider, session, &primary, content, cancel, &on_token).await;
+    match outcome {
+        Ok(reply) => Ok((reply, primary)),
+        Err(ProviderCallError::Transient(message)) if !cancel.is_cancelled() => {
+            let Some(fallback) = config.fallback_target() else {
+                return Err((ProviderCallError::Transient(message), primary));
+            };
+            if sent_tokens {
+                tracing::warn!(
+                    "Primary provider {} failed ({}) after streaming part of the reply, not retrying with fallback {}",
+                    primary.provider,
+                    message,
+                    fallback.provider
+                );
+                return Err((ProviderCallError::Transient(message), primary));
+            }
+            tracing::warn!(
+                "Primary provider {} failed ({}), retrying with fallback {}",
+                primary.provider,
+                message,
+                fallback.provider
+            );
+            let (outcome, _) =
+                stream_attempt(provider, session, &fallback, content, cancel, &on_token).await;
+            match outcome {
+                Ok(reply) => Ok((reply, fallback)),
+                Err(e) => Err((e, fallback)),
+            }
+        }
+        Err(e) => Err((e, primary)),
+    }
+}
+
+/// Stream one reply from `target` to `on_token`, failing it transiently
+/// when no token arrives within `gateway.first_token_timeout_secs`. Also
+/// returns whether any token reached `on_token`.
+async fn stream_attempt(
+    provider: &dyn ChatProvider,
+    session: &GatewaySession,
+    target: &ProviderTarget,
+    content: &str,
+    cancel: &CancellationToken,
+    on_token: &mpsc::Sender<String>,
+) -> (Result<ProviderReply, ProviderCallError>, bool) {
+    let sent_tokens = AtomicBool::new(false);
+    let (token_tx, mut token_rx) = mpsc::channel(64);
+    let relay = async {
+        while let Some(chunk) = token_rx.recv().await {
+            sent_tokens.store(true, Ordering::SeqCst);
+            let _ = on_token.send(chunk).await;
+        }
+    };
+    let attempt = async {
+        let (reply, ()) = tokio::join!(
+            provider.reply_streaming(session, target, content, cancel, token_tx),
+            relay
+        );
+        reply
+    };
+
+    let first_token_timeout = session.config.gateway.first_token_timeout_secs;
+    let first_token_deadline = async {
+        if first_token_timeout > 0 {
+            tokio::time::sleep(Duration::from_secs(first_token_timeout)).await;
+            if !sent_tokens.load(Ordering::SeqCst) {
+                return Duration::from_secs(first_token_timeout);
+            }
+        }
+        std::future::pending().await
+    };
+
+    tokio::select! {
+        reply = attempt => (reply, sent_tokens.load(Ordering::SeqCst)),
+        limit = first_token_deadline => {
+            let timeout = zeroclaw::providers::traits::StreamError::Timeout(format!(
+                "no content received within {limit:?}"
+            ));
+            (Err(ProviderCallError::Transient(timeout.to_string())), false)
+        }
+    }
 }
 
 /// Errors that can occur in the bridge
@@ -257,6 +1300,57 @@ impl std::fmt::Display for ZeroClawError {
 impl std::error::Error for ZeroClawError {}
 
 // Helper functions
+
+/// History file for `config`; `None` when no `data_dir` is set
+fn history_path(config: &ZeroClawConfig) -> Option<PathBuf> {
+    let data_dir = config.data_dir.trim();
+    (!data_dir.is_empty()).then(|| Path::new(data_dir).join(HISTORY_FILE))
+}
+
+/// Read saved history. A missing file is an empty history; an unreadable or
+/// corrupt one is logged and also treated as empty, so the app starts fresh.
+fn load_history(path: &Path) -> Vec<ChatMessage> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            tracing::warn!("Cannot read conversation history, starting fresh: {}", e);
+            return Vec::new();
+        }
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        tracing::warn!("Corrupt conversation history, starting fresh: {}", e);
+        Vec::new()
+    })
+}
+
+/// Write history via a temp file and rename, so a process killed mid-write
+/// leaves the previous file intact
+fn write_history(path: &Path, messages: &[ChatMessage]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec(messages)?)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Reduce agent output to the final user-facing answer with the gateway's
+/// own reply sanitizer
+fn final_answer(response: &str, session: &GatewaySession) -> String {
+    zeroclaw::gateway::sanitize_gateway_response(
+        response,
+        &session.tools,
+        &session.config.security.outbound_leak_guard,
+        &session.config.gateway.reply_strip_prefixes,
+    )
+}
+
+/// Token count as `u32` for `SendResult`, clamped rather than wrapped
+fn saturating_u32(tokens: u64) -> u32 {
+    u32::try_from(tokens).unwrap_or(u32::MAX)
+}
+
 fn uuid_v4() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let now = SystemTime::now()
@@ -277,6 +1371,50 @@ fn current_timestamp_ms() -> i64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Weak;
+
+    /// Mark the gateway running without spawning the HTTP server
+    fn start_detached(controller: &ZeroClawController) {
+        start_detached_with(controller, |_| {});
+    }
+
+    /// `start_detached` with session overrides, e.g. gateway timeouts
+    fn start_detached_with(
+        controller: &ZeroClawController,
+        configure: impl FnOnce(&mut GatewaySession),
+    ) {
+        let mut session = GatewaySession::from_config(&controller.get_config().unwrap());
+        configure(&mut session);
+        controller
+            .attach_gateway(GatewayHandle {
+                session: Arc::new(session),
+                server: None,
+                requests: RequestDrain::new(),
+            })
+            .unwrap();
+    }
+
+    /// Fresh, empty `data_dir` so history files never leak between tests
+    struct TempDataDir(PathBuf);
+
+    impl TempDataDir {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("zeroclaw-bridge-{}", uuid_v4())))
+        }
+
+        fn config(&self) -> ZeroClawConfig {
+            ZeroClawConfig {
+                data_dir: self.0.to_string_lossy().into_owned(),
+                ..ZeroClawConfig::default()
+            }
+        }
+    }
+
+    impl Drop for TempDataDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
 
     #[test]
     fn test_controller_creation() {
@@ -287,19 +1425,1073 @@ mod tests {
     #[test]
     fn test_start_stop() {
         let controller = ZeroClawController::with_defaults("/tmp/zeroclaw".to_string());
-        controller.start().unwrap();
+        start_detached(&controller);
         assert!(matches!(controller.get_status(), AgentStatus::Running));
         controller.stop().unwrap();
         assert!(matches!(controller.get_status(), AgentStatus::Stopped));
+        assert!(controller.gateway.lock().unwrap().is_none());
     }
 
     #[test]
-    fn test_send_message() {
-        let controller = ZeroClawController::with_defaults("/tmp/zeroclaw".to_string());
-        let result = controller.send_message("Hello".to_string());
-        assert!(result.success);
+    fn test_start_binds_before_running() {
+        let data_dir = TempDataDir::new();
+        let controller = ZeroClawController::new(ZeroClawConfig {
+            gateway_port: Some(0),
+            ..data_dir.config()
+        });
+        controller.start().unwrap();
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+        controller.stop().unwrap();
+
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let controller = ZeroClawController::new(ZeroClawConfig {
+            gateway_port: Some(taken.local_addr().unwrap().port()),
+            ..data_dir.config()
+        });
+        let err = controller.start().unwrap_err();
+        assert!(matches!(err, ZeroClawError::GatewayError { .. }));
+        assert!(matches!(controller.get_status(), AgentStatus::Error { .. }));
+        assert!(controller.gateway.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_auto_start_starts_gateway_from_constructor() {
+        let data_dir = TempDataDir::new();
+        let controller = ZeroClawController::new(ZeroClawConfig {
+            api_key: "sk-test".to_string(),
+            auto_start: true,
+            gateway_port: Some(0),
+            ..data_dir.config()
+        });
+        assert!(matches!(
+            controller.get_status(),
+            AgentStatus::Starting | AgentStatus::Running
+        ));
+        assert!(matches!(settled_status(&controller), AgentStatus::Running));
+        controller.stop().unwrap();
+
+        // Without an API key there is nothing to start.
+        let unconfigured = ZeroClawController::new(ZeroClawConfig {
+            auto_start: true,
+            ..data_dir.config()
+        });
+        assert!(matches!(unconfigured.get_status(), AgentStatus::Stopped));
+    }
+
+    #[test]
+    fn test_auto_start_reports_bind_failure_as_error() {
+        let data_dir = TempDataDir::new();
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let controller = ZeroClawController::new(ZeroClawConfig {
+            api_key: "sk-test".to_string(),
+            auto_start: true,
+            gateway_port: Some(taken.local_addr().unwrap().port()),
+            ..data_dir.config()
+        });
+        assert!(matches!(
+            settled_status(&controller),
+            AgentStatus::Error { .. }
+        ));
+        assert!(controller.gateway.lock().unwrap().is_none());
+    }
+
+    /// Wait for a background start to leave `Starting`
+    fn settled_status(controller: &ZeroClawController) -> AgentStatus {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            let status = controller.get_status();
+            if !matches!(status, AgentStatus::Starting) {
+                return status;
+            }
+            assert!(std::time::Instant::now() < deadline, "auto-start stalled");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_send_message() {
+        let (controller, _stub) = controller_with_stub(Vec::new());
+        let result = controller.send_message("Hello".to_string());
+        assert!(result.success);
 
         let messages = controller.get_messages();
         assert_eq!(messages.len(), 2); // User + assistant
+        assert_eq!(result.provider_used.as_deref(), Some("anthropic"));
+        assert_eq!(messages[1].content, "anthropic (claude-sonnet-4-5): Hello");
+    }
+
+    #[test]
+    fn test_send_message_requires_started_gateway() {
+        let dir = TempDataDir::new();
+        let controller = ZeroClawController::new(dir.config());
+        let result = controller.send_message("Hello".to_string());
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("gateway not started"));
+        assert!(controller.get_messages().is_empty());
+    }
+
+    /// Backend that records the controller status seen mid-request
+    struct StatusProbe {
+        controller: OnceLock<Weak<ZeroClawController>>,
+        seen: Mutex<Option<AgentStatus>>,
+    }
+
+    impl ChatProvider for StatusProbe {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            _target: &ProviderTarget,
+            _content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            let controller = self.controller.get().and_then(Weak::upgrade).unwrap();
+            *self.seen.lock().unwrap() = Some(controller.get_status());
+            Err(ProviderCallError::Transient("503".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_status_is_thinking_while_request_in_flight() {
+        let probe = Arc::new(StatusProbe {
+            controller: OnceLock::new(),
+            seen: Mutex::new(None),
+        });
+        let controller =
+            ZeroClawController::with_provider(ZeroClawConfig::default(), probe.clone());
+        probe.controller.set(Arc::downgrade(&controller)).ok();
+        start_detached(&controller);
+
+        let result = controller.send_message("Hello".to_string());
+        assert!(!result.success);
+        assert!(matches!(
+            *probe.seen.lock().unwrap(),
+            Some(AgentStatus::Thinking)
+        ));
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    #[test]
+    fn test_agent_errors_are_classified_and_sanitized() {
+        let auth = anyhow::anyhow!("anthropic API error (401 Unauthorized): bad key");
+        assert!(matches!(
+            ProviderCallError::from_agent_error(&auth),
+            ProviderCallError::Auth(_)
+        ));
+
+        for rejected in [
+            anyhow::anyhow!("openrouter API error (400 Bad Request): invalid tools"),
+            anyhow::anyhow!("prompt is too long: 210000 tokens"),
+            anyhow::anyhow!("gpio_write failed: pin 401 out of range"),
+        ] {
+            assert!(matches!(
+                ProviderCallError::from_agent_error(&rejected),
+                ProviderCallError::Rejected(_)
+            ));
+        }
+
+        let leaked = anyhow::anyhow!(
+            "openrouter API error (502 Bad Gateway): key sk-abcdefghijklmnopqrstuvwxyz"
+        );
+        match ProviderCallError::from_agent_error(&leaked) {
+            ProviderCallError::Transient(message) => {
+                assert!(!message.contains("sk-abcdefghijklmnopqrstuvwxyz"));
+                assert!(message.contains("[REDACTED]"));
+            }
+            other => panic!("expected transient error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_assistant_message_carries_provider_and_model() {
+        let (controller, _stub) = controller_with_stub(vec![(
+            "anthropic",
+            ProviderCallError::Transient("connection reset".to_string()),
+        )]);
+        assert!(controller.send_message("Hello".to_string()).success);
+
+        let messages = controller.get_messages();
+        let user = &messages[0];
+        assert_eq!(user.role, "user");
+        assert!(user.provider.is_none());
+        assert!(user.model.is_none());
+        assert!(user.tokens.is_none());
+        assert!(!user.is_error);
+
+        let assistant = &messages[1];
+        assert_eq!(assistant.role, "assistant");
+        assert_eq!(assistant.provider.as_deref(), Some("openrouter"));
+        assert_eq!(assistant.model.as_deref(), Some("fallback-model"));
+        assert!(!assistant.is_error);
+    }
+
+    #[test]
+    fn test_chat_message_serialization_tolerates_missing_metadata() {
+        let old: ChatMessage =
+            serde_json::from_str(r#"{"id":"1","content":"hi","role":"user","timestamp_ms":5}"#)
+                .unwrap();
+        assert!(old.provider.is_none());
+        assert!(!old.is_error);
+
+        let assistant = ChatMessage {
+            provider: Some("anthropic".to_string()),
+            model: Some("claude-sonnet-4-5".to_string()),
+            ..old
+        };
+        let round_trip: ChatMessage =
+            serde_json::from_str(&serde_json::to_string(&assistant).unwrap()).unwrap();
+        assert_eq!(round_trip.provider.as_deref(), Some("anthropic"));
+        assert_eq!(round_trip.model.as_deref(), Some("claude-sonnet-4-5"));
+    }
+
+    #[test]
+    fn test_log_format_parse() {
+        assert_eq!(LogFormat::parse(None), Ok(LogFormat::Text));
+        assert_eq!(LogFormat::parse(Some(" JSON ")), Ok(LogFormat::Json));
+        assert_eq!(LogFormat::parse(Some("text")), Ok(LogFormat::Text));
+        assert_eq!(LogFormat::parse(Some("xml")), Err("xml".to_string()));
+    }
+
+    #[test]
+    fn test_controller_accepts_log_formats() {
+        for format in ["json", "xml"] {
+            let config = ZeroClawConfig {
+                log_format: Some(format.to_string()),
+                ..ZeroClawConfig::default()
+            };
+            let controller = ZeroClawController::new(config);
+            assert!(matches!(controller.get_status(), AgentStatus::Stopped));
+        }
+    }
+
+    /// Stub backend that fails for selected providers
+    struct StubProvider {
+        failures: Vec<(&'static str, ProviderCallError)>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl ChatProvider for StubProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            target: &ProviderTarget,
+            content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            self.calls.lock().unwrap().push(target.provider.clone());
+            match self
+                .failures
+                .iter()
+                .find(|(name, _)| *name == target.provider)
+            {
+                Some((_, err)) => Err(err.clone()),
+                None => Ok(format!("{} ({}): {}", target.provider, target.model, content).into()),
+            }
+        }
+    }
+
+    fn controller_with_stub(
+        failures: Vec<(&'static str, ProviderCallError)>,
+    ) -> (Arc<ZeroClawController>, Arc<StubProvider>) {
+        let stub = Arc::new(StubProvider {
+            failures,
+            calls: Mutex::new(Vec::new()),
+        });
+        let config = ZeroClawConfig {
+            fallback_provider: Some("openrouter".to_string()),
+            fallback_model: Some("fallback-model".to_string()),
+            fallback_api_key: Some("sk-fallback".to_string()),
+            ..ZeroClawConfig::default()
+        };
+        let controller = ZeroClawController::with_provider(config, stub.clone());
+        start_detached(&controller);
+        (controller, stub)
+    }
+
+    #[test]
+    fn test_history_survives_restart() {
+        let dir = TempDataDir::new();
+        let stub = Arc::new(StubProvider {
+            failures: Vec::new(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let controller = ZeroClawController::with_provider(dir.config(), stub.clone());
+        start_detached(&controller);
+        assert!(controller.send_message("Hello".to_string()).success);
+        drop(controller);
+
+        let restarted = ZeroClawController::with_provider(dir.config(), stub);
+        let messages = restarted.get_messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "Hello");
+        assert_eq!(messages[1].provider.as_deref(), Some("anthropic"));
+
+        let exported: Vec<ChatMessage> = serde_json::from_str(&restarted.export_history()).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[1].id, messages[1].id);
+
+        restarted.clear_messages();
+        assert!(restarted.load_messages().is_empty());
+        let on_disk = std::fs::read_to_string(dir.0.join(HISTORY_FILE)).unwrap();
+        assert_eq!(on_disk, "[]");
+    }
+
+    #[test]
+    fn test_corrupt_history_starts_fresh() {
+        let dir = TempDataDir::new();
+        std::fs::create_dir_all(&dir.0).unwrap();
+        std::fs::write(dir.0.join(HISTORY_FILE), r#"[{"id":"1","content":"trunc"#).unwrap();
+
+        let stub = Arc::new(StubProvider {
+            failures: Vec::new(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let controller = ZeroClawController::with_provider(dir.config(), stub);
+        assert!(controller.get_messages().is_empty());
+        assert!(controller.load_messages().is_empty());
+
+        start_detached(&controller);
+        assert!(controller.send_message("Hello".to_string()).success);
+        let on_disk = std::fs::read_to_string(dir.0.join(HISTORY_FILE)).unwrap();
+        let saved: Vec<ChatMessage> = serde_json::from_str(&on_disk).unwrap();
+        assert_eq!(saved.len(), 2);
+    }
+
+    #[test]
+    fn test_fallback_on_transient_failure() {
+        let (controller, stub) = controller_with_stub(vec![(
+            "anthropic",
+            ProviderCallError::Transient("connection reset".to_string()),
+        )]);
+
+        let result = controller.send_message("Hello".to_string());
+        assert!(result.success);
+        assert_eq!(result.provider_used.as_deref(), Some("openrouter"));
+        assert_eq!(*stub.calls.lock().unwrap(), vec!["anthropic", "openrouter"]);
+
+        let messages = controller.get_messages();
+        assert_eq!(messages[1].content, "openrouter (fallback-model): Hello");
+    }
+
+    #[test]
+    fn test_no_fallback_on_auth_failure() {
+        let (controller, stub) = controller_with_stub(vec![(
+            "anthropic",
+            ProviderCallError::Auth("invalid api key".to_string()),
+        )]);
+
+        let result = controller.send_message("Hello".to_string());
+        assert!(!result.success);
+        assert!(result.provider_used.is_none());
+        assert!(result.error.unwrap().contains("invalid api key"));
+        assert_eq!(*stub.calls.lock().unwrap(), vec!["anthropic"]);
+    }
+
+    #[test]
+    fn test_no_fallback_on_rejected_request() {
+        for error in [
+            anyhow::anyhow!("anthropic API error (400 Bad Request): invalid tools"),
+            anyhow::anyhow!("Your input exceeds the context window of this model."),
+        ] {
+            let (controller, stub) = controller_with_stub(vec![(
+                "anthropic",
+                ProviderCallError::from_agent_error(&error),
+            )]);
+
+            let result = controller.send_message("Hello".to_string());
+            assert!(!result.success);
+            assert!(result.provider_used.is_none());
+            assert_eq!(*stub.calls.lock().unwrap(), vec!["anthropic"]);
+        }
+    }
+
+    #[test]
+    fn test_failed_request_is_recorded_as_sanitized_error() {
+        let leaked = anyhow::anyhow!(
+            "anthropic API error (401 Unauthorized): key sk-abcdefghijklmnopqrstuvwxyz"
+        );
+        let (controller, _stub) = controller_with_stub(vec![(
+            "anthropic",
+            ProviderCallError::from_agent_error(&leaked),
+        )]);
+
+        let result = controller.send_message("Hello".to_string());
+        assert!(!result.success);
+
+        let messages = controller.get_messages();
+        assert_eq!(messages.len(), 2);
+        assert!(!messages[0].is_error);
+        let failure = &messages[1];
+        assert_eq!(failure.role, "assistant");
+        assert!(failure.is_error);
+        assert!(failure.content.starts_with("Provider auth failed:"));
+        assert!(!failure.content.contains("sk-abcdefghijklmnopqrstuvwxyz"));
+        assert_eq!(failure.provider.as_deref(), Some("anthropic"));
+        assert_eq!(
+            failure.model.as_deref(),
+            Some(ZeroClawConfig::default().model.as_str())
+        );
+    }
+
+    #[test]
+    fn test_fallback_failure_is_reported() {
+        let (controller, stub) = controller_with_stub(vec![
+            (
+                "anthropic",
+                ProviderCallError::Transient("timeout".to_string()),
+            ),
+            (
+                "openrouter",
+                ProviderCallError::Transient("503".to_string()),
+            ),
+        ]);
+
+        let result = controller.send_message("Hello".to_string());
+        assert!(!result.success);
+        assert_eq!(*stub.calls.lock().unwrap(), vec!["anthropic", "openrouter"]);
+        // The failure is recorded against the provider that failed last.
+        let messages = controller.get_messages();
+        assert_eq!(messages.len(), 2);
+        let failure = &messages[1];
+        assert_eq!(failure.role, "assistant");
+        assert!(failure.is_error);
+        assert_eq!(failure.content, result.error.unwrap());
+        assert_eq!(failure.provider.as_deref(), Some("openrouter"));
+        assert!(failure.tokens.is_none());
+    }
+
+    /// Backend that reports usage for the providers listed in `usage`
+    struct MeteredProvider {
+        usage: Vec<(&'static str, TurnUsage)>,
+    }
+
+    impl ChatProvider for MeteredProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            target: &ProviderTarget,
+            content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            let usage = self
+                .usage
+                .iter()
+                .find(|(name, _)| *name == target.provider)
+                .map(|(_, usage)| *usage)
+                .unwrap_or_default();
+            Ok(ProviderReply {
+                text: content.to_string(),
+                usage,
+            })
+        }
+    }
+
+    #[test]
+    fn test_send_result_reports_usage_and_session_totals() {
+        let metered = TurnUsage {
+            input_tokens: Some(1_200),
+            output_tokens: Some(300),
+            cost_usd: Some(0.0081),
+        };
+        let controller = ZeroClawController::with_provider(
+            ZeroClawConfig::default(),
+            Arc::new(MeteredProvider {
+                usage: vec![("anthropic", metered)],
+            }),
+        );
+        start_detached(&controller);
+        assert_eq!(controller.get_usage_total(), UsageStats::default());
+
+        let first = controller.send_message("Hello".to_string());
+        assert_eq!(first.input_tokens, Some(1_200));
+        assert_eq!(first.output_tokens, Some(300));
+        assert_eq!(first.cost_usd, Some(0.0081));
+        assert_eq!(controller.get_messages()[1].tokens, Some(1_500));
+
+        controller.send_message("Again".to_string());
+        let total = controller.get_usage_total();
+        assert_eq!(total.input_tokens, Some(2_400));
+        assert_eq!(total.output_tokens, Some(600));
+        assert!((total.cost_usd.unwrap() - 0.0162).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unreported_usage_stays_unknown() {
+        let (controller, _stub) = controller_with_stub(Vec::new());
+        let result = controller.send_message("Hello".to_string());
+        assert!(result.success);
+        assert!(result.input_tokens.is_none());
+        assert!(result.output_tokens.is_none());
+        assert!(result.cost_usd.is_none());
+        assert!(controller.get_messages()[1].tokens.is_none());
+        assert_eq!(controller.get_usage_total(), UsageStats::default());
+    }
+
+    /// Backend that replies with tool narration around the final answer
+    struct NarratingProvider;
+
+    const NARRATED_REPLY: &str = r#"Let me check the schedule.
+<tool_call>
+{"name":"schedule","arguments":{"action":"list"}}
+</tool_call>
+{"name":"schedule","parameters":{"action":"list"}}
+{"result":{"status":"ok","items":1}}
+You have one meeting at 10:00."#;
+
+    impl ChatProvider for NarratingProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            _target: &ProviderTarget,
+            _content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            Ok(NARRATED_REPLY.to_string().into())
+        }
+    }
+
+    #[test]
+    fn test_final_answer_only_strips_tool_narration() {
+        let data_dir = TempDataDir::new();
+        let config = ZeroClawConfig {
+            final_answer_only: true,
+            ..data_dir.config()
+        };
+        let controller = ZeroClawController::with_provider(config, Arc::new(NarratingProvider));
+        start_detached_with(&controller, |session| session.load_tools().unwrap());
+
+        let result = controller.send_message("What's on today?".to_string());
+        assert!(result.success);
+        let reply = result.reply.unwrap();
+        let lines: Vec<_> = reply
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "Let me check the schedule.",
+                "You have one meeting at 10:00."
+            ]
+        );
+        assert!(!reply.contains("tool_call"));
+        assert!(!reply.contains("\"result\""));
+
+        // Internal history keeps the full transcript
+        let messages = controller.get_messages();
+        assert_eq!(messages[1].content, NARRATED_REPLY);
+    }
+
+    #[test]
+    fn test_final_answer_only_streams_final_answer_once() {
+        let data_dir = TempDataDir::new();
+        let config = ZeroClawConfig {
+            final_answer_only: true,
+            ..data_dir.config()
+        };
+        let controller = ZeroClawController::with_provider(config, Arc::new(NarratingProvider));
+        start_detached_with(&controller, |session| session.load_tools().unwrap());
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("What's on today?".to_string(), callback);
+
+        let (token, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        let StreamEvent::Token(answer) = token else {
+            panic!("expected the final answer, got {token:?}");
+        };
+        assert!(answer.contains("You have one meeting at 10:00."));
+        assert!(!answer.contains("tool_call"));
+        assert!(!answer.contains("\"result\""));
+        let (done, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert!(matches!(done, StreamEvent::Complete(_)));
+        assert_eq!(controller.get_messages()[1].content, NARRATED_REPLY);
+    }
+
+    /// Callback events, forwarded to the test thread
+    #[derive(Debug, PartialEq)]
+    enum StreamEvent {
+        Token(String),
+        Complete(String),
+        Error(String),
+    }
+
+    struct ChannelCallback {
+        events: Mutex<std::sync::mpsc::Sender<(StreamEvent, std::thread::ThreadId)>>,
+    }
+
+    impl ChannelCallback {
+        fn channel() -> (
+            Box<dyn ResponseCallback>,
+            std::sync::mpsc::Receiver<(StreamEvent, std::thread::ThreadId)>,
+        ) {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let callback = Box::new(Self {
+                events: Mutex::new(tx),
+            });
+            (callback, rx)
+        }
+
+        fn emit(&self, event: StreamEvent) {
+            let _ = self
+                .events
+                .lock()
+                .unwrap()
+                .send((event, std::thread::current().id()));
+        }
+    }
+
+    impl ResponseCallback for ChannelCallback {
+        fn on_token(&self, chunk: String) {
+            self.emit(StreamEvent::Token(chunk));
+        }
+
+        fn on_complete(&self, message_id: String) {
+            self.emit(StreamEvent::Complete(message_id));
+        }
+
+        fn on_error(&self, message: String) {
+            self.emit(StreamEvent::Error(message));
+        }
+    }
+
+    const STREAM_WAIT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_streaming_forwards_tokens_then_completes_off_caller_thread() {
+        let (controller, _stub) = controller_with_stub(vec![(
+            "anthropic",
+            ProviderCallError::Transient("connection reset".to_string()),
+        )]);
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        let (token, token_thread) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(
+            token,
+            StreamEvent::Token("openrouter (fallback-model): Hello".to_string())
+        );
+        assert_ne!(token_thread, std::thread::current().id());
+        let (done, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+
+        let messages = controller.get_messages();
+        assert_eq!(done, StreamEvent::Complete(messages[0].id.clone()));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].provider.as_deref(), Some("openrouter"));
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    #[test]
+    fn test_streaming_requires_started_gateway() {
+        let dir = TempDataDir::new();
+        let controller = ZeroClawController::new(dir.config());
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        let (event, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(event, StreamEvent::Error("gateway not started".to_string()));
+        assert!(controller.get_messages().is_empty());
+    }
+
+    /// How the primary provider of `FlakyStreamProvider` streams
+    enum PrimaryStream {
+        /// One chunk, then a transient failure
+        PartialThenFail,
+        /// Nothing at all, and never finishes
+        Silent,
+    }
+
+    /// Backend whose primary misbehaves while the fallback streams a normal
+    /// reply
+    struct FlakyStreamProvider {
+        primary: PrimaryStream,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl FlakyStreamProvider {
+        fn controller(
+            primary: PrimaryStream,
+            first_token_timeout_secs: u64,
+        ) -> (Arc<ZeroClawController>, Arc<Self>) {
+            let provider = Arc::new(Self {
+                primary,
+                calls: Mutex::new(Vec::new()),
+            });
+            let config = ZeroClawConfig {
+                fallback_provider: Some("openrouter".to_string()),
+                fallback_model: Some("fallback-model".to_string()),
+                ..ZeroClawConfig::default()
+            };
+            let controller = ZeroClawController::with_provider(config, provider.clone());
+            start_detached_with(&controller, |session| {
+                session.config.gateway.first_token_timeout_secs = first_token_timeout_secs;
+            });
+            (controller, provider)
+        }
+    }
+
+    impl ChatProvider for FlakyStreamProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            _target: &ProviderTarget,
+            _content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            unreachable!("streaming test only")
+        }
+
+        fn reply_streaming<'a>(
+            &'a self,
+            _session: &'a GatewaySession,
+            target: &'a ProviderTarget,
+            content: &'a str,
+            _cancel: &'a CancellationToken,
+            on_token: mpsc::Sender<String>,
+        ) -> ReplyFuture<'a> {
+            self.calls.lock().unwrap().push(target.provider.clone());
+            let primary = target.provider == "anthropic";
+            Box::pin(async move {
+                if !primary {
+                    let reply = format!("{}: {}", target.provider, content);
+                    let _ = on_token.send(reply.clone()).await;
+                    return Ok(reply.into());
+                }
+                match self.primary {
+                    PrimaryStream::PartialThenFail => {
+                        let _ = on_token.send("partial".to_string()).await;
+                        Err(ProviderCallError::Transient("connection reset".to_string()))
+                    }
+                    PrimaryStream::Silent => std::future::pending().await,
+                }
+            })
+        }
+    }
+
+    #[test]
+    fn test_streaming_does_not_fall_back_after_partial_reply() {
+        let (controller, provider) =
+            FlakyStreamProvider::controller(PrimaryStream::PartialThenFail, 0);
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        let (token, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(token, StreamEvent::Token("partial".to_string()));
+        let (event, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(
+            event,
+            StreamEvent::Error("Provider unavailable: connection reset".to_string())
+        );
+        assert_eq!(*provider.calls.lock().unwrap(), vec!["anthropic"]);
+        let messages = controller.get_messages();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].is_error);
+        assert_eq!(
+            messages[1].content,
+            "Provider unavailable: connection reset"
+        );
+        assert_eq!(messages[1].provider.as_deref(), Some("anthropic"));
+    }
+
+    #[test]
+    fn test_streaming_first_token_timeout_falls_back() {
+        let (controller, provider) = FlakyStreamProvider::controller(PrimaryStream::Silent, 1);
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        let (token, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(token, StreamEvent::Token("openrouter: Hello".to_string()));
+        let (done, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert!(matches!(done, StreamEvent::Complete(_)));
+        assert_eq!(
+            *provider.calls.lock().unwrap(),
+            vec!["anthropic", "openrouter"]
+        );
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    #[test]
+    fn test_streaming_first_token_timeout_without_fallback() {
+        let provider = Arc::new(FlakyStreamProvider {
+            primary: PrimaryStream::Silent,
+            calls: Mutex::new(Vec::new()),
+        });
+        let controller = ZeroClawController::with_provider(ZeroClawConfig::default(), provider);
+        start_detached_with(&controller, |session| {
+            session.config.gateway.first_token_timeout_secs = 1;
+        });
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        let (event, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(
+            event,
+            StreamEvent::Error(
+                "Provider unavailable: Stream timed out: no content received within 1s".to_string()
+            )
+        );
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    /// Sets its flag when dropped, i.e. when the stream future is cancelled
+    struct DropFlag(Arc<std::sync::atomic::AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// Backend that sends one chunk and then never finishes
+    struct HangingProvider {
+        cancelled: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    impl ChatProvider for HangingProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            _target: &ProviderTarget,
+            _content: &str,
+            _cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            unreachable!("streaming test only")
+        }
+
+        fn reply_streaming<'a>(
+            &'a self,
+            _session: &'a GatewaySession,
+            _target: &'a ProviderTarget,
+            _content: &'a str,
+            _cancel: &'a CancellationToken,
+            on_token: mpsc::Sender<String>,
+        ) -> ReplyFuture<'a> {
+            let guard = DropFlag(Arc::clone(&self.cancelled));
+            Box::pin(async move {
+                let _guard = guard;
+                let _ = on_token.send("partial".to_string()).await;
+                std::future::pending().await
+            })
+        }
+    }
+
+    #[test]
+    fn test_dropping_controller_cancels_stream() {
+        let cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let provider = Arc::new(HangingProvider {
+            cancelled: Arc::clone(&cancelled),
+        });
+        let controller = ZeroClawController::with_provider(ZeroClawConfig::default(), provider);
+        start_detached(&controller);
+
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+        let (event, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(event, StreamEvent::Token("partial".to_string()));
+        assert!(!cancelled.load(std::sync::atomic::Ordering::SeqCst));
+
+        drop(controller);
+        // Aborting drops the task, and with it the callback: the channel
+        // closes without on_complete or on_error.
+        assert!(matches!(
+            events.recv_timeout(STREAM_WAIT),
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected)
+        ));
+        assert!(cancelled.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    /// Backend that signals once it is called, then waits for the request
+    /// to be cancelled and fails the way the agent loop does
+    struct CancellableProvider {
+        started: Mutex<std::sync::mpsc::Sender<()>>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl CancellableProvider {
+        fn new() -> (Arc<Self>, std::sync::mpsc::Receiver<()>) {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let provider = Arc::new(Self {
+                started: Mutex::new(tx),
+                calls: Mutex::new(Vec::new()),
+            });
+            (provider, rx)
+        }
+
+        async fn wait_for_cancel(
+            &self,
+            target: &ProviderTarget,
+            cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            self.calls.lock().unwrap().push(target.provider.clone());
+            let _ = self.started.lock().unwrap().send(());
+            cancel.cancelled().await;
+            Err(ProviderCallError::Transient(
+                "tool loop cancelled".to_string(),
+            ))
+        }
+    }
+
+    impl ChatProvider for CancellableProvider {
+        fn reply(
+            &self,
+            _session: &GatewaySession,
+            target: &ProviderTarget,
+            _content: &str,
+            cancel: &CancellationToken,
+        ) -> Result<ProviderReply, ProviderCallError> {
+            runtime().block_on(self.wait_for_cancel(target, cancel))
+        }
+
+        fn reply_streaming<'a>(
+            &'a self,
+            _session: &'a GatewaySession,
+            target: &'a ProviderTarget,
+            _content: &'a str,
+            cancel: &'a CancellationToken,
+            _on_token: mpsc::Sender<String>,
+        ) -> ReplyFuture<'a> {
+            Box::pin(self.wait_for_cancel(target, cancel))
+        }
+    }
+
+    fn controller_with_cancellable() -> (
+        Arc<ZeroClawController>,
+        Arc<CancellableProvider>,
+        std::sync::mpsc::Receiver<()>,
+    ) {
+        let (provider, started) = CancellableProvider::new();
+        let config = ZeroClawConfig {
+            fallback_provider: Some("openrouter".to_string()),
+            fallback_model: Some("fallback-model".to_string()),
+            fallback_api_key: Some("sk-fallback".to_string()),
+            ..ZeroClawConfig::default()
+        };
+        let controller = ZeroClawController::with_provider(config, provider.clone());
+        start_detached(&controller);
+        (controller, provider, started)
+    }
+
+    #[test]
+    fn test_cancel_without_request_is_noop() {
+        let (controller, _stub) = controller_with_stub(Vec::new());
+        controller.cancel().unwrap();
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+        assert!(controller.send_message("Hello".to_string()).success);
+    }
+
+    #[test]
+    fn test_cancel_stops_in_flight_send_message() {
+        let (controller, provider, started) = controller_with_cancellable();
+        let request = std::thread::spawn({
+            let controller = Arc::clone(&controller);
+            move || controller.send_message("Hello".to_string())
+        });
+
+        started.recv_timeout(STREAM_WAIT).unwrap();
+        assert!(matches!(controller.get_status(), AgentStatus::Thinking));
+        controller.cancel().unwrap();
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+
+        let result = request.join().unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some(CANCELLED));
+        assert!(result.message_id.is_some());
+        // The cancelled request is not retried against the fallback.
+        assert_eq!(*provider.calls.lock().unwrap(), vec!["anthropic"]);
+        assert_eq!(controller.get_messages().len(), 1);
+        assert!(controller.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stop_cancels_requests_left_after_grace() {
+        let (provider, started) = CancellableProvider::new();
+        let controller =
+            ZeroClawController::with_provider(ZeroClawConfig::default(), provider.clone());
+        start_detached_with(&controller, |session| {
+            session.config.gateway.shutdown_grace_secs = 0;
+        });
+        let request = std::thread::spawn({
+            let controller = Arc::clone(&controller);
+            move || controller.send_message("Hello".to_string())
+        });
+
+        started.recv_timeout(STREAM_WAIT).unwrap();
+        controller.stop().unwrap();
+        assert!(matches!(controller.get_status(), AgentStatus::Stopped));
+
+        let result = request.join().unwrap();
+        assert_eq!(result.error.as_deref(), Some(CANCELLED));
+        assert!(controller.in_flight.lock().unwrap().is_empty());
+        // No new requests once stopped.
+        assert!(!controller.send_message("Again".to_string()).success);
+    }
+
+    #[test]
+    fn test_status_stays_thinking_until_last_request_finishes() {
+        let (controller, _stub) = controller_with_stub(Vec::new());
+        controller.begin_request("first", "Hello").unwrap();
+        controller.begin_request("second", "Hello").unwrap();
+
+        controller.finish_request("first");
+        assert!(matches!(controller.get_status(), AgentStatus::Thinking));
+        controller.finish_request("second");
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    #[test]
+    fn test_cancel_stops_in_flight_stream() {
+        let (controller, provider, started) = controller_with_cancellable();
+        let (callback, events) = ChannelCallback::channel();
+        Arc::clone(&controller).send_message_streaming("Hello".to_string(), callback);
+
+        started.recv_timeout(STREAM_WAIT).unwrap();
+        controller.cancel().unwrap();
+
+        let (event, _) = events.recv_timeout(STREAM_WAIT).unwrap();
+        assert_eq!(event, StreamEvent::Error(CANCELLED.to_string()));
+        assert_eq!(*provider.calls.lock().unwrap(), vec!["anthropic"]);
+        assert_eq!(controller.get_messages().len(), 1);
+        assert!(matches!(controller.get_status(), AgentStatus::Running));
+    }
+
+    #[test]
+    fn test_final_answer_matches_gateway_sanitizer() {
+        let data_dir = TempDataDir::new();
+        let mut session = GatewaySession::from_config(&data_dir.config());
+        session.load_tools().unwrap();
+
+        let only_call = r#"{"name":"schedule","parameters":{"action":"list"}}"#;
+        assert!(final_answer(only_call, &session).contains("malformed tool-call output"));
+        assert_eq!(
+            final_answer("Assistant: plain answer", &session),
+            "plain answer"
+        );
+    }
+
+    #[test]
+    fn test_discovery_maps_scanned_device_fields() {
+        let devices = devices_from_scan(|| {
+            vec![zeroclaw::DiscoveredDevice {
+                name: "nucleo-f401re".into(),
+                detail: Some("STM32 STLink".into()),
+                device_path: Some("/dev/ttyACM0".into()),
+                transport: zeroclaw::config::HardwareTransport::Serial,
+                vid: Some(0x0483),
+                pid: Some(0x374b),
+                serial_number: Some("066DFF".into()),
+                capabilities_hint: Some("ARM Cortex-M4".into()),
+            }]
+        });
+
+        assert_eq!(
+            devices,
+            vec![DiscoveredDevice {
+                name: "nucleo-f401re".into(),
+                detail: Some("STM32 STLink".into()),
+                device_path: Some("/dev/ttyACM0".into()),
+                transport: "serial".into(),
+                vid: Some(0x0483),
+                pid: Some(0x374b),
+                serial_number: Some("066DFF".into()),
+                capabilities_hint: Some("ARM Cortex-M4".into()),
+            }]
+        );
     }
 }
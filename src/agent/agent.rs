@@ -13,6 +13,7 @@ use crate::runtime;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool, ToolSpec};
 use anyhow::Result;
+use futures_util::StreamExt;
 use std::collections::HashMap;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
@@ -288,7 +289,7 @@ impl Agent {
             None
         };
 
-        let tools = tools::all_tools_with_runtime(
+        let mut tools = tools::all_tools_with_runtime(
             Arc::new(config.clone()),
             &security,
             runtime,
@@ -303,6 +304,7 @@ impl Agent {
             config.api_key.as_deref(),
             config,
         );
+        tools::limit_tools(&mut tools, &config.agent);
 
         let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
 
@@ -437,14 +439,24 @@ impl Agent {
 
         ToolExecutionResult {
             name: call.name.clone(),
-            output: result,
+            output: crate::agent::loop_::cap_tool_result(
+                &call.name,
+                result,
+                self.config.max_tool_result_chars,
+            ),
             success: true,
             tool_call_id: call.tool_call_id.clone(),
         }
     }
 
     async fn execute_tools(&self, calls: &[ParsedToolCall]) -> Vec<ToolExecutionResult> {
-        if !self.config.parallel_tools {
+        let parallel_safe = calls.iter().all(|call| {
+            self.tools
+                .iter()
+                .find(|t| t.name() == call.name)
+                .is_none_or(|tool| tool.parallel_safe())
+        });
+        if !self.config.parallel_tools || !parallel_safe {
             let mut results = Vec::with_capacity(calls.len());
             for call in calls {
                 results.push(self.execute_tool_call(call).await);
@@ -452,11 +464,14 @@ impl Agent {
             return results;
         }
 
-        let futs: Vec<_> = calls
-            .iter()
-            .map(|call| self.execute_tool_call(call))
-            .collect();
-        futures_util::future::join_all(futs).await
+        let max_parallel = match self.config.max_parallel_tools {
+            0 => calls.len().max(1),
+            max => max,
+        };
+        futures_util::stream::iter(calls.iter().map(|call| self.execute_tool_call(call)))
+            .buffered(max_parallel)
+            .collect()
+            .await
     }
 
     fn classify_model(&self, user_message: &str) -> String {
@@ -569,11 +584,7 @@ impl Agent {
             .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
 
         let effective_model = self.classify_model(user_message);
-        let mut loop_detector = LoopDetector::new(LoopDetectionConfig {
-            no_progress_threshold: self.config.loop_detection_no_progress_threshold,
-            ping_pong_cycles: self.config.loop_detection_ping_pong_cycles,
-            failure_streak_threshold: self.config.loop_detection_failure_streak,
-        });
+        let mut loop_detector = LoopDetector::new(LoopDetectionConfig::from_config(&self.config));
 
         for iteration in 0..self.config.max_tool_iterations {
             let messages = self.tool_dispatcher.to_provider_messages(&self.history);
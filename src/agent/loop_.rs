@@ -36,7 +36,9 @@ pub(crate) mod detection;
 mod execution;
 mod history;
 mod parsing;
+mod usage;
 
+pub(crate) use context::recall_context_entries;
 use context::{build_context, build_hardware_context};
 use detection::{DetectionVerdict, LoopDetectionConfig, LoopDetector};
 use execution::{
@@ -53,6 +55,8 @@ use parsing::{
     parse_perl_style_tool_calls, parse_structured_tool_calls, parse_tool_call_value,
     parse_tool_calls, parse_tool_calls_from_json_value, tool_call_signature, ParsedToolCall,
 };
+pub use usage::TurnUsage;
+use usage::TurnUsageObserver;
 
 /// Minimum characters per chunk when relaying LLM text to a streaming draft.
 const STREAM_CHUNK_MIN_CHARS: usize = 80;
@@ -248,6 +252,14 @@ pub(crate) fn scrub_credentials(input: &str) -> String {
 /// used when callers omit the parameter.
 const DEFAULT_MAX_HISTORY_MESSAGES: usize = 50;
 
+/// Default cap, in characters, on a single tool result fed back to the model.
+/// Applies when no `agent.max_tool_result_chars` value is scoped for the loop.
+pub(crate) const DEFAULT_MAX_TOOL_RESULT_CHARS: usize = 50_000;
+
+/// Default bound on tool calls from one model response running at once.
+/// Applies when no `agent.max_parallel_tools` value is scoped for the loop.
+pub(crate) const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+
 /// Minimum interval between progress sends to avoid flooding the draft channel.
 pub(crate) const PROGRESS_MIN_INTERVAL_MS: u64 = 500;
 
@@ -302,6 +314,9 @@ tokio::task_local! {
     static SAFETY_HEARTBEAT_CONFIG: Option<SafetyHeartbeatConfig>;
     static TOOL_LOOP_PROGRESS_MODE: ProgressMode;
     static TOOL_LOOP_COST_ENFORCEMENT_CONTEXT: Option<CostEnforcementContext>;
+    static TOOL_RESULT_MAX_CHARS: usize;
+    static TOOL_LOOP_MAX_PARALLEL_TOOLS: usize;
+    static TOOL_LOOP_SESSION_TOOL_ALLOWLIST: Option<Vec<String>>;
 }
 
 /// Configuration for periodic safety-constraint re-injection (heartbeat).
@@ -363,6 +378,71 @@ where
         .await
 }
 
+pub(crate) async fn scope_tool_result_cap<F>(max_chars: usize, future: F) -> F::Output
+where
+    F: Future,
+{
+    TOOL_RESULT_MAX_CHARS.scope(max_chars, future).await
+}
+
+/// Run `future` with at most `max_parallel` independent tool calls executing
+/// at once (`0` = unbounded).
+pub(crate) async fn scope_max_parallel_tools<F>(max_parallel: usize, future: F) -> F::Output
+where
+    F: Future,
+{
+    TOOL_LOOP_MAX_PARALLEL_TOOLS
+        .scope(max_parallel, future)
+        .await
+}
+
+/// Run `future` with only the tools in `allowlist` advertised and executable
+/// (`None` = no session restriction). Other calls get a "not permitted for
+/// this session" result.
+pub(crate) async fn scope_session_tool_allowlist<F>(
+    allowlist: Option<Vec<String>>,
+    future: F,
+) -> F::Output
+where
+    F: Future,
+{
+    TOOL_LOOP_SESSION_TOOL_ALLOWLIST
+        .scope(allowlist, future)
+        .await
+}
+
+/// The tool allowlist set by [`scope_session_tool_allowlist`] for the
+/// current task, if any.
+fn session_tool_allowlist() -> Option<Vec<String>> {
+    TOOL_LOOP_SESSION_TOOL_ALLOWLIST
+        .try_with(Clone::clone)
+        .ok()
+        .flatten()
+}
+
+/// Truncate a tool result longer than `max_chars` before it goes back to the
+/// model, with a note telling it to ask for less. `0` disables the cap.
+pub(crate) fn cap_tool_result(tool_name: &str, output: String, max_chars: usize) -> String {
+    if max_chars == 0 {
+        return output;
+    }
+    let total_chars = output.chars().count();
+    if total_chars <= max_chars {
+        return output;
+    }
+    tracing::debug!(
+        tool = tool_name,
+        total_chars,
+        max_chars,
+        "Truncating oversized tool result"
+    );
+    format!(
+        "{}\n\n[Tool result truncated: {total_chars} characters exceeded the {max_chars}-character limit. \
+         To see more, call `{tool_name}` again with a narrower request (a smaller range, an offset, or a more specific query).]",
+        truncate_with_ellipsis(&output, max_chars)
+    )
+}
+
 fn should_inject_safety_heartbeat(counter: usize, interval: usize) -> bool {
     interval > 0 && counter > 0 && counter % interval == 0
 }
@@ -803,6 +883,72 @@ pub(crate) fn is_tool_loop_cancelled(err: &anyhow::Error) -> bool {
     err.chain().any(|source| source.is::<ToolLoopCancelled>())
 }
 
+/// A whole turn (every provider and tool call in it) ran past its
+/// wall-clock budget and was stopped.
+#[derive(Debug)]
+pub(crate) struct TurnBudgetExceeded {
+    pub budget: Duration,
+}
+
+impl std::fmt::Display for TurnBudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Agent turn exceeded its {}s time budget and was stopped",
+            self.budget.as_secs_f64()
+        )
+    }
+}
+
+impl std::error::Error for TurnBudgetExceeded {}
+
+/// Run a tool loop under an overall wall-clock budget.
+///
+/// `run` receives the cancellation token to hand to the loop; it fires once
+/// `budget` elapses or `cancel` fires, which stops the in-flight provider or
+/// tool call. `None` or a zero budget runs without a limit. Only the budget
+/// timer turns the cancellation into [`TurnBudgetExceeded`].
+pub(crate) async fn run_with_turn_budget<F, Fut>(
+    budget: Option<Duration>,
+    cancel: Option<CancellationToken>,
+    run: F,
+) -> Result<String>
+where
+    F: FnOnce(Option<CancellationToken>) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let Some(budget) = budget.filter(|budget| !budget.is_zero()) else {
+        return run(cancel).await;
+    };
+
+    let token = cancel
+        .as_ref()
+        .map_or_else(CancellationToken::new, CancellationToken::child_token);
+    let timer = {
+        let token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(budget).await;
+            token.cancel();
+        })
+    };
+    let result = run(Some(token.clone())).await;
+    timer.abort();
+
+    let cancelled_by_caller = cancel.is_some_and(|cancel| cancel.is_cancelled());
+    match result {
+        Err(err)
+            if token.is_cancelled() && !cancelled_by_caller && is_tool_loop_cancelled(&err) =>
+        {
+            tracing::warn!(
+                budget_secs = budget.as_secs_f64(),
+                "agent turn budget exceeded"
+            );
+            Err(TurnBudgetExceeded { budget }.into())
+        }
+        other => other,
+    }
+}
+
 pub(crate) fn is_tool_iteration_limit_error(err: &anyhow::Error) -> bool {
     err.chain().any(|source| {
         source
@@ -834,6 +980,8 @@ pub(crate) async fn agent_turn(
     silent: bool,
     multimodal_config: &crate::config::MultimodalConfig,
     max_tool_iterations: usize,
+    cancellation_token: Option<CancellationToken>,
+    on_delta: Option<tokio::sync::mpsc::Sender<String>>,
 ) -> Result<String> {
     run_tool_call_loop(
         provider,
@@ -848,8 +996,8 @@ pub(crate) async fn agent_turn(
         "channel",
         multimodal_config,
         max_tool_iterations,
-        None,
-        None,
+        cancellation_token,
+        on_delta,
         None,
         &[],
     )
@@ -1021,9 +1169,16 @@ pub async fn run_tool_call_loop(
         max_tool_iterations
     };
 
+    let session_allowlist = session_tool_allowlist();
+    let permitted_for_session = |name: &str| {
+        session_allowlist
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|tool| tool == name))
+    };
     let tool_specs: Vec<crate::tools::ToolSpec> = tools_registry
         .iter()
         .filter(|tool| !excluded_tools.iter().any(|ex| ex == tool.name()))
+        .filter(|tool| permitted_for_session(tool.name()))
         .map(|tool| tool.spec())
         .collect();
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
@@ -1036,6 +1191,12 @@ pub async fn run_tool_call_loop(
         .unwrap_or_default();
     let mut loop_detector = LoopDetector::new(ld_config);
     let mut loop_detection_prompt: Option<String> = None;
+    let max_tool_result_chars = TOOL_RESULT_MAX_CHARS
+        .try_with(|max| *max)
+        .unwrap_or(DEFAULT_MAX_TOOL_RESULT_CHARS);
+    let max_parallel_tools = TOOL_LOOP_MAX_PARALLEL_TOOLS
+        .try_with(|max| *max)
+        .unwrap_or(DEFAULT_MAX_PARALLEL_TOOLS);
     let heartbeat_config = SAFETY_HEARTBEAT_CONFIG
         .try_with(Clone::clone)
         .ok()
@@ -1088,7 +1249,10 @@ pub async fn run_tool_call_loop(
 
         let prepared_messages =
             multimodal::prepare_messages_for_provider(history, multimodal_config).await?;
-        let mut request_messages = prepared_messages.messages.clone();
+        let mut request_messages = crate::providers::traits::place_system_prompt(
+            &prepared_messages.messages,
+            provider.system_prompt_placement(),
+        );
         if let Some(prompt) = missing_tool_call_retry_prompt.take() {
             request_messages.push(ChatMessage::user(prompt));
         }
@@ -1331,6 +1495,20 @@ pub async fn run_tool_call_loop(
             chat_future.await
         };
 
+        crate::observability::provider_debug::record_exchange(
+            &crate::observability::provider_debug::ProviderExchange {
+                turn_id: &turn_id,
+                provider: provider_name,
+                model: active_model.as_str(),
+                temperature,
+                iteration: iteration + 1,
+                messages: &request_messages,
+                tools: request_tools,
+                response: chat_result.as_ref(),
+            },
+        )
+        .await;
+
         let (
             response_text,
             parsed_text,
@@ -1588,13 +1766,15 @@ pub async fn run_tool_call_loop(
         // Execute tool calls and build results. `individual_results` tracks per-call output so
         // native-mode history can emit one role=tool message per tool call with the correct ID.
         //
-        // When multiple tool calls are present and interactive CLI approval is not needed, run
-        // tool executions concurrently for lower wall-clock latency.
+        // When multiple tool calls are present, interactive CLI approval is not needed, and every
+        // called tool is parallel-safe, run tool executions concurrently (at most
+        // `max_parallel_tools` at once) for lower wall-clock latency.
         let mut tool_results = String::new();
         let mut individual_results: Vec<(Option<String>, String)> = Vec::new();
         let mut ordered_results: Vec<Option<(String, Option<String>, ToolExecutionOutcome)>> =
             (0..tool_calls.len()).map(|_| None).collect();
-        let allow_parallel_execution = should_execute_tools_in_parallel(&tool_calls, approval);
+        let allow_parallel_execution =
+            should_execute_tools_in_parallel(&tool_calls, tools_registry, approval);
         let mut executable_indices: Vec<usize> = Vec::new();
         let mut executable_calls: Vec<ParsedToolCall> = Vec::new();
         let mut progress_indices: Vec<Option<usize>> = Vec::new();
@@ -1681,6 +1861,36 @@ pub async fn run_tool_call_loop(
                 continue;
             }
 
+            if !permitted_for_session(&tool_name) {
+                let blocked = format!("Tool '{tool_name}' is not permitted for this session.");
+                runtime_trace::record_event(
+                    "tool_call_result",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(active_model.as_str()),
+                    Some(&turn_id),
+                    Some(false),
+                    Some(&blocked),
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "tool": tool_name.clone(),
+                        "arguments": scrub_credentials(&tool_args.to_string()),
+                        "blocked_by_session_policy": true,
+                    }),
+                );
+                ordered_results[idx] = Some((
+                    tool_name.clone(),
+                    call.tool_call_id.clone(),
+                    ToolExecutionOutcome {
+                        output: blocked.clone(),
+                        success: false,
+                        error_reason: Some(blocked),
+                        duration: Duration::ZERO,
+                    },
+                ));
+                continue;
+            }
+
             // ── Approval hook ────────────────────────────────
             if let Some(mgr) = approval {
                 let non_cli_session_granted =
@@ -1854,6 +2064,7 @@ pub async fn run_tool_call_loop(
                 tools_registry,
                 observer,
                 cancellation_token.as_ref(),
+                max_parallel_tools,
             )
             .await?
         } else {
@@ -1919,6 +2130,14 @@ pub async fn run_tool_call_loop(
                     .await;
             }
 
+            // Tools apply their own output limits first; this bounds what any
+            // single result can add to the next model call.
+            outcome.output = cap_tool_result(
+                &call.name,
+                std::mem::take(&mut outcome.output),
+                max_tool_result_chars,
+            );
+
             if let Some(idx) = progress_idx {
                 let secs = outcome.duration.as_secs();
                 progress_tracker.complete(*idx, outcome.success, secs);
@@ -2159,6 +2378,47 @@ pub(crate) fn build_shell_policy_instructions(autonomy: &crate::config::Autonomy
 // interactive REPL mode. The interactive loop manages history compaction
 // and hard trimming to keep the context window bounded.
 
+/// Build the provider for `agent.helper_provider`, when one is configured.
+///
+/// The main `api_key` is only reused when the helper provider is the main
+/// provider; any other helper resolves its own credential.
+fn create_helper_provider(
+    config: &Config,
+    main_provider_name: &str,
+    options: &providers::ProviderRuntimeOptions,
+) -> Result<Option<Box<dyn Provider>>> {
+    let Some(name) = config
+        .agent
+        .helper_provider
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    else {
+        return Ok(None);
+    };
+    let api_key = if name == main_provider_name {
+        config.api_key.as_deref()
+    } else {
+        None
+    };
+    providers::create_provider_with_options(name, api_key, options).map(Some)
+}
+
+/// Provider and model for internal helper calls (compaction summaries,
+/// pre-compaction fact flush). Each falls back to the main chat one.
+fn helper_llm<'a>(
+    main_provider: &'a dyn Provider,
+    main_model: &'a str,
+    helper_provider: Option<&'a dyn Provider>,
+    helper_model: Option<&'a str>,
+) -> (&'a dyn Provider, &'a str) {
+    let model = helper_model
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .unwrap_or(main_model);
+    (helper_provider.unwrap_or(main_provider), model)
+}
+
 #[allow(clippy::too_many_lines)]
 /// Run the agent loop with the given configuration.
 ///
@@ -2241,6 +2501,9 @@ pub async fn run(
         tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
         tools_registry.extend(peripheral_tools);
     }
+    let hardware_tools = crate::hardware::shared_hardware_tools(&config, &security).await;
+    crate::hardware::merge_hardware_tools(&mut tools_registry, &hardware_tools);
+    tools::limit_tools(&mut tools_registry, &config.agent);
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
@@ -2266,6 +2529,7 @@ pub async fn run(
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        system_prompt_placement: config.provider.system_prompt_placement,
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -2277,6 +2541,8 @@ pub async fn run(
         &model_name,
         &provider_runtime_options,
     )?;
+    let helper_provider =
+        create_helper_provider(&config, provider_name, &provider_runtime_options)?;
 
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
@@ -2501,11 +2767,7 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let ld_cfg = LoopDetectionConfig {
-            no_progress_threshold: config.agent.loop_detection_no_progress_threshold,
-            ping_pong_cycles: config.agent.loop_detection_ping_pong_cycles,
-            failure_streak_threshold: config.agent.loop_detection_failure_streak,
-        };
+        let ld_cfg = LoopDetectionConfig::from_config(&config.agent);
         let hb_cfg = if config.agent.safety_heartbeat_interval > 0 {
             Some(SafetyHeartbeatConfig {
                 body: security.summary_for_heartbeat(),
@@ -2518,25 +2780,31 @@ pub async fn run(
             cost_enforcement_context.clone(),
             SAFETY_HEARTBEAT_CONFIG.scope(
                 hb_cfg,
-                LOOP_DETECTION_CONFIG.scope(
-                    ld_cfg,
-                    run_tool_call_loop(
-                        provider.as_ref(),
-                        &mut history,
-                        &tools_registry,
-                        observer.as_ref(),
-                        provider_name,
-                        &model_name,
-                        temperature,
-                        false,
-                        approval_manager.as_ref(),
-                        channel_name,
-                        &config.multimodal,
-                        config.agent.max_tool_iterations,
-                        None,
-                        None,
-                        effective_hooks,
-                        &[],
+                scope_max_parallel_tools(
+                    config.agent.max_parallel_tools,
+                    scope_tool_result_cap(
+                        config.agent.max_tool_result_chars,
+                        LOOP_DETECTION_CONFIG.scope(
+                            ld_cfg,
+                            run_tool_call_loop(
+                                provider.as_ref(),
+                                &mut history,
+                                &tools_registry,
+                                observer.as_ref(),
+                                provider_name,
+                                &model_name,
+                                temperature,
+                                false,
+                                approval_manager.as_ref(),
+                                channel_name,
+                                &config.multimodal,
+                                config.agent.max_tool_iterations,
+                                None,
+                                None,
+                                effective_hooks,
+                                &[],
+                            ),
+                        ),
                     ),
                 ),
             ),
@@ -2686,11 +2954,7 @@ pub async fn run(
                 history.push(ChatMessage::user(reminder));
             }
 
-            let ld_cfg = LoopDetectionConfig {
-                no_progress_threshold: config.agent.loop_detection_no_progress_threshold,
-                ping_pong_cycles: config.agent.loop_detection_ping_pong_cycles,
-                failure_streak_threshold: config.agent.loop_detection_failure_streak,
-            };
+            let ld_cfg = LoopDetectionConfig::from_config(&config.agent);
             let hb_cfg = if config.agent.safety_heartbeat_interval > 0 {
                 Some(SafetyHeartbeatConfig {
                     body: security.summary_for_heartbeat(),
@@ -2703,25 +2967,31 @@ pub async fn run(
                 cost_enforcement_context.clone(),
                 SAFETY_HEARTBEAT_CONFIG.scope(
                     hb_cfg,
-                    LOOP_DETECTION_CONFIG.scope(
-                        ld_cfg,
-                        run_tool_call_loop(
-                            provider.as_ref(),
-                            &mut history,
-                            &tools_registry,
-                            observer.as_ref(),
-                            provider_name,
-                            &model_name,
-                            temperature,
-                            false,
-                            approval_manager.as_ref(),
-                            channel_name,
-                            &config.multimodal,
-                            config.agent.max_tool_iterations,
-                            None,
-                            None,
-                            effective_hooks,
-                            &[],
+                    scope_max_parallel_tools(
+                        config.agent.max_parallel_tools,
+                        scope_tool_result_cap(
+                            config.agent.max_tool_result_chars,
+                            LOOP_DETECTION_CONFIG.scope(
+                                ld_cfg,
+                                run_tool_call_loop(
+                                    provider.as_ref(),
+                                    &mut history,
+                                    &tools_registry,
+                                    observer.as_ref(),
+                                    provider_name,
+                                    &model_name,
+                                    temperature,
+                                    false,
+                                    approval_manager.as_ref(),
+                                    channel_name,
+                                    &config.multimodal,
+                                    config.agent.max_tool_iterations,
+                                    None,
+                                    None,
+                                    effective_hooks,
+                                    &[],
+                                ),
+                            ),
                         ),
                     ),
                 ),
@@ -2776,10 +3046,16 @@ pub async fn run(
             observer.record_event(&ObserverEvent::TurnComplete);
 
             // Auto-compaction before hard trimming to preserve long-context signal.
-            if let Ok(compacted) = auto_compact_history(
-                &mut history,
+            let (helper, helper_model) = helper_llm(
                 provider.as_ref(),
                 &model_name,
+                helper_provider.as_deref(),
+                config.agent.helper_model.as_deref(),
+            );
+            if let Ok(compacted) = auto_compact_history(
+                &mut history,
+                helper,
+                helper_model,
                 config.agent.max_history_messages,
                 effective_hooks,
                 Some(mem.as_ref()),
@@ -2819,13 +3095,181 @@ pub async fn process_message_with_session(
     message: &str,
     session_id: Option<&str>,
 ) -> Result<String> {
+    Box::pin(process_message_with_system_prompt(
+        config, message, session_id, None,
+    ))
+    .await
+}
+
+/// Like [`process_message_with_session`], but `system_prompt` (when set)
+/// replaces the workspace-derived base prompt. Tool instructions and the shell
+/// policy are still appended so the agent keeps its guardrails.
+pub async fn process_message_with_system_prompt(
+    config: Config,
+    message: &str,
+    session_id: Option<&str>,
+    system_prompt: Option<&str>,
+) -> Result<String> {
+    Box::pin(process_message_with_turn_budget(
+        config,
+        message,
+        session_id,
+        system_prompt,
+        None,
+        None,
+    ))
+    .await
+    .map(|(reply, _)| reply)
+}
+
+/// Like [`process_message_with_system_prompt`], but stops the whole turn
+/// with [`TurnBudgetExceeded`] once `max_turn_duration` has elapsed. When
+/// `cancel` fires, the turn stops at the next provider or tool boundary
+/// with a "tool loop cancelled" error.
+///
+/// Returns the reply together with the turn's token usage and cost.
+pub async fn process_message_with_turn_budget(
+    config: Config,
+    message: &str,
+    session_id: Option<&str>,
+    system_prompt: Option<&str>,
+    max_turn_duration: Option<Duration>,
+    cancel: Option<CancellationToken>,
+) -> Result<(String, TurnUsage)> {
+    Box::pin(process_message_with_deltas(
+        config,
+        message,
+        session_id,
+        system_prompt,
+        max_turn_duration,
+        cancel,
+        None,
+    ))
+    .await
+}
+
+/// Like [`process_message_with_turn_budget`], but sends the final answer to
+/// `on_token` in chunks as it is relayed. Progress updates are not forwarded.
+/// Returns the full answer and its usage once the turn completes.
+pub async fn process_message_streaming(
+    config: Config,
+    message: &str,
+    session_id: Option<&str>,
+    system_prompt: Option<&str>,
+    max_turn_duration: Option<Duration>,
+    cancel: Option<CancellationToken>,
+    on_token: tokio::sync::mpsc::Sender<String>,
+) -> Result<(String, TurnUsage)> {
+    let (delta_tx, delta_rx) = tokio::sync::mpsc::channel(64);
+    let (result, ()) = tokio::join!(
+        Box::pin(process_message_with_deltas(
+            config,
+            message,
+            session_id,
+            system_prompt,
+            max_turn_duration,
+            cancel,
+            Some(delta_tx),
+        )),
+        forward_answer_deltas(delta_rx, on_token),
+    );
+    result
+}
+
+/// Relay answer text from the tool loop's draft deltas, dropping the clear
+/// marker and progress lines that only draft-editing channels understand.
+async fn forward_answer_deltas(
+    mut deltas: tokio::sync::mpsc::Receiver<String>,
+    on_token: tokio::sync::mpsc::Sender<String>,
+) {
+    while let Some(delta) = deltas.recv().await {
+        if delta == DRAFT_CLEAR_SENTINEL
+            || delta.starts_with(DRAFT_PROGRESS_SENTINEL)
+            || delta.starts_with(DRAFT_PROGRESS_BLOCK_SENTINEL)
+        {
+            continue;
+        }
+        // Keep draining after the receiver is gone so the loop never blocks.
+        let _ = on_token.send(delta).await;
+    }
+}
+
+async fn process_message_with_deltas(
+    config: Config,
+    message: &str,
+    session_id: Option<&str>,
+    system_prompt: Option<&str>,
+    max_turn_duration: Option<Duration>,
+    cancel: Option<CancellationToken>,
+    on_delta: Option<tokio::sync::mpsc::Sender<String>>,
+) -> Result<(String, TurnUsage)> {
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let model_name = crate::config::resolve_default_model_id(
+        config.default_model.as_deref(),
+        Some(provider_name),
+    );
+    let provider_runtime_options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        provider_transport: config.effective_provider_transport(),
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning_level: config.effective_provider_reasoning_level(),
+        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+        max_tokens_override: None,
+        model_support_vision: config.model_support_vision,
+        system_prompt_placement: config.provider.system_prompt_placement,
+    };
+    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        &model_name,
+        &provider_runtime_options,
+    )?;
+
+    Box::pin(process_message_with_provider(
+        &config,
+        provider.as_ref(),
+        provider_name,
+        &model_name,
+        message,
+        session_id,
+        system_prompt,
+        max_turn_duration,
+        cancel,
+        on_delta,
+    ))
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_message_with_provider(
+    config: &Config,
+    provider: &dyn Provider,
+    provider_name: &str,
+    model_name: &str,
+    message: &str,
+    session_id: Option<&str>,
+    system_prompt_override: Option<&str>,
+    max_turn_duration: Option<Duration>,
+    cancel: Option<CancellationToken>,
+    on_delta: Option<tokio::sync::mpsc::Sender<String>>,
+) -> Result<(String, TurnUsage)> {
     if let Err(error) = crate::plugins::runtime::initialize_from_config(&config.plugins) {
         tracing::warn!("plugin registry initialization skipped: {error}");
     }
     let base_observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
-    let observer: Arc<dyn Observer> = Arc::new(
-        crate::plugins::bridge::observer::ObserverBridge::new(base_observer),
+    let observer = TurnUsageObserver::new(
+        Arc::new(crate::plugins::bridge::observer::ObserverBridge::new(
+            base_observer,
+        )),
+        config.cost.prices.clone(),
+        provider_name,
     );
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
@@ -2861,38 +3305,14 @@ pub async fn process_message_with_session(
         &config.workspace_dir,
         &config.agents,
         config.api_key.as_deref(),
-        &config,
+        config,
     );
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
-
-    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
-    let model_name = crate::config::resolve_default_model_id(
-        config.default_model.as_deref(),
-        Some(provider_name),
-    );
-    let provider_runtime_options = providers::ProviderRuntimeOptions {
-        auth_profile_override: None,
-        provider_api_url: config.api_url.clone(),
-        provider_transport: config.effective_provider_transport(),
-        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
-        secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
-        reasoning_level: config.effective_provider_reasoning_level(),
-        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
-        max_tokens_override: None,
-        model_support_vision: config.model_support_vision,
-    };
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        &model_name,
-        &provider_runtime_options,
-    )?;
+    let hardware_tools = crate::hardware::shared_hardware_tools(config, &security).await;
+    crate::hardware::merge_hardware_tools(&mut tools_registry, &hardware_tools);
+    tools::limit_tools(&mut tools_registry, &config.agent);
 
     let hardware_rag: Option<crate::rag::HardwareRag> = config
         .peripherals
@@ -2909,7 +3329,7 @@ pub async fn process_message_with_session(
         .map(|b| b.board.clone())
         .collect();
 
-    let skills = crate::skills::load_skills_with_config(&config.workspace_dir, &config);
+    let skills = crate::skills::load_skills_with_config(&config.workspace_dir, config);
     let mut tool_descs: Vec<(&str, &str)> = vec![
         ("shell", "Execute terminal commands."),
         ("file_read", "Read file contents."),
@@ -2967,22 +3387,30 @@ pub async fn process_message_with_session(
             "Query connected hardware for reported GPIO pins and LED pin. Use when user asks what pins are available.",
         ));
     }
+    if let Some(allowed) = session_tool_allowlist() {
+        let permitted = |name: &str| allowed.iter().any(|tool| tool == name);
+        tools_registry.retain(|tool| permitted(tool.name()));
+        tool_descs.retain(|(name, _)| permitted(name));
+    }
     let bootstrap_max_chars = if config.agent.compact_context {
         Some(6000)
     } else {
         None
     };
     let native_tools = provider.supports_native_tools();
-    let mut system_prompt = crate::channels::build_system_prompt_with_mode(
-        &config.workspace_dir,
-        &model_name,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
-        bootstrap_max_chars,
-        native_tools,
-        config.skills.prompt_injection_mode,
-    );
+    let mut system_prompt = match system_prompt_override {
+        Some(custom) => custom.to_string(),
+        None => crate::channels::build_system_prompt_with_mode(
+            &config.workspace_dir,
+            model_name,
+            &tool_descs,
+            &skills,
+            Some(&config.identity),
+            bootstrap_max_chars,
+            native_tools,
+            config.skills.prompt_injection_mode,
+        ),
+    };
     if !native_tools {
         system_prompt.push_str(&build_tool_instructions(&tools_registry));
     }
@@ -3023,25 +3451,46 @@ pub async fn process_message_with_session(
     } else {
         None
     };
-    scope_cost_enforcement_context(
+    let started = Instant::now();
+    let result = Box::pin(scope_cost_enforcement_context(
         cost_enforcement_context,
         SAFETY_HEARTBEAT_CONFIG.scope(
             hb_cfg,
-            agent_turn(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                provider_name,
-                &model_name,
-                config.default_temperature,
-                true,
-                &config.multimodal,
-                config.agent.max_tool_iterations,
+            scope_max_parallel_tools(
+                config.agent.max_parallel_tools,
+                scope_tool_result_cap(
+                    config.agent.max_tool_result_chars,
+                    run_with_turn_budget(max_turn_duration, cancel, |cancellation_token| {
+                        agent_turn(
+                            provider,
+                            &mut history,
+                            &tools_registry,
+                            &observer,
+                            provider_name,
+                            model_name,
+                            config.default_temperature,
+                            true,
+                            &config.multimodal,
+                            config.agent.max_tool_iterations,
+                            cancellation_token,
+                            on_delta,
+                        )
+                    }),
+                ),
             ),
         ),
-    )
-    .await
+    ))
+    .await;
+
+    let usage = observer.usage();
+    observer.record_event(&ObserverEvent::AgentEnd {
+        provider: provider_name.to_string(),
+        model: model_name.to_string(),
+        duration: started.elapsed(),
+        tokens_used: usage.total_tokens(),
+        cost_usd: usage.cost_usd,
+    });
+    result.map(|reply| (reply, usage))
 }
 
 #[cfg(test)]
@@ -3243,6 +3692,7 @@ mod tests {
     struct ScriptedProvider {
         responses: Arc<Mutex<VecDeque<ChatResponse>>>,
         capabilities: ProviderCapabilities,
+        seen_requests: Arc<Mutex<Vec<Vec<ChatMessage>>>>,
     }
 
     impl ScriptedProvider {
@@ -3260,6 +3710,7 @@ mod tests {
             Self {
                 responses: Arc::new(Mutex::new(scripted)),
                 capabilities: ProviderCapabilities::default(),
+                seen_requests: Arc::new(Mutex::new(Vec::new())),
             }
         }
 
@@ -3287,10 +3738,14 @@ mod tests {
 
         async fn chat(
             &self,
-            _request: ChatRequest<'_>,
+            request: ChatRequest<'_>,
             _model: &str,
             _temperature: f64,
         ) -> anyhow::Result<ChatResponse> {
+            self.seen_requests
+                .lock()
+                .expect("requests lock should be valid")
+                .push(request.messages.to_vec());
             let mut responses = self
                 .responses
                 .lock()
@@ -3356,6 +3811,7 @@ mod tests {
         delay_ms: u64,
         active: Arc<AtomicUsize>,
         max_active: Arc<AtomicUsize>,
+        parallel_safe: bool,
     }
 
     impl DelayTool {
@@ -3370,8 +3826,48 @@ mod tests {
                 delay_ms,
                 active,
                 max_active,
+                parallel_safe: true,
             }
         }
+
+        /// Mark the tool as driving shared state, like hardware actuation.
+        fn actuating(mut self) -> Self {
+            self.parallel_safe = false;
+            self
+        }
+    }
+
+    struct BulkyTool {
+        output_chars: usize,
+    }
+
+    #[async_trait]
+    impl Tool for BulkyTool {
+        fn name(&self) -> &str {
+            "bulky_tool"
+        }
+
+        fn description(&self) -> &str {
+            "Returns a large output for result-cap tests"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({
+                "type": "object",
+                "properties": {}
+            })
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "x".repeat(self.output_chars),
+                error: None,
+            })
+        }
     }
 
     struct FailingTool;
@@ -3471,6 +3967,10 @@ mod tests {
                 error: None,
             })
         }
+
+        fn parallel_safe(&self) -> bool {
+            self.parallel_safe
+        }
     }
 
     #[tokio::test]
@@ -3638,7 +4138,7 @@ mod tests {
             tool_call_id: None,
         }];
 
-        assert!(!should_execute_tools_in_parallel(&calls, None));
+        assert!(!should_execute_tools_in_parallel(&calls, &[], None));
     }
 
     #[test]
@@ -3660,6 +4160,7 @@ mod tests {
 
         assert!(!should_execute_tools_in_parallel(
             &calls,
+            &[],
             Some(&approval_mgr)
         ));
     }
@@ -3686,10 +4187,255 @@ mod tests {
 
         assert!(should_execute_tools_in_parallel(
             &calls,
+            &[],
             Some(&approval_mgr)
         ));
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_writes_provider_debug_log_when_enabled() {
+        use crate::observability::provider_debug;
+
+        let tmp = TempDir::new().unwrap();
+        let debug_config = crate::config::ObservabilityConfig {
+            provider_debug_log: true,
+            provider_debug_log_path: "provider-debug.jsonl".into(),
+            ..crate::config::ObservabilityConfig::default()
+        };
+        provider_debug::init_from_config(&debug_config, tmp.path());
+
+        let provider = ScriptedProvider::from_text_responses(vec!["debug-capture-reply"]);
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("capture this exchange"),
+        ];
+        let observer = NoopObserver;
+        let multimodal = crate::config::MultimodalConfig::default();
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "gateway",
+            &multimodal,
+            4,
+            None,
+            None,
+            None,
+            &[],
+        )
+        .await;
+        provider_debug::init_from_config(
+            &crate::config::ObservabilityConfig::default(),
+            tmp.path(),
+        );
+        assert_eq!(result.unwrap(), "debug-capture-reply");
+
+        // Other tests may run the loop while capture is on; find ours.
+        let raw = std::fs::read_to_string(tmp.path().join("provider-debug.jsonl")).unwrap();
+        let record = raw
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .find(|record| record["response"]["text"] == "debug-capture-reply")
+            .expect("exchange should be captured");
+        assert_eq!(record["provider"], "mock-provider");
+        assert_eq!(record["model"], "mock-model");
+        assert_eq!(record["iteration"], 1);
+        assert_eq!(
+            record["request"]["messages"][1]["content"],
+            "capture this exchange"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_with_turn_budget_stops_slow_multi_tool_turn() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"slow_a","arguments":{"value":"A"}}
+</tool_call>"#,
+            r#"<tool_call>
+{"name":"slow_b","arguments":{"value":"B"}}
+</tool_call>"#,
+            "done",
+        ]);
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![
+            Box::new(DelayTool::new(
+                "slow_a",
+                150,
+                Arc::clone(&active),
+                Arc::clone(&max_active),
+            )),
+            Box::new(DelayTool::new(
+                "slow_b",
+                10_000,
+                Arc::clone(&active),
+                Arc::clone(&max_active),
+            )),
+        ];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run slow tools"),
+        ];
+        let observer = NoopObserver;
+        let multimodal = crate::config::MultimodalConfig::default();
+
+        let started = std::time::Instant::now();
+        let err = run_with_turn_budget(Some(Duration::from_millis(300)), None, |token| {
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &observer,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "gateway",
+                &multimodal,
+                4,
+                token,
+                None,
+                None,
+                &[],
+            )
+        })
+        .await
+        .expect_err("turn should be cut off by the budget");
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(err.is::<TurnBudgetExceeded>(), "{err:#}");
+        assert!(err.to_string().contains("time budget"));
+        // The first tool finished within budget; the second was interrupted.
+        assert!(history
+            .iter()
+            .any(|msg| msg.content.contains("name=\"slow_a\"")));
+        assert!(!history
+            .iter()
+            .any(|msg| msg.content.contains("name=\"slow_b\"")));
+    }
+
+    #[tokio::test]
+    async fn run_with_turn_budget_passes_through_fast_turns() {
+        let reply = run_with_turn_budget(Some(Duration::from_secs(5)), None, |token| async move {
+            assert!(token.is_some());
+            Ok("done".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(reply, "done");
+
+        let reply = run_with_turn_budget(None, None, |token| async move {
+            assert!(token.is_none());
+            Ok("unbounded".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(reply, "unbounded");
+    }
+
+    #[tokio::test]
+    async fn run_with_turn_budget_reports_caller_cancel_as_cancelled() {
+        let cancel = CancellationToken::new();
+        let err = run_with_turn_budget(Some(Duration::from_secs(30)), Some(cancel.clone()), {
+            let cancel = cancel.clone();
+            |token| async move {
+                let token = token.expect("budgeted turn gets a token");
+                cancel.cancel();
+                token.cancelled().await;
+                Err(ToolLoopCancelled.into())
+            }
+        })
+        .await
+        .expect_err("caller cancel should stop the turn");
+
+        assert!(is_tool_loop_cancelled(&err), "{err:#}");
+        assert!(!err.is::<TurnBudgetExceeded>());
+
+        let reply =
+            run_with_turn_budget(None, Some(CancellationToken::new()), |token| async move {
+                assert!(
+                    token.is_some(),
+                    "unbudgeted turn still gets the caller token"
+                );
+                Ok("done".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(reply, "done");
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_truncates_oversized_tool_result_before_next_turn() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"bulky_tool","arguments":{}}
+</tool_call>"#,
+            "done",
+        ]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(BulkyTool { output_chars: 500 })];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("fetch something big"),
+        ];
+        let observer = NoopObserver;
+
+        let result = scope_tool_result_cap(
+            100,
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &observer,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "cli",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("loop should complete");
+        assert_eq!(result, "done");
+
+        let requests = provider.seen_requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let tool_results = requests[1]
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]"))
+            .expect("second turn should carry the tool result");
+        assert!(tool_results.content.contains(&"x".repeat(100)));
+        assert!(!tool_results.content.contains(&"x".repeat(101)));
+        assert!(tool_results
+            .content
+            .contains("[Tool result truncated: 500 characters exceeded the 100-character limit."));
+        assert!(tool_results.content.contains("call `bulky_tool` again"));
+    }
+
+    #[test]
+    fn cap_tool_result_keeps_short_output_and_honours_zero() {
+        assert_eq!(cap_tool_result("t", "short".to_string(), 10), "short");
+        let long = "y".repeat(50);
+        assert_eq!(cap_tool_result("t", long.clone(), 0), long);
+        assert!(
+            cap_tool_result("t", long, 10).starts_with("yyyyyyyyyy...\n\n[Tool result truncated")
+        );
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_executes_multiple_tools_with_ordered_results() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -3776,6 +4522,115 @@ mod tests {
         );
     }
 
+    fn three_delay_calls_provider() -> ScriptedProvider {
+        ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"delay_a","arguments":{"value":"A"}}
+</tool_call>
+<tool_call>
+{"name":"delay_b","arguments":{"value":"B"}}
+</tool_call>
+<tool_call>
+{"name":"delay_c","arguments":{"value":"C"}}
+</tool_call>"#,
+            "done",
+        ])
+    }
+
+    async fn run_three_delay_calls(
+        tools_registry: &[Box<dyn Tool>],
+        max_parallel: usize,
+    ) -> Vec<ChatMessage> {
+        let provider = three_delay_calls_provider();
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let result = scope_max_parallel_tools(
+            max_parallel,
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                tools_registry,
+                &NoopObserver,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "gateway",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("tool calls should complete");
+        assert_eq!(result, "done");
+        history
+    }
+
+    #[tokio::test]
+    async fn independent_read_tools_run_concurrently_up_to_max_parallel_tools() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = ["delay_a", "delay_b", "delay_c"]
+            .into_iter()
+            .map(|name| {
+                Box::new(DelayTool::new(
+                    name,
+                    100,
+                    Arc::clone(&active),
+                    Arc::clone(&max_active),
+                )) as Box<dyn Tool>
+            })
+            .collect();
+
+        let history = run_three_delay_calls(&tools_registry, 2).await;
+
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+        let results = history
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]"))
+            .expect("tool results message should be present");
+        let positions: Vec<usize> = ["delay_a", "delay_b", "delay_c"]
+            .iter()
+            .map(|name| results.content.find(&format!("name=\"{name}\"")).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn actuating_tool_in_batch_forces_sequential_execution() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![
+            Box::new(DelayTool::new(
+                "delay_a",
+                50,
+                Arc::clone(&active),
+                Arc::clone(&max_active),
+            )),
+            Box::new(
+                DelayTool::new("delay_b", 50, Arc::clone(&active), Arc::clone(&max_active))
+                    .actuating(),
+            ),
+            Box::new(DelayTool::new(
+                "delay_c",
+                50,
+                Arc::clone(&active),
+                Arc::clone(&max_active),
+            )),
+        ];
+
+        run_three_delay_calls(&tools_registry, 0).await;
+
+        assert_eq!(max_active.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_denies_supervised_tools_on_non_cli_channels() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -4094,6 +4949,71 @@ mod tests {
         );
     }
 
+    async fn run_gpio_write_call(allowlist: Option<Vec<String>>) -> (usize, String) {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"gpio_write","arguments":{"value":"on"}}
+</tool_call>"#,
+            "done",
+        ]);
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "gpio_write",
+            10,
+            Arc::clone(&active),
+            Arc::clone(&max_active),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("turn the light on"),
+        ];
+
+        scope_session_tool_allowlist(
+            allowlist,
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &NoopObserver,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "gateway",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("tool loop should complete");
+
+        let tool_results = history
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]"))
+            .expect("tool results message should be present")
+            .content
+            .clone();
+        (max_active.load(Ordering::SeqCst), tool_results)
+    }
+
+    #[tokio::test]
+    async fn restricted_session_cannot_call_tool_outside_its_allowlist() {
+        let (executed, results) =
+            run_gpio_write_call(Some(vec!["memory_recall".to_string()])).await;
+        assert_eq!(executed, 0, "blocked tool must not execute");
+        assert!(results.contains("not permitted for this session"));
+
+        let (executed, results) = run_gpio_write_call(None).await;
+        assert_eq!(executed, 1, "unrestricted session runs the tool");
+        assert!(results.contains("ok:on"));
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_deduplicates_repeated_tool_calls() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -6095,4 +7015,338 @@ Let me check the result."#;
         assert!(completed.contains("✅ shell (2s)"));
         assert!(completed.contains("❌ web_search (1s)"));
     }
+
+    /// Provider that records the system prompt of every request it receives.
+    #[derive(Default)]
+    struct SystemPromptRecorder {
+        system_prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for SystemPromptRecorder {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("chat_with_system should not be used in recorder tests");
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            if let Some(system) = request.messages.iter().find(|m| m.role == "system") {
+                self.system_prompts
+                    .lock()
+                    .expect("recorder lock should be valid")
+                    .push(system.content.clone());
+            }
+            Ok(ChatResponse {
+                text: Some("ok".to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+                quota_metadata: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn process_message_sends_each_sessions_system_prompt_to_provider() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().to_path_buf();
+        config.config_path = tmp.path().join("config.toml");
+        config.memory.backend = "none".into();
+        let provider = SystemPromptRecorder::default();
+
+        for (session, prompt) in [
+            ("support", Some("You are a support bot.")),
+            ("coding", Some("You are a coding bot.")),
+            ("default", None),
+        ] {
+            let reply = process_message_with_provider(
+                &config,
+                &provider,
+                "recorder",
+                "test-model",
+                "hello",
+                Some(session),
+                prompt,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .0;
+            assert_eq!(reply, "ok");
+        }
+
+        let prompts = provider.system_prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 3);
+        assert!(prompts[0].starts_with("You are a support bot."));
+        assert!(!prompts[0].contains("You are a coding bot."));
+        assert!(prompts[1].starts_with("You are a coding bot."));
+        assert!(!prompts[2].starts_with("You are a"));
+    }
+
+    #[tokio::test]
+    async fn restricted_session_prompt_lists_only_allowed_tools() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().to_path_buf();
+        config.config_path = tmp.path().join("config.toml");
+        config.memory.backend = "none".into();
+        let provider = SystemPromptRecorder::default();
+        assert!(!provider.supports_native_tools());
+
+        for allowlist in [Some(vec!["memory_recall".to_string()]), None] {
+            scope_session_tool_allowlist(
+                allowlist,
+                Box::pin(process_message_with_provider(
+                    &config,
+                    &provider,
+                    "recorder",
+                    "test-model",
+                    "hello",
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )),
+            )
+            .await
+            .unwrap();
+        }
+
+        let prompts = provider.system_prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[0].contains("**memory_recall**"));
+        assert!(!prompts[0].contains("**file_write**"));
+        assert!(!prompts[0].contains("Write file contents."));
+        assert!(prompts[1].contains("**file_write**"));
+        assert!(prompts[1].contains("Write file contents."));
+    }
+
+    /// Provider that records the model of every call it receives.
+    struct ModelRecorder {
+        reply: &'static str,
+        models: Mutex<Vec<String>>,
+    }
+
+    impl ModelRecorder {
+        fn new(reply: &'static str) -> Self {
+            Self {
+                reply,
+                models: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn models(&self) -> Vec<String> {
+            self.models
+                .lock()
+                .expect("recorder lock should be valid")
+                .clone()
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ModelRecorder {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.models
+                .lock()
+                .expect("recorder lock should be valid")
+                .push(model.to_string());
+            Ok(self.reply.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn streaming_forwards_only_answer_chunks() {
+        const ANSWER: &str = "The deployment finished without errors. All three services \
+            report healthy, the migration ran once, and the cache was warmed before traffic \
+            moved over to the new pods.";
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().to_path_buf();
+        config.config_path = tmp.path().join("config.toml");
+        config.memory.backend = "none".into();
+        let provider = ModelRecorder::new(ANSWER);
+
+        let (delta_tx, delta_rx) = tokio::sync::mpsc::channel(64);
+        let (token_tx, mut token_rx) = tokio::sync::mpsc::channel(64);
+        let (reply, ()) = tokio::join!(
+            Box::pin(process_message_with_provider(
+                &config,
+                &provider,
+                "recorder",
+                "test-model",
+                "status?",
+                None,
+                None,
+                None,
+                None,
+                Some(delta_tx),
+            )),
+            forward_answer_deltas(delta_rx, token_tx),
+        );
+        let (reply, usage) = reply.unwrap();
+        assert_eq!(usage, TurnUsage::default());
+
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = token_rx.try_recv() {
+            chunks.push(chunk);
+        }
+        assert!(chunks.len() > 1, "answer should arrive in several chunks");
+        assert!(chunks.iter().all(|chunk| !chunk.contains('\x00')));
+        assert_eq!(chunks.concat(), reply);
+        assert_eq!(reply, ANSWER);
+    }
+
+    #[tokio::test]
+    async fn compaction_uses_helper_model_while_chat_uses_primary() {
+        let primary = ModelRecorder::new("primary reply");
+        let helper = ModelRecorder::new("- summarized context");
+        let mut history = vec![ChatMessage::system("sys")];
+        for idx in 0..30 {
+            history.push(ChatMessage::user(format!("old-{idx}")));
+        }
+
+        let reply = agent_turn(
+            &primary,
+            &mut history,
+            &[],
+            &NoopObserver,
+            "primary",
+            "flagship-model",
+            0.7,
+            true,
+            &crate::config::MultimodalConfig::default(),
+            5,
+            None,
+            None,
+        )
+        .await
+        .expect("turn should succeed");
+        assert_eq!(reply, "primary reply");
+
+        let (compaction_provider, compaction_model) = helper_llm(
+            &primary,
+            "flagship-model",
+            Some(&helper),
+            Some("cheap-model"),
+        );
+        let compacted = auto_compact_history(
+            &mut history,
+            compaction_provider,
+            compaction_model,
+            20,
+            None,
+            None,
+        )
+        .await
+        .expect("compaction should succeed");
+
+        assert!(compacted);
+        assert_eq!(primary.models(), vec!["flagship-model"]);
+        assert_eq!(helper.models(), vec!["cheap-model"]);
+        assert!(history[1].content.contains("- summarized context"));
+    }
+
+    #[test]
+    fn helper_llm_falls_back_to_main_provider_and_model() {
+        let primary = ModelRecorder::new("ok");
+        let (_, model) = helper_llm(&primary, "flagship-model", None, Some("  "));
+        assert_eq!(model, "flagship-model");
+    }
+
+    struct PlacementRecorder {
+        placement: crate::config::SystemPromptPlacement,
+        seen: Mutex<Vec<ChatMessage>>,
+    }
+
+    #[async_trait]
+    impl Provider for PlacementRecorder {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("done".to_string())
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            *self.seen.lock().expect("recorder lock should be valid") = messages.to_vec();
+            Ok("done".to_string())
+        }
+
+        fn system_prompt_placement(&self) -> crate::config::SystemPromptPlacement {
+            self.placement
+        }
+    }
+
+    async fn messages_sent_with(
+        placement: crate::config::SystemPromptPlacement,
+    ) -> Vec<ChatMessage> {
+        let provider = PlacementRecorder {
+            placement,
+            seen: Mutex::new(Vec::new()),
+        };
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        agent_turn(
+            &provider,
+            &mut history,
+            &[],
+            &NoopObserver,
+            "recorder",
+            "model",
+            0.7,
+            true,
+            &crate::config::MultimodalConfig::default(),
+            5,
+            None,
+            None,
+        )
+        .await
+        .expect("turn should succeed");
+        provider
+            .seen
+            .into_inner()
+            .expect("recorder lock should be valid")
+    }
+
+    #[tokio::test]
+    async fn system_prompt_goes_in_provider_appropriate_slot() {
+        use crate::config::SystemPromptPlacement;
+
+        let sent = messages_sent_with(SystemPromptPlacement::SystemMessage).await;
+        assert_eq!(sent[0].role, "system");
+        assert_eq!(sent[0].content, "sys");
+        assert_eq!(sent[1].content, "hi");
+
+        let sent = messages_sent_with(SystemPromptPlacement::FirstUserMessage).await;
+        assert!(sent.iter().all(|m| m.role != "system"));
+        assert_eq!(sent[0].role, "user");
+        assert_eq!(sent[0].content, "sys\n\nhi");
+    }
 }
@@ -1,4 +1,4 @@
-use crate::memory::{self, decay, Memory, MemoryCategory};
+use crate::memory::{self, decay, Memory, MemoryCategory, MemoryEntry};
 use std::fmt::Write;
 
 /// Default half-life (days) for time decay in context building.
@@ -15,7 +15,9 @@ const CONTEXT_ENTRY_LIMIT: usize = 5;
 /// that Core boost and re-ranking can select the best subset.
 const RECALL_OVER_FETCH_FACTOR: usize = 2;
 
-/// Build context preamble by searching memory for relevant entries.
+/// Recall the memory entries that [`build_context`] would inject for
+/// `user_msg`, best first.
+///
 /// Entries with a hybrid score below `min_relevance_score` are dropped to
 /// prevent unrelated memories from bleeding into the conversation.
 ///
@@ -24,50 +26,64 @@ const RECALL_OVER_FETCH_FACTOR: usize = 2;
 /// `Core` category memories receive a score boost so that durable facts,
 /// preferences, and project rules are more likely to appear in context
 /// even when semantic similarity to the current message is moderate.
-pub(super) async fn build_context(
+pub(crate) async fn recall_context_entries(
     mem: &dyn Memory,
     user_msg: &str,
     min_relevance_score: f64,
     session_id: Option<&str>,
-) -> String {
-    let mut context = String::new();
-
+) -> Vec<MemoryEntry> {
     // Over-fetch so Core-boosted entries can compete fairly after re-ranking.
     let fetch_limit = CONTEXT_ENTRY_LIMIT * RECALL_OVER_FETCH_FACTOR;
-    if let Ok(mut entries) = mem.recall(user_msg, fetch_limit, session_id).await {
-        // Apply time decay: older non-Core memories score lower.
-        decay::apply_time_decay(&mut entries, CONTEXT_DECAY_HALF_LIFE_DAYS);
-
-        // Apply Core category boost and filter by minimum relevance.
-        let mut scored: Vec<_> = entries
-            .iter()
-            .filter(|e| !memory::is_assistant_autosave_key(&e.key))
-            .filter_map(|e| {
-                let base = e.score.unwrap_or(min_relevance_score);
-                let boosted = if e.category == MemoryCategory::Core {
-                    (base + CORE_CATEGORY_SCORE_BOOST).min(1.0)
-                } else {
-                    base
-                };
-                if boosted >= min_relevance_score {
-                    Some((e, boosted))
-                } else {
-                    None
-                }
-            })
-            .collect();
+    let Ok(mut entries) = mem.recall(user_msg, fetch_limit, session_id).await else {
+        return Vec::new();
+    };
 
-        // Sort by boosted score descending, then truncate to output limit.
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        scored.truncate(CONTEXT_ENTRY_LIMIT);
+    // Apply time decay: older non-Core memories score lower.
+    decay::apply_time_decay(&mut entries, CONTEXT_DECAY_HALF_LIFE_DAYS);
 
-        if !scored.is_empty() {
-            context.push_str("[Memory context]\n");
-            for (entry, _) in &scored {
-                let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
+    // Apply Core category boost and filter by minimum relevance.
+    let mut scored: Vec<_> = entries
+        .into_iter()
+        .filter(|e| !memory::is_assistant_autosave_key(&e.key))
+        .filter_map(|e| {
+            let base = e.score.unwrap_or(min_relevance_score);
+            let boosted = if e.category == MemoryCategory::Core {
+                (base + CORE_CATEGORY_SCORE_BOOST).min(1.0)
+            } else {
+                base
+            };
+            if boosted >= min_relevance_score {
+                Some((e, boosted))
+            } else {
+                None
             }
-            context.push('\n');
+        })
+        .collect();
+
+    // Sort by boosted score descending, then truncate to output limit.
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(CONTEXT_ENTRY_LIMIT);
+
+    scored.into_iter().map(|(e, _)| e).collect()
+}
+
+/// Build context preamble by searching memory for relevant entries.
+/// See [`recall_context_entries`] for how entries are selected.
+pub(super) async fn build_context(
+    mem: &dyn Memory,
+    user_msg: &str,
+    min_relevance_score: f64,
+    session_id: Option<&str>,
+) -> String {
+    let mut context = String::new();
+
+    let entries = recall_context_entries(mem, user_msg, min_relevance_score, session_id).await;
+    if !entries.is_empty() {
+        context.push_str("[Memory context]\n");
+        for entry in &entries {
+            let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
         }
+        context.push('\n');
     }
 
     context
@@ -1,13 +1,16 @@
 //! Loop detection for the agent tool-call loop.
 //!
-//! Detects three patterns of unproductive looping:
+//! Detects four patterns of unproductive looping:
 //! 1. **No-progress repeat** — same tool + same args + same output hash.
 //! 2. **Ping-pong** — two calls alternating (A→B→A→B) with no progress.
 //! 3. **Consecutive failure streak** — same tool failing repeatedly.
+//! 4. **Tool churn** — cycling through many different tools (A→B→C→D→A…)
+//!    without a single useful result.
 //!
 //! On first detection an `InjectWarning` verdict gives the LLM a chance to
 //! self-correct.  If the pattern persists the next check returns `HardStop`.
 
+use crate::config::AgentConfig;
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
@@ -15,6 +18,9 @@ use std::hash::{DefaultHasher, Hash, Hasher};
 /// Keeps hashing fast and bounded for large outputs.
 const OUTPUT_HASH_PREFIX_BYTES: usize = 4096;
 
+/// Maximum characters of a call's arguments quoted in a detection reason.
+const ARGS_EXCERPT_CHARS: usize = 120;
+
 // ─── Configuration ───────────────────────────────────────────────────────────
 
 /// Tuning knobs for each detection strategy.
@@ -29,6 +35,12 @@ pub(crate) struct LoopDetectionConfig {
     /// Consecutive failures of the *same* tool before triggering.
     /// `0` = disabled.  Default: `3`.
     pub failure_streak_threshold: usize,
+    /// Number of most recent calls inspected for tool churn.
+    /// `0` = disabled.  Default: `6`.
+    pub tool_churn_window: usize,
+    /// Distinct tools within the churn window, all failed or empty, before
+    /// triggering.  `0` = disabled.  Default: `4`.
+    pub tool_churn_unique_threshold: usize,
 }
 
 impl Default for LoopDetectionConfig {
@@ -37,6 +49,21 @@ impl Default for LoopDetectionConfig {
             no_progress_threshold: 3,
             ping_pong_cycles: 2,
             failure_streak_threshold: 3,
+            tool_churn_window: 6,
+            tool_churn_unique_threshold: 4,
+        }
+    }
+}
+
+impl LoopDetectionConfig {
+    /// Thresholds from the `[agent]` `loop_detection_*` keys.
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self {
+            no_progress_threshold: config.loop_detection_no_progress_threshold,
+            ping_pong_cycles: config.loop_detection_ping_pong_cycles,
+            failure_streak_threshold: config.loop_detection_failure_streak,
+            tool_churn_window: config.loop_detection_tool_churn_window,
+            tool_churn_unique_threshold: config.loop_detection_tool_churn_unique_threshold,
         }
     }
 }
@@ -61,6 +88,8 @@ struct CallRecord {
     args_sig: String,
     result_hash: u64,
     success: bool,
+    /// Output was empty or whitespace only.
+    empty_output: bool,
 }
 
 // ─── Detector ────────────────────────────────────────────────────────────────
@@ -95,6 +124,7 @@ impl LoopDetector {
             args_sig: args_sig.to_owned(),
             result_hash,
             success,
+            empty_output: output.trim().is_empty(),
         });
 
         if success {
@@ -112,7 +142,8 @@ impl LoopDetector {
         let reason = self
             .check_no_progress_repeat()
             .or_else(|| self.check_ping_pong())
-            .or_else(|| self.check_failure_streak());
+            .or_else(|| self.check_failure_streak())
+            .or_else(|| self.check_tool_churn());
 
         match reason {
             None => DetectionVerdict::Continue,
@@ -147,9 +178,11 @@ impl LoopDetector {
             .count();
         if streak >= threshold {
             Some(format!(
-                "Tool '{}' called {} times with identical arguments and identical results \
+                "Tool '{}' called {} times with identical arguments {} and identical results \
                  — no progress detected",
-                last.tool_name, streak
+                last.tool_name,
+                streak,
+                args_excerpt(&last.args_sig)
             ))
         } else {
             None
@@ -189,8 +222,12 @@ impl LoopDetector {
 
         if is_ping_pong {
             Some(format!(
-                "Ping-pong loop detected: '{}' and '{}' alternating {} times with no progress",
-                a.tool_name, b.tool_name, cycles
+                "Ping-pong loop detected: '{}' {} and '{}' {} alternating {} times with no progress",
+                a.tool_name,
+                args_excerpt(&a.args_sig),
+                b.tool_name,
+                args_excerpt(&b.args_sig),
+                cycles
             ))
         } else {
             None
@@ -206,14 +243,50 @@ impl LoopDetector {
         }
         for (tool, count) in &self.consecutive_failures {
             if *count >= threshold {
+                let last_args = self
+                    .history
+                    .iter()
+                    .rev()
+                    .find(|r| r.tool_name == *tool && !r.success)
+                    .map_or_else(String::new, |r| {
+                        format!(" (last arguments: {})", args_excerpt(&r.args_sig))
+                    });
                 return Some(format!(
-                    "Tool '{}' failed {} consecutive times",
-                    tool, count
+                    "Tool '{}' failed {} consecutive times{}",
+                    tool, count, last_args
                 ));
             }
         }
         None
     }
+
+    // ── Strategy 4: tool churn ───────────────────────────────────────────
+
+    fn check_tool_churn(&self) -> Option<String> {
+        let window = self.config.tool_churn_window;
+        let threshold = self.config.tool_churn_unique_threshold;
+        if window == 0 || threshold == 0 || self.history.len() < window {
+            return None;
+        }
+        let tail = &self.history[self.history.len() - window..];
+        if tail.iter().any(|r| r.success && !r.empty_output) {
+            return None;
+        }
+        let mut distinct: Vec<&str> = tail.iter().map(|r| r.tool_name.as_str()).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() >= threshold {
+            Some(format!(
+                "Tool churn detected: last {} calls switched between {} different tools \
+                 ({}) without a useful result",
+                window,
+                distinct.len(),
+                distinct.join(", ")
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
@@ -231,6 +304,16 @@ fn hash_output(output: &str) -> u64 {
     hasher.finish()
 }
 
+/// `args_sig` cut to [`ARGS_EXCERPT_CHARS`] characters for a reason string.
+fn args_excerpt(args_sig: &str) -> String {
+    if args_sig.chars().count() <= ARGS_EXCERPT_CHARS {
+        return args_sig.to_owned();
+    }
+    let mut excerpt: String = args_sig.chars().take(ARGS_EXCERPT_CHARS).collect();
+    excerpt.push('…');
+    excerpt
+}
+
 fn format_warning(reason: &str) -> String {
     format!(
         "IMPORTANT: A loop pattern has been detected in your tool usage. {reason}. \
@@ -257,6 +340,8 @@ mod tests {
             no_progress_threshold: 0,
             ping_pong_cycles: 0,
             failure_streak_threshold: 0,
+            tool_churn_window: 0,
+            tool_churn_unique_threshold: 0,
         }
     }
 
@@ -279,6 +364,7 @@ mod tests {
         match det.check() {
             DetectionVerdict::InjectWarning(msg) => {
                 assert!(msg.contains("no progress"), "msg: {msg}");
+                assert!(msg.contains(r#"{"msg":"hi"}"#), "msg: {msg}");
             }
             other => panic!("expected InjectWarning, got {other:?}"),
         }
@@ -307,6 +393,7 @@ mod tests {
         match det.check() {
             DetectionVerdict::HardStop(msg) => {
                 assert!(msg.contains("no progress"), "msg: {msg}");
+                assert!(msg.contains(r#"{"msg":"hi"}"#), "msg: {msg}");
             }
             other => panic!("expected HardStop, got {other:?}"),
         }
@@ -324,6 +411,8 @@ mod tests {
         match det.check() {
             DetectionVerdict::InjectWarning(msg) => {
                 assert!(msg.contains("Ping-pong"), "msg: {msg}");
+                assert!(msg.contains(r#"'tool_a' {"x":1}"#), "msg: {msg}");
+                assert!(msg.contains(r#"'tool_b' {"y":2}"#), "msg: {msg}");
             }
             other => panic!("expected InjectWarning, got {other:?}"),
         }
@@ -350,6 +439,10 @@ mod tests {
         match det.check() {
             DetectionVerdict::InjectWarning(msg) => {
                 assert!(msg.contains("failed 3 consecutive"), "msg: {msg}");
+                assert!(
+                    msg.contains(r#"last arguments: {"cmd":"bad3"}"#),
+                    "msg: {msg}"
+                );
             }
             other => panic!("expected InjectWarning, got {other:?}"),
         }
@@ -389,7 +482,75 @@ mod tests {
         assert_eq!(det.check(), DetectionVerdict::Continue);
     }
 
-    // 11. UTF-8 boundary safety: hash_output must not panic on CJK text
+    // 11. Cycling through many tools with nothing useful → tool churn
+    #[test]
+    fn tool_churn_triggers_warning() {
+        let mut det = LoopDetector::new(default_config());
+        for round in 0..2 {
+            det.record_call("web_search", &format!(r#"{{"q":{round}}}"#), "", true);
+            det.record_call("file_read", &format!(r#"{{"p":{round}}}"#), "error", false);
+            det.record_call("shell", &format!(r#"{{"c":{round}}}"#), "  ", true);
+        }
+        assert_eq!(det.check(), DetectionVerdict::Continue);
+
+        det.record_call("http_request", r#"{"u":1}"#, "timeout", false);
+        match det.check() {
+            DetectionVerdict::InjectWarning(msg) => {
+                assert!(msg.contains("Tool churn"), "msg: {msg}");
+                assert!(msg.contains("4 different tools"), "msg: {msg}");
+            }
+            other => panic!("expected InjectWarning, got {other:?}"),
+        }
+    }
+
+    // 12. Switching tools while making progress → no tool churn
+    #[test]
+    fn tool_churn_with_progress_does_not_trigger() {
+        let mut det = LoopDetector::new(default_config());
+        det.record_call("web_search", r#"{"q":1}"#, "", true);
+        det.record_call("file_read", r#"{"p":1}"#, "error", false);
+        det.record_call("shell", r#"{"c":1}"#, "build ok", true);
+        det.record_call("http_request", r#"{"u":1}"#, "timeout", false);
+        det.record_call("memory_recall", r#"{"k":1}"#, "", true);
+        det.record_call("glob", r#"{"g":1}"#, "error", false);
+        assert_eq!(det.check(), DetectionVerdict::Continue);
+    }
+
+    // 13. Long arguments are cut in the reason
+    #[test]
+    fn long_args_are_truncated_in_reason() {
+        let mut det = LoopDetector::new(default_config());
+        let long_args = format!(r#"{{"cmd":"{}"}}"#, "x".repeat(500));
+        for _ in 0..3 {
+            det.record_call("shell", &long_args, "same", true);
+        }
+        match det.check() {
+            DetectionVerdict::InjectWarning(msg) => {
+                assert!(msg.contains(&format!("{}…", &long_args[..ARGS_EXCERPT_CHARS])));
+                assert!(!msg.contains(&long_args));
+            }
+            other => panic!("expected InjectWarning, got {other:?}"),
+        }
+    }
+
+    // 14. Thresholds come from `[agent]` config
+    #[test]
+    fn configured_no_progress_threshold_is_respected() {
+        let agent: AgentConfig =
+            toml::from_str("loop_detection_no_progress_threshold = 5").expect("agent config");
+        let mut det = LoopDetector::new(LoopDetectionConfig::from_config(&agent));
+        for _ in 0..3 {
+            det.record_call("echo", r#"{"msg":"hi"}"#, "hello", true);
+        }
+        assert_eq!(det.check(), DetectionVerdict::Continue);
+
+        for _ in 0..2 {
+            det.record_call("echo", r#"{"msg":"hi"}"#, "hello", true);
+        }
+        assert!(matches!(det.check(), DetectionVerdict::InjectWarning(_)));
+    }
+
+    // 15. UTF-8 boundary safety: hash_output must not panic on CJK text
     #[test]
     fn hash_output_utf8_boundary_safe() {
         // Create a string where byte 4096 lands inside a multi-byte char
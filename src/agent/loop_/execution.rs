@@ -5,6 +5,7 @@ use crate::observability::{Observer, ObserverEvent};
 use crate::tools::Tool;
 use anyhow::Result;
 use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
 
 fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool> {
@@ -100,12 +101,22 @@ pub(super) struct ToolExecutionOutcome {
 
 pub(super) fn should_execute_tools_in_parallel(
     tool_calls: &[ParsedToolCall],
+    tools_registry: &[Box<dyn Tool>],
     approval: Option<&ApprovalManager>,
 ) -> bool {
     if tool_calls.len() <= 1 {
         return false;
     }
 
+    if tool_calls
+        .iter()
+        .any(|call| find_tool(tools_registry, &call.name).is_some_and(|tool| !tool.parallel_safe()))
+    {
+        // Tools that drive shared state (e.g. hardware actuation) must see the
+        // batch in the order the model issued it.
+        return false;
+    }
+
     if let Some(mgr) = approval {
         if tool_calls.iter().any(|call| mgr.needs_approval(&call.name)) {
             // Approval-gated calls must keep sequential handling so the caller can
@@ -122,17 +133,28 @@ pub(super) async fn execute_tools_parallel(
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
+    max_parallel: usize,
 ) -> Result<Vec<ToolExecutionOutcome>> {
+    let permits = (max_parallel > 0).then(|| Semaphore::new(max_parallel));
     let futures: Vec<_> = tool_calls
         .iter()
         .map(|call| {
-            execute_one_tool(
-                &call.name,
-                call.arguments.clone(),
-                tools_registry,
-                observer,
-                cancellation_token,
-            )
+            let permits = permits.as_ref();
+            async move {
+                // The semaphore is never closed, so acquiring cannot fail.
+                let _permit = match permits {
+                    Some(permits) => permits.acquire().await.ok(),
+                    None => None,
+                };
+                execute_one_tool(
+                    &call.name,
+                    call.arguments.clone(),
+                    tools_registry,
+                    observer,
+                    cancellation_token,
+                )
+                .await
+            }
         })
         .collect();
 
@@ -0,0 +1,167 @@
+use super::lookup_model_pricing;
+use crate::config::schema::ModelPricing;
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{Observer, ObserverEvent};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Token usage and cost of one agent turn, summed over its provider calls.
+///
+/// A field is `None` when no call in the turn reported it, so callers can
+/// tell "unknown" apart from zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TurnUsage {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    /// Priced per call from `[cost].prices`; `None` when no call reported
+    /// token counts.
+    pub cost_usd: Option<f64>,
+}
+
+impl TurnUsage {
+    /// Input plus output tokens, as recorded in `AgentEnd::tokens_used`.
+    pub fn total_tokens(&self) -> Option<u64> {
+        match (self.input_tokens, self.output_tokens) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0).saturating_add(output.unwrap_or(0))),
+        }
+    }
+
+    fn add(&mut self, input_tokens: Option<u64>, output_tokens: Option<u64>, cost_usd: f64) {
+        fn sum(total: Option<u64>, more: Option<u64>) -> Option<u64> {
+            match (total, more) {
+                (Some(total), Some(more)) => Some(total.saturating_add(more)),
+                (total, more) => total.or(more),
+            }
+        }
+        self.input_tokens = sum(self.input_tokens, input_tokens);
+        self.output_tokens = sum(self.output_tokens, output_tokens);
+        self.cost_usd = Some(self.cost_usd.unwrap_or(0.0) + cost_usd);
+    }
+}
+
+/// Forwards every event to `inner` and sums the usage reported by each
+/// successful `LlmResponse` into a [`TurnUsage`].
+pub(super) struct TurnUsageObserver {
+    inner: Arc<dyn Observer>,
+    prices: HashMap<String, ModelPricing>,
+    provider: String,
+    usage: Mutex<TurnUsage>,
+}
+
+impl TurnUsageObserver {
+    pub(super) fn new(
+        inner: Arc<dyn Observer>,
+        prices: HashMap<String, ModelPricing>,
+        provider: &str,
+    ) -> Self {
+        Self {
+            inner,
+            prices,
+            provider: provider.to_string(),
+            usage: Mutex::new(TurnUsage::default()),
+        }
+    }
+
+    pub(super) fn usage(&self) -> TurnUsage {
+        self.usage.lock().map(|usage| *usage).unwrap_or_default()
+    }
+}
+
+impl Observer for TurnUsageObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::LlmResponse {
+            model,
+            success: true,
+            input_tokens,
+            output_tokens,
+            ..
+        } = event
+        {
+            if input_tokens.is_some() || output_tokens.is_some() {
+                let (input_price, output_price) =
+                    lookup_model_pricing(&self.prices, &self.provider, model);
+                let cost = crate::cost::TokenUsage::new(
+                    model.as_str(),
+                    input_tokens.unwrap_or(0),
+                    output_tokens.unwrap_or(0),
+                    input_price,
+                    output_price,
+                )
+                .cost();
+                if let Ok(mut usage) = self.usage.lock() {
+                    usage.add(*input_tokens, *output_tokens, cost);
+                }
+            }
+        }
+        self.inner.record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.inner.record_metric(metric);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        "turn-usage"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+    use std::time::Duration;
+
+    fn response(success: bool, input: Option<u64>, output: Option<u64>) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "openai".into(),
+            model: "gpt-4o-mini".into(),
+            duration: Duration::from_millis(5),
+            success,
+            error_message: None,
+            input_tokens: input,
+            output_tokens: output,
+        }
+    }
+
+    fn observer() -> TurnUsageObserver {
+        TurnUsageObserver::new(
+            Arc::new(NoopObserver),
+            crate::config::CostConfig::default().prices,
+            "openai",
+        )
+    }
+
+    #[test]
+    fn usage_is_unknown_until_a_call_reports_tokens() {
+        let obs = observer();
+        obs.record_event(&response(true, None, None));
+        obs.record_event(&ObserverEvent::TurnComplete);
+        assert_eq!(obs.usage(), TurnUsage::default());
+        assert_eq!(obs.usage().total_tokens(), None);
+    }
+
+    #[test]
+    fn usage_sums_successful_calls_and_prices_them() {
+        let obs = observer();
+        obs.record_event(&response(true, Some(1_000_000), Some(100_000)));
+        obs.record_event(&response(true, Some(1_000_000), None));
+        obs.record_event(&response(false, Some(500), Some(500)));
+
+        let usage = obs.usage();
+        assert_eq!(usage.input_tokens, Some(2_000_000));
+        assert_eq!(usage.output_tokens, Some(100_000));
+        assert_eq!(usage.total_tokens(), Some(2_100_000));
+        // gpt-4o-mini: $0.15 in / $0.60 out per 1M tokens.
+        let cost = usage.cost_usd.expect("priced");
+        assert!((cost - 0.36).abs() < 1e-9, "{cost}");
+    }
+}
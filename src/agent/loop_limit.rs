@@ -0,0 +1,182 @@
+//! Process-wide cap on concurrent agent loops.
+//!
+//! Each agent loop holds its history, tool outputs and provider stream in
+//! memory for the whole turn, so on small hosts and phones a burst of
+//! requests can exhaust the runtime before any of them finishes. Gateway
+//! handlers and the Android bridge take an [`AgentLoopPermit`] from the
+//! shared limiter before running a loop: once `agent.max_concurrent_loops`
+//! are running, further requests wait in a queue of at most
+//! `agent.max_queued_loops`, and requests beyond that fail with
+//! [`ServerBusy`].
+
+use crate::config::AgentConfig;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default number of requests allowed to wait for a running loop to finish.
+pub const DEFAULT_MAX_QUEUED_LOOPS: usize = 16;
+
+/// Returned when every loop permit is taken and the wait queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerBusy;
+
+impl std::fmt::Display for ServerBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("server busy: too many agent requests in progress, try again later")
+    }
+}
+
+impl std::error::Error for ServerBusy {}
+
+/// Permission to run one agent loop; released when dropped.
+pub struct AgentLoopPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Bounds how many agent loops run at once and how many wait for a slot.
+pub struct AgentLoopLimiter {
+    /// `None` when loops are unlimited.
+    permits: Option<Arc<Semaphore>>,
+    max_queued: usize,
+    queued: AtomicUsize,
+}
+
+/// Counts one request as queued until dropped, so a caller that gives up
+/// while waiting frees its queue slot.
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl AgentLoopLimiter {
+    /// Allow `max_concurrent` loops at once (`0` = unlimited), with up to
+    /// `max_queued` more waiting for a slot.
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self {
+            permits: (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent))),
+            max_queued,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Limiter for `agent.max_concurrent_loops` / `agent.max_queued_loops`.
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self::new(config.max_concurrent_loops, config.max_queued_loops)
+    }
+
+    /// Take a loop permit, waiting in the queue while all are in use.
+    /// Fails with [`ServerBusy`] when the queue is already full.
+    pub async fn acquire(&self) -> Result<AgentLoopPermit, ServerBusy> {
+        let Some(permits) = &self.permits else {
+            return Ok(AgentLoopPermit { _permit: None });
+        };
+        // The semaphore is fair, so this cannot jump ahead of queued waiters.
+        if let Ok(permit) = Arc::clone(permits).try_acquire_owned() {
+            return Ok(AgentLoopPermit {
+                _permit: Some(permit),
+            });
+        }
+
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.max_queued {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            return Err(ServerBusy);
+        }
+        let _slot = QueueSlot(&self.queued);
+        let permit = Arc::clone(permits)
+            .acquire_owned()
+            .await
+            .map_err(|_| ServerBusy)?;
+        Ok(AgentLoopPermit {
+            _permit: Some(permit),
+        })
+    }
+
+    /// Requests currently waiting for a permit.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+}
+
+fn limiter_cell() -> &'static RwLock<Arc<AgentLoopLimiter>> {
+    static CELL: OnceLock<RwLock<Arc<AgentLoopLimiter>>> = OnceLock::new();
+    CELL.get_or_init(|| RwLock::new(Arc::new(AgentLoopLimiter::new(0, 0))))
+}
+
+/// Replace the process-wide limiter with one built from `config`.
+///
+/// Loops already holding a permit keep it; only new requests see the new
+/// limits.
+pub fn configure(config: &AgentConfig) {
+    *limiter_cell().write() = Arc::new(AgentLoopLimiter::from_config(config));
+}
+
+/// Take a permit from the process-wide limiter; see
+/// [`AgentLoopLimiter::acquire`].
+pub async fn acquire_agent_loop() -> Result<AgentLoopPermit, ServerBusy> {
+    let limiter = Arc::clone(&limiter_cell().read());
+    limiter.acquire().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn extra_requests_queue_then_get_rejected_when_queue_is_full() {
+        let limiter = Arc::new(AgentLoopLimiter::new(2, 1));
+        let first = limiter.acquire().await.unwrap();
+        let _second = limiter.acquire().await.unwrap();
+
+        // The third request waits for a running loop to finish.
+        let waiting = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.acquire().await.map(|_| ()) }
+        });
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while limiter.queued() == 0 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("third request should be queued");
+        assert!(!waiting.is_finished());
+
+        // The fourth finds the queue full.
+        assert_eq!(limiter.acquire().await.err(), Some(ServerBusy));
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("queued request should get the released permit")
+            .unwrap()
+            .unwrap();
+        assert_eq!(limiter.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn abandoned_waiter_frees_its_queue_slot() {
+        let limiter = AgentLoopLimiter::new(1, 1);
+        let _running = limiter.acquire().await.unwrap();
+
+        let gave_up = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+        assert!(gave_up.is_err());
+        assert_eq!(limiter.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn zero_concurrent_loops_means_unlimited() {
+        let limiter = AgentLoopLimiter::new(0, 0);
+        let permits: Vec<_> = futures_util::future::join_all((0..8).map(|_| limiter.acquire()))
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(permits.len(), 8);
+    }
+}
@@ -3,6 +3,7 @@ pub mod agent;
 pub mod classifier;
 pub mod dispatcher;
 pub mod loop_;
+pub mod loop_limit;
 pub mod memory_loader;
 pub mod prompt;
 pub mod quota_aware;
@@ -16,4 +17,8 @@ mod tests;
 #[allow(unused_imports)]
 pub use agent::{Agent, AgentBuilder};
 #[allow(unused_imports)]
-pub use loop_::{process_message, process_message_with_session, run, run_tool_call_loop};
+pub use loop_::{
+    process_message, process_message_streaming, process_message_with_session,
+    process_message_with_system_prompt, process_message_with_turn_budget, run, run_tool_call_loop,
+    TurnUsage,
+};
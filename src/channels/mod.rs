@@ -253,6 +253,8 @@ struct ChannelRuntimeDefaults {
     cost: crate::config::CostConfig,
     auto_save_memory: bool,
     max_tool_iterations: usize,
+    max_tool_result_chars: usize,
+    max_parallel_tools: usize,
     min_relevance_score: f64,
     message_timeout_secs: u64,
     interrupt_on_new_message: bool,
@@ -1075,6 +1077,8 @@ fn runtime_defaults_from_config(config: &Config) -> ChannelRuntimeDefaults {
         cost: config.cost.clone(),
         auto_save_memory: config.memory.auto_save,
         max_tool_iterations: config.agent.max_tool_iterations,
+        max_tool_result_chars: config.agent.max_tool_result_chars,
+        max_parallel_tools: config.agent.max_parallel_tools,
         min_relevance_score: config.memory.min_relevance_score,
         message_timeout_secs,
         interrupt_on_new_message,
@@ -1129,6 +1133,8 @@ fn runtime_defaults_snapshot(ctx: &ChannelRuntimeContext) -> ChannelRuntimeDefau
         cost: crate::config::CostConfig::default(),
         auto_save_memory: ctx.auto_save_memory,
         max_tool_iterations: ctx.max_tool_iterations,
+        max_tool_result_chars: crate::agent::loop_::DEFAULT_MAX_TOOL_RESULT_CHARS,
+        max_parallel_tools: crate::agent::loop_::DEFAULT_MAX_PARALLEL_TOOLS,
         min_relevance_score: ctx.min_relevance_score,
         message_timeout_secs: ctx.message_timeout_secs,
         interrupt_on_new_message: ctx.interrupt_on_new_message,
@@ -3780,6 +3786,10 @@ or tune thresholds in config.",
             Duration::from_secs(timeout_budget_secs),
             crate::agent::loop_::scope_cost_enforcement_context(
                 cost_enforcement_context,
+                crate::agent::loop_::scope_tool_result_cap(
+                runtime_defaults.max_tool_result_chars,
+                crate::agent::loop_::scope_max_parallel_tools(
+                runtime_defaults.max_parallel_tools,
                 run_tool_call_loop_with_non_cli_approval_context(
                     active_provider.as_ref(),
                     &mut history,
@@ -3801,6 +3811,8 @@ or tune thresholds in config.",
                     progress_mode,
                     ctx.safety_heartbeat.clone(),
                 ),
+                ),
+                ),
             ),
         ) => LlmExecutionResult::Completed(result),
     };
@@ -5357,6 +5369,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        system_prompt_placement: config.provider.system_prompt_placement,
     };
     let provider: Arc<dyn Provider> = Arc::from(
         create_resilient_provider_nonblocking(
@@ -5471,6 +5484,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         }
     }
 
+    tools::limit_tools(&mut built_tools, &config.agent);
     let tools_registry = Arc::new(built_tools);
 
     let skills = crate::skills::load_skills_with_config(&workspace, &config);
@@ -9552,6 +9566,8 @@ BTC is currently around $65,000 based on latest tool output."#
                         cost: crate::config::CostConfig::default(),
                         auto_save_memory: false,
                         max_tool_iterations: 5,
+                        max_tool_result_chars: crate::agent::loop_::DEFAULT_MAX_TOOL_RESULT_CHARS,
+                        max_parallel_tools: crate::agent::loop_::DEFAULT_MAX_PARALLEL_TOOLS,
                         min_relevance_score: 0.0,
                         message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
                         interrupt_on_new_message: false,
@@ -437,6 +437,22 @@ pub struct ProviderConfig {
     /// Existing configs that omit `provider.transport` remain valid and fall back to defaults.
     #[serde(default)]
     pub transport: Option<String>,
+    /// Where the system prompt is sent. Unset keeps the provider's own
+    /// preference (a `system` message for most providers).
+    #[serde(default)]
+    pub system_prompt_placement: Option<SystemPromptPlacement>,
+}
+
+/// How the agent loop delivers the system prompt to a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptPlacement {
+    /// A leading `system` role message.
+    #[default]
+    SystemMessage,
+    /// Prepended to the first user message, for models that ignore or
+    /// reject the `system` role.
+    FirstUserMessage,
 }
 
 // ── Delegate Agents ──────────────────────────────────────────────
@@ -573,7 +589,44 @@ impl std::fmt::Display for HardwareTransport {
     }
 }
 
+/// What to do with the device-registry hardware tools (`gpio_*`,
+/// `device_capabilities`) when no devices were discovered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareNoDeviceBehavior {
+    /// Omit the tools when discovery found nothing and no peripherals are configured.
+    #[default]
+    Hide,
+    /// Always register the tools; they report that no device was found.
+    Keep,
+}
+
+/// How the device registry recognises a board it has seen before, so a
+/// replugged board keeps its alias.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareDeviceIdentity {
+    /// VID:PID plus the USB serial number, falling back to VID:PID plus the
+    /// port path when the board reports no serial number.
+    #[default]
+    Serial,
+    /// VID:PID plus the port path, ignoring serial numbers.
+    Port,
+}
+
+/// Shape of the `output` returned by a successful `gpio_read` / `gpio_write`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareGpioOutputFormat {
+    /// A sentence such as `GPIO 25 set HIGH on pico0`.
+    #[default]
+    Prose,
+    /// A JSON object: `{"device","pin","value","state"}`.
+    Json,
+}
+
 /// Wizard-driven hardware configuration for physical world interaction.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HardwareConfig {
     /// Whether hardware access is enabled
@@ -594,12 +647,127 @@ pub struct HardwareConfig {
     /// Enable workspace datasheet RAG (index PDF schematics for AI pin lookups)
     #[serde(default)]
     pub workspace_datasheets: bool,
+    /// Periodically watch for a Pico entering BOOTSEL (RPI-RP2 mount) while
+    /// the daemon runs. Requires the `hardware` feature.
+    #[serde(default)]
+    pub bootsel_watch: bool,
+    /// Seconds between BOOTSEL mount checks
+    #[serde(default = "default_bootsel_watch_interval_secs")]
+    pub bootsel_watch_interval_secs: u64,
+    /// UF2 image copied onto a newly detected RPI-RP2 mount (auto-flash)
+    #[serde(default)]
+    pub bootsel_auto_flash_uf2: Option<PathBuf>,
+    /// UF2 images per board name (e.g. `pico`, `pico-w`) for BOOTSEL
+    /// auto-flash. When set, the board must match one of these or nothing is
+    /// flashed.
+    #[serde(default)]
+    pub firmware_bundles: HashMap<String, PathBuf>,
+    /// Board name used to pick from `firmware_bundles`; unset reads the
+    /// `Board-ID` from the BOOTSEL volume
+    #[serde(default)]
+    pub bootsel_board: Option<String>,
+    /// Hardware tool registration when no devices are found: "hide" or "keep"
+    #[serde(default)]
+    pub no_device_behavior: HardwareNoDeviceBehavior,
+    /// Append-only JSONL audit log of every command hardware tools send to a
+    /// device (relative to workspace). Unset disables auditing.
+    #[serde(default)]
+    pub command_audit_log: Option<PathBuf>,
+    /// Seconds between keepalive pings to registered serial devices; a failed
+    /// ping marks the device disconnected. `0` disables.
+    #[serde(default)]
+    pub keepalive_interval_secs: u64,
+    /// Ping every registered serial device once in the background at
+    /// startup (recovering it once on failure) so the device summary shows
+    /// which boards are actually reachable. Never delays startup.
+    #[serde(default)]
+    pub probe_on_startup: bool,
+    /// Unknown-VID serial ports ping-probed at once during discovery.
+    /// Registration order (and so aliasing) is unaffected.
+    #[serde(default = "default_discovery_parallelism")]
+    pub discovery_parallelism: usize,
+    /// Identity fingerprint used to rebind a returning device to its alias
+    /// on rescan: "serial" (VID:PID + USB serial number) or "port"
+    #[serde(default)]
+    pub device_identity: HardwareDeviceIdentity,
+    /// Auto-select preference (device aliases or kinds, e.g. `["pico0",
+    /// "esp32"]`) used when several devices match and no `device` is given.
+    /// Empty keeps the "multiple devices available" error.
+    #[serde(default)]
+    pub device_preference: Vec<String>,
+    /// Replace raw device paths (e.g. `/dev/ttyACM0`) with the device alias
+    /// in errors returned by hardware tools.
+    #[serde(default)]
+    pub redact_device_paths: bool,
+    /// Also use aliases instead of device paths in info/warn logs about
+    /// reconnects. Debug logs keep paths.
+    #[serde(default)]
+    pub redact_device_paths_in_logs: bool,
+    /// Attempts made when reconnecting a rebooted device before giving up
+    #[serde(default = "default_reconnect_attempts")]
+    pub reconnect_attempts: u32,
+    /// Wait before the second reconnect attempt (ms); doubles per failure
+    #[serde(default = "default_reconnect_backoff_ms")]
+    pub reconnect_backoff_ms: u64,
+    /// Upper bound for the wait between reconnect attempts (ms)
+    #[serde(default = "default_reconnect_max_backoff_ms")]
+    pub reconnect_max_backoff_ms: u64,
+    /// Per-device cap on state-changing commands (`gpio_write`,
+    /// `gpio_config`, ...) within any one second. `0` disables.
+    #[serde(default)]
+    pub max_write_commands_per_sec: u32,
+    /// Per-device cap on query commands (`gpio_read`, `capabilities`)
+    /// within any one second. `0` disables.
+    #[serde(default)]
+    pub max_read_commands_per_sec: u32,
+    /// Chat message that engages the hardware emergency stop when it is the
+    /// whole message (case-insensitive). Unset disables the safe word.
+    #[serde(default)]
+    pub estop_safe_word: Option<String>,
+    /// Pins driven to a safe level when the emergency stop engages.
+    #[serde(default)]
+    pub estop_safe_pins: Vec<HardwareSafePin>,
+    /// Default output of `gpio_read` / `gpio_write`: "prose" or "json".
+    /// A call's `format` argument overrides it.
+    #[serde(default)]
+    pub gpio_output_format: HardwareGpioOutputFormat,
+}
+
+/// A GPIO pin and the level it is driven to when the emergency stop engages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HardwareSafePin {
+    /// Device alias (e.g. `pico0`).
+    pub device: String,
+    pub pin: u64,
+    /// `0` (LOW) or `1` (HIGH).
+    #[serde(default)]
+    pub value: u8,
 }
 
 fn default_baud_rate() -> u32 {
     115_200
 }
 
+fn default_bootsel_watch_interval_secs() -> u64 {
+    5
+}
+
+fn default_discovery_parallelism() -> usize {
+    4
+}
+
+fn default_reconnect_attempts() -> u32 {
+    3
+}
+
+fn default_reconnect_backoff_ms() -> u64 {
+    500
+}
+
+fn default_reconnect_max_backoff_ms() -> u64 {
+    2_000
+}
+
 impl HardwareConfig {
     /// Return the active transport mode.
     pub fn transport_mode(&self) -> HardwareTransport {
@@ -616,6 +784,28 @@ impl Default for HardwareConfig {
             baud_rate: default_baud_rate(),
             probe_target: None,
             workspace_datasheets: false,
+            bootsel_watch: false,
+            bootsel_watch_interval_secs: default_bootsel_watch_interval_secs(),
+            bootsel_auto_flash_uf2: None,
+            firmware_bundles: HashMap::new(),
+            bootsel_board: None,
+            no_device_behavior: HardwareNoDeviceBehavior::default(),
+            command_audit_log: None,
+            keepalive_interval_secs: 0,
+            probe_on_startup: false,
+            discovery_parallelism: default_discovery_parallelism(),
+            device_identity: HardwareDeviceIdentity::default(),
+            device_preference: Vec::new(),
+            redact_device_paths: false,
+            redact_device_paths_in_logs: false,
+            reconnect_attempts: default_reconnect_attempts(),
+            reconnect_backoff_ms: default_reconnect_backoff_ms(),
+            reconnect_max_backoff_ms: default_reconnect_max_backoff_ms(),
+            max_write_commands_per_sec: 0,
+            max_read_commands_per_sec: 0,
+            estop_safe_word: None,
+            estop_safe_pins: Vec::new(),
+            gpio_output_format: HardwareGpioOutputFormat::default(),
         }
     }
 }
@@ -845,6 +1035,11 @@ pub struct AgentConfig {
     /// Enable parallel tool execution within a single iteration. Default: `false`.
     #[serde(default)]
     pub parallel_tools: bool,
+    /// Maximum independent tool calls from one model response executed at
+    /// once. Batches containing a non-parallel-safe tool (hardware
+    /// actuation) always run in order. `0` disables the bound. Default: `4`.
+    #[serde(default = "default_agent_max_parallel_tools")]
+    pub max_parallel_tools: usize,
     /// Tool dispatch strategy (e.g. `"auto"`). Default: `"auto"`.
     #[serde(default = "default_agent_tool_dispatcher")]
     pub tool_dispatcher: String,
@@ -863,6 +1058,17 @@ pub struct AgentConfig {
     /// Set to `0` to disable. Default: `3`.
     #[serde(default = "default_loop_detection_failure_streak")]
     pub loop_detection_failure_streak: usize,
+    /// Loop detection: tool churn window.
+    /// Number of most recent tool calls inspected for rapid tool switching.
+    /// Set to `0` to disable. Default: `6`.
+    #[serde(default = "default_loop_detection_tool_churn_window")]
+    pub loop_detection_tool_churn_window: usize,
+    /// Loop detection: tool churn distinct-tool threshold.
+    /// Triggers when the churn window holds at least this many different tools
+    /// and every call in it failed or returned empty output.
+    /// Set to `0` to disable. Default: `4`.
+    #[serde(default = "default_loop_detection_tool_churn_unique_threshold")]
+    pub loop_detection_tool_churn_unique_threshold: usize,
     /// Safety heartbeat injection interval inside `run_tool_call_loop`.
     /// Injects a security-constraint reminder every N tool iterations.
     /// Set to `0` to disable. Default: `5`.
@@ -877,6 +1083,37 @@ pub struct AgentConfig {
     /// set to `0` for explicit disable.
     #[serde(default = "default_safety_heartbeat_turn_interval")]
     pub safety_heartbeat_turn_interval: usize,
+    /// Maximum DOCX/PPTX extractions running at once on the blocking pool.
+    /// Setting to `0` falls back to the default. Default: `2`.
+    #[serde(default = "default_agent_max_document_extractions")]
+    pub max_document_extractions: usize,
+    /// Maximum agent loops running at once across the process (gateway
+    /// requests and the Android bridge). `0` means no limit. Default: `0`.
+    #[serde(default)]
+    pub max_concurrent_loops: usize,
+    /// Requests allowed to wait for a slot once `max_concurrent_loops` are
+    /// running; further requests fail with "server busy". Default: `16`.
+    #[serde(default = "default_agent_max_queued_loops")]
+    pub max_queued_loops: usize,
+    /// Maximum characters of a single tool result fed back to the model;
+    /// longer output is truncated with a note. `0` disables. Default: `50000`.
+    #[serde(default = "default_agent_max_tool_result_chars")]
+    pub max_tool_result_chars: usize,
+    /// Provider for internal helper LLM calls (history compaction summaries,
+    /// pre-compaction fact extraction). Unset uses the main chat provider.
+    #[serde(default)]
+    pub helper_provider: Option<String>,
+    /// Model for internal helper LLM calls. Unset uses the main chat model.
+    #[serde(default)]
+    pub helper_model: Option<String>,
+    /// Maximum number of tools advertised to the model; lower-priority tools
+    /// beyond the cap are omitted. `0` means no cap. Default: `0`.
+    #[serde(default)]
+    pub max_tools: usize,
+    /// Per-tool priority overrides used when `max_tools` applies (higher is
+    /// kept first). Tools not listed use their built-in default priority.
+    #[serde(default)]
+    pub tool_priorities: HashMap<String, i32>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -960,6 +1197,14 @@ fn default_loop_detection_failure_streak() -> usize {
     3
 }
 
+fn default_loop_detection_tool_churn_window() -> usize {
+    6
+}
+
+fn default_loop_detection_tool_churn_unique_threshold() -> usize {
+    4
+}
+
 fn default_safety_heartbeat_interval() -> usize {
     5
 }
@@ -968,6 +1213,22 @@ fn default_safety_heartbeat_turn_interval() -> usize {
     10
 }
 
+fn default_agent_max_document_extractions() -> usize {
+    crate::tools::extraction_limit::DEFAULT_EXTRACTION_PERMITS
+}
+
+fn default_agent_max_queued_loops() -> usize {
+    crate::agent::loop_limit::DEFAULT_MAX_QUEUED_LOOPS
+}
+
+fn default_agent_max_tool_result_chars() -> usize {
+    crate::agent::loop_::DEFAULT_MAX_TOOL_RESULT_CHARS
+}
+
+fn default_agent_max_parallel_tools() -> usize {
+    crate::agent::loop_::DEFAULT_MAX_PARALLEL_TOOLS
+}
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -976,12 +1237,24 @@ impl Default for AgentConfig {
             max_tool_iterations: default_agent_max_tool_iterations(),
             max_history_messages: default_agent_max_history_messages(),
             parallel_tools: false,
+            max_parallel_tools: default_agent_max_parallel_tools(),
             tool_dispatcher: default_agent_tool_dispatcher(),
             loop_detection_no_progress_threshold: default_loop_detection_no_progress_threshold(),
             loop_detection_ping_pong_cycles: default_loop_detection_ping_pong_cycles(),
             loop_detection_failure_streak: default_loop_detection_failure_streak(),
+            loop_detection_tool_churn_window: default_loop_detection_tool_churn_window(),
+            loop_detection_tool_churn_unique_threshold:
+                default_loop_detection_tool_churn_unique_threshold(),
             safety_heartbeat_interval: default_safety_heartbeat_interval(),
             safety_heartbeat_turn_interval: default_safety_heartbeat_turn_interval(),
+            max_document_extractions: default_agent_max_document_extractions(),
+            max_concurrent_loops: 0,
+            max_queued_loops: default_agent_max_queued_loops(),
+            max_tool_result_chars: default_agent_max_tool_result_chars(),
+            helper_provider: None,
+            helper_model: None,
+            max_tools: 0,
+            tool_priorities: HashMap::new(),
         }
     }
 }
@@ -1402,6 +1675,32 @@ pub struct PeripheralBoardConfig {
     /// Baud rate for serial (default: 115200)
     #[serde(default = "default_peripheral_baud")]
     pub baud: u32,
+    /// Serial: delay between flushing a command and reading the response,
+    /// for firmware that needs a moment before it can answer (default: 0)
+    #[serde(default)]
+    pub post_write_delay_ms: u64,
+    /// Serial: command sent by health checks; the board is healthy when it
+    /// answers `ok:true` (default: "ping")
+    #[serde(default = "default_peripheral_health_command")]
+    pub health_command: String,
+    /// Firmware command names used instead of the standard ones by the
+    /// hardware tools, e.g. `{ gpio_write = "set_pin" }` (default: none)
+    #[serde(default)]
+    pub commands: HashMap<String, String>,
+    /// Output pins driven to a known level when the device is closed on
+    /// shutdown or found disconnected, e.g. `[{ pin = 5, value = 0 }]`
+    /// (default: none)
+    #[serde(default)]
+    pub safe_state: Vec<PeripheralSafePin>,
+}
+
+/// A GPIO pin and the level it is left at when its board is released.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PeripheralSafePin {
+    pub pin: u64,
+    /// `0` (LOW) or `1` (HIGH).
+    #[serde(default)]
+    pub value: u8,
 }
 
 // ── Economic Agent Config ─────────────────────────────────────────
@@ -1489,6 +1788,37 @@ fn default_peripheral_baud() -> u32 {
     115_200
 }
 
+fn default_peripheral_health_command() -> String {
+    "ping".into()
+}
+
+/// Standard serial baud rates; anything else is accepted with a warning.
+pub const STANDARD_SERIAL_BAUD_RATES: &[u32] = &[
+    300, 1_200, 2_400, 4_800, 9_600, 14_400, 19_200, 38_400, 57_600, 115_200, 230_400, 460_800,
+    921_600, 1_000_000, 2_000_000,
+];
+
+/// Upper bound for a plausible serial baud rate.
+pub const MAX_SERIAL_BAUD: u32 = 4_000_000;
+
+impl PeripheralBoardConfig {
+    /// Reject a zero or implausibly high `baud` with a hint at common values.
+    pub fn validate_baud(&self) -> Result<()> {
+        if self.baud == 0 || self.baud > MAX_SERIAL_BAUD {
+            anyhow::bail!(
+                "baud must be between 1 and {MAX_SERIAL_BAUD} (got {}); common values: 9600, 115200, 921600",
+                self.baud
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether `baud` is one of [`STANDARD_SERIAL_BAUD_RATES`].
+    pub fn has_standard_baud(&self) -> bool {
+        STANDARD_SERIAL_BAUD_RATES.contains(&self.baud)
+    }
+}
+
 impl Default for PeripheralBoardConfig {
     fn default() -> Self {
         Self {
@@ -1496,6 +1826,10 @@ impl Default for PeripheralBoardConfig {
             transport: default_peripheral_transport(),
             path: None,
             baud: default_peripheral_baud(),
+            post_write_delay_ms: 0,
+            health_command: default_peripheral_health_command(),
+            commands: HashMap::new(),
+            safe_state: Vec::new(),
         }
     }
 }
@@ -1505,6 +1839,7 @@ impl Default for PeripheralBoardConfig {
 /// Gateway server configuration (`[gateway]` section).
 ///
 /// Controls the HTTP gateway for webhook and pairing endpoints.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GatewayConfig {
     /// Gateway port (default: 42617)
@@ -1551,6 +1886,265 @@ pub struct GatewayConfig {
     /// Node-control protocol scaffold (`[gateway.node_control]`).
     #[serde(default)]
     pub node_control: NodeControlConfig,
+
+    /// Inject only prior user turns (no assistant turns) into the context
+    /// block built by the OpenAI-compatible `/v1/chat/completions` shim.
+    /// Callers can override per request with `X-ZeroClaw-Context-Roles`.
+    #[serde(default)]
+    pub compat_context_user_only: bool,
+
+    /// Token budget for context injected by `/api/chat` and the compat shim.
+    /// Oldest context lines are dropped first; the current message is always
+    /// kept. `None` (default) applies only the line-count cap.
+    #[serde(default)]
+    pub context_token_budget: Option<usize>,
+
+    /// Per-model overrides for `context_token_budget`, keyed by model name.
+    #[serde(default)]
+    pub context_token_budget_by_model: HashMap<String, usize>,
+
+    /// Drop `/api/chat` `context` lines whose text already appears in the
+    /// session memory recalled for the message. Off by default: it costs an
+    /// extra memory recall per request that carries `context`.
+    #[serde(default)]
+    pub context_dedup_memory: bool,
+
+    /// Maximum number of `/api/chat` `context` lines accepted per request.
+    #[serde(default = "default_api_chat_max_context_lines")]
+    pub api_chat_max_context_lines: usize,
+
+    /// What `/api/chat` does with a `context` array longer than
+    /// `api_chat_max_context_lines`.
+    #[serde(default)]
+    pub api_chat_context_overflow: ApiChatContextOverflow,
+
+    /// Maximum length of a `session_id` (`/api/chat`) or `user` (compat shim)
+    /// value. Longer ids, or ids with characters outside
+    /// `[A-Za-z0-9._:@-]`, are rejected with `400`.
+    #[serde(default = "default_session_id_max_len")]
+    pub session_id_max_len: usize,
+
+    /// With `memory.auto_save`, also store `/api/chat` assistant replies as
+    /// conversation memory for the session, not just the user message.
+    #[serde(default)]
+    pub api_chat_store_replies: bool,
+
+    /// Interval between `: keep-alive` SSE comments sent by the compat
+    /// shim's simulated stream while the agent loop runs. `0` disables.
+    #[serde(default = "default_compat_stream_heartbeat_secs")]
+    pub compat_stream_heartbeat_secs: u64,
+
+    /// `Cache-Control` value for the compat shim's simulated stream
+    /// (empty omits the header).
+    #[serde(default = "default_compat_stream_cache_control")]
+    pub compat_stream_cache_control: String,
+
+    /// Send `Connection: keep-alive` on the compat shim's simulated stream.
+    #[serde(default = "default_true")]
+    pub compat_stream_keep_alive: bool,
+
+    /// How the compat shim splits a finished reply into
+    /// `chat.completion.chunk` events on the simulated stream.
+    #[serde(default)]
+    pub compat_stream_granularity: CompatStreamGranularity,
+
+    /// Chunk size in characters for the `chars` granularity.
+    #[serde(default = "default_compat_stream_chunk_chars")]
+    pub compat_stream_chunk_chars: usize,
+
+    /// Delay between simulated stream chunks, in milliseconds.
+    #[serde(default = "default_compat_stream_chunk_delay_ms")]
+    pub compat_stream_chunk_delay_ms: u64,
+
+    /// Extra agent runs the compat shim makes when a JSON-mode
+    /// (`response_format`) reply does not parse as a JSON object, before
+    /// failing with `invalid_json_output`.
+    #[serde(default = "default_compat_json_mode_retries")]
+    pub compat_json_mode_retries: u32,
+
+    /// Regexes for role labels some models echo at the start of a reply
+    /// (e.g. `Assistant:`). A leading match is stripped, case-insensitively,
+    /// before gateway replies are stored or returned.
+    #[serde(default = "default_reply_strip_prefixes")]
+    #[schemars(with = "Vec<String>")]
+    pub reply_strip_prefixes: ReplyStripPrefixes,
+
+    /// Fail a streamed reply when no content arrives within this many
+    /// seconds (`0` disables). Applies to webhook and `/v1/chat/completions`
+    /// provider streams, the compat shim's simulated stream, and the Android
+    /// bridge's `send_message_streaming`.
+    #[serde(default)]
+    pub first_token_timeout_secs: u64,
+
+    /// Upper bound on a whole streamed provider reply, in seconds
+    /// (`0` disables).
+    #[serde(default)]
+    pub stream_total_timeout_secs: u64,
+
+    /// Wall-clock budget for one agent turn (every provider and tool call it
+    /// makes), in seconds (`0` disables). A turn that runs over is stopped
+    /// with a timeout error.
+    #[serde(default)]
+    pub max_turn_duration_secs: u64,
+
+    /// Seconds to wait for in-flight requests on shutdown before cancelling
+    /// them. New requests are rejected with `503` while draining.
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+
+    /// Observe-only mode for integration testing: chat endpoints run full
+    /// request validation, then echo the message back as a `[dry mode]`
+    /// reply instead of calling the model, and actuating hardware tools
+    /// report what they would send without touching the device. Responses
+    /// carry `X-ZeroClaw-Dry-Mode: true`.
+    #[serde(default)]
+    pub dry_mode: bool,
+
+    /// Tools each session may use in gateway agent loops, keyed by session
+    /// ID. Other tools are hidden from the model and refused. Sessions not
+    /// listed are unrestricted unless `/api/chat` sets `allowed_tools`.
+    #[serde(default)]
+    pub session_tool_allowlists: HashMap<String, Vec<String>>,
+
+    /// Maximum number of streaming (`text/event-stream`) responses open at
+    /// once on the `/v1/chat/completions` shim (`0` = unlimited).
+    /// Non-streaming requests are not counted.
+    #[serde(default)]
+    pub max_concurrent_streams: usize,
+
+    /// What happens to a streaming request once `max_concurrent_streams`
+    /// are open.
+    #[serde(default)]
+    pub stream_limit_policy: StreamLimitPolicy,
+}
+
+/// Handling of an oversized `/api/chat` `context` array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiChatContextOverflow {
+    /// Keep the most recent lines and log a warning.
+    #[default]
+    Truncate,
+    /// Reject the request with `400 Bad Request`.
+    Reject,
+}
+
+/// Handling of a streaming request beyond `gateway.max_concurrent_streams`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamLimitPolicy {
+    /// Return `503 Service Unavailable` with `Retry-After`.
+    #[default]
+    Reject,
+    /// Answer with a regular non-streaming JSON response.
+    Downgrade,
+}
+
+/// Content chunking used by the compat shim's simulated stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CompatStreamGranularity {
+    /// Send the whole reply as one content chunk.
+    #[default]
+    Single,
+    /// One chunk per word (trailing whitespace included).
+    Words,
+    /// One chunk per sentence.
+    Sentences,
+    /// Fixed-size chunks of `compat_stream_chunk_chars` characters.
+    Chars,
+}
+
+fn default_compat_stream_heartbeat_secs() -> u64 {
+    15
+}
+
+fn default_api_chat_max_context_lines() -> usize {
+    100
+}
+
+fn default_session_id_max_len() -> usize {
+    128
+}
+
+fn default_compat_stream_chunk_chars() -> usize {
+    32
+}
+
+fn default_compat_stream_chunk_delay_ms() -> u64 {
+    20
+}
+
+fn default_compat_json_mode_retries() -> u32 {
+    1
+}
+
+fn default_shutdown_grace_secs() -> u64 {
+    10
+}
+
+fn default_reply_strip_prefixes() -> ReplyStripPrefixes {
+    ReplyStripPrefixes::new(vec!["Assistant:".into()]).expect("default pattern is a valid regex")
+}
+
+/// `gateway.reply_strip_prefixes`, compiled once when the config is loaded.
+///
+/// Serialized as the list of pattern strings; an invalid regex fails
+/// deserialization.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct ReplyStripPrefixes {
+    patterns: Vec<String>,
+    anchored: Vec<regex::Regex>,
+}
+
+impl ReplyStripPrefixes {
+    /// Compile `patterns`, each anchored to the start of a reply.
+    pub fn new(patterns: Vec<String>) -> Result<Self> {
+        let anchored = patterns
+            .iter()
+            .enumerate()
+            .map(|(i, pattern)| {
+                regex::Regex::new(&format!(r"(?i)\A\s*(?:{pattern})\s*")).with_context(|| {
+                    format!("gateway.reply_strip_prefixes[{i}] is not a valid regex")
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns, anchored })
+    }
+
+    /// The configured patterns, as written.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// `response` without one leading match of any pattern; text elsewhere
+    /// in the reply is left alone.
+    pub fn strip<'a>(&self, response: &'a str) -> &'a str {
+        self.anchored
+            .iter()
+            .filter_map(|re| re.find(response))
+            .find(|found| found.end() > 0)
+            .map_or(response, |found| &response[found.end()..])
+    }
+}
+
+impl TryFrom<Vec<String>> for ReplyStripPrefixes {
+    type Error = anyhow::Error;
+
+    fn try_from(patterns: Vec<String>) -> Result<Self> {
+        Self::new(patterns)
+    }
+}
+
+impl From<ReplyStripPrefixes> for Vec<String> {
+    fn from(prefixes: ReplyStripPrefixes) -> Self {
+        prefixes.patterns
+    }
+}
+
+fn default_compat_stream_cache_control() -> String {
+    "no-cache".into()
 }
 
 /// Node-control scaffold settings under `[gateway.node_control]`.
@@ -1618,6 +2212,30 @@ impl Default for GatewayConfig {
             idempotency_ttl_secs: default_idempotency_ttl_secs(),
             idempotency_max_keys: default_gateway_idempotency_max_keys(),
             node_control: NodeControlConfig::default(),
+            compat_context_user_only: false,
+            context_token_budget: None,
+            context_token_budget_by_model: HashMap::new(),
+            context_dedup_memory: false,
+            api_chat_max_context_lines: default_api_chat_max_context_lines(),
+            api_chat_context_overflow: ApiChatContextOverflow::default(),
+            session_id_max_len: default_session_id_max_len(),
+            api_chat_store_replies: false,
+            compat_stream_heartbeat_secs: default_compat_stream_heartbeat_secs(),
+            compat_stream_cache_control: default_compat_stream_cache_control(),
+            compat_stream_keep_alive: true,
+            compat_stream_granularity: CompatStreamGranularity::default(),
+            compat_stream_chunk_chars: default_compat_stream_chunk_chars(),
+            compat_stream_chunk_delay_ms: default_compat_stream_chunk_delay_ms(),
+            compat_json_mode_retries: default_compat_json_mode_retries(),
+            reply_strip_prefixes: default_reply_strip_prefixes(),
+            first_token_timeout_secs: 0,
+            stream_total_timeout_secs: 0,
+            max_turn_duration_secs: 0,
+            shutdown_grace_secs: default_shutdown_grace_secs(),
+            dry_mode: false,
+            session_tool_allowlists: HashMap::new(),
+            max_concurrent_streams: 0,
+            stream_limit_policy: StreamLimitPolicy::default(),
         }
     }
 }
@@ -1906,11 +2524,12 @@ fn default_http_timeout_secs() -> u64 {
 /// for all public hosts). `blocked_domains` takes priority over `allowed_domains`.
 /// If `allowed_domains` is empty, all requests are rejected (deny-by-default).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct WebFetchConfig {
     /// Enable `web_fetch` tool for fetching web page content
     #[serde(default)]
     pub enabled: bool,
-    /// Provider: "fast_html2md", "nanohtml2text", "firecrawl", or "tavily"
+    /// Provider: "fast_html2md", "nanohtml2text", "readability", "firecrawl", or "tavily"
     #[serde(default = "default_web_fetch_provider")]
     pub provider: String,
     /// Optional provider API key (required for provider = "firecrawl" or "tavily").
@@ -1935,6 +2554,47 @@ pub struct WebFetchConfig {
     /// User-Agent string sent with fetch requests (env: ZEROCLAW_WEB_FETCH_USER_AGENT)
     #[serde(default = "default_user_agent")]
     pub user_agent: String,
+    /// Fail fetches whose extracted text is shorter than this many characters
+    /// (soft 404s, JS-only pages). `0` disables the check.
+    #[serde(default)]
+    pub min_content_chars: usize,
+    /// Pretty-print `application/json` responses (and `json_pointer`
+    /// selections) instead of returning the raw body.
+    #[serde(default)]
+    pub json_pretty: bool,
+    /// Number of successful fetches kept in an in-memory LRU cache keyed by
+    /// URL. `0` disables caching.
+    #[serde(default)]
+    pub cache_capacity: usize,
+    /// Seconds a cached fetch stays valid.
+    #[serde(default = "default_web_fetch_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// When HTML converts to empty text, retry with the alternate converter
+    /// over a lighter clean-up before reporting the page as unreadable.
+    #[serde(default = "default_true")]
+    pub html_fallback: bool,
+    /// Log the full decision trail (host, allow/block list contents, resolved
+    /// IPs, rejecting rule) when a URL is blocked. Log-only; the tool error
+    /// returned to the model stays terse.
+    #[serde(default)]
+    pub explain_blocked: bool,
+    /// Redirects followed on the firecrawl/tavily API endpoint; each must
+    /// stay on the `api_url` host. `0` treats any redirect as an error.
+    #[serde(default = "default_web_fetch_api_max_redirects")]
+    pub api_max_redirects: u32,
+    /// Append a hash of the extracted text to each result and accept an
+    /// `if_changed_since_hash` argument that returns a short "unchanged"
+    /// result when the page text still hashes the same.
+    #[serde(default)]
+    pub content_hash: bool,
+}
+
+fn default_web_fetch_api_max_redirects() -> u32 {
+    3
+}
+
+fn default_web_fetch_cache_ttl_secs() -> u64 {
+    300
 }
 
 fn default_web_fetch_max_response_size() -> usize {
@@ -1961,6 +2621,14 @@ impl Default for WebFetchConfig {
             max_response_size: default_web_fetch_max_response_size(),
             timeout_secs: default_web_fetch_timeout_secs(),
             user_agent: default_user_agent(),
+            min_content_chars: 0,
+            json_pretty: false,
+            cache_capacity: 0,
+            cache_ttl_secs: default_web_fetch_cache_ttl_secs(),
+            html_fallback: true,
+            explain_blocked: false,
+            api_max_redirects: default_web_fetch_api_max_redirects(),
+            content_hash: false,
         }
     }
 }
@@ -2749,18 +3417,39 @@ pub struct QdrantConfig {
     /// Falls back to `QDRANT_API_KEY` env var if not set.
     #[serde(default)]
     pub api_key: Option<String>,
+    /// `sqlite_qdrant_hybrid` only: refuse to start, and fail memory
+    /// operations, while Qdrant is unreachable instead of degrading to
+    /// SQLite-only recall.
+    #[serde(default)]
+    pub strict_startup: bool,
+    /// `sqlite_qdrant_hybrid` only: how often to re-probe Qdrant while running
+    /// SQLite-only (default: 60).
+    #[serde(default = "default_qdrant_recheck_interval_secs")]
+    pub recheck_interval_secs: u64,
+    /// `sqlite_qdrant_hybrid` only: memory categories stored in SQLite without
+    /// a Qdrant embedding (e.g. `["conversation"]`). They are still recalled
+    /// through SQLite keyword search.
+    #[serde(default)]
+    pub skip_embedding_categories: Vec<String>,
 }
 
 fn default_qdrant_collection() -> String {
     "zeroclaw_memories".into()
 }
 
+fn default_qdrant_recheck_interval_secs() -> u64 {
+    60
+}
+
 impl Default for QdrantConfig {
     fn default() -> Self {
         Self {
             url: None,
             collection: default_qdrant_collection(),
             api_key: None,
+            strict_startup: false,
+            recheck_interval_secs: default_qdrant_recheck_interval_secs(),
+            skip_embedding_categories: Vec::new(),
         }
     }
 }
@@ -2860,6 +3549,12 @@ pub struct MemoryConfig {
     #[serde(default = "default_sqlite_journal_mode")]
     pub sqlite_journal_mode: String,
 
+    /// Upper bound on results per recall, applied to the `memory_recall`
+    /// tool's `limit` and to the hybrid backend's Qdrant candidate query
+    /// (3× this). `0` disables the cap.
+    #[serde(default = "default_max_recall_limit")]
+    pub max_recall_limit: usize,
+
     // ── Qdrant backend options ─────────────────────────────────
     /// Configuration for Qdrant vector database backend.
     /// Used when `backend = "qdrant"` or `backend = "sqlite_qdrant_hybrid"`.
@@ -2871,6 +3566,10 @@ fn default_sqlite_journal_mode() -> String {
     "wal".into()
 }
 
+fn default_max_recall_limit() -> usize {
+    100
+}
+
 fn default_embedding_provider() -> String {
     "none".into()
 }
@@ -2939,6 +3638,7 @@ impl Default for MemoryConfig {
             auto_hydrate: true,
             sqlite_open_timeout_secs: None,
             sqlite_journal_mode: default_sqlite_journal_mode(),
+            max_recall_limit: default_max_recall_limit(),
             qdrant: QdrantConfig::default(),
         }
     }
@@ -2972,6 +3672,15 @@ pub struct ObservabilityConfig {
     /// Maximum entries retained when runtime_trace_mode = "rolling".
     #[serde(default = "default_runtime_trace_max_entries")]
     pub runtime_trace_max_entries: usize,
+
+    /// Write every sanitized provider request/response to a JSONL debug log.
+    /// Off by default; secrets are redacted but prompts are written verbatim.
+    #[serde(default)]
+    pub provider_debug_log: bool,
+
+    /// Provider debug log path. Relative paths are resolved under workspace_dir.
+    #[serde(default = "default_provider_debug_log_path")]
+    pub provider_debug_log_path: String,
 }
 
 impl Default for ObservabilityConfig {
@@ -2983,6 +3692,8 @@ impl Default for ObservabilityConfig {
             runtime_trace_mode: default_runtime_trace_mode(),
             runtime_trace_path: default_runtime_trace_path(),
             runtime_trace_max_entries: default_runtime_trace_max_entries(),
+            provider_debug_log: false,
+            provider_debug_log_path: default_provider_debug_log_path(),
         }
     }
 }
@@ -2999,6 +3710,10 @@ fn default_runtime_trace_max_entries() -> usize {
     200
 }
 
+fn default_provider_debug_log_path() -> String {
+    "state/provider-debug.jsonl".to_string()
+}
+
 // ── Hooks ────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -3077,12 +3792,36 @@ pub struct PluginsConfig {
     /// Per-plugin configuration entries.
     #[serde(default)]
     pub entries: std::collections::HashMap<String, PluginEntryConfig>,
+
+    /// Strict tool arguments — when `true`, plugin tool calls only forward
+    /// argument keys declared in the tool's manifest `parameters`, and
+    /// missing or mistyped required arguments are rejected. Default: `false`.
+    #[serde(default)]
+    pub strict_tool_args: bool,
+
+    /// Maximum plugin manifests loaded from `load_paths`; later manifests
+    /// are skipped with a warning. `0` disables the limit. Default: `256`.
+    #[serde(default = "default_plugins_max_plugins")]
+    pub max_plugins: usize,
+
+    /// Time allowed to read and parse one plugin manifest before it is
+    /// skipped, in milliseconds. Default: `2000`.
+    #[serde(default = "default_plugins_load_timeout_ms")]
+    pub load_timeout_ms: u64,
 }
 
 fn default_plugins_enabled() -> bool {
     true
 }
 
+fn default_plugins_max_plugins() -> usize {
+    256
+}
+
+fn default_plugins_load_timeout_ms() -> u64 {
+    2_000
+}
+
 impl Default for PluginsConfig {
     fn default() -> Self {
         Self {
@@ -3091,6 +3830,9 @@ impl Default for PluginsConfig {
             deny: Vec::new(),
             load_paths: Vec::new(),
             entries: std::collections::HashMap::new(),
+            strict_tool_args: false,
+            max_plugins: default_plugins_max_plugins(),
+            load_timeout_ms: default_plugins_load_timeout_ms(),
         }
     }
 }
@@ -5593,6 +6335,41 @@ pub struct UrlAccessConfig {
     /// Supports exact, `*.example.com`, and `*`.
     #[serde(default)]
     pub approved_domains: Vec<String>,
+
+    /// Treat an empty per-tool `allowed_domains` list as `["*"]`.
+    /// Intended for fully-internal deployments behind their own egress proxy.
+    /// Private/local host blocking still applies. Default: `false`.
+    #[serde(default)]
+    pub allow_all_domains: bool,
+
+    /// Reject the global `"*"` wildcard in domain allowlists (and
+    /// `allow_all_domains`) at config validation, for strict deployments.
+    /// `*.example.com` patterns remain allowed. Default: `false`.
+    #[serde(default)]
+    pub forbid_wildcard_domains: bool,
+
+    /// Connect to the exact address that passed the DNS-rebinding check
+    /// instead of letting the HTTP client resolve the host again. Closes the
+    /// window where a record could change between check and connect.
+    /// Currently honored by `http_request`. Default: `false`.
+    #[serde(default)]
+    pub pin_resolved_ip: bool,
+
+    /// Check the host's `/robots.txt` before fetching and refuse paths it
+    /// disallows for the tool's user agent. Rules are cached per host for the
+    /// process lifetime. Currently honored by `web_fetch`. Default: `false`.
+    #[serde(default)]
+    pub respect_robots: bool,
+
+    /// Seconds allowed to establish a connection, separate from the tool's
+    /// overall request timeout, which must not be shorter. `0` uses the
+    /// default. Currently honored by `web_fetch`. Default: `10`.
+    #[serde(default = "default_url_access_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+}
+
+fn default_url_access_connect_timeout_secs() -> u64 {
+    10
 }
 
 impl Default for UrlAccessConfig {
@@ -5607,6 +6384,11 @@ impl Default for UrlAccessConfig {
             domain_allowlist: Vec::new(),
             domain_blocklist: Vec::new(),
             approved_domains: Vec::new(),
+            allow_all_domains: false,
+            forbid_wildcard_domains: false,
+            pin_resolved_ip: false,
+            respect_robots: false,
+            connect_timeout_secs: default_url_access_connect_timeout_secs(),
         }
     }
 }
@@ -7758,6 +8540,53 @@ impl Config {
                 "security.url_access.enforce_domain_allowlist=true requires non-empty security.url_access.domain_allowlist"
             );
         }
+        if self.security.url_access.forbid_wildcard_domains {
+            if self.security.url_access.allow_all_domains {
+                anyhow::bail!(
+                    "security.url_access.allow_all_domains=true conflicts with security.url_access.forbid_wildcard_domains=true"
+                );
+            }
+            let url_access = &self.security.url_access;
+            let mut allowlists = vec![
+                (
+                    "security.url_access.allow_domains",
+                    &url_access.allow_domains,
+                ),
+                (
+                    "security.url_access.domain_allowlist",
+                    &url_access.domain_allowlist,
+                ),
+                (
+                    "security.url_access.approved_domains",
+                    &url_access.approved_domains,
+                ),
+            ];
+            if self.browser.enabled {
+                allowlists.push(("browser.allowed_domains", &self.browser.allowed_domains));
+            }
+            if self.http_request.enabled {
+                allowlists.push((
+                    "http_request.allowed_domains",
+                    &self.http_request.allowed_domains,
+                ));
+            }
+            if self.web_fetch.enabled {
+                allowlists.push(("web_fetch.allowed_domains", &self.web_fetch.allowed_domains));
+            }
+            for (field, domains) in allowlists {
+                crate::tools::url_validation::normalize_allowed_domains_checked(
+                    domains.clone(),
+                    true,
+                    field,
+                )?;
+            }
+        }
+        if self.web_fetch.enabled {
+            crate::tools::web_fetch::resolve_timeouts(
+                self.security.url_access.connect_timeout_secs,
+                self.web_fetch.timeout_secs,
+            )?;
+        }
         let mut seen_http_credential_profiles = std::collections::HashSet::new();
         for (profile_name, profile) in &self.http_request.credential_profiles {
             let normalized_name = profile_name.trim();
@@ -8243,6 +9072,22 @@ impl Config {
             anyhow::bail!("coordination.max_seen_message_ids must be greater than 0");
         }
 
+        // Peripherals
+        for (i, board) in self.peripherals.boards.iter().enumerate() {
+            if board.transport != "serial" {
+                continue;
+            }
+            board
+                .validate_baud()
+                .map_err(|e| anyhow::anyhow!("peripherals.boards[{i}].{e}"))?;
+            if !board.has_standard_baud() {
+                tracing::warn!(
+                    "peripherals.boards[{i}].baud = {} is not a standard rate; common values: 9600, 115200, 921600",
+                    board.baud
+                );
+            }
+        }
+
         // WASM config
         if self.wasm.memory_limit_mb == 0 || self.wasm.memory_limit_mb > 256 {
             anyhow::bail!(
@@ -11188,6 +12033,30 @@ channel_id = "C123"
                 auth_token: Some("node-token".into()),
                 allowed_node_ids: vec!["node-1".into(), "node-2".into()],
             },
+            compat_context_user_only: true,
+            context_token_budget: Some(2048),
+            context_token_budget_by_model: HashMap::from([("small-model".into(), 512)]),
+            context_dedup_memory: true,
+            api_chat_max_context_lines: 20,
+            api_chat_context_overflow: ApiChatContextOverflow::Reject,
+            session_id_max_len: 64,
+            api_chat_store_replies: true,
+            compat_stream_heartbeat_secs: 5,
+            compat_stream_cache_control: "no-store".into(),
+            compat_stream_keep_alive: false,
+            compat_stream_granularity: CompatStreamGranularity::Words,
+            compat_stream_chunk_chars: 16,
+            compat_stream_chunk_delay_ms: 0,
+            compat_json_mode_retries: 2,
+            reply_strip_prefixes: ReplyStripPrefixes::new(vec!["AI:".into()]).unwrap(),
+            first_token_timeout_secs: 10,
+            stream_total_timeout_secs: 120,
+            max_turn_duration_secs: 300,
+            shutdown_grace_secs: 30,
+            dry_mode: true,
+            session_tool_allowlists: HashMap::from([("public".into(), vec!["web_fetch".into()])]),
+            max_concurrent_streams: 8,
+            stream_limit_policy: StreamLimitPolicy::Downgrade,
         };
         let toml_str = toml::to_string(&g).unwrap();
         let parsed: GatewayConfig = toml::from_str(&toml_str).unwrap();
@@ -11209,6 +12078,23 @@ channel_id = "C123"
             parsed.node_control.allowed_node_ids,
             vec!["node-1", "node-2"]
         );
+        assert_eq!(
+            parsed.compat_stream_granularity,
+            CompatStreamGranularity::Words
+        );
+        assert_eq!(parsed.compat_stream_chunk_chars, 16);
+        assert_eq!(parsed.compat_json_mode_retries, 2);
+        assert!(parsed.dry_mode);
+        assert_eq!(parsed.max_concurrent_streams, 8);
+        assert_eq!(parsed.stream_limit_policy, StreamLimitPolicy::Downgrade);
+        assert_eq!(parsed.reply_strip_prefixes.patterns(), ["AI:"]);
+    }
+
+    #[test]
+    async fn gateway_reply_strip_prefixes_reject_invalid_regex() {
+        let err = toml::from_str::<GatewayConfig>("reply_strip_prefixes = [\"Bot:\", \"(\"]")
+            .unwrap_err();
+        assert!(err.to_string().contains("reply_strip_prefixes[1]"));
     }
 
     #[test]
@@ -13158,6 +14044,28 @@ default_model = "legacy-model"
         assert_eq!(b.baud, 115_200);
     }
 
+    #[test]
+    async fn peripheral_baud_validation_rejects_zero_and_accepts_standard() {
+        let mut config = Config::default();
+        config.peripherals.boards = vec![PeripheralBoardConfig {
+            board: "nucleo-f401re".into(),
+            path: Some("/dev/ttyACM0".into()),
+            baud: 0,
+            ..PeripheralBoardConfig::default()
+        }];
+        let err = config.validate().expect_err("zero baud must be rejected");
+        assert!(err.to_string().contains("peripherals.boards[0].baud"));
+        assert!(err.to_string().contains("115200"));
+
+        config.peripherals.boards[0].baud = 115_200;
+        config.validate().unwrap();
+
+        // Non-standard but plausible rates only warn.
+        config.peripherals.boards[0].baud = 250_000;
+        config.validate().unwrap();
+        assert!(!config.peripherals.boards[0].has_standard_baud());
+    }
+
     #[test]
     async fn peripherals_config_toml_roundtrip() {
         let p = PeripheralsConfig {
@@ -13167,6 +14075,10 @@ default_model = "legacy-model"
                 transport: "serial".into(),
                 path: Some("/dev/ttyACM0".into()),
                 baud: 115_200,
+                post_write_delay_ms: 0,
+                health_command: "ping".into(),
+                commands: HashMap::new(),
+                safe_state: Vec::new(),
             }],
             datasheet_dir: None,
         };
@@ -13682,6 +14594,7 @@ default_temperature = 0.7
         assert!(parsed.security.url_access.domain_allowlist.is_empty());
         assert!(parsed.security.url_access.domain_blocklist.is_empty());
         assert!(parsed.security.url_access.approved_domains.is_empty());
+        assert!(!parsed.security.url_access.allow_all_domains);
         assert!(!parsed.security.perplexity_filter.enable_perplexity_filter);
         assert!(parsed.security.outbound_leak_guard.enabled);
         assert_eq!(
@@ -13870,6 +14783,42 @@ sensitivity = 0.9
             .contains("security.url_access.enforce_domain_allowlist"));
     }
 
+    #[test]
+    async fn security_validation_rejects_global_wildcard_when_forbidden() {
+        let mut config = Config::default();
+        config.security.url_access.forbid_wildcard_domains = true;
+        config.web_fetch.enabled = true;
+        config.web_fetch.allowed_domains = vec!["*".into()];
+        let err = config
+            .validate()
+            .expect_err("expected wildcard allowlist to be rejected");
+        assert!(err.to_string().contains("web_fetch.allowed_domains"));
+
+        config.web_fetch.allowed_domains = vec!["*.example.com".into()];
+        config.validate().unwrap();
+
+        config.security.url_access.forbid_wildcard_domains = false;
+        config.web_fetch.allowed_domains = vec!["*".into()];
+        config.validate().unwrap();
+    }
+
+    #[test]
+    async fn validation_rejects_web_fetch_connect_timeout_above_overall_timeout() {
+        let mut config = Config::default();
+        config.security.url_access.connect_timeout_secs = 45;
+        config.web_fetch.timeout_secs = 30;
+        config.validate().unwrap();
+
+        config.web_fetch.enabled = true;
+        let err = config
+            .validate()
+            .expect_err("expected inconsistent web_fetch timeouts to be rejected");
+        assert!(err.to_string().contains("connect_timeout_secs"));
+
+        config.web_fetch.timeout_secs = 45;
+        config.validate().unwrap();
+    }
+
     #[test]
     async fn security_validation_rejects_invalid_http_credential_profile_env_var() {
         let mut config = Config::default();
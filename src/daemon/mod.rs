@@ -103,6 +103,20 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    #[cfg(feature = "hardware")]
+    if config.hardware.bootsel_watch {
+        let hardware_cfg = config.hardware.clone();
+        handles.push(spawn_component_supervisor(
+            "bootsel",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = hardware_cfg.clone();
+                async move { crate::hardware::bootsel::run(cfg).await }
+            },
+        ));
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
@@ -110,6 +124,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
 
     tokio::signal::ctrl_c().await?;
     crate::health::mark_component_error("daemon", "shutdown requested");
+    crate::hardware::close_live_devices().await;
 
     for handle in &handles {
         handle.abort();
@@ -216,6 +216,19 @@ pub async fn handle_api_tools(
     Json(serde_json::json!({"tools": tools})).into_response()
 }
 
+/// GET /api/tools/openai — registered tools in OpenAI `tools` format
+pub async fn handle_api_tools_openai(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let tools = crate::tools::openai_tool_specs(&state.tools_registry);
+    Json(serde_json::json!({"tools": tools})).into_response()
+}
+
 /// GET /api/cron — list cron jobs
 pub async fn handle_api_cron_list(
     State(state): State<AppState>,
@@ -529,6 +542,47 @@ pub async fn handle_api_health(
     Json(serde_json::json!({"health": snapshot})).into_response()
 }
 
+/// GET /api/estop — hardware emergency stop status
+pub async fn handle_api_estop_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let engaged = crate::hardware::HardwareEstop::global().is_engaged();
+    Json(serde_json::json!({"engaged": engaged})).into_response()
+}
+
+/// POST /api/estop — halt hardware actuation and drive
+/// `hardware.estop_safe_pins` to their safe levels
+pub async fn handle_api_estop_engage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let safe_state = crate::hardware::estop::engage_from_config(&config).await;
+    Json(serde_json::json!({"engaged": true, "safe_state": safe_state})).into_response()
+}
+
+/// DELETE /api/estop — clear the hardware emergency stop
+pub async fn handle_api_estop_clear(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let was_engaged = crate::hardware::HardwareEstop::global().clear();
+    Json(serde_json::json!({"engaged": false, "was_engaged": was_engaged})).into_response()
+}
+
 /// GET /api/pairing/devices — list paired devices
 pub async fn handle_api_pairing_devices(
     State(state): State<AppState>,
@@ -0,0 +1,238 @@
+//! Token-budgeted context injection shared by `/api/chat` and the
+//! `/v1/chat/completions` compat shim.
+//!
+//! Both endpoints prepend recent conversation lines to the current message.
+//! A line-count cap alone does not bound prompt size, and token limits differ
+//! per model, so the context block is additionally trimmed (oldest first) to
+//! fit a token budget. The current message is always kept, even when it alone
+//! exceeds the budget.
+//!
+//! `/api/chat` also drops context lines the agent will already see through
+//! recalled session memory ([`dedup_context_against_memory`]).
+
+/// Estimates how many tokens a piece of text consumes.
+///
+/// The default [`CharHeuristicEstimator`] is tokenizer-free; implement this
+/// trait to plug in a model-specific tokenizer.
+pub trait TokenEstimator: Send + Sync {
+    /// Estimated token count for `text`.
+    fn estimate(&self, text: &str) -> usize;
+}
+
+/// Tokenizer-free heuristic: roughly four characters per token.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharHeuristicEstimator;
+
+impl TokenEstimator for CharHeuristicEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Header line prepended to the context block.
+const CONTEXT_HEADER: &str = "Recent conversation context:";
+/// Separator between the context block and the current message.
+const CURRENT_MESSAGE_HEADER: &str = "Current message:";
+
+/// Build the message sent to the agent loop from optional context lines.
+///
+/// Keeps at most `max_lines` of the most recent context lines. When
+/// `token_budget` is set, older lines are dropped until the whole prompt
+/// (framing + context + message) fits the budget. If no context line fits,
+/// the bare message is returned.
+pub fn build_enriched_message(
+    context: &[String],
+    message: &str,
+    max_lines: usize,
+    token_budget: Option<usize>,
+    estimator: &dyn TokenEstimator,
+) -> String {
+    let start = context.len().saturating_sub(max_lines);
+    let mut recent = &context[start..];
+
+    if let Some(budget) = token_budget {
+        let framing = estimator.estimate(CONTEXT_HEADER)
+            + estimator.estimate(CURRENT_MESSAGE_HEADER)
+            + estimator.estimate(message);
+        let mut used = framing;
+        let mut kept = 0;
+        for line in recent.iter().rev() {
+            let cost = estimator.estimate(line);
+            if used + cost > budget {
+                break;
+            }
+            used += cost;
+            kept += 1;
+        }
+        recent = &recent[recent.len() - kept..];
+    }
+
+    if recent.is_empty() {
+        return message.to_string();
+    }
+
+    format!(
+        "{CONTEXT_HEADER}\n{}\n\n{CURRENT_MESSAGE_HEADER}\n{message}",
+        recent.join("\n")
+    )
+}
+
+/// Normalize a context or memory line for duplicate detection: drop a
+/// leading `User:`/`Assistant:` role label, lowercase, collapse whitespace.
+fn normalize_for_dedup(line: &str) -> String {
+    let trimmed = line.trim();
+    let body = ["User:", "Assistant:"]
+        .iter()
+        .find_map(|label| trimmed.strip_prefix(label))
+        .unwrap_or(trimmed);
+    body.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Drop context lines whose normalized text already appears in `recalled`
+/// memory (as a whole entry or one of its lines). Order is preserved.
+pub fn dedup_context_against_memory(context: &[String], recalled: &[String]) -> Vec<String> {
+    let known: std::collections::HashSet<String> = recalled
+        .iter()
+        .flat_map(|entry| std::iter::once(entry.as_str()).chain(entry.lines()))
+        .map(normalize_for_dedup)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if known.is_empty() {
+        return context.to_vec();
+    }
+    context
+        .iter()
+        .filter(|line| !known.contains(&normalize_for_dedup(line)))
+        .cloned()
+        .collect()
+}
+
+/// Resolve the context token budget for `model` from gateway config.
+///
+/// A per-model entry in `context_token_budget_by_model` wins over the
+/// global `context_token_budget`. `None` disables token trimming.
+pub fn context_token_budget_for(
+    config: &crate::config::GatewayConfig,
+    model: &str,
+) -> Option<usize> {
+    config
+        .context_token_budget_by_model
+        .get(model)
+        .copied()
+        .or(config.context_token_budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GatewayConfig;
+
+    fn lines(n: usize) -> Vec<String> {
+        (0..n)
+            .map(|i| format!("User: message number {i}"))
+            .collect()
+    }
+
+    #[test]
+    fn heuristic_rounds_up() {
+        let est = CharHeuristicEstimator;
+        assert_eq!(est.estimate(""), 0);
+        assert_eq!(est.estimate("abc"), 1);
+        assert_eq!(est.estimate("abcde"), 2);
+    }
+
+    #[test]
+    fn no_context_returns_bare_message() {
+        let got = build_enriched_message(&[], "hi", 10, None, &CharHeuristicEstimator);
+        assert_eq!(got, "hi");
+    }
+
+    #[test]
+    fn line_cap_keeps_most_recent() {
+        let context = lines(15);
+        let got = build_enriched_message(&context, "now", 10, None, &CharHeuristicEstimator);
+        assert!(!got.contains("message number 4\n"));
+        assert!(got.contains("message number 5\n"));
+        assert!(got.ends_with("Current message:\nnow"));
+    }
+
+    #[test]
+    fn low_budget_drops_oldest_context_first() {
+        let context = lines(5);
+        let est = CharHeuristicEstimator;
+        let framing = est.estimate(CONTEXT_HEADER)
+            + est.estimate(CURRENT_MESSAGE_HEADER)
+            + est.estimate("now");
+        // Room for exactly two context lines.
+        let budget = framing + 2 * est.estimate(&context[0]);
+
+        let got = build_enriched_message(&context, "now", 10, Some(budget), &est);
+
+        assert!(!got.contains("message number 2"));
+        assert!(got.contains("message number 3"));
+        assert!(got.contains("message number 4"));
+        assert!(got.ends_with("Current message:\nnow"));
+    }
+
+    #[test]
+    fn budget_smaller_than_message_still_keeps_message() {
+        let context = lines(3);
+        let message = "a very long current message that alone blows the budget";
+        let got = build_enriched_message(&context, message, 10, Some(1), &CharHeuristicEstimator);
+        assert_eq!(got, message);
+    }
+
+    #[test]
+    fn custom_estimator_is_honored() {
+        struct PerLine;
+        impl TokenEstimator for PerLine {
+            fn estimate(&self, text: &str) -> usize {
+                usize::from(text.starts_with("User:"))
+            }
+        }
+        let context = lines(5);
+        let got = build_enriched_message(&context, "now", 10, Some(3), &PerLine);
+        assert!(!got.contains("message number 1"));
+        assert!(got.contains("message number 2"));
+        assert!(got.contains("message number 4"));
+    }
+
+    #[test]
+    fn per_model_budget_overrides_default() {
+        let mut config = GatewayConfig {
+            context_token_budget: Some(4000),
+            ..GatewayConfig::default()
+        };
+        config
+            .context_token_budget_by_model
+            .insert("small-model".into(), 512);
+
+        assert_eq!(context_token_budget_for(&config, "small-model"), Some(512));
+        assert_eq!(context_token_budget_for(&config, "big-model"), Some(4000));
+        assert_eq!(
+            context_token_budget_for(&GatewayConfig::default(), "any"),
+            None
+        );
+    }
+
+    #[test]
+    fn context_line_matching_recalled_memory_is_dropped() {
+        let context = vec![
+            "User: My  favourite colour is BLUE".to_string(),
+            "User: I am flying to Lisbon tomorrow".to_string(),
+        ];
+        let recalled = vec!["my favourite colour is blue".to_string()];
+
+        let kept = dedup_context_against_memory(&context, &recalled);
+        assert_eq!(kept, vec!["User: I am flying to Lisbon tomorrow"]);
+    }
+
+    #[test]
+    fn dedup_without_recalled_memory_keeps_all_lines() {
+        let context = lines(3);
+        assert_eq!(dedup_context_against_memory(&context, &[]), context);
+    }
+}
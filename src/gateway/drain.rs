@@ -0,0 +1,261 @@
+//! Graceful shutdown — drain in-flight requests before the gateway stops.
+//!
+//! Every request passes through [`track_requests`], which counts it as in
+//! flight until its response body is sent, so streamed (SSE) replies are
+//! drained too. On shutdown, [`RequestDrain::drain`] stops accepting new requests
+//! (they get `503`), waits up to `gateway.shutdown_grace_secs` for the
+//! in-flight ones to finish, then cancels whatever is left so agent loops do
+//! not outlive the server.
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::StreamExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// In-flight request counter with drain and cancel signals.
+#[derive(Default)]
+pub struct RequestDrain {
+    in_flight: AtomicUsize,
+    idle: Notify,
+    draining: CancellationToken,
+    cancel: CancellationToken,
+}
+
+/// Marks one request as in flight until dropped.
+pub struct InFlightGuard {
+    drain: Arc<RequestDrain>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.drain.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drain.idle.notify_waiters();
+        }
+    }
+}
+
+impl RequestDrain {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Count a new request, or `None` once draining has started.
+    pub fn enter(self: &Arc<Self>) -> Option<InFlightGuard> {
+        if self.is_draining() {
+            return None;
+        }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        Some(InFlightGuard {
+            drain: Arc::clone(self),
+        })
+    }
+
+    /// Requests currently being handled.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.is_cancelled()
+    }
+
+    /// Resolves once [`drain`](Self::drain) has been called; used as the
+    /// server's graceful-shutdown signal so it stops accepting connections.
+    pub async fn draining(self: Arc<Self>) {
+        self.draining.cancelled().await;
+    }
+
+    /// Stop accepting requests and wait up to `grace` for in-flight ones to
+    /// finish. Requests still running afterwards are cancelled.
+    ///
+    /// Returns the number of requests that were cancelled.
+    pub async fn drain(&self, grace: Duration) -> usize {
+        self.draining.cancel();
+        let idle = async {
+            loop {
+                let notified = self.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        let _ = tokio::time::timeout(grace, idle).await;
+
+        let remaining = self.in_flight();
+        if remaining > 0 {
+            self.cancel.cancel();
+        }
+        remaining
+    }
+}
+
+/// Wait for Ctrl+C, then [`drain`](RequestDrain::drain) with `grace`.
+pub async fn drain_on_ctrl_c(drain: &RequestDrain, grace: Duration) {
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+    tracing::info!(
+        in_flight = drain.in_flight(),
+        "gateway shutting down; draining in-flight requests"
+    );
+    let cancelled = drain.drain(grace).await;
+    if cancelled > 0 {
+        tracing::warn!(
+            "cancelled {cancelled} request(s) still running after the shutdown grace period"
+        );
+    }
+}
+
+/// Middleware: reject requests while draining and cancel in-flight ones when
+/// the grace period runs out.
+pub async fn track_requests(
+    State(drain): State<Arc<RequestDrain>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(guard) = drain.enter() else {
+        return unavailable("Gateway is shutting down");
+    };
+    let response = tokio::select! {
+        response = next.run(request) => response,
+        () = drain.cancel.cancelled() => unavailable("Request cancelled: gateway shut down"),
+    };
+    if response.body().size_hint().exact().is_some() {
+        return response;
+    }
+    // A streamed body outlives the handler: it owns the guard, and ends when
+    // the grace period runs out.
+    let cancel = drain.cancel.clone();
+    response.map(|body| {
+        let stream = body
+            .into_data_stream()
+            .take_until(async move { cancel.cancelled().await })
+            .map(move |chunk| {
+                let _ = &guard;
+                chunk
+            });
+        Body::from_stream(stream)
+    })
+}
+
+fn unavailable(message: &str) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::Service;
+
+    fn app(drain: &Arc<RequestDrain>, delay: Duration) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(move || async move {
+                    tokio::time::sleep(delay).await;
+                    "done"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                drain.clone(),
+                track_requests,
+            ))
+    }
+
+    async fn status(mut app: Router) -> StatusCode {
+        let request = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+        app.call(request).await.unwrap().status()
+    }
+
+    async fn wait_for_in_flight(drain: &RequestDrain, count: usize) {
+        while drain.in_flight() != count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn in_flight_request_completes_during_grace_while_new_one_is_rejected() {
+        let drain = RequestDrain::new();
+        let app = app(&drain, Duration::from_millis(100));
+
+        let in_flight = tokio::spawn(status(app.clone()));
+        wait_for_in_flight(&drain, 1).await;
+
+        let draining = tokio::spawn({
+            let drain = drain.clone();
+            async move { drain.drain(Duration::from_secs(5)).await }
+        });
+        while !drain.is_draining() {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(status(app).await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(in_flight.await.unwrap(), StatusCode::OK);
+        assert_eq!(draining.await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn streamed_body_counts_as_in_flight_until_sent() {
+        use http_body_util::BodyExt;
+
+        let drain = RequestDrain::new();
+        let mut app = Router::new()
+            .route(
+                "/stream",
+                get(|| async {
+                    let chunks = futures_util::stream::iter(["data: a\n\n", "data: b\n\n"]).then(
+                        |chunk| async move {
+                            tokio::time::sleep(Duration::from_millis(20)).await;
+                            Ok::<_, std::convert::Infallible>(chunk)
+                        },
+                    );
+                    Body::from_stream(chunks)
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                drain.clone(),
+                track_requests,
+            ));
+
+        let request = Request::builder()
+            .uri("/stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        // The handler has returned, but the body is still streaming.
+        assert_eq!(drain.in_flight(), 1);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"data: a\n\ndata: b\n\n");
+        assert_eq!(drain.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn requests_outliving_grace_are_cancelled() {
+        let drain = RequestDrain::new();
+        let app = app(&drain, Duration::from_secs(30));
+
+        let in_flight = tokio::spawn(status(app));
+        wait_for_in_flight(&drain, 1).await;
+
+        assert_eq!(drain.drain(Duration::from_millis(20)).await, 1);
+        assert_eq!(in_flight.await.unwrap(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(drain.in_flight(), 0);
+    }
+}
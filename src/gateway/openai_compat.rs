@@ -511,11 +511,14 @@ fn handle_streaming(
     }
 
     // Provider supports native streaming
-    let provider_stream = state.provider.stream_chat_with_history(
-        &messages,
-        &model,
-        temperature,
-        StreamOptions::new(true),
+    let provider_stream = super::with_gateway_stream_timeouts(
+        &state,
+        state.provider.stream_chat_with_history(
+            &messages,
+            &model,
+            temperature,
+            StreamOptions::new(true),
+        ),
     );
 
     let model_for_stream = model.clone();
@@ -25,9 +25,18 @@
 //! is provided for backward compatibility and may be deprecated once all callers
 //! have migrated to the native endpoint.
 
+use super::context_budget::{
+    build_enriched_message, context_token_budget_for, dedup_context_against_memory,
+    CharHeuristicEstimator,
+};
+use super::stream_limit::STREAM_RETRY_AFTER_SECS;
 use super::{
-    client_key_from_request, run_gateway_chat_with_tools, sanitize_gateway_response, AppState,
-    RATE_LIMIT_WINDOW_SECS,
+    client_key_from_request, is_server_busy, run_gateway_chat_with_system_prompt,
+    run_gateway_chat_with_tools, sanitize_gateway_response, AppState, RATE_LIMIT_WINDOW_SECS,
+};
+use crate::agent::loop_::recall_context_entries;
+use crate::config::{
+    ApiChatContextOverflow, CompatStreamGranularity, GatewayConfig, StreamLimitPolicy,
 };
 use crate::memory::MemoryCategory;
 use crate::providers;
@@ -38,8 +47,9 @@ use axum::{
     response::{IntoResponse, Json},
 };
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::net::SocketAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -62,12 +72,112 @@ pub struct ApiChatBody {
     /// semantic memory might not surface (e.g., the last few exchanges).
     #[serde(default)]
     pub context: Vec<String>,
+
+    /// Optional system prompt for this session, replacing the global one.
+    /// Remembered per `session_id`, so it only needs to be sent once.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+
+    /// Optional tool allowlist for this session. Only the first one sent for
+    /// a `session_id` is kept, and `gateway.session_tool_allowlists` wins.
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+/// Maximum context lines injected ahead of the current message
+/// (`/api/chat` `context` or the compat shim's `messages[]` history).
+const MAX_CONTEXT_MESSAGES: usize = 10;
+
+/// Enforce `gateway.api_chat_max_context_lines` on an `/api/chat` `context`
+/// array: keep the most recent lines or reject, per `overflow`.
+fn cap_api_chat_context(
+    mut context: Vec<String>,
+    max_lines: usize,
+    overflow: ApiChatContextOverflow,
+) -> Result<Vec<String>, String> {
+    if context.len() <= max_lines {
+        return Ok(context);
+    }
+    match overflow {
+        ApiChatContextOverflow::Reject => Err(format!(
+            "context has {} lines (max {max_lines})",
+            context.len()
+        )),
+        ApiChatContextOverflow::Truncate => {
+            tracing::warn!(
+                lines = context.len(),
+                max = max_lines,
+                "/api/chat context too long; keeping the most recent lines"
+            );
+            context.drain(..context.len() - max_lines);
+            Ok(context)
+        }
+    }
+}
+
+/// Trim `session_id` and check it is safe to use in memory scoping and
+/// storage keys: at most `max_len` characters from `[A-Za-z0-9._:@-]`, with
+/// no `..`. Blank ids count as absent.
+fn validate_session_id(session_id: Option<&str>, max_len: usize) -> Result<Option<&str>, String> {
+    let Some(id) = session_id.map(str::trim).filter(|id| !id.is_empty()) else {
+        return Ok(None);
+    };
+    if id.len() > max_len {
+        return Err(format!(
+            "session_id is {} characters (max {max_len})",
+            id.len()
+        ));
+    }
+    let safe = id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | ':' | '@' | '-'));
+    if !safe || id.contains("..") {
+        return Err(
+            "session_id may only contain letters, digits, '.', '_', ':', '@' and '-'".to_string(),
+        );
+    }
+    Ok(Some(id))
 }
 
 fn api_chat_memory_key() -> String {
     format!("api_chat_msg_{}", Uuid::new_v4())
 }
 
+fn api_chat_reply_memory_key() -> String {
+    format!("api_chat_reply_{}", Uuid::new_v4())
+}
+
+/// `memory.auto_save`: store the `/api/chat` user message.
+pub(super) async fn auto_save_api_chat_message(
+    state: &AppState,
+    message: &str,
+    session_id: Option<&str>,
+) {
+    if state.auto_save {
+        let key = api_chat_memory_key();
+        let _ = state
+            .mem
+            .store(&key, message, MemoryCategory::Conversation, session_id)
+            .await;
+    }
+}
+
+/// `memory.auto_save` plus `gateway.api_chat_store_replies`: store the
+/// assistant reply alongside the user message.
+pub(super) async fn auto_save_api_chat_reply(
+    state: &AppState,
+    reply: &str,
+    session_id: Option<&str>,
+) {
+    if state.auto_save && state.config.lock().gateway.api_chat_store_replies {
+        let key = api_chat_reply_memory_key();
+        let _ = state
+            .mem
+            .store(&key, reply, MemoryCategory::Conversation, session_id)
+            .await;
+    }
+}
+
 /// `POST /api/chat` — full agent loop with tools and memory.
 ///
 /// Request:  `{ "message": "...", "session_id": "...", "context": [...] }`
@@ -119,52 +229,103 @@ pub async fn handle_api_chat(
     }
 
     // ── Parse body ──
-    let Json(chat_body) = match body {
+    let Json(mut chat_body) = match body {
         Ok(b) => b,
         Err(e) => {
             tracing::warn!("/api/chat JSON parse error: {e}");
-            let err = serde_json::json!({
-                "error": "Invalid JSON body. Expected: {\"message\": \"...\"}"
-            });
+            let err = serde_json::json!({ "error": api_chat_body_error(&e) });
             return (StatusCode::BAD_REQUEST, Json(err));
         }
     };
 
     let message = chat_body.message.trim();
-    let session_id = chat_body
-        .session_id
-        .as_deref()
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
+    let session_id_max_len = state.config.lock().gateway.session_id_max_len;
+    let session_id = match validate_session_id(chat_body.session_id.as_deref(), session_id_max_len)
+    {
+        Ok(id) => id,
+        Err(e) => {
+            let err = serde_json::json!({ "error": e });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
     if message.is_empty() {
         let err = serde_json::json!({ "error": "Message cannot be empty" });
         return (StatusCode::BAD_REQUEST, Json(err));
     }
 
+    let (max_context_lines, context_overflow) = {
+        let gateway = &state.config.lock().gateway;
+        (
+            gateway.api_chat_max_context_lines,
+            gateway.api_chat_context_overflow,
+        )
+    };
+    chat_body.context = match cap_api_chat_context(
+        std::mem::take(&mut chat_body.context),
+        max_context_lines,
+        context_overflow,
+    ) {
+        Ok(context) => context,
+        Err(e) => {
+            let err = serde_json::json!({ "error": e });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    let system_prompt = match state
+        .session_prompts
+        .resolve(session_id, chat_body.system_prompt.as_deref())
+    {
+        Ok(prompt) => prompt,
+        Err(e) => {
+            let err = serde_json::json!({ "error": e });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
     // ── Auto-save to memory ──
-    if state.auto_save {
-        let key = api_chat_memory_key();
-        let _ = state
-            .mem
-            .store(&key, message, MemoryCategory::Conversation, session_id)
-            .await;
-    }
+    auto_save_api_chat_message(&state, message, session_id).await;
 
     // ── Build enriched message with optional context ──
-    let enriched_message = if chat_body.context.is_empty() {
-        message.to_string()
-    } else {
-        let recent: Vec<&String> = chat_body.context.iter().rev().take(10).rev().collect();
-        let context_block = recent
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        format!(
-            "Recent conversation context:\n{}\n\nCurrent message:\n{}",
-            context_block, message
+    let (dedup_memory, min_relevance_score) = {
+        let config = state.config.lock();
+        (
+            config.gateway.context_dedup_memory,
+            config.memory.min_relevance_score,
         )
     };
+    let token_budget = context_token_budget_for(&state.config.lock().gateway, &state.model);
+    let mut enriched_message = build_enriched_message(
+        &chat_body.context,
+        message,
+        MAX_CONTEXT_MESSAGES,
+        token_budget,
+        &CharHeuristicEstimator,
+    );
+    if dedup_memory && !chat_body.context.is_empty() {
+        // The agent loop recalls memory for the enriched message, so query
+        // with the same text to compare against what it will inject.
+        let recalled: Vec<String> = recall_context_entries(
+            state.mem.as_ref(),
+            &enriched_message,
+            min_relevance_score,
+            session_id,
+        )
+        .await
+        .into_iter()
+        .map(|entry| entry.content)
+        .collect();
+        let context = dedup_context_against_memory(&chat_body.context, &recalled);
+        if context.len() < chat_body.context.len() {
+            enriched_message = build_enriched_message(
+                &context,
+                message,
+                MAX_CONTEXT_MESSAGES,
+                token_budget,
+                &CharHeuristicEstimator,
+            );
+        }
+    }
 
     // ── Observability ──
     let provider_label = state
@@ -191,16 +352,28 @@ pub async fn handle_api_chat(
         });
 
     // ── Run the full agent loop ──
-    match run_gateway_chat_with_tools(&state, &enriched_message, session_id).await {
+    match run_gateway_chat_with_system_prompt(
+        &state,
+        &enriched_message,
+        session_id,
+        system_prompt.as_deref(),
+        chat_body.allowed_tools.as_deref(),
+    )
+    .await
+    {
         Ok(response) => {
             let leak_guard_cfg = state.config.lock().security.outbound_leak_guard.clone();
+            let reply_prefixes = state.config.lock().gateway.reply_strip_prefixes.clone();
             let safe_response = sanitize_gateway_response(
                 &response,
                 state.tools_registry_exec.as_ref(),
                 &leak_guard_cfg,
+                &reply_prefixes,
             );
             let duration = started_at.elapsed();
 
+            auto_save_api_chat_reply(&state, &safe_response, session_id).await;
+
             state
                 .observer
                 .record_event(&crate::observability::ObserverEvent::LlmResponse {
@@ -260,6 +433,11 @@ pub async fn handle_api_chat(
                     cost_usd: None,
                 });
 
+            if is_server_busy(&e) {
+                tracing::warn!("/api/chat rejected: {sanitized}");
+                let err = serde_json::json!({ "error": e.to_string() });
+                return (StatusCode::SERVICE_UNAVAILABLE, Json(err));
+            }
             tracing::error!("/api/chat provider error: {sanitized}");
             let err = serde_json::json!({"error": "LLM request failed"});
             (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
@@ -271,8 +449,9 @@ pub async fn handle_api_chat(
 // /v1/chat/completions — OpenAI-compatible shim (full agent loop)
 // ══════════════════════════════════════════════════════════════════════════════
 
-/// Maximum context messages extracted from the `messages[]` array for injection.
-const MAX_CONTEXT_MESSAGES: usize = 10;
+/// Per-request override for which prior roles are injected as context.
+/// Accepts `user` (user turns only) or `all` (user + assistant turns).
+const CONTEXT_ROLES_HEADER: &str = "X-ZeroClaw-Context-Roles";
 
 /// OpenAI-compatible request body.
 #[derive(Debug, Deserialize)]
@@ -297,8 +476,42 @@ pub struct OaiChatRequest {
     pub stop: Option<serde_json::Value>,
     #[serde(default)]
     pub user: Option<String>,
+    #[serde(default)]
+    pub response_format: Option<OaiResponseFormat>,
 }
 
+/// OpenAI `response_format`. Only `type` is read: `json_object` and
+/// `json_schema` enable JSON mode (a schema is accepted but not enforced),
+/// `text` is the default behaviour.
+#[derive(Debug, Deserialize)]
+pub struct OaiResponseFormat {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl OaiResponseFormat {
+    /// Whether this format asks for JSON output, or an error for an
+    /// unsupported `type`.
+    fn json_mode(&self) -> Result<bool, String> {
+        match self.kind.as_str() {
+            "text" => Ok(false),
+            "json_object" | "json_schema" => Ok(true),
+            other => Err(format!(
+                "Unsupported response_format type '{other}' (expected text, json_object or json_schema)"
+            )),
+        }
+    }
+}
+
+/// Appended to the agent prompt in JSON mode.
+const JSON_MODE_INSTRUCTION: &str = "Respond with a single valid JSON object only. \
+Do not include any prose, explanation, or Markdown code fences.";
+
+/// Appended instead of [`JSON_MODE_INSTRUCTION`] when retrying after a reply
+/// that did not parse.
+const JSON_MODE_RETRY_INSTRUCTION: &str = "Your previous reply was not valid JSON. \
+Respond again with a single valid JSON object only, with no prose or Markdown code fences.";
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OaiMessage {
     pub role: String,
@@ -463,6 +676,25 @@ pub async fn handle_v1_chat_completions_with_tools(
         return (StatusCode::BAD_REQUEST, Json(err)).into_response();
     }
 
+    let json_mode = match request
+        .response_format
+        .as_ref()
+        .map(OaiResponseFormat::json_mode)
+    {
+        None => false,
+        Some(Ok(json_mode)) => json_mode,
+        Some(Err(e)) => {
+            let err = serde_json::json!({
+                "error": {
+                    "message": e,
+                    "type": "invalid_request_error",
+                    "code": "invalid_response_format"
+                }
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+
     // ── Extract last user message + context ──
     let last_user_msg = request
         .messages
@@ -485,50 +717,68 @@ pub async fn handle_v1_chat_completions_with_tools(
         }
     };
 
+    let user_only_context = match headers
+        .get(CONTEXT_ROLES_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("user") => true,
+        Some("all") => false,
+        _ => state.config.lock().gateway.compat_context_user_only,
+    };
+
     // Build context from conversation history (exclude the last user message)
-    let context_messages: Vec<String> = request
-        .messages
-        .iter()
-        .rev()
-        .skip(1)
-        .rev()
-        .filter(|m| m.role == "user" || m.role == "assistant")
-        .map(|m| {
-            let role_label = if m.role == "user" {
-                "User"
-            } else {
-                "Assistant"
-            };
-            format!("{}: {}", role_label, m.content)
-        })
-        .collect();
+    let context_messages = compat_context_messages(&request.messages, user_only_context);
+
+    let token_budget = context_token_budget_for(&state.config.lock().gateway, &state.model);
+    let enriched_message = build_enriched_message(
+        &context_messages,
+        &message,
+        MAX_CONTEXT_MESSAGES,
+        token_budget,
+        &CharHeuristicEstimator,
+    );
 
-    let enriched_message = if context_messages.is_empty() {
-        message.clone()
-    } else {
-        let recent: Vec<&String> = context_messages
-            .iter()
-            .rev()
-            .take(MAX_CONTEXT_MESSAGES)
-            .rev()
-            .collect();
-        let context_block = recent
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<&str>>()
-            .join("\n");
-        format!(
-            "Recent conversation context:\n{}\n\nCurrent message:\n{}",
-            context_block, message
-        )
+    let is_stream = request.stream.unwrap_or(false);
+    let session_id_max_len = state.config.lock().gateway.session_id_max_len;
+    let session_id = match validate_session_id(request.user.as_deref(), session_id_max_len) {
+        Ok(id) => id,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": {
+                    "message": e.replace("session_id", "user"),
+                    "type": "invalid_request_error",
+                    "code": "invalid_user"
+                }
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
     };
 
-    let is_stream = request.stream.unwrap_or(false);
-    let session_id = request
-        .user
-        .as_deref()
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
+    // ── Streaming connection cap ──
+    let mut stream_permit = None;
+    if is_stream {
+        let (max_streams, policy) = {
+            let config = state.config.lock();
+            (
+                config.gateway.max_concurrent_streams,
+                config.gateway.stream_limit_policy,
+            )
+        };
+        stream_permit = state.streams.try_acquire(max_streams);
+        if stream_permit.is_none() {
+            tracing::warn!(
+                max_streams,
+                ?policy,
+                "/v1/chat/completions (compat) stream limit reached"
+            );
+            if policy == StreamLimitPolicy::Reject {
+                return stream_limit_response();
+            }
+        }
+    }
+
     let request_id = format!("chatcmpl-{}", Uuid::new_v4().to_string().replace('-', ""));
     let created = unix_timestamp();
 
@@ -566,19 +816,263 @@ pub async fn handle_v1_chat_completions_with_tools(
         });
 
     tracing::info!(
-        stream = is_stream,
+        stream = stream_permit.is_some(),
         messages_count = request.messages.len(),
         "Processing /v1/chat/completions (compat shim — full agent loop)"
     );
 
-    // ── Run the full agent loop ──
-    let reply = match run_gateway_chat_with_tools(&state, &enriched_message, session_id).await {
+    let model_name = request.model.clone().unwrap_or_else(|| state.model.clone());
+    let session_id = session_id.map(str::to_string);
+    let agent_run = {
+        let state = state.clone();
+        let enriched_message = enriched_message.clone();
+        async move {
+            // Dry-mode echoes are not JSON, so they skip JSON-mode validation.
+            if !json_mode || state.config.lock().gateway.dry_mode {
+                return run_compat_agent(
+                    state,
+                    enriched_message,
+                    session_id,
+                    provider_label,
+                    model_label,
+                    started_at,
+                )
+                .await;
+            }
+            let retries = state.config.lock().gateway.compat_json_mode_retries;
+            run_json_mode(retries, |instruction| {
+                run_compat_agent(
+                    state.clone(),
+                    format!("{enriched_message}\n\n{instruction}"),
+                    session_id.clone(),
+                    provider_label.clone(),
+                    model_label.clone(),
+                    started_at,
+                )
+            })
+            .await
+        }
+    };
+
+    if let Some(permit) = stream_permit {
+        use futures_util::StreamExt;
+
+        // ── Simulated streaming SSE ──
+        // The full agent loop returns a complete response; we chunk it into SSE
+        // format, sending comment heartbeats while the loop is still running.
+        let gateway = state.config.lock().gateway.clone();
+        let options = SimulatedStreamOptions::from_config(&gateway);
+        let body = simulated_sse_stream(agent_run, request_id, created, model_name, options);
+        // The body owns the permit, so the slot frees when the client is done.
+        let body = body.map(move |chunk| {
+            let _ = &permit;
+            chunk
+        });
+        return simulated_sse_response(&gateway, Body::from_stream(body));
+    }
+
+    let reply = match agent_run.await {
+        Ok(reply) => reply,
+        Err(e) => {
+            let err = serde_json::json!({ "error": e.to_error_body() });
+            return (e.status(), Json(err)).into_response();
+        }
+    };
+
+    #[allow(clippy::cast_possible_truncation)]
+    let prompt_tokens = (enriched_message.len() / 4) as u32;
+    #[allow(clippy::cast_possible_truncation)]
+    let completion_tokens = (reply.len() / 4) as u32;
+
+    // ── Non-streaming JSON ──
+    let response = OaiChatResponse {
+        id: request_id,
+        object: "chat.completion",
+        created,
+        model: model_name,
+        choices: vec![OaiChoice {
+            index: 0,
+            message: OaiMessage {
+                role: "assistant".into(),
+                content: reply,
+            },
+            finish_reason: "stop",
+        }],
+        usage: OaiUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        },
+    };
+    Json(serde_json::to_value(response).unwrap()).into_response()
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// HELPERS
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// `503` for a streaming request beyond `gateway.max_concurrent_streams`.
+fn stream_limit_response() -> axum::response::Response {
+    let err = serde_json::json!({
+        "error": {
+            "message": "Too many streaming responses in progress. Retry later or send \"stream\": false.",
+            "type": "server_error",
+            "code": "too_many_streams"
+        }
+    });
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, STREAM_RETRY_AFTER_SECS.to_string())],
+        Json(err),
+    )
+        .into_response()
+}
+
+/// Client-facing description of a rejected `/api/chat` body.
+///
+/// Includes serde's field/line/column detail so callers can fix the request,
+/// but never echoes values from the body.
+fn api_chat_body_error(rejection: &axum::extract::rejection::JsonRejection) -> String {
+    use axum::extract::rejection::JsonRejection;
+
+    let detail = match rejection {
+        JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+            redact_json_error_literals(&rejection.body_text())
+        }
+        other => other.body_text(),
+    };
+    format!("Invalid JSON body: {detail}. Expected: {{\"message\": \"...\"}}")
+}
+
+/// Replace quoted or backticked literals in a serde error with `<redacted>`.
+///
+/// serde quotes offending values (`invalid type: string "hunter2"`); field
+/// names (`missing field `message``) are kept.
+fn redact_json_error_literals(msg: &str) -> String {
+    let mut out = String::with_capacity(msg.len());
+    let mut rest = msg;
+    while let Some(start) = rest.find(['"', '`']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let Some(len) = rest[start + 1..].find(quote) else {
+            break;
+        };
+        let literal = &rest[start..start + len + 2];
+        out.push_str(&rest[..start]);
+        if quote == '`' && rest[..start].ends_with("field ") {
+            out.push_str(literal);
+        } else {
+            out.push_str("<redacted>");
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Why a compat agent run produced no reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CompatRunError {
+    /// The agent loop failed; carries the sanitized provider error.
+    Provider(String),
+    /// JSON mode was requested and no attempt produced a valid JSON object.
+    InvalidJson,
+    /// Too many agent loops were running and the wait queue was full.
+    Busy,
+}
+
+impl CompatRunError {
+    /// OpenAI-style `error` object returned to the client. The provider
+    /// error text is logged, never returned.
+    fn to_error_body(&self) -> serde_json::Value {
+        match self {
+            Self::Provider(_) => serde_json::json!({
+                "message": "LLM request failed",
+                "type": "server_error",
+                "code": "provider_error"
+            }),
+            Self::InvalidJson => serde_json::json!({
+                "message": "Model reply was not valid JSON",
+                "type": "server_error",
+                "code": "invalid_json_output"
+            }),
+            Self::Busy => serde_json::json!({
+                "message": crate::agent::loop_limit::ServerBusy.to_string(),
+                "type": "server_error",
+                "code": "server_busy"
+            }),
+        }
+    }
+
+    /// HTTP status for a non-streamed response.
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Busy => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Provider(_) | Self::InvalidJson => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Run `run` in JSON mode, retrying up to `retries` times while the reply
+/// does not parse as a JSON object.
+///
+/// `run` receives the instruction to append to the prompt for each attempt.
+/// Returns the JSON text with surrounding whitespace and code fences removed.
+async fn run_json_mode<F, Fut>(retries: u32, mut run: F) -> Result<String, CompatRunError>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<String, CompatRunError>>,
+{
+    let mut instruction = JSON_MODE_INSTRUCTION;
+    for attempt in 0..=retries {
+        let reply = run(instruction).await?;
+        if let Some(json) = json_object_reply(&reply) {
+            return Ok(json.to_string());
+        }
+        tracing::warn!(
+            attempt,
+            "/v1/chat/completions (compat) JSON mode reply did not parse as a JSON object"
+        );
+        instruction = JSON_MODE_RETRY_INSTRUCTION;
+    }
+    Err(CompatRunError::InvalidJson)
+}
+
+/// The JSON object in `reply`, tolerating surrounding whitespace and a
+/// Markdown code fence, or `None` if it does not parse as an object.
+fn json_object_reply(reply: &str) -> Option<&str> {
+    let trimmed = reply.trim();
+    let body = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Object(_)) => Some(body),
+        _ => None,
+    }
+}
+
+/// Run the full agent loop for the compat shim, recording observer events.
+///
+/// Returns the sanitized reply, or the sanitized provider error.
+async fn run_compat_agent(
+    state: AppState,
+    message: String,
+    session_id: Option<String>,
+    provider_label: String,
+    model_label: String,
+    started_at: Instant,
+) -> Result<String, CompatRunError> {
+    match run_gateway_chat_with_tools(&state, &message, session_id.as_deref()).await {
         Ok(response) => {
             let leak_guard_cfg = state.config.lock().security.outbound_leak_guard.clone();
+            let reply_prefixes = state.config.lock().gateway.reply_strip_prefixes.clone();
             let safe = sanitize_gateway_response(
                 &response,
                 state.tools_registry_exec.as_ref(),
                 &leak_guard_cfg,
+                &reply_prefixes,
             );
             let duration = started_at.elapsed();
 
@@ -606,7 +1100,7 @@ pub async fn handle_v1_chat_completions_with_tools(
                     cost_usd: None,
                 });
 
-            safe
+            Ok(safe)
         }
         Err(e) => {
             let duration = started_at.elapsed();
@@ -636,121 +1130,320 @@ pub async fn handle_v1_chat_completions_with_tools(
                     cost_usd: None,
                 });
 
+            if is_server_busy(&e) {
+                tracing::warn!("/v1/chat/completions (compat) rejected: {sanitized}");
+                return Err(CompatRunError::Busy);
+            }
             tracing::error!("/v1/chat/completions (compat) provider error: {sanitized}");
-            let err = serde_json::json!({
-                "error": {
-                    "message": "LLM request failed",
-                    "type": "server_error",
-                    "code": "provider_error"
-                }
-            });
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response();
+            Err(CompatRunError::Provider(sanitized))
         }
-    };
+    }
+}
 
-    let model_name = request.model.unwrap_or_else(|| state.model.clone());
+/// Split a finished reply into streamed content pieces.
+///
+/// Concatenating the pieces always yields `reply`; whitespace stays attached
+/// to the preceding word or sentence.
+fn split_reply(
+    reply: &str,
+    granularity: CompatStreamGranularity,
+    chunk_chars: usize,
+) -> Vec<String> {
+    let mut pieces = match granularity {
+        CompatStreamGranularity::Single => vec![reply.to_string()],
+        CompatStreamGranularity::Words => split_at_boundaries(reply, |piece, next| {
+            piece.ends_with(char::is_whitespace) && !next.is_whitespace()
+        }),
+        CompatStreamGranularity::Sentences => split_at_boundaries(reply, |piece, next| {
+            piece.ends_with(char::is_whitespace)
+                && !next.is_whitespace()
+                && piece.trim_end().ends_with(['.', '!', '?'])
+        }),
+        CompatStreamGranularity::Chars => reply
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(chunk_chars.max(1))
+            .map(|chunk| chunk.iter().collect())
+            .collect(),
+    };
+    if pieces.is_empty() {
+        pieces.push(String::new());
+    }
+    pieces
+}
 
-    #[allow(clippy::cast_possible_truncation)]
-    let prompt_tokens = (enriched_message.len() / 4) as u32;
-    #[allow(clippy::cast_possible_truncation)]
-    let completion_tokens = (reply.len() / 4) as u32;
+/// Start a new piece before `next` whenever `boundary(current_piece, next)`.
+fn split_at_boundaries(reply: &str, boundary: impl Fn(&str, char) -> bool) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for c in reply.chars() {
+        if !current.is_empty() && boundary(&current, c) {
+            pieces.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
 
-    if is_stream {
-        // ── Simulated streaming SSE ──
-        // The full agent loop returns a complete response; we chunk it into SSE format.
-        let role_chunk = OaiStreamChunk {
-            id: request_id.clone(),
+/// Render a finished compat reply as SSE events: the role chunk, one content
+/// chunk per piece, then the stop chunk followed by `[DONE]`.
+fn render_sse_reply(
+    pieces: Vec<String>,
+    request_id: &str,
+    created: u64,
+    model_name: &str,
+) -> Vec<String> {
+    let event = |delta: OaiDelta, finish_reason: Option<&'static str>| {
+        let chunk = OaiStreamChunk {
+            id: request_id.to_string(),
             object: "chat.completion.chunk",
             created,
-            model: model_name.clone(),
+            model: model_name.to_string(),
             choices: vec![OaiStreamChoice {
                 index: 0,
-                delta: OaiDelta {
-                    role: Some("assistant"),
-                    content: None,
-                },
-                finish_reason: None,
+                delta,
+                finish_reason,
             }],
         };
+        format!(
+            "data: {}\n\n",
+            serde_json::to_string(&chunk).unwrap_or_else(|_| "{}".into())
+        )
+    };
 
-        let content_chunk = OaiStreamChunk {
-            id: request_id.clone(),
-            object: "chat.completion.chunk",
-            created,
-            model: model_name.clone(),
-            choices: vec![OaiStreamChoice {
-                index: 0,
-                delta: OaiDelta {
-                    role: None,
-                    content: Some(reply),
-                },
-                finish_reason: None,
-            }],
-        };
+    let mut events = Vec::with_capacity(pieces.len() + 2);
+    events.push(event(
+        OaiDelta {
+            role: Some("assistant"),
+            content: None,
+        },
+        None,
+    ));
+    for piece in pieces {
+        events.push(event(
+            OaiDelta {
+                role: None,
+                content: Some(piece),
+            },
+            None,
+        ));
+    }
+    let mut stop = event(
+        OaiDelta {
+            role: None,
+            content: None,
+        },
+        Some("stop"),
+    );
+    stop.push_str("data: [DONE]\n\n");
+    events.push(stop);
+    events
+}
 
-        let stop_chunk = OaiStreamChunk {
-            id: request_id,
-            object: "chat.completion.chunk",
-            created,
-            model: model_name,
-            choices: vec![OaiStreamChoice {
-                index: 0,
-                delta: OaiDelta {
-                    role: None,
-                    content: None,
-                },
-                finish_reason: Some("stop"),
-            }],
-        };
+/// Render a failed compat run as an SSE error event.
+fn render_sse_error(error: &CompatRunError) -> String {
+    let err = serde_json::json!({ "error": error.to_error_body() });
+    format!("data: {err}\n\ndata: [DONE]\n\n")
+}
 
-        let mut output = String::new();
-        output.push_str("data: ");
-        output.push_str(&serde_json::to_string(&role_chunk).unwrap_or_else(|_| "{}".into()));
-        output.push_str("\n\n");
-        output.push_str("data: ");
-        output.push_str(&serde_json::to_string(&content_chunk).unwrap_or_else(|_| "{}".into()));
-        output.push_str("\n\n");
-        output.push_str("data: ");
-        output.push_str(&serde_json::to_string(&stop_chunk).unwrap_or_else(|_| "{}".into()));
-        output.push_str("\n\n");
-        output.push_str("data: [DONE]\n\n");
-
-        axum::response::Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/event-stream")
-            .header(header::CACHE_CONTROL, "no-cache")
-            .header(header::CONNECTION, "keep-alive")
-            .body(Body::from(output))
-            .unwrap()
-            .into_response()
-    } else {
-        // ── Non-streaming JSON ──
-        let response = OaiChatResponse {
-            id: request_id,
-            object: "chat.completion",
-            created,
-            model: model_name,
-            choices: vec![OaiChoice {
-                index: 0,
-                message: OaiMessage {
-                    role: "assistant".into(),
-                    content: reply,
-                },
-                finish_reason: "stop",
-            }],
-            usage: OaiUsage {
-                prompt_tokens,
-                completion_tokens,
-                total_tokens: prompt_tokens + completion_tokens,
-            },
-        };
-        Json(serde_json::to_value(response).unwrap()).into_response()
+/// Render an SSE error for a compat run that produced no content within
+/// `limit`.
+fn render_sse_timeout(limit: Duration) -> String {
+    let err = serde_json::json!({
+        "error": {
+            "message": format!("no content received within {limit:?}"),
+            "type": "server_error",
+            "code": "timeout"
+        }
+    });
+    format!("data: {err}\n\ndata: [DONE]\n\n")
+}
+
+/// SSE comment sent while the agent loop runs, so proxies do not time out
+/// the connection before the first content chunk.
+const SSE_HEARTBEAT: &str = ": keep-alive\n\n";
+
+/// Simulated-stream settings from `[gateway]`.
+#[derive(Debug, Clone, Copy)]
+struct SimulatedStreamOptions {
+    heartbeat: Duration,
+    granularity: CompatStreamGranularity,
+    chunk_chars: usize,
+    chunk_delay: Duration,
+    first_token_timeout: Option<Duration>,
+}
+
+impl SimulatedStreamOptions {
+    fn from_config(gateway: &GatewayConfig) -> Self {
+        Self {
+            heartbeat: Duration::from_secs(gateway.compat_stream_heartbeat_secs),
+            granularity: gateway.compat_stream_granularity,
+            chunk_chars: gateway.compat_stream_chunk_chars,
+            chunk_delay: Duration::from_millis(gateway.compat_stream_chunk_delay_ms),
+            first_token_timeout: (gateway.first_token_timeout_secs > 0)
+                .then(|| Duration::from_secs(gateway.first_token_timeout_secs)),
+        }
     }
 }
 
-// ══════════════════════════════════════════════════════════════════════════════
-// HELPERS
-// ══════════════════════════════════════════════════════════════════════════════
+enum SimulatedStreamState<F> {
+    Running {
+        run: std::pin::Pin<Box<F>>,
+        ticker: Option<tokio::time::Interval>,
+        deadline: Option<tokio::time::Instant>,
+        request_id: String,
+        model_name: String,
+    },
+    Sending(std::vec::IntoIter<String>),
+}
+
+/// Body stream for the simulated SSE path.
+///
+/// Emits [`SSE_HEARTBEAT`] every `options.heartbeat` (zero disables) until
+/// `run` finishes, then the rendered reply events with `options.chunk_delay`
+/// between them. If `run` outlasts `options.first_token_timeout`, it is
+/// dropped and only a timeout error event is sent. Dropping the stream drops
+/// `run`.
+fn simulated_sse_stream<F>(
+    run: F,
+    request_id: String,
+    created: u64,
+    model_name: String,
+    options: SimulatedStreamOptions,
+) -> impl futures_util::Stream<Item = Result<axum::body::Bytes, std::io::Error>> + Send + 'static
+where
+    F: Future<Output = Result<String, CompatRunError>> + Send + 'static,
+{
+    let heartbeat = options.heartbeat;
+    let ticker = (!heartbeat.is_zero())
+        .then(|| tokio::time::interval_at(tokio::time::Instant::now() + heartbeat, heartbeat));
+    let deadline = options
+        .first_token_timeout
+        .map(|limit| tokio::time::Instant::now() + limit);
+    let initial = Some(SimulatedStreamState::Running {
+        run: Box::pin(run),
+        ticker,
+        deadline,
+        request_id,
+        model_name,
+    });
+
+    futures_util::stream::unfold(initial, move |state| async move {
+        match state? {
+            SimulatedStreamState::Running {
+                mut run,
+                mut ticker,
+                deadline,
+                request_id,
+                model_name,
+            } => {
+                let finished = {
+                    let heartbeat = async {
+                        match ticker.as_mut() {
+                            Some(ticker) => {
+                                ticker.tick().await;
+                            }
+                            None => std::future::pending().await,
+                        }
+                    };
+                    let expired = async {
+                        match deadline {
+                            Some(at) => tokio::time::sleep_until(at).await,
+                            None => std::future::pending().await,
+                        }
+                    };
+                    tokio::select! {
+                        result = &mut run => Some(result),
+                        () = heartbeat => None,
+                        () = expired => {
+                            let limit = options.first_token_timeout.unwrap_or_default();
+                            let event = render_sse_timeout(limit);
+                            return Some((Ok(axum::body::Bytes::from(event)), None));
+                        }
+                    }
+                };
+                match finished {
+                    Some(Ok(reply)) => {
+                        let pieces = split_reply(&reply, options.granularity, options.chunk_chars);
+                        let mut events =
+                            render_sse_reply(pieces, &request_id, created, &model_name).into_iter();
+                        let first = events.next()?;
+                        Some((
+                            Ok(axum::body::Bytes::from(first)),
+                            Some(SimulatedStreamState::Sending(events)),
+                        ))
+                    }
+                    Some(Err(e)) => Some((Ok(axum::body::Bytes::from(render_sse_error(&e))), None)),
+                    None => Some((
+                        Ok(axum::body::Bytes::from_static(SSE_HEARTBEAT.as_bytes())),
+                        Some(SimulatedStreamState::Running {
+                            run,
+                            ticker,
+                            deadline,
+                            request_id,
+                            model_name,
+                        }),
+                    )),
+                }
+            }
+            SimulatedStreamState::Sending(mut events) => {
+                let next = events.next()?;
+                if !options.chunk_delay.is_zero() {
+                    tokio::time::sleep(options.chunk_delay).await;
+                }
+                Some((
+                    Ok(axum::body::Bytes::from(next)),
+                    Some(SimulatedStreamState::Sending(events)),
+                ))
+            }
+        }
+    })
+}
+
+/// Wrap a simulated SSE body with anti-buffering and the configured
+/// cache/keep-alive headers.
+fn simulated_sse_response(gateway: &GatewayConfig, body: Body) -> axum::response::Response {
+    let mut builder = axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header("x-accel-buffering", "no");
+    let cache_control = gateway.compat_stream_cache_control.trim();
+    if !cache_control.is_empty() {
+        builder = builder.header(header::CACHE_CONTROL, cache_control);
+    }
+    if gateway.compat_stream_keep_alive {
+        builder = builder.header(header::CONNECTION, "keep-alive");
+    }
+    builder
+        .body(body)
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+/// Render prior conversation turns as `Role: content` context lines.
+///
+/// The final message is always skipped (it is the current user message).
+/// With `user_only`, assistant turns are dropped so the model does not
+/// re-read its own earlier output.
+fn compat_context_messages(messages: &[OaiMessage], user_only: bool) -> Vec<String> {
+    messages
+        .iter()
+        .rev()
+        .skip(1)
+        .rev()
+        .filter(|m| m.role == "user" || (!user_only && m.role == "assistant"))
+        .map(|m| {
+            let role_label = if m.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            format!("{}: {}", role_label, m.content)
+        })
+        .collect()
+}
 
 fn unix_timestamp() -> u64 {
     std::time::SystemTime::now()
@@ -767,6 +1460,37 @@ fn unix_timestamp() -> u64 {
 mod tests {
     use super::*;
 
+    async fn api_chat_rejection(body: &'static str) -> String {
+        use axum::extract::FromRequest;
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/chat")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let rejection = Json::<ApiChatBody>::from_request(request, &())
+            .await
+            .err()
+            .expect("body should be rejected");
+        api_chat_body_error(&rejection)
+    }
+
+    #[tokio::test]
+    async fn api_chat_missing_message_error_names_the_field() {
+        let error = api_chat_rejection(r#"{"session_id": "s1"}"#).await;
+        assert!(error.contains("missing field `message`"), "{error}");
+        assert!(error.contains("line 1"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn api_chat_body_error_does_not_echo_values() {
+        let error = api_chat_rejection(r#"{"message": "hi", "context": "sk-secret-value"}"#).await;
+        assert!(!error.contains("sk-secret-value"), "{error}");
+        assert!(error.contains("context"), "{error}");
+        assert!(error.contains("<redacted>"), "{error}");
+    }
+
     #[test]
     fn api_chat_body_deserializes_minimal() {
         let json = r#"{"message": "Hello"}"#;
@@ -774,6 +1498,7 @@ mod tests {
         assert_eq!(body.message, "Hello");
         assert!(body.session_id.is_none());
         assert!(body.context.is_empty());
+        assert!(body.system_prompt.is_none());
     }
 
     #[test]
@@ -781,12 +1506,55 @@ mod tests {
         let json = r#"{
             "message": "What's my schedule?",
             "session_id": "sess-123",
-            "context": ["User: hi", "Assistant: hello"]
+            "context": ["User: hi", "Assistant: hello"],
+            "system_prompt": "You are a support bot."
         }"#;
         let body: ApiChatBody = serde_json::from_str(json).unwrap();
         assert_eq!(body.message, "What's my schedule?");
         assert_eq!(body.session_id.as_deref(), Some("sess-123"));
         assert_eq!(body.context.len(), 2);
+        assert_eq!(
+            body.system_prompt.as_deref(),
+            Some("You are a support bot.")
+        );
+    }
+
+    #[test]
+    fn oversized_api_chat_context_is_truncated_or_rejected_per_policy() {
+        let context: Vec<String> = (0..25).map(|i| format!("User: line {i}")).collect();
+
+        let kept =
+            cap_api_chat_context(context.clone(), 20, ApiChatContextOverflow::Truncate).unwrap();
+        assert_eq!(kept.len(), 20);
+        assert_eq!(kept.first().unwrap(), "User: line 5");
+        assert_eq!(kept.last().unwrap(), "User: line 24");
+
+        let err =
+            cap_api_chat_context(context.clone(), 20, ApiChatContextOverflow::Reject).unwrap_err();
+        assert_eq!(err, "context has 25 lines (max 20)");
+
+        let within = cap_api_chat_context(context, 25, ApiChatContextOverflow::Reject).unwrap();
+        assert_eq!(within.len(), 25);
+    }
+
+    #[test]
+    fn valid_session_id_is_trimmed_and_accepted() {
+        assert_eq!(
+            validate_session_id(Some("  user-42:chat_1@web.app "), 128),
+            Ok(Some("user-42:chat_1@web.app"))
+        );
+        assert_eq!(validate_session_id(Some("   "), 128), Ok(None));
+        assert_eq!(validate_session_id(None, 128), Ok(None));
+    }
+
+    #[test]
+    fn overlong_or_unsafe_session_id_is_rejected() {
+        let err = validate_session_id(Some(&"a".repeat(129)), 128).unwrap_err();
+        assert_eq!(err, "session_id is 129 characters (max 128)");
+
+        for bad in ["../../etc/passwd", "a/b", "tab\there", "sess..1", "naïve"] {
+            assert!(validate_session_id(Some(bad), 128).is_err(), "{bad:?}");
+        }
     }
 
     #[test]
@@ -810,6 +1578,100 @@ mod tests {
         assert_eq!(req.max_tokens, Some(1000));
     }
 
+    #[test]
+    fn oai_request_deserializes_response_format() {
+        let req: OaiChatRequest = serde_json::from_str(
+            r#"{"messages": [{"role": "user", "content": "Hi"}],
+                "response_format": {"type": "json_schema", "json_schema": {"name": "x"}}}"#,
+        )
+        .unwrap();
+        let format = req.response_format.expect("response_format");
+        assert_eq!(format.kind, "json_schema");
+        assert_eq!(format.json_mode(), Ok(true));
+
+        let req: OaiChatRequest =
+            serde_json::from_str(r#"{"messages": [{"role": "user", "content": "Hi"}]}"#).unwrap();
+        assert!(req.response_format.is_none());
+
+        for (kind, expected) in [
+            ("text", Some(false)),
+            ("json_object", Some(true)),
+            ("xml", None),
+        ] {
+            let format = OaiResponseFormat { kind: kind.into() };
+            assert_eq!(format.json_mode().ok(), expected, "{kind}");
+        }
+    }
+
+    #[test]
+    fn json_object_reply_accepts_fenced_objects_only() {
+        assert_eq!(json_object_reply(" {\"a\": 1}\n"), Some(r#"{"a": 1}"#));
+        assert_eq!(
+            json_object_reply("```json\n{\"a\": 1}\n```"),
+            Some(r#"{"a": 1}"#)
+        );
+        assert_eq!(json_object_reply("Sure! {\"a\": 1}"), None);
+        assert_eq!(json_object_reply("[1, 2]"), None);
+        assert_eq!(json_object_reply(""), None);
+    }
+
+    #[tokio::test]
+    async fn json_mode_rejects_non_json_reply_after_retries() {
+        let mut instructions = Vec::new();
+        let result = run_json_mode(1, |instruction| {
+            instructions.push(instruction);
+            async { Ok("Here is your data: a=1".to_string()) }
+        })
+        .await;
+        assert_eq!(result, Err(CompatRunError::InvalidJson));
+        assert_eq!(
+            instructions,
+            [JSON_MODE_INSTRUCTION, JSON_MODE_RETRY_INSTRUCTION]
+        );
+        let body = render_sse_error(&CompatRunError::InvalidJson);
+        assert!(body.contains("invalid_json_output"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn json_mode_retry_recovers_and_provider_errors_are_not_retried() {
+        let mut attempts = 0;
+        let result = run_json_mode(1, |_| {
+            attempts += 1;
+            let reply = if attempts == 1 {
+                "not json"
+            } else {
+                "```\n{\"ok\": true}\n```"
+            };
+            async move { Ok(reply.to_string()) }
+        })
+        .await;
+        assert_eq!(result.as_deref(), Ok(r#"{"ok": true}"#));
+
+        let mut attempts = 0;
+        let result = run_json_mode(3, |_| {
+            attempts += 1;
+            async { Err(CompatRunError::Provider("down".into())) }
+        })
+        .await;
+        assert_eq!(result, Err(CompatRunError::Provider("down".into())));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn busy_run_maps_to_service_unavailable() {
+        let busy = CompatRunError::Busy;
+        assert_eq!(busy.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(busy.to_error_body()["code"], "server_busy");
+        assert_eq!(
+            CompatRunError::Provider("down".into()).status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let err = anyhow::Error::from(crate::agent::loop_limit::ServerBusy);
+        assert!(is_server_busy(&err));
+        assert!(!is_server_busy(&anyhow::anyhow!("provider down")));
+    }
+
     #[test]
     fn oai_response_serializes_correctly() {
         let response = OaiChatResponse {
@@ -919,29 +1781,190 @@ mod tests {
         }"#;
         let req: OaiChatRequest = serde_json::from_str(json).unwrap();
 
-        // Replicate the handler's context extraction logic
-        let context_messages: Vec<String> = req
-            .messages
-            .iter()
-            .rev()
-            .skip(1)
-            .rev()
-            .filter(|m| m.role == "user" || m.role == "assistant")
-            .map(|m| {
-                format!(
-                    "{}: {}",
-                    if m.role == "user" {
-                        "User"
-                    } else {
-                        "Assistant"
-                    },
-                    m.content
-                )
-            })
-            .collect();
+        let context_messages = compat_context_messages(&req.messages, false);
 
         assert_eq!(context_messages.len(), 2);
         assert!(context_messages[0].starts_with("User: first"));
         assert!(context_messages[1].starts_with("Assistant: reply"));
     }
+
+    #[test]
+    fn oai_context_extraction_user_only_drops_assistant_turns() {
+        let json = r#"{
+            "messages": [
+                {"role": "system", "content": "be brief"},
+                {"role": "user", "content": "first"},
+                {"role": "assistant", "content": "reply"},
+                {"role": "user", "content": "second"},
+                {"role": "assistant", "content": "another reply"},
+                {"role": "user", "content": "third"}
+            ]
+        }"#;
+        let req: OaiChatRequest = serde_json::from_str(json).unwrap();
+
+        let context_messages = compat_context_messages(&req.messages, true);
+        let enriched_message = build_enriched_message(
+            &context_messages,
+            "third",
+            MAX_CONTEXT_MESSAGES,
+            None,
+            &CharHeuristicEstimator,
+        );
+
+        assert_eq!(context_messages, vec!["User: first", "User: second"]);
+        assert!(!enriched_message.contains("Assistant:"));
+        assert!(!enriched_message.contains("reply"));
+        assert_eq!(
+            enriched_message,
+            "Recent conversation context:\nUser: first\nUser: second\n\nCurrent message:\nthird"
+        );
+    }
+
+    #[tokio::test]
+    async fn simulated_stream_sends_heartbeats_until_reply() {
+        let run = async {
+            tokio::time::sleep(Duration::from_millis(120)).await;
+            Ok::<_, CompatRunError>("final answer".to_string())
+        };
+        let stream = simulated_sse_stream(
+            run,
+            "chatcmpl-test".into(),
+            0,
+            "test-model".into(),
+            SimulatedStreamOptions {
+                heartbeat: Duration::from_millis(20),
+                ..SimulatedStreamOptions::from_config(&GatewayConfig::default())
+            },
+        );
+        let response = simulated_sse_response(&GatewayConfig::default(), Body::from_stream(stream));
+
+        let headers = response.headers();
+        assert_eq!(headers[header::CONTENT_TYPE], "text/event-stream");
+        assert_eq!(headers["x-accel-buffering"], "no");
+        assert_eq!(headers[header::CACHE_CONTROL], "no-cache");
+        assert_eq!(headers[header::CONNECTION], "keep-alive");
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(body.starts_with(SSE_HEARTBEAT), "{body}");
+        assert!(body.matches(SSE_HEARTBEAT).count() >= 2, "{body}");
+        let reply_at = body.find("final answer").unwrap();
+        assert!(body.rfind(SSE_HEARTBEAT).unwrap() < reply_at);
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[tokio::test]
+    async fn simulated_stream_headers_and_heartbeat_are_configurable() {
+        let gateway = GatewayConfig {
+            compat_stream_heartbeat_secs: 0,
+            compat_stream_cache_control: String::new(),
+            compat_stream_keep_alive: false,
+            ..GatewayConfig::default()
+        };
+        let run = async { Err::<String, _>(CompatRunError::Provider("provider down".to_string())) };
+        let stream = simulated_sse_stream(
+            run,
+            "chatcmpl-test".into(),
+            0,
+            "test-model".into(),
+            SimulatedStreamOptions::from_config(&gateway),
+        );
+        let response = simulated_sse_response(&gateway, Body::from_stream(stream));
+
+        let headers = response.headers();
+        assert_eq!(headers["x-accel-buffering"], "no");
+        assert!(headers.get(header::CACHE_CONTROL).is_none());
+        assert!(headers.get(header::CONNECTION).is_none());
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(!body.contains(SSE_HEARTBEAT));
+        assert!(body.contains("provider_error"));
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[tokio::test]
+    async fn simulated_stream_first_token_timeout_sends_only_error() {
+        let run = async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok::<_, CompatRunError>("too late".to_string())
+        };
+        let stream = simulated_sse_stream(
+            run,
+            "chatcmpl-test".into(),
+            0,
+            "test-model".into(),
+            SimulatedStreamOptions {
+                heartbeat: Duration::ZERO,
+                first_token_timeout: Some(Duration::from_millis(30)),
+                ..SimulatedStreamOptions::from_config(&GatewayConfig::default())
+            },
+        );
+        let bytes = axum::body::to_bytes(Body::from_stream(stream), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+
+        assert!(body.contains(r#""code":"timeout""#), "{body}");
+        assert!(!body.contains("too late"));
+        assert!(!body.contains("chat.completion.chunk"));
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[tokio::test]
+    async fn word_granularity_streams_multiple_content_chunks() {
+        let gateway = GatewayConfig {
+            compat_stream_heartbeat_secs: 0,
+            compat_stream_granularity: CompatStreamGranularity::Words,
+            compat_stream_chunk_delay_ms: 1,
+            ..GatewayConfig::default()
+        };
+        let run = async { Ok::<_, CompatRunError>("the quick brown fox".to_string()) };
+        let stream = simulated_sse_stream(
+            run,
+            "chatcmpl-test".into(),
+            0,
+            "test-model".into(),
+            SimulatedStreamOptions::from_config(&gateway),
+        );
+        let bytes = axum::body::to_bytes(Body::from_stream(stream), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+
+        let contents: Vec<String> = body
+            .split("\n\n")
+            .filter_map(|event| event.strip_prefix("data: "))
+            .filter_map(|data| serde_json::from_str::<serde_json::Value>(data).ok())
+            .filter_map(|chunk| {
+                chunk["choices"][0]["delta"]["content"]
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .collect();
+        assert_eq!(contents, vec!["the ", "quick ", "brown ", "fox"]);
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[test]
+    fn split_reply_pieces_rejoin_to_reply() {
+        let reply = "Hi there. How are you?  Fine!";
+        assert_eq!(
+            split_reply(reply, CompatStreamGranularity::Sentences, 0),
+            vec!["Hi there. ", "How are you?  ", "Fine!"]
+        );
+        assert_eq!(
+            split_reply("abcdefg", CompatStreamGranularity::Chars, 3),
+            vec!["abc", "def", "g"]
+        );
+        assert_eq!(
+            split_reply(reply, CompatStreamGranularity::Single, 0),
+            vec![reply]
+        );
+        assert_eq!(split_reply("", CompatStreamGranularity::Words, 0), vec![""]);
+    }
 }
@@ -0,0 +1,112 @@
+//! Per-session system prompt overrides for `/api/chat`.
+//!
+//! Clients sharing one gateway (e.g. a support bot and a coding bot) can send
+//! `system_prompt` with a `session_id`. The prompt is remembered for that
+//! session, so later requests in the same session reuse it without resending.
+//! Sessions without an override fall back to the global system prompt.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Maximum accepted length of a per-session system prompt, in characters.
+pub const MAX_SESSION_SYSTEM_PROMPT_CHARS: usize = 8_000;
+
+/// Maximum number of sessions with a stored override.
+const MAX_STORED_SESSIONS: usize = 1_024;
+
+/// In-memory map of session ID → system prompt override.
+#[derive(Default)]
+pub struct SessionPromptStore {
+    prompts: Mutex<HashMap<String, String>>,
+}
+
+impl SessionPromptStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the system prompt override for one request.
+    ///
+    /// A supplied prompt is validated and, when a session is given, stored for
+    /// that session (replacing any earlier one). Without a supplied prompt the
+    /// session's stored override is returned, if any.
+    pub fn resolve(
+        &self,
+        session_id: Option<&str>,
+        requested: Option<&str>,
+    ) -> Result<Option<String>, String> {
+        let requested = requested.map(str::trim).filter(|p| !p.is_empty());
+
+        let Some(prompt) = requested else {
+            return Ok(session_id.and_then(|id| self.prompts.lock().get(id).cloned()));
+        };
+
+        let chars = prompt.chars().count();
+        if chars > MAX_SESSION_SYSTEM_PROMPT_CHARS {
+            return Err(format!(
+                "system_prompt is too long ({chars} chars, max {MAX_SESSION_SYSTEM_PROMPT_CHARS})"
+            ));
+        }
+
+        if let Some(id) = session_id {
+            let mut prompts = self.prompts.lock();
+            if prompts.len() < MAX_STORED_SESSIONS || prompts.contains_key(id) {
+                prompts.insert(id.to_string(), prompt.to_string());
+            } else {
+                tracing::warn!(
+                    "session system prompt store is full; not remembering prompt for this session"
+                );
+            }
+        }
+
+        Ok(Some(prompt.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_prompt_and_no_session_uses_global() {
+        let store = SessionPromptStore::new();
+        assert_eq!(store.resolve(None, None).unwrap(), None);
+        assert_eq!(store.resolve(Some("s1"), Some("   ")).unwrap(), None);
+    }
+
+    #[test]
+    fn prompt_is_remembered_per_session() {
+        let store = SessionPromptStore::new();
+        assert_eq!(
+            store
+                .resolve(Some("support"), Some("You are support."))
+                .unwrap(),
+            Some("You are support.".to_string())
+        );
+        assert_eq!(
+            store
+                .resolve(Some("coding"), Some("You are a coder."))
+                .unwrap(),
+            Some("You are a coder.".to_string())
+        );
+
+        assert_eq!(
+            store.resolve(Some("support"), None).unwrap(),
+            Some("You are support.".to_string())
+        );
+        assert_eq!(
+            store.resolve(Some("coding"), None).unwrap(),
+            Some("You are a coder.".to_string())
+        );
+        assert_eq!(store.resolve(Some("other"), None).unwrap(), None);
+    }
+
+    #[test]
+    fn oversized_prompt_is_rejected_and_not_stored() {
+        let store = SessionPromptStore::new();
+        let long = "x".repeat(MAX_SESSION_SYSTEM_PROMPT_CHARS + 1);
+        let err = store.resolve(Some("s1"), Some(&long)).unwrap_err();
+        assert!(err.contains("too long"));
+        assert_eq!(store.resolve(Some("s1"), None).unwrap(), None);
+    }
+}
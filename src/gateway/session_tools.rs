@@ -0,0 +1,104 @@
+//! Per-session tool allowlists for gateway agent loops.
+//!
+//! A multi-tenant gateway can limit what each session may do, e.g. a public
+//! session without hardware control. An allowlist comes from
+//! `gateway.session_tool_allowlists` (keyed by session ID) or from the
+//! `allowed_tools` field of the first `/api/chat` request in a session.
+//! Tools outside the list are neither advertised to the model nor executed.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Maximum number of sessions with a stored allowlist.
+const MAX_STORED_SESSIONS: usize = 1_024;
+
+/// In-memory map of session ID → tools that session may use.
+#[derive(Default)]
+pub struct SessionToolStore {
+    allowlists: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl SessionToolStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the tool allowlist for one request; `None` means unrestricted.
+    ///
+    /// A configured allowlist for the session always wins. Otherwise the
+    /// first allowlist requested for a session is remembered and later
+    /// requests cannot replace it, so a session can't widen its own access.
+    /// Without a session ID a requested allowlist applies to that request only.
+    pub fn resolve(
+        &self,
+        configured: &HashMap<String, Vec<String>>,
+        session_id: Option<&str>,
+        requested: Option<&[String]>,
+    ) -> Option<Vec<String>> {
+        if let Some(tools) = session_id.and_then(|id| configured.get(id)) {
+            return Some(tools.clone());
+        }
+        let Some(id) = session_id else {
+            return requested.map(<[String]>::to_vec);
+        };
+
+        let mut allowlists = self.allowlists.lock();
+        if let Some(tools) = allowlists.get(id) {
+            return Some(tools.clone());
+        }
+        let tools = requested?.to_vec();
+        if allowlists.len() < MAX_STORED_SESSIONS {
+            allowlists.insert(id.to_string(), tools.clone());
+        } else {
+            tracing::warn!(
+                "session tool allowlist store is full; not remembering allowlist for this session"
+            );
+        }
+        Some(tools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| (*name).to_string()).collect()
+    }
+
+    #[test]
+    fn configured_allowlist_wins_over_requested() {
+        let store = SessionToolStore::new();
+        let configured = HashMap::from([("public".to_string(), tools(&["web_fetch"]))]);
+
+        assert_eq!(
+            store.resolve(&configured, Some("public"), Some(&tools(&["gpio_write"]))),
+            Some(tools(&["web_fetch"]))
+        );
+        assert_eq!(store.resolve(&configured, Some("admin"), None), None);
+    }
+
+    #[test]
+    fn first_requested_allowlist_sticks_to_the_session() {
+        let store = SessionToolStore::new();
+        let configured = HashMap::new();
+
+        assert_eq!(
+            store.resolve(&configured, Some("s1"), Some(&tools(&["memory_recall"]))),
+            Some(tools(&["memory_recall"]))
+        );
+        assert_eq!(
+            store.resolve(&configured, Some("s1"), Some(&tools(&["shell"]))),
+            Some(tools(&["memory_recall"]))
+        );
+        assert_eq!(
+            store.resolve(&configured, Some("s1"), None),
+            Some(tools(&["memory_recall"]))
+        );
+        assert_eq!(
+            store.resolve(&configured, None, Some(&tools(&["shell"]))),
+            Some(tools(&["shell"]))
+        );
+        assert_eq!(store.resolve(&configured, None, None), None);
+    }
+}
@@ -0,0 +1,75 @@
+//! Cap on concurrent streaming (`text/event-stream`) responses.
+//!
+//! Each open stream holds an agent run and its buffered reply until the
+//! client disconnects, so a burst of slow readers can pin memory long after
+//! the work is done. Streaming handlers take a [`StreamPermit`] for the
+//! lifetime of the response body; once `gateway.max_concurrent_streams` are
+//! open, `gateway.stream_limit_policy` decides whether further requests get a
+//! plain JSON reply or `503 Service Unavailable`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Seconds sent in `Retry-After` when a stream is rejected.
+pub const STREAM_RETRY_AFTER_SECS: u64 = 5;
+
+/// Counts open streaming responses.
+#[derive(Default)]
+pub struct StreamLimiter {
+    active: AtomicUsize,
+}
+
+/// One open stream; released when dropped.
+pub struct StreamPermit {
+    limiter: Arc<StreamLimiter>,
+}
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        self.limiter.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl StreamLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a stream unless `max` are already open (`0` = unlimited).
+    pub fn try_acquire(self: &Arc<Self>, max: usize) -> Option<StreamPermit> {
+        let opened = self
+            .active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                (max == 0 || active < max).then_some(active + 1)
+            });
+        opened.ok().map(|_| StreamPermit {
+            limiter: Arc::clone(self),
+        })
+    }
+
+    /// Streams currently open.
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permits_are_capped_and_released_on_drop() {
+        let limiter = Arc::new(StreamLimiter::new());
+        let first = limiter.try_acquire(2).unwrap();
+        let _second = limiter.try_acquire(2).unwrap();
+        assert!(limiter.try_acquire(2).is_none());
+        assert_eq!(limiter.active(), 2);
+
+        drop(first);
+        assert!(limiter.try_acquire(2).is_some());
+        assert_eq!(limiter.active(), 1);
+
+        let unlimited: Vec<_> = (0..8).filter_map(|_| limiter.try_acquire(0)).collect();
+        assert_eq!(unlimited.len(), 8);
+    }
+}
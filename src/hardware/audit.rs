@@ -0,0 +1,126 @@
+//! Device command audit log — an append-only JSONL record of every command
+//! the hardware tools send to a device.
+//!
+//! Enabled with `hardware.command_audit_log`. Each line holds the timestamp,
+//! tool, device alias, command, params, and outcome. Writes are async appends;
+//! a full or unwritable disk is logged and never fails the tool call.
+
+use super::protocol::{ZcCommand, ZcResponse};
+use crate::config::HardwareConfig;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Append-only JSONL sink for device commands.
+pub struct CommandAuditLog {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl CommandAuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Build the audit log from config; `None` when auditing is off.
+    /// Relative paths resolve under `workspace_dir`.
+    pub fn from_config(config: &HardwareConfig, workspace_dir: &Path) -> Option<Arc<Self>> {
+        let path = config.command_audit_log.as_ref()?;
+        let path = if path.is_absolute() {
+            path.clone()
+        } else {
+            workspace_dir.join(path)
+        };
+        Some(Arc::new(Self::new(path)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one record for `cmd` sent by `tool` to `device`.
+    pub async fn record(
+        &self,
+        tool: &str,
+        device: &str,
+        cmd: &ZcCommand,
+        outcome: &Result<ZcResponse, String>,
+    ) {
+        let (success, error) = match outcome {
+            Ok(resp) => (resp.ok, resp.error.clone()),
+            Err(e) => (false, Some(e.clone())),
+        };
+        let entry = serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "tool": tool,
+            "device": device,
+            "cmd": cmd.cmd,
+            "params": cmd.params,
+            "success": success,
+            "error": error,
+        });
+
+        if let Err(e) = self.append(&entry).await {
+            tracing::warn!(
+                path = %self.path.display(),
+                "hardware command audit write failed: {e}"
+            );
+        }
+    }
+
+    async fn append(&self, entry: &serde_json::Value) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut options = tokio::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(&self.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn unwritable_path_is_logged_not_fatal() {
+        let tmp = tempfile::tempdir().unwrap();
+        let blocker = tmp.path().join("not-a-dir");
+        std::fs::write(&blocker, b"").unwrap();
+        let audit = CommandAuditLog::new(blocker.join("audit.jsonl"));
+
+        let cmd = ZcCommand::new("gpio_write", json!({"pin": 25, "value": 1}));
+        audit
+            .record("gpio_write", "pico0", &cmd, &Err("transport error".into()))
+            .await;
+        assert!(!blocker.join("audit.jsonl").exists());
+    }
+
+    #[test]
+    fn relative_config_path_resolves_under_workspace() {
+        let config = HardwareConfig {
+            command_audit_log: Some(PathBuf::from("state/hw-audit.jsonl")),
+            ..HardwareConfig::default()
+        };
+        let audit = CommandAuditLog::from_config(&config, Path::new("/ws")).unwrap();
+        assert_eq!(audit.path(), Path::new("/ws/state/hw-audit.jsonl"));
+        assert!(
+            CommandAuditLog::from_config(&HardwareConfig::default(), Path::new("/ws")).is_none()
+        );
+    }
+}
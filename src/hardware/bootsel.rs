@@ -0,0 +1,258 @@
+//! BOOTSEL watcher — notices a Raspberry Pi Pico entering BOOTSEL mode while
+//! the daemon is running.
+//!
+//! In BOOTSEL the RP2040 ROM exposes a UF2 mass-storage volume labelled
+//! `RPI-RP2`. The watcher polls for that mount every
+//! `hardware.bootsel_watch_interval_secs`, logs once per appearance, and — if
+//! `hardware.firmware_bundles` or `hardware.bootsel_auto_flash_uf2` is set —
+//! copies the matching image onto it (see [`super::firmware`]).
+//!
+//! Detection is behind [`MountDetector`] so tests can script a Pico entering
+//! BOOTSEL mid-run without real hardware.
+
+use super::firmware::{board_id_from_info_uf2, FirmwareBundles};
+use crate::config::HardwareConfig;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Volume label of an RP2040 in BOOTSEL mode.
+pub const RPI_RP2_LABEL: &str = "RPI-RP2";
+
+/// File present at the root of every UF2 bootloader volume.
+const INFO_UF2: &str = "INFO_UF2.TXT";
+
+/// Source of RPI-RP2 mount observations.
+pub trait MountDetector: Send + Sync {
+    /// Path of the currently mounted RPI-RP2 volume, if any.
+    fn find_rp2_mount(&self) -> Option<PathBuf>;
+}
+
+/// Detector that checks the usual desktop automount locations.
+pub struct FsMountDetector;
+
+impl MountDetector for FsMountDetector {
+    fn find_rp2_mount(&self) -> Option<PathBuf> {
+        find_rpi_rp2_mount()
+    }
+}
+
+/// Look for an RPI-RP2 volume under the standard automount roots.
+///
+/// A candidate only counts if it contains `INFO_UF2.TXT`, so a stale empty
+/// mountpoint directory is not mistaken for a Pico.
+pub fn find_rpi_rp2_mount() -> Option<PathBuf> {
+    let user = std::env::var("USER").unwrap_or_default();
+    let mut candidates = vec![
+        PathBuf::from("/Volumes").join(RPI_RP2_LABEL),
+        PathBuf::from("/media").join(RPI_RP2_LABEL),
+    ];
+    if !user.is_empty() {
+        candidates.push(PathBuf::from("/media").join(&user).join(RPI_RP2_LABEL));
+        candidates.push(PathBuf::from("/run/media").join(&user).join(RPI_RP2_LABEL));
+    }
+    candidates.into_iter().find(|p| p.join(INFO_UF2).is_file())
+}
+
+/// Debounces raw mount observations into one event per appearance.
+pub struct BootselWatcher<D: MountDetector> {
+    detector: D,
+    last_seen: Option<PathBuf>,
+}
+
+impl<D: MountDetector> BootselWatcher<D> {
+    pub fn new(detector: D) -> Self {
+        Self {
+            detector,
+            last_seen: None,
+        }
+    }
+
+    /// Check once. Returns the mount path only when it newly appeared;
+    /// a mount that stays present is not reported again until it goes away.
+    pub fn poll(&mut self) -> Option<PathBuf> {
+        let current = self.detector.find_rp2_mount();
+        let appeared = match (&current, &self.last_seen) {
+            (Some(now), Some(before)) => now != before,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        self.last_seen.clone_from(&current);
+        if appeared {
+            current
+        } else {
+            None
+        }
+    }
+}
+
+/// Copy `uf2` onto the BOOTSEL volume; the Pico reboots into it when done.
+pub async fn flash_uf2(mount: &Path, uf2: &Path) -> anyhow::Result<()> {
+    let file_name = uf2
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("invalid UF2 path: {}", uf2.display()))?;
+    tokio::fs::copy(uf2, mount.join(file_name)).await?;
+    Ok(())
+}
+
+/// Board reported by the BOOTSEL volume's `INFO_UF2.TXT`, if readable.
+fn detect_board(mount: &Path) -> Option<String> {
+    let info = std::fs::read_to_string(mount.join(INFO_UF2)).ok()?;
+    board_id_from_info_uf2(&info).map(str::to_string)
+}
+
+/// Pick the image for the Pico on `mount` and copy it over. Returns the
+/// flashed image.
+pub async fn flash_detected(
+    mount: &Path,
+    bundles: &FirmwareBundles,
+    board: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    let board = board.map(str::to_string).or_else(|| detect_board(mount));
+    let uf2 = bundles.select(board.as_deref())?;
+    flash_uf2(mount, uf2).await?;
+    Ok(uf2.to_path_buf())
+}
+
+/// Poll for BOOTSEL Picos until the task is aborted.
+pub async fn run(config: HardwareConfig) -> anyhow::Result<()> {
+    let interval = Duration::from_secs(config.bootsel_watch_interval_secs.max(1));
+    run_with_detector(
+        FsMountDetector,
+        interval,
+        FirmwareBundles::from_config(&config),
+        config.bootsel_board,
+    )
+    .await
+}
+
+async fn run_with_detector<D: MountDetector>(
+    detector: D,
+    interval: Duration,
+    bundles: FirmwareBundles,
+    board: Option<String>,
+) -> anyhow::Result<()> {
+    let mut watcher = BootselWatcher::new(detector);
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let Some(mount) = watcher.poll() else {
+            continue;
+        };
+        tracing::info!(mount = %mount.display(), "Pico detected in BOOTSEL mode");
+        if bundles.is_empty() {
+            continue;
+        }
+        match flash_detected(&mount, &bundles, board.as_deref()).await {
+            Ok(uf2) => tracing::info!(
+                mount = %mount.display(),
+                uf2 = %uf2.display(),
+                "auto-flashed UF2 image"
+            ),
+            Err(e) => tracing::warn!(mount = %mount.display(), "auto-flash failed: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Detector that replays a scripted sequence of observations.
+    struct ScriptedDetector {
+        script: Mutex<std::vec::IntoIter<Option<PathBuf>>>,
+    }
+
+    impl ScriptedDetector {
+        fn new(script: Vec<Option<&str>>) -> Self {
+            let script: Vec<Option<PathBuf>> =
+                script.into_iter().map(|p| p.map(PathBuf::from)).collect();
+            Self {
+                script: Mutex::new(script.into_iter()),
+            }
+        }
+    }
+
+    impl MountDetector for ScriptedDetector {
+        fn find_rp2_mount(&self) -> Option<PathBuf> {
+            self.script.lock().unwrap().next().flatten()
+        }
+    }
+
+    #[test]
+    fn pico_entering_bootsel_mid_run_is_reported_once() {
+        let mount = "/media/pi/RPI-RP2";
+        let mut watcher = BootselWatcher::new(ScriptedDetector::new(vec![
+            None,
+            None,
+            Some(mount),
+            Some(mount),
+            Some(mount),
+        ]));
+
+        let events: Vec<Option<PathBuf>> = (0..5).map(|_| watcher.poll()).collect();
+        assert_eq!(
+            events,
+            vec![None, None, Some(PathBuf::from(mount)), None, None]
+        );
+    }
+
+    #[test]
+    fn pico_re_entering_bootsel_is_reported_again() {
+        let mount = "/media/pi/RPI-RP2";
+        let mut watcher =
+            BootselWatcher::new(ScriptedDetector::new(vec![Some(mount), None, Some(mount)]));
+
+        assert!(watcher.poll().is_some());
+        assert!(watcher.poll().is_none());
+        assert!(watcher.poll().is_some());
+    }
+
+    #[tokio::test]
+    async fn auto_flash_copies_uf2_onto_mount() {
+        let mount = tempfile::tempdir().unwrap();
+        let src = tempfile::tempdir().unwrap();
+        let uf2 = src.path().join("firmware.uf2");
+        std::fs::write(&uf2, b"UF2\n").unwrap();
+
+        flash_uf2(mount.path(), &uf2).await.unwrap();
+        assert_eq!(
+            std::fs::read(mount.path().join("firmware.uf2")).unwrap(),
+            b"UF2\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_flash_picks_bundle_for_detected_board() {
+        let mount = tempfile::tempdir().unwrap();
+        std::fs::write(
+            mount.path().join(INFO_UF2),
+            "UF2 Bootloader v3.0\nBoard-ID: RP2350\n",
+        )
+        .unwrap();
+        let src = tempfile::tempdir().unwrap();
+        let rp2040 = src.path().join("rp2040.uf2");
+        let rp2350 = src.path().join("rp2350.uf2");
+        std::fs::write(&rp2040, b"2040").unwrap();
+        std::fs::write(&rp2350, b"2350").unwrap();
+        let bundles = FirmwareBundles::new(
+            [
+                ("rpi-rp2".to_string(), rp2040),
+                ("rp2350".to_string(), rp2350.clone()),
+            ],
+            None,
+        );
+
+        let flashed = flash_detected(mount.path(), &bundles, None).await.unwrap();
+        assert_eq!(flashed, rp2350);
+        assert_eq!(
+            std::fs::read(mount.path().join("rp2350.uf2")).unwrap(),
+            b"2350"
+        );
+
+        let err = flash_detected(mount.path(), &bundles, Some("pico-w"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'pico-w'"), "{err}");
+    }
+}
@@ -0,0 +1,243 @@
+//! Device capabilities tool — live `capabilities` query for a connected device.
+//!
+//! The system-prompt device summary is built once at startup. This tool asks
+//! the firmware directly so the LLM can verify pins and buses before
+//! attempting an operation the board might not support. Queries go through
+//! the same reconnect, audit and rate-limit path as the GPIO tools.
+//!
+//! Wire protocol (ZeroClaw serial JSON):
+//! ```text
+//! capabilities:
+//!   Host → Device:  {"cmd":"capabilities","params":{}}\n
+//!   Device → Host:  {"ok":true,"data":{"gpio":[2,3,25],"led_pin":25,"i2c":true,"spi":false}}\n
+//! ```
+
+use super::device::DeviceRegistry;
+use super::gpio::{send_audited, HardwareSendOptions};
+use super::protocol::ZcCommand;
+use crate::tools::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Bus/peripheral flags reported in the `capabilities` response.
+const CAPABILITY_BUSES: &[&str] = &["i2c", "spi", "uart", "adc", "pwm", "swd"];
+
+/// Tool: query a device's live capabilities (GPIO pins, LED pin, buses).
+///
+/// Read-only — the `capabilities` command has no side effects on the board.
+pub struct DeviceCapabilitiesTool {
+    registry: Arc<RwLock<DeviceRegistry>>,
+    send_options: HardwareSendOptions,
+}
+
+impl DeviceCapabilitiesTool {
+    pub fn new(registry: Arc<RwLock<DeviceRegistry>>) -> Self {
+        Self {
+            registry,
+            send_options: HardwareSendOptions::default(),
+        }
+    }
+
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
+    }
+}
+
+/// Render a `capabilities` response payload as human-readable lines.
+fn format_capabilities(alias: &str, data: &serde_json::Value) -> String {
+    let gpio = data
+        .get("gpio")
+        .and_then(|v| v.as_array())
+        .map(|pins| {
+            pins.iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .filter(|pins| !pins.is_empty())
+        .unwrap_or_else(|| "none reported".to_string());
+    let led_pin = data
+        .get("led_pin")
+        .filter(|v| !v.is_null())
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    let buses: Vec<&str> = CAPABILITY_BUSES
+        .iter()
+        .copied()
+        .filter(|bus| data.get(*bus).and_then(|v| v.as_bool()).unwrap_or(false))
+        .collect();
+    let buses = if buses.is_empty() {
+        "none reported".to_string()
+    } else {
+        buses.join(", ")
+    };
+
+    format!(
+        "{} capabilities:\n  gpio pins: {}\n  led_pin: {}\n  buses: {}",
+        alias, gpio, led_pin, buses
+    )
+}
+
+#[async_trait]
+impl Tool for DeviceCapabilitiesTool {
+    fn name(&self) -> &str {
+        "device_capabilities"
+    }
+
+    fn description(&self) -> &str {
+        "Query a connected device for its live capabilities (GPIO pins, LED pin, supported buses). Use before an operation to verify the hardware supports it."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "device": {
+                    "type": "string",
+                    "description": "Device alias e.g. pico0, arduino0"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        // Resolve device alias and obtain an owned context (Arc-based) before
+        // dropping the registry read guard — avoids holding the lock across async I/O.
+        let (device_alias, ctx) = {
+            let registry = self.registry.read().await;
+            match registry.resolve_device(&args) {
+                Ok(resolved) => resolved,
+                Err(msg) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(msg),
+                    });
+                }
+            }
+            // registry read guard dropped here
+        };
+
+        let cmd = ZcCommand::simple("capabilities");
+
+        let outcome = send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await;
+
+        match outcome {
+            Ok(resp) if resp.ok => Ok(ToolResult {
+                success: true,
+                output: format_capabilities(&device_alias, &resp.data),
+                error: None,
+            }),
+            Ok(resp) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "device '{}' does not support the capabilities query: {}",
+                    device_alias,
+                    resp.error
+                        .unwrap_or_else(|| "device returned ok:false".to_string())
+                )),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{
+        device::DeviceCapabilities, protocol::ZcResponse, test_support::registry_with_mock,
+    };
+
+    #[tokio::test]
+    async fn device_capabilities_reports_live_payload() {
+        let (reg, mock) = registry_with_mock(
+            ZcResponse::success(json!({
+                "gpio": [2, 3, 25],
+                "led_pin": 25,
+                "i2c": true,
+                "spi": false,
+                "pwm": true
+            })),
+            DeviceCapabilities::default(),
+        );
+        let tool = DeviceCapabilitiesTool::new(reg);
+
+        let result = tool.execute(json!({"device": "pico0"})).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            "pico0 capabilities:\n  gpio pins: 2, 3, 25\n  led_pin: 25\n  buses: i2c, pwm"
+        );
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.cmd, "capabilities");
+    }
+
+    #[tokio::test]
+    async fn device_capabilities_auto_selects_single_device() {
+        let (reg, _mock) = registry_with_mock(
+            ZcResponse::success(json!({})),
+            DeviceCapabilities::default(),
+        );
+        let tool = DeviceCapabilitiesTool::new(reg);
+
+        let result = tool.execute(json!({})).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.output.contains("gpio pins: none reported"));
+        assert!(result.output.contains("led_pin: none"));
+    }
+
+    #[tokio::test]
+    async fn device_capabilities_unsupported_query() {
+        let (reg, _mock) = registry_with_mock(
+            ZcResponse::error("unknown command: capabilities"),
+            DeviceCapabilities::default(),
+        );
+        let tool = DeviceCapabilitiesTool::new(reg);
+
+        let result = tool.execute(json!({"device": "pico0"})).await.unwrap();
+
+        assert!(!result.success);
+        let err = result.error.unwrap();
+        assert!(err.contains("does not support the capabilities query"));
+        assert!(err.contains("unknown command"));
+    }
+
+    #[tokio::test]
+    async fn device_capabilities_unknown_device() {
+        let (reg, _mock) = registry_with_mock(
+            ZcResponse::success(json!({})),
+            DeviceCapabilities::default(),
+        );
+        let tool = DeviceCapabilitiesTool::new(reg);
+
+        let result = tool.execute(json!({"device": "arduino0"})).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not found"));
+    }
+}
@@ -4,9 +4,23 @@
 //! by raw `/dev/` paths. The `DeviceRegistry` assigns these aliases at startup
 //! and provides lookup + context building for tool execution.
 
+use super::protocol::ZcCommand;
 use super::transport::Transport;
+use crate::config::{HardwareDeviceIdentity, PeripheralBoardConfig, PeripheralSafePin};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use regex::Regex;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+/// Default baud rate for ZeroClaw serial devices.
+pub const DEFAULT_BAUD: u32 = 115_200;
+
+/// Serial device paths that may appear in transport and connector errors.
+static DEVICE_PATH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"/dev/[^\s:;,'\x22)]+|\bCOM\d+\b").expect("device path regex compilation failed")
+});
 
 // ── DeviceRuntime ─────────────────────────────────────────────────────────────
 
@@ -86,6 +100,20 @@ impl DeviceKind {
     }
 }
 
+impl DeviceKind {
+    /// Highest GPIO number for boards of this kind, when it is fixed.
+    ///
+    /// Arduino and Nucleo families span boards with very different pin
+    /// counts, so they (and `Generic`) report `None`.
+    pub fn default_max_pin(&self) -> Option<u64> {
+        match self {
+            Self::Pico => Some(28),
+            Self::Esp32 => Some(39),
+            Self::Arduino | Self::Nucleo | Self::Generic => None,
+        }
+    }
+}
+
 impl std::fmt::Display for DeviceKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -111,6 +139,21 @@ pub struct DeviceCapabilities {
     pub uart: bool,
     pub adc: bool,
     pub pwm: bool,
+    /// Highest valid GPIO pin number, when known. `None` skips host-side
+    /// pin range checks and leaves validation to the firmware.
+    pub max_pin: Option<u64>,
+}
+
+impl DeviceCapabilities {
+    /// Reject `pin` if it is outside `0..=max_pin` (no-op when unknown).
+    pub fn check_pin(&self, pin: u64) -> Result<(), String> {
+        match self.max_pin {
+            Some(max) if pin > max => Err(format!(
+                "pin {pin} is out of range for this device (valid pins: 0..={max})"
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// A discovered and registered hardware device.
@@ -131,10 +174,17 @@ pub struct Device {
     /// Raw device path (e.g. `"/dev/ttyACM0"`) — internal use only.
     /// Tools MUST NOT use this directly; always go through Transport.
     pub device_path: Option<String>,
+    /// USB serial number read during discovery, when the board reports one.
+    pub serial_number: Option<String>,
+    /// Serial baud rate used for this device's transport.
+    pub baud: u32,
     /// Architecture description (e.g. `"ARM Cortex-M0+"`).
     pub architecture: Option<String>,
     /// Firmware identifier reported by device during ping handshake.
     pub firmware: Option<String>,
+    /// Wire-protocol version negotiated during the ping handshake.
+    /// `None` until a handshake reports one (or for pre-versioning firmware).
+    pub protocol_version: Option<u32>,
 }
 
 impl Device {
@@ -142,6 +192,33 @@ impl Device {
     pub fn port(&self) -> Option<&str> {
         self.device_path.as_deref()
     }
+
+    /// Identity key used to rebind this device to its alias on rescan.
+    pub fn fingerprint(&self, identity: HardwareDeviceIdentity) -> String {
+        device_fingerprint(
+            self.vid.unwrap_or(0),
+            self.pid.unwrap_or(0),
+            self.serial_number.as_deref(),
+            self.device_path.as_deref().unwrap_or_default(),
+            identity,
+        )
+    }
+}
+
+/// `VID:PID:serial`, or `VID:PID@port` when no serial number is known or
+/// `identity` is [`HardwareDeviceIdentity::Port`]. Identical boards only get
+/// distinct keys from their serial numbers (or ports).
+fn device_fingerprint(
+    vid: u16,
+    pid: u16,
+    serial_number: Option<&str>,
+    port: &str,
+    identity: HardwareDeviceIdentity,
+) -> String {
+    match (identity, serial_number) {
+        (HardwareDeviceIdentity::Serial, Some(serial)) => format!("{vid:04x}:{pid:04x}:{serial}"),
+        _ => format!("{vid:04x}:{pid:04x}@{port}"),
+    }
 }
 
 /// Context passed to hardware tools during execution.
@@ -155,6 +232,21 @@ pub struct DeviceContext {
     pub transport: Arc<dyn Transport>,
     /// Device capabilities (gpio, i2c, spi, etc.).
     pub capabilities: DeviceCapabilities,
+    /// Firmware command names replacing the standard ones, from the
+    /// matching `[peripherals].boards` entry's `commands`.
+    pub commands: HashMap<String, String>,
+}
+
+impl DeviceContext {
+    /// `cmd` as this device's firmware expects it: the same params under
+    /// the configured command name, if one replaces `cmd.cmd`.
+    pub fn firmware_command(&self, cmd: &ZcCommand) -> ZcCommand {
+        let mut wire = cmd.clone();
+        if let Some(name) = self.commands.get(&cmd.cmd) {
+            wire.cmd.clone_from(name);
+        }
+        wire
+    }
 }
 
 /// A registered device entry with its transport and capabilities.
@@ -162,6 +254,26 @@ struct RegisteredDevice {
     device: Arc<Device>,
     transport: Option<Arc<dyn Transport>>,
     capabilities: DeviceCapabilities,
+    /// Set when a keepalive ping fails; cleared when a transport is attached.
+    disconnected: bool,
+}
+
+impl RegisteredDevice {
+    /// Install `transport`, copying any protocol version it negotiated onto
+    /// the device record. With `redact_logs` the transport logs the alias
+    /// instead of the path.
+    fn set_transport(&mut self, transport: Arc<dyn Transport>, redact_logs: bool) {
+        transport.set_log_alias(redact_logs.then_some(self.device.alias.as_str()));
+        if let Some(version) = transport.protocol_version() {
+            if self.device.protocol_version != Some(version) {
+                let mut updated = (*self.device).clone();
+                updated.protocol_version = Some(version);
+                self.device = Arc::new(updated);
+            }
+        }
+        self.transport = Some(transport);
+        self.disconnected = false;
+    }
 }
 
 /// Summary string returned by [`DeviceRegistry::prompt_summary`] when no
@@ -169,15 +281,95 @@ struct RegisteredDevice {
 /// duplicating the literal.
 pub const NO_HW_DEVICES_SUMMARY: &str = "No hardware devices connected.";
 
+/// Opens fresh transports when [`DeviceRegistry::reconnect`] brings a
+/// disconnected device back.
+///
+/// `discover()` installs the serial connector; tests install mocks via
+/// [`DeviceRegistry::set_connector`].
+#[async_trait]
+pub trait TransportConnector: Send + Sync {
+    /// Open a transport on `port_path` at `baud` and confirm the firmware
+    /// responds.
+    async fn connect(&self, port_path: &str, baud: u32) -> anyhow::Result<Arc<dyn Transport>>;
+
+    /// Ports currently enumerated with the given USB vendor ID.
+    fn ports_for_vid(&self, vid: u16) -> Vec<ScannedPort>;
+}
+
+/// A serial port found by the discovery scan, before any handshake.
+#[derive(Debug, Clone)]
+pub struct ScannedPort {
+    /// Full port path (e.g. `"/dev/ttyACM0"`).
+    pub port_path: String,
+    /// USB Vendor ID; `0` when unknown, which requires a ping handshake.
+    pub vid: u16,
+    /// USB Product ID; `0` when unknown.
+    pub pid: u16,
+    /// USB serial number, when the board reports one.
+    pub serial_number: Option<String>,
+    /// Board name from the board registry, if VID/PID was recognised.
+    pub board_name: Option<String>,
+    /// Architecture description from the board registry.
+    pub architecture: Option<String>,
+    /// Baud rate to open the port at; see [`board_baud`].
+    pub baud: u32,
+}
+
+impl ScannedPort {
+    /// Identity key of the board on this port; see [`Device::fingerprint`].
+    pub fn fingerprint(&self, identity: HardwareDeviceIdentity) -> String {
+        device_fingerprint(
+            self.vid,
+            self.pid,
+            self.serial_number.as_deref(),
+            &self.port_path,
+            identity,
+        )
+    }
+}
+
+/// Retry schedule for [`DeviceRegistry::reconnect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Total attempts, including the first (at least one is made).
+    pub attempts: u32,
+    /// Wait before the second attempt; doubles after each failure.
+    pub initial_backoff: Duration,
+    /// Upper bound for the wait between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
 /// Registry of discovered devices with stable session aliases.
 ///
 /// - Scans at startup (via `hardware::discover`)
+/// - Picks up hot-plugged and unplugged boards on [`rescan`](Self::rescan)
 /// - Assigns aliases: `pico0`, `pico1`, `arduino0`, `nucleo0`, `device0`, etc.
 /// - Provides alias-based lookup for tool dispatch
 /// - Generates prompt summaries for LLM context
 pub struct DeviceRegistry {
     devices: HashMap<String, RegisteredDevice>,
     alias_counters: HashMap<String, u32>,
+    connector: Option<Arc<dyn TransportConnector>>,
+    preference: Vec<String>,
+    redact_paths: bool,
+    redact_paths_in_logs: bool,
+    reconnect_policy: ReconnectPolicy,
+    /// Concurrent unknown-VID handshakes during [`rescan`](Self::rescan).
+    discovery_parallelism: usize,
+    identity: HardwareDeviceIdentity,
+    /// Configured boards whose `baud` applies on [`rescan`](Self::rescan)
+    /// and whose `commands` apply to every [`context`](Self::context).
+    boards: Vec<PeripheralBoardConfig>,
 }
 
 impl DeviceRegistry {
@@ -186,9 +378,106 @@ impl DeviceRegistry {
         Self {
             devices: HashMap::new(),
             alias_counters: HashMap::new(),
+            connector: None,
+            preference: Vec::new(),
+            redact_paths: false,
+            redact_paths_in_logs: false,
+            reconnect_policy: ReconnectPolicy::default(),
+            discovery_parallelism: 1,
+            identity: HardwareDeviceIdentity::default(),
+            boards: Vec::new(),
+        }
+    }
+
+    /// Install the connector used by [`reconnect`](Self::reconnect) and
+    /// [`reconnect_shared`].
+    pub fn set_connector(&mut self, connector: Arc<dyn TransportConnector>) {
+        self.connector = Some(connector);
+    }
+
+    /// Set the auto-select preference order (aliases or kinds, first wins)
+    /// used when several devices match and no `device` is given.
+    pub fn set_preference(&mut self, preference: Vec<String>) {
+        self.preference = preference;
+    }
+
+    /// Set the fingerprint used to rebind returning devices on
+    /// [`rescan`](Self::rescan).
+    pub fn set_identity(&mut self, identity: HardwareDeviceIdentity) {
+        self.identity = identity;
+    }
+
+    /// Set the configured boards (see [`board_config`]) that supply the
+    /// baud on [`rescan`](Self::rescan) and firmware command names.
+    pub fn set_boards(&mut self, boards: &[PeripheralBoardConfig]) {
+        self.boards = boards.to_vec();
+    }
+
+    /// Set the retry schedule used by [`reconnect`](Self::reconnect).
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect_policy = policy;
+    }
+
+    /// Replace raw device paths with aliases in user-facing errors
+    /// (`errors`) and in info/warn logs (`logs`).
+    pub fn set_path_redaction(&mut self, errors: bool, logs: bool) {
+        self.redact_paths = errors;
+        self.redact_paths_in_logs = logs;
+        for entry in self.devices.values() {
+            if let Some(transport) = &entry.transport {
+                transport.set_log_alias(logs.then_some(entry.device.alias.as_str()));
+            }
         }
     }
 
+    /// Rewrite device paths in `message`, an error about `alias`, to aliases.
+    ///
+    /// Registered paths become their owner's alias; any other `/dev/…` or
+    /// `COMn` path (e.g. a reconnect candidate) becomes `alias`. Returns the
+    /// message unchanged unless error redaction is enabled.
+    pub fn redact_paths(&self, alias: &str, message: &str) -> String {
+        if !self.redact_paths {
+            return message.to_string();
+        }
+        let mut known: Vec<(&str, &str)> = self
+            .devices
+            .values()
+            .filter_map(|e| Some((e.device.device_path.as_deref()?, e.device.alias.as_str())))
+            .collect();
+        // Longest first so `/dev/ttyACM10` is not rewritten as `pico1`+`0`.
+        known.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+        let mut redacted = message.to_string();
+        for (path, owner) in known {
+            redacted = redacted.replace(path, owner);
+        }
+        DEVICE_PATH_REGEX.replace_all(&redacted, alias).into_owned()
+    }
+
+    /// `port` as it should appear in info/warn logs about `alias`.
+    fn log_port<'a>(&self, alias: &'a str, port: &'a str) -> &'a str {
+        if self.redact_paths_in_logs {
+            alias
+        } else {
+            port
+        }
+    }
+
+    /// First of `matching` named by the preference order, by alias or kind.
+    fn preferred_of(&self, matching: &[String]) -> Option<String> {
+        self.preference.iter().find_map(|wanted| {
+            let wanted = wanted.trim();
+            matching
+                .iter()
+                .find(|alias| {
+                    alias.eq_ignore_ascii_case(wanted)
+                        || self
+                            .get_device(alias)
+                            .is_some_and(|d| d.kind.to_string().eq_ignore_ascii_case(wanted))
+                })
+                .cloned()
+        })
+    }
+
     /// Register a discovered device and assign a stable alias.
     ///
     /// Returns the assigned alias (e.g. `"pico0"`).
@@ -218,8 +507,11 @@ impl DeviceRegistry {
             vid,
             pid,
             device_path,
+            serial_number: None,
+            baud: DEFAULT_BAUD,
             architecture,
             firmware: None,
+            protocol_version: None,
         });
 
         self.devices.insert(
@@ -228,6 +520,7 @@ impl DeviceRegistry {
                 device,
                 transport: None,
                 capabilities: DeviceCapabilities::default(),
+                disconnected: false,
             },
         );
 
@@ -245,7 +538,7 @@ impl DeviceRegistry {
         capabilities: DeviceCapabilities,
     ) -> anyhow::Result<()> {
         if let Some(entry) = self.devices.get_mut(alias) {
-            entry.transport = Some(transport);
+            entry.set_transport(transport, self.redact_paths_in_logs);
             entry.capabilities = capabilities;
             Ok(())
         } else {
@@ -267,15 +560,88 @@ impl DeviceRegistry {
                 device: e.device.clone(),
                 transport: t.clone(),
                 capabilities: e.capabilities.clone(),
+                commands: board_config(
+                    &self.boards,
+                    e.device.device_path.as_deref().unwrap_or_default(),
+                    Some(e.device.board_name.as_str()),
+                )
+                .map(|b| b.commands.clone())
+                .unwrap_or_default(),
             })
         })
     }
 
+    /// Mark a device disconnected until a fresh transport is attached
+    /// (via [`reconnect`](Self::reconnect) or [`reconnect_shared`]).
+    ///
+    /// Returns `true` when the flag changed.
+    pub fn mark_disconnected(&mut self, alias: &str) -> bool {
+        match self.devices.get_mut(alias) {
+            Some(entry) if !entry.disconnected => {
+                entry.disconnected = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `alias` has been marked disconnected.
+    pub fn is_marked_disconnected(&self, alias: &str) -> bool {
+        self.devices.get(alias).is_some_and(|e| e.disconnected)
+    }
+
     /// List all registered device aliases.
     pub fn aliases(&self) -> Vec<&str> {
         self.devices.keys().map(|s| s.as_str()).collect()
     }
 
+    /// Drive `alias` to its board's configured `safe_state`, one
+    /// `gpio_write` per pin. Failures are logged, not returned; a no-op
+    /// without a transport or safe pins.
+    pub async fn apply_safe_state(&self, alias: &str) {
+        if let Some(safe) = self.safe_state(alias) {
+            safe.apply().await;
+        }
+    }
+
+    /// The `safe_state` pins of `alias` with the transport to drive them
+    /// over, or `None` without a transport or safe pins. Lets callers
+    /// holding a shared registry release the lock before the sends.
+    pub fn safe_state(&self, alias: &str) -> Option<SafeState> {
+        let ctx = self.context(alias)?;
+        let board = board_config(
+            &self.boards,
+            ctx.device.device_path.as_deref().unwrap_or_default(),
+            Some(ctx.device.board_name.as_str()),
+        )?;
+        if board.safe_state.is_empty() {
+            return None;
+        }
+        Some(SafeState {
+            alias: alias.to_string(),
+            pins: board.safe_state.clone(),
+            ctx,
+        })
+    }
+
+    /// Graceful shutdown: apply each connected device's `safe_state`, then
+    /// drop every transport. Devices stay registered.
+    pub async fn close(&mut self) {
+        let mut connected: Vec<String> = self
+            .devices
+            .iter()
+            .filter(|(_, e)| !e.disconnected && e.transport.is_some())
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        connected.sort();
+        for alias in &connected {
+            self.apply_safe_state(alias).await;
+        }
+        for entry in self.devices.values_mut() {
+            entry.transport = None;
+        }
+    }
+
     /// Return a summary of connected devices for the LLM system prompt.
     pub fn prompt_summary(&self) -> String {
         if self.devices.is_empty() {
@@ -291,7 +657,7 @@ impl DeviceRegistry {
                 .transport
                 .as_ref()
                 .map(|t| {
-                    if t.is_connected() {
+                    if t.is_connected() && !entry.disconnected {
                         "connected"
                     } else {
                         "disconnected"
@@ -314,8 +680,10 @@ impl DeviceRegistry {
     /// Resolve a GPIO-capable device alias from tool arguments.
     ///
     /// If `args["device"]` is provided, uses that alias directly.
-    /// Otherwise, auto-selects the single GPIO-capable device, returning an
-    /// error description if zero or multiple GPIO devices are available.
+    /// Otherwise, auto-selects the single GPIO-capable device — narrowed to
+    /// `args["kind"]` (e.g. `"pico"`) when given — returning an error
+    /// description if zero or multiple GPIO devices match. With a preference
+    /// order set, the first preferred device among several matches is picked.
     ///
     /// On success returns `(alias, DeviceContext)` — both are owned / Arc-based
     /// so the caller can drop the registry lock before doing async I/O.
@@ -323,29 +691,135 @@ impl DeviceRegistry {
         &self,
         args: &serde_json::Value,
     ) -> Result<(String, DeviceContext), String> {
+        let (device_alias, ctx) =
+            self.resolve_matching(args, "GPIO-capable device", |c| c.capabilities.gpio)?;
+
+        // Verify the device advertises GPIO capability.
+        if !ctx.capabilities.gpio {
+            return Err(format!(
+                "device '{}' does not support GPIO; specify a GPIO-capable device",
+                device_alias
+            ));
+        }
+
+        Ok((device_alias, ctx))
+    }
+
+    /// Resolve an I2C-capable device alias from tool arguments.
+    ///
+    /// Same selection rules as [`resolve_gpio_device`](Self::resolve_gpio_device),
+    /// gated on `capabilities.i2c`.
+    pub fn resolve_i2c_device(
+        &self,
+        args: &serde_json::Value,
+    ) -> Result<(String, DeviceContext), String> {
+        let (device_alias, ctx) =
+            self.resolve_matching(args, "I2C-capable device", |c| c.capabilities.i2c)?;
+
+        if !ctx.capabilities.i2c {
+            return Err(format!(
+                "device '{}' does not support I2C; specify an I2C-capable device",
+                device_alias
+            ));
+        }
+
+        Ok((device_alias, ctx))
+    }
+
+    /// Resolve a PWM-capable device alias from tool arguments.
+    ///
+    /// Same selection rules as [`resolve_gpio_device`](Self::resolve_gpio_device),
+    /// gated on `capabilities.pwm`.
+    pub fn resolve_pwm_device(
+        &self,
+        args: &serde_json::Value,
+    ) -> Result<(String, DeviceContext), String> {
+        let (device_alias, ctx) =
+            self.resolve_matching(args, "PWM-capable device", |c| c.capabilities.pwm)?;
+
+        if !ctx.capabilities.pwm {
+            return Err(format!(
+                "device '{}' does not support PWM; specify a PWM-capable device",
+                device_alias
+            ));
+        }
+
+        Ok((device_alias, ctx))
+    }
+
+    /// Resolve any device alias from tool arguments, regardless of capabilities.
+    ///
+    /// If `args["device"]` is provided, uses that alias directly.
+    /// Otherwise, auto-selects the single device with an attached transport
+    /// (of `args["kind"]`, if given), returning an error description if zero
+    /// or multiple devices match.
+    pub fn resolve_device(
+        &self,
+        args: &serde_json::Value,
+    ) -> Result<(String, DeviceContext), String> {
+        self.resolve_matching(args, "connected device", |_| true)
+    }
+
+    /// Shared alias/kind resolution for the `resolve_*` helpers.
+    ///
+    /// `eligible` filters auto-selection candidates; `candidate` names them in
+    /// the "none found" error.
+    fn resolve_matching(
+        &self,
+        args: &serde_json::Value,
+        candidate: &str,
+        eligible: impl Fn(&DeviceContext) -> bool,
+    ) -> Result<(String, DeviceContext), String> {
+        let kind = args
+            .get("kind")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|k| !k.is_empty());
+        let kind_matches = |c: &DeviceContext| {
+            kind.map_or(true, |k| c.device.kind.to_string().eq_ignore_ascii_case(k))
+        };
+
         let device_alias: String = match args.get("device").and_then(|v| v.as_str()) {
             Some(a) => a.to_string(),
             None => {
-                let gpio_aliases: Vec<String> = self
+                let mut matching: Vec<String> = self
                     .aliases()
                     .into_iter()
                     .filter(|a| {
                         self.context(a)
-                            .map(|c| c.capabilities.gpio)
-                            .unwrap_or(false)
+                            .is_some_and(|c| eligible(&c) && kind_matches(&c))
                     })
                     .map(|a| a.to_string())
                     .collect();
-                match gpio_aliases.as_slice() {
-                    [single] => single.clone(),
-                    [] => {
-                        return Err("no GPIO-capable device found; specify \"device\" parameter"
-                            .to_string());
+                matching.sort();
+                let preferred = if matching.len() > 1 {
+                    self.preferred_of(&matching)
+                } else {
+                    None
+                };
+                match (matching.as_slice(), kind, preferred) {
+                    ([single], _, _) => single.clone(),
+                    (_, _, Some(preferred)) => preferred,
+                    ([], None, None) => {
+                        return Err(format!(
+                            "no {candidate} found; specify \"device\" parameter"
+                        ));
                     }
-                    _ => {
+                    ([], Some(k), None) => {
+                        return Err(format!(
+                            "no {candidate} of kind '{k}' found; specify \"device\" parameter"
+                        ));
+                    }
+                    (_, None, None) => {
                         return Err(format!(
                             "multiple devices available ({}); specify \"device\" parameter",
-                            gpio_aliases.join(", ")
+                            matching.join(", ")
+                        ));
+                    }
+                    (_, Some(k), None) => {
+                        return Err(format!(
+                            "multiple {k} devices available ({}); specify \"device\" parameter",
+                            matching.join(", ")
                         ));
                     }
                 }
@@ -359,17 +833,24 @@ impl DeviceRegistry {
             )
         })?;
 
-        // Verify the device advertises GPIO capability.
-        if !ctx.capabilities.gpio {
+        if !kind_matches(&ctx) {
             return Err(format!(
-                "device '{}' does not support GPIO; specify a GPIO-capable device",
-                device_alias
+                "device '{}' is a {} board, not '{}'",
+                device_alias,
+                ctx.device.kind,
+                kind.unwrap_or_default()
             ));
         }
 
         Ok((device_alias, ctx))
     }
 
+    /// Whether any registered device advertises a capability, e.g.
+    /// `|c| c.i2c`.
+    pub fn any_capable(&self, capable: impl Fn(&DeviceCapabilities) -> bool) -> bool {
+        self.devices.values().any(|e| capable(&e.capabilities))
+    }
+
     /// Number of registered devices.
     pub fn len(&self) -> usize {
         self.devices.len()
@@ -415,130 +896,655 @@ impl DeviceRegistry {
     /// 1. Call `discover::scan_serial_devices()` to enumerate port paths + VID/PID.
     /// 2. For each device with a recognised VID: register and attach a transport.
     /// 3. For unknown VID (`0`): attempt a 300 ms ping handshake; register only
-    ///    if the device responds with ZeroClaw firmware.
+    ///    if the device responds with ZeroClaw firmware. Up to `parallelism`
+    ///    handshakes run at once.
     /// 4. Return the populated registry.
     ///
+    /// Ports matching one of `boards` are opened at that board's `baud`
+    /// (see [`board_baud`]); the rest use [`DEFAULT_BAUD`]. With
+    /// `redact_paths_in_logs` the registration logs already name devices by
+    /// alias (see [`set_path_redaction`](Self::set_path_redaction)).
+    ///
     /// Returns an empty registry when no devices are found or the `hardware`
     /// feature is disabled.
     #[cfg(feature = "hardware")]
-    pub async fn discover() -> Self {
-        use super::{
-            discover::scan_serial_devices,
-            serial::{HardwareSerialTransport, DEFAULT_BAUD},
-        };
+    pub async fn discover(
+        parallelism: usize,
+        boards: &[PeripheralBoardConfig],
+        redact_paths_in_logs: bool,
+    ) -> Self {
+        use super::serial::SerialTransportConnector;
 
         let mut registry = Self::new();
+        registry.discovery_parallelism = parallelism;
+        registry.redact_paths_in_logs = redact_paths_in_logs;
+        registry.boards = boards.to_vec();
+        // The connector's `connect` is the ping handshake; on success the
+        // probed transport is reused below.
+        let ports = scanned_serial_ports(boards);
+        let open = serial_port_opener(boards, &ports);
+        registry
+            .register_discovered(
+                ports,
+                Arc::new(SerialTransportConnector::new(boards.to_vec())),
+                open,
+            )
+            .await;
+        registry
+    }
 
-        for info in scan_serial_devices() {
-            let is_known_vid = info.vid != 0;
-
-            // For unknown VIDs, run the ping handshake before registering.
-            // This avoids registering random USB-serial adapters.
-            // If the probe succeeds we reuse the same transport instance below.
-            let probe_transport = if !is_known_vid {
-                let probe = HardwareSerialTransport::new(&info.port_path, DEFAULT_BAUD);
-                if !probe.ping_handshake().await {
-                    tracing::debug!(
-                        port = %info.port_path,
-                        "skipping unknown device: no ZeroClaw firmware response"
-                    );
-                    continue;
-                }
-                Some(probe)
-            } else {
+    /// Populate a registry from scanned `ports`.
+    ///
+    /// Unknown-VID ports must pass `connector.connect` (the ping handshake)
+    /// to be registered; these probes run concurrently, at most
+    /// `parallelism` at a time. Known-VID ports get a transport from `open`
+    /// without probing. Every port is opened or probed at its `baud`.
+    /// Devices are registered in scan order regardless of which probe
+    /// finishes first, so aliases are stable across runs.
+    pub async fn discover_ports(
+        ports: Vec<ScannedPort>,
+        connector: Arc<dyn TransportConnector>,
+        open: impl Fn(&str, u32) -> Arc<dyn Transport>,
+        parallelism: usize,
+    ) -> Self {
+        let mut registry = Self::new();
+        registry.discovery_parallelism = parallelism;
+        registry.register_discovered(ports, connector, open).await;
+        registry
+    }
+
+    /// Install `connector` and register every port of `ports` that opens;
+    /// the body of [`discover_ports`](Self::discover_ports).
+    async fn register_discovered(
+        &mut self,
+        ports: Vec<ScannedPort>,
+        connector: Arc<dyn TransportConnector>,
+        open: impl Fn(&str, u32) -> Arc<dyn Transport>,
+    ) {
+        self.set_connector(connector.clone());
+        let transports = open_scanned_ports(
+            &ports,
+            Some(connector.as_ref()),
+            open,
+            self.discovery_parallelism,
+        )
+        .await;
+        for (info, transport) in ports.into_iter().zip(transports) {
+            if let Some(transport) = transport {
+                self.register_scanned(info, transport);
+            }
+        }
+    }
+
+    /// Re-enumerate serial devices to pick up boards plugged in or removed
+    /// since [`discover`](Self::discover).
+    ///
+    /// See [`rescan_ports`](Self::rescan_ports).
+    #[cfg(feature = "hardware")]
+    pub async fn rescan(&mut self) -> RescanReport {
+        let ports = scanned_serial_ports(&self.boards);
+        let open = serial_port_opener(&self.boards, &ports);
+        self.rescan_ports(ports, open).await
+    }
+
+    /// Reconcile the registry with a fresh scan.
+    ///
+    /// - Ports not owned by a connected device are opened the same way as in
+    ///   [`discover_ports`](Self::discover_ports) (unknown VIDs must pass the
+    ///   installed connector's handshake).
+    /// - A port whose [fingerprint](Device::fingerprint) matches a device
+    ///   marked disconnected is reattached under that device's alias, on
+    ///   whatever path it now has.
+    /// - Any other new port is registered under a fresh alias.
+    /// - Devices whose port is gone, or now holds a board with a different
+    ///   fingerprint, are marked disconnected and their transport dropped,
+    ///   but they stay registered so the alias survives a replug.
+    pub async fn rescan_ports(
+        &mut self,
+        ports: Vec<ScannedPort>,
+        open: impl Fn(&str, u32) -> Arc<dyn Transport>,
+    ) -> RescanReport {
+        let mut report = RescanReport::default();
+        let identity = self.identity;
+        let present = |device: &Device| {
+            let fingerprint = device.fingerprint(identity);
+            ports.iter().any(|p| {
+                device.device_path.as_deref() == Some(p.port_path.as_str())
+                    && p.fingerprint(identity) == fingerprint
+            })
+        };
+
+        let mut vanished: Vec<String> = self
+            .devices
+            .iter()
+            .filter(|(_, e)| !e.disconnected && e.device.device_path.is_some())
+            .filter(|(_, e)| !present(e.device.as_ref()))
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        vanished.sort();
+        for alias in vanished {
+            // The port is gone, but the link may still take a last write.
+            if self.devices[&alias]
+                .transport
+                .as_ref()
+                .is_some_and(|t| t.is_connected())
+            {
+                self.apply_safe_state(&alias).await;
+            }
+            if let Some(entry) = self.devices.get_mut(&alias) {
+                entry.transport = None;
+                entry.disconnected = true;
+            }
+            tracing::info!(alias = %alias, "device unplugged; marked disconnected");
+            report.removed.push(alias);
+        }
+
+        let connected: Vec<&str> = self
+            .devices
+            .values()
+            .filter(|e| !e.disconnected)
+            .filter_map(|e| e.device.device_path.as_deref())
+            .collect();
+        let fresh: Vec<ScannedPort> = ports
+            .into_iter()
+            .filter(|p| !connected.contains(&p.port_path.as_str()))
+            .collect();
+
+        let connector = match self.reconnect_connector() {
+            Ok(connector) => Some(connector),
+            Err(e) => {
+                tracing::debug!(err = %e, "rescan: unknown-VID ports cannot be probed");
                 None
+            }
+        };
+        let transports = open_scanned_ports(
+            &fresh,
+            connector.as_deref(),
+            open,
+            self.discovery_parallelism,
+        )
+        .await;
+
+        for (info, transport) in fresh.into_iter().zip(transports) {
+            let Some(transport) = transport else {
+                continue;
             };
+            match self.returning_alias(&info) {
+                Some(alias) => {
+                    if let Some(entry) = self.devices.get_mut(&alias) {
+                        if entry.device.device_path.as_deref() != Some(info.port_path.as_str())
+                            || entry.device.baud != info.baud
+                        {
+                            let mut updated = (*entry.device).clone();
+                            updated.device_path = Some(info.port_path.clone());
+                            updated.baud = info.baud;
+                            entry.device = Arc::new(updated);
+                        }
+                        entry.set_transport(transport, self.redact_paths_in_logs);
+                    }
+                    let port = self.log_port(&alias, &info.port_path);
+                    tracing::info!(alias = %alias, port = %port, "device replugged");
+                    report.reconnected.push(alias);
+                }
+                None => report.added.push(self.register_scanned(info, transport)),
+            }
+        }
+        // A board that only moved to another path is reported as reconnected.
+        report
+            .removed
+            .retain(|alias| !report.reconnected.contains(alias));
 
-            let board_name = info.board_name.as_deref().unwrap_or("unknown").to_string();
+        report
+    }
 
-            let alias = registry.register(
-                &board_name,
-                if info.vid != 0 { Some(info.vid) } else { None },
-                if info.pid != 0 { Some(info.pid) } else { None },
-                Some(info.port_path.clone()),
-                info.architecture,
-            );
+    /// Alias of the disconnected device with the same fingerprint as `info`,
+    /// if any.
+    fn returning_alias(&self, info: &ScannedPort) -> Option<String> {
+        let fingerprint = info.fingerprint(self.identity);
+        let mut returning: Vec<&Device> = self
+            .devices
+            .values()
+            .filter(|e| e.disconnected && e.device.fingerprint(self.identity) == fingerprint)
+            .map(|e| e.device.as_ref())
+            .collect();
+        // Lowest alias wins if a fallback fingerprint is shared.
+        returning.sort_by(|a, b| a.alias.cmp(&b.alias));
+        returning.first().map(|d| d.alias.clone())
+    }
 
-            // For unknown-VID devices that passed ping: mark as Generic.
-            // (register() will have already set kind = Generic for vid=None)
+    /// Register a scanned port with its opened `transport`, returning the
+    /// new alias.
+    fn register_scanned(&mut self, info: ScannedPort, transport: Arc<dyn Transport>) -> String {
+        let board_name = info.board_name.as_deref().unwrap_or("unknown").to_string();
+
+        // Unknown-VID devices that passed the ping register as Generic
+        // (register() sets kind = Generic for vid=None).
+        let alias = self.register(
+            &board_name,
+            if info.vid != 0 { Some(info.vid) } else { None },
+            if info.pid != 0 { Some(info.pid) } else { None },
+            Some(info.port_path.clone()),
+            info.architecture,
+        );
+        if let Some(entry) = self.devices.get_mut(&alias) {
+            if info.serial_number.is_some() || info.baud != entry.device.baud {
+                let mut updated = (*entry.device).clone();
+                updated.serial_number = info.serial_number;
+                updated.baud = info.baud;
+                entry.device = Arc::new(updated);
+            }
+        }
 
-            let transport: Arc<dyn super::transport::Transport> =
-                if let Some(probe) = probe_transport {
-                    Arc::new(probe)
-                } else {
-                    Arc::new(HardwareSerialTransport::new(&info.port_path, DEFAULT_BAUD))
-                };
-            let caps = DeviceCapabilities {
-                gpio: true, // assume GPIO; Phase 3 will populate via capabilities handshake
-                ..DeviceCapabilities::default()
-            };
-            registry.attach_transport(&alias, transport, caps)
-                .unwrap_or_else(|e| tracing::warn!(alias = %alias, err = %e, "attach_transport: unexpected unknown alias"));
-
-            tracing::info!(
-                alias = %alias,
-                port  = %info.port_path,
-                vid   = %info.vid,
-                "device registered"
+        let caps = DeviceCapabilities {
+            gpio: true, // assume GPIO; Phase 3 will populate via capabilities handshake
+            max_pin: self.get(&alias).and_then(|d| d.kind.default_max_pin()),
+            ..DeviceCapabilities::default()
+        };
+        self.attach_transport(&alias, transport, caps)
+            .unwrap_or_else(|e| tracing::warn!(alias = %alias, err = %e, "attach_transport: unexpected unknown alias"));
+
+        let port = self.log_port(&alias, &info.port_path);
+        tracing::info!(
+            alias = %alias,
+            port  = %port,
+            vid   = %info.vid,
+            "device registered"
+        );
+        alias
+    }
+}
+
+/// Aliases changed by [`DeviceRegistry::rescan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RescanReport {
+    /// Newly registered devices, in scan order.
+    pub added: Vec<String>,
+    /// Devices whose port vanished; now marked disconnected, sorted.
+    pub removed: Vec<String>,
+    /// Disconnected devices reattached under their old alias, in scan order.
+    pub reconnected: Vec<String>,
+}
+
+/// The configured board for a device found on `port_path` (named
+/// `board_name` by the board registry, if recognised).
+///
+/// A configured board applies when its `path` is `port_path`, or, for a
+/// board without a `path`, when its `board` matches `board_name`.
+pub fn board_config<'a>(
+    boards: &'a [PeripheralBoardConfig],
+    port_path: &str,
+    board_name: Option<&str>,
+) -> Option<&'a PeripheralBoardConfig> {
+    boards
+        .iter()
+        .find(|b| b.path.as_deref() == Some(port_path))
+        .or_else(|| {
+            boards.iter().find(|b| {
+                b.path.is_none()
+                    && board_name.is_some_and(|name| b.board.eq_ignore_ascii_case(name))
+            })
+        })
+}
+
+/// Baud rate for a board found on `port_path`: that of its
+/// [`board_config`], falling back to [`DEFAULT_BAUD`].
+pub fn board_baud(
+    boards: &[PeripheralBoardConfig],
+    port_path: &str,
+    board_name: Option<&str>,
+) -> u32 {
+    board_config(boards, port_path, board_name).map_or(DEFAULT_BAUD, |b| b.baud)
+}
+
+/// Serial ports from `discover::scan_serial_devices()`, at the baud
+/// configured for them in `boards`.
+#[cfg(feature = "hardware")]
+pub(super) fn scanned_serial_ports(boards: &[PeripheralBoardConfig]) -> Vec<ScannedPort> {
+    super::discover::scan_serial_devices()
+        .into_iter()
+        .map(|info| ScannedPort {
+            baud: board_baud(boards, &info.port_path, info.board_name.as_deref()),
+            port_path: info.port_path,
+            vid: info.vid,
+            pid: info.pid,
+            serial_number: info.serial_number,
+            board_name: info.board_name,
+            architecture: info.architecture,
+        })
+        .collect()
+}
+
+/// Delay between sending a command to a board found on `port_path` and
+/// reading its reply: that of its [`board_config`], or none.
+pub fn board_post_write_delay(
+    boards: &[PeripheralBoardConfig],
+    port_path: &str,
+    board_name: Option<&str>,
+) -> Duration {
+    board_config(boards, port_path, board_name).map_or(Duration::ZERO, |b| {
+        Duration::from_millis(b.post_write_delay_ms)
+    })
+}
+
+/// Opener for the known-VID entries of `ports`: a transport opened lazily on
+/// first use, with the board's [`board_post_write_delay`].
+#[cfg(feature = "hardware")]
+fn serial_port_opener(
+    boards: &[PeripheralBoardConfig],
+    ports: &[ScannedPort],
+) -> impl Fn(&str, u32) -> Arc<dyn Transport> {
+    use super::serial::HardwareSerialTransport;
+    let delays: HashMap<String, Duration> = ports
+        .iter()
+        .map(|info| {
+            let delay = board_post_write_delay(boards, &info.port_path, info.board_name.as_deref());
+            (info.port_path.clone(), delay)
+        })
+        .collect();
+    move |port, baud| {
+        let delay = delays.get(port).copied().unwrap_or_default();
+        Arc::new(HardwareSerialTransport::new(port, baud).with_post_write_delay(delay))
+    }
+}
+
+/// A transport per entry of `ports`, in order.
+///
+/// Known-VID ports get one from `open`. Unknown-VID ports must pass
+/// `connector.connect` (the ping handshake), at most `parallelism` at a
+/// time; they get `None` when it fails or no connector is given. This avoids
+/// registering random USB-serial adapters.
+async fn open_scanned_ports(
+    ports: &[ScannedPort],
+    connector: Option<&dyn TransportConnector>,
+    open: impl Fn(&str, u32) -> Arc<dyn Transport>,
+    parallelism: usize,
+) -> Vec<Option<Arc<dyn Transport>>> {
+    let mut probed: HashMap<usize, Arc<dyn Transport>> = match connector {
+        Some(connector) => {
+            // Owned candidates: a stream over borrowing iterator adapters is
+            // not `Send` for every lifetime, which breaks spawned callers.
+            let unknown: Vec<(usize, ScannedPort)> = ports
+                .iter()
+                .enumerate()
+                .filter(|(_, info)| info.vid == 0)
+                .map(|(index, info)| (index, info.clone()))
+                .collect();
+            futures_util::stream::iter(unknown)
+                .map(|(index, info)| async move {
+                    match connector.connect(&info.port_path, info.baud).await {
+                        Ok(transport) => Some((index, transport)),
+                        Err(e) => {
+                            tracing::debug!(
+                                port = %info.port_path,
+                                err = %e,
+                                "skipping unknown device: no ZeroClaw firmware response"
+                            );
+                            None
+                        }
+                    }
+                })
+                .buffer_unordered(parallelism.max(1))
+                .filter_map(std::future::ready)
+                .collect()
+                .await
+        }
+        None => HashMap::new(),
+    };
+
+    ports
+        .iter()
+        .enumerate()
+        .map(|(index, info)| {
+            if info.vid != 0 {
+                Some(open(&info.port_path, info.baud))
+            } else {
+                probed.remove(&index)
+            }
+        })
+        .collect()
+}
+
+/// A device's `safe_state` pins, captured by [`DeviceRegistry::safe_state`].
+pub struct SafeState {
+    alias: String,
+    pins: Vec<PeripheralSafePin>,
+    ctx: DeviceContext,
+}
+
+impl SafeState {
+    /// Send one `gpio_write` per pin. Failures are logged, not returned.
+    pub async fn apply(&self) {
+        let (alias, ctx) = (&self.alias, &self.ctx);
+        for safe in &self.pins {
+            let cmd = ZcCommand::new(
+                "gpio_write",
+                serde_json::json!({ "pin": safe.pin, "value": u8::from(safe.value != 0) }),
             );
+            match ctx.transport.send(&ctx.firmware_command(&cmd)).await {
+                Ok(resp) if resp.ok => tracing::info!(
+                    alias = %alias,
+                    pin = safe.pin,
+                    value = safe.value,
+                    "safe state applied"
+                ),
+                Ok(resp) => tracing::warn!(
+                    alias = %alias,
+                    pin = safe.pin,
+                    "failed to apply safe state: {}",
+                    resp.error.unwrap_or_else(|| "device returned ok:false".into())
+                ),
+                Err(e) => tracing::warn!(
+                    alias = %alias,
+                    pin = safe.pin,
+                    "failed to apply safe state: {e}"
+                ),
+            }
         }
+    }
+}
 
-        registry
+/// A reconnect planned under the registry lock: the device's candidate
+/// ports, baud and retry schedule. [`connect`](Self::connect) does the port
+/// I/O and backoff without holding the lock.
+pub struct PendingReconnect {
+    alias: String,
+    /// Caller-supplied port, then the device's path.
+    preferred: Vec<String>,
+    vid: Option<u16>,
+    /// The device's [fingerprint](Device::fingerprint); a same-VID port
+    /// is only a candidate when its board has the same one.
+    fingerprint: String,
+    identity: HardwareDeviceIdentity,
+    baud: u32,
+    /// Paths owned by other registered devices.
+    taken: Vec<String>,
+    connector: Arc<dyn TransportConnector>,
+    policy: ReconnectPolicy,
+}
+
+impl PendingReconnect {
+    /// Retry per the [`ReconnectPolicy`], so a board that is still booting
+    /// is picked up once its firmware answers the handshake. Returns the
+    /// port that connected and its transport.
+    pub async fn connect(&self) -> anyhow::Result<(String, Arc<dyn Transport>)> {
+        let alias = self.alias.as_str();
+        let attempts = self.policy.attempts.max(1);
+        let mut backoff = self.policy.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.connect_once().await {
+                Ok(connected) => return Ok(connected),
+                Err(e) if attempt >= attempts => {
+                    return Err(e.context(format!(
+                        "gave up reconnecting {alias} after {attempts} attempt(s)"
+                    )));
+                }
+                Err(e) => {
+                    tracing::debug!(alias = %alias, attempt, err = %e, "reconnect attempt failed; retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.policy.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// One pass over the candidate ports: the preferred paths, then free
+    /// ports with the same VID whose board has the device's fingerprint
+    /// (boards often re-enumerate on a new path after a USB reset). Without
+    /// a serial number the fingerprint includes the port, so only the
+    /// preferred paths are tried.
+    async fn connect_once(&self) -> anyhow::Result<(String, Arc<dyn Transport>)> {
+        let alias = self.alias.as_str();
+        let mut candidates = self.preferred.clone();
+        if let Some(vid) = self.vid {
+            for info in self.connector.ports_for_vid(vid) {
+                if info.fingerprint(self.identity) == self.fingerprint
+                    && !candidates.contains(&info.port_path)
+                    && !self.taken.contains(&info.port_path)
+                {
+                    candidates.push(info.port_path);
+                }
+            }
+        }
+        if candidates.is_empty() {
+            anyhow::bail!("device {alias} has no port path to reconnect on");
+        }
+
+        let mut last_err = None;
+        for port in candidates {
+            match self.connector.connect(&port, self.baud).await {
+                Ok(transport) => return Ok((port, transport)),
+                Err(e) => {
+                    tracing::debug!(alias = %alias, port = %port, err = %e, "reconnect attempt failed");
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err
+            .unwrap_or_else(|| anyhow::anyhow!("no candidate ports"))
+            .context(format!("could not reconnect {alias}")))
     }
 }
 
 impl DeviceRegistry {
     /// Reconnect a device after reboot/reflash.
     ///
-    /// Drops the old transport, creates a fresh [`HardwareSerialTransport`] for
-    /// the given (or existing) port path, runs the ping handshake to confirm
-    /// ZeroClaw firmware is alive, and re-attaches the transport.
+    /// Drops the old transport and retries per the registry's
+    /// [`ReconnectPolicy`]. Each attempt tries the device's path first, then
+    /// any free port holding a board with the device's
+    /// [fingerprint](Device::fingerprint), updating the path if the OS moved
+    /// it. Ports are opened at the device's `baud`.
     ///
     /// Pass `new_port` when the OS assigned a different path after reboot;
-    /// pass `None` to reuse the device's current path.
-    #[cfg(feature = "hardware")]
+    /// pass `None` to reuse the device's current path. For a registry shared
+    /// behind a lock, use [`reconnect_shared`] so the backoff does not run
+    /// under it.
     pub async fn reconnect(&mut self, alias: &str, new_port: Option<&str>) -> anyhow::Result<()> {
-        use super::serial::{HardwareSerialTransport, DEFAULT_BAUD};
+        let pending = self.prepare_reconnect(alias, new_port)?;
+        // Drop the stale transport.
+        if let Some(entry) = self.devices.get_mut(alias) {
+            entry.transport = None;
+        }
+        let (port, transport) = pending.connect().await?;
+        self.finish_reconnect(alias, &port, transport)
+    }
 
+    /// Plan a reconnect of `alias`; see [`PendingReconnect`]. `new_port`,
+    /// when given, is tried before the device's current path.
+    pub fn prepare_reconnect(
+        &self,
+        alias: &str,
+        new_port: Option<&str>,
+    ) -> anyhow::Result<PendingReconnect> {
+        let connector = self.reconnect_connector()?;
+        let device = self
+            .get_device(alias)
+            .ok_or_else(|| anyhow::anyhow!("unknown device alias: {alias}"))?;
+        let mut preferred: Vec<String> = new_port.map(str::to_string).into_iter().collect();
+        if let Some(path) = &device.device_path {
+            if !preferred.contains(path) {
+                preferred.push(path.clone());
+            }
+        }
+        let taken = self
+            .devices
+            .iter()
+            .filter(|(other, _)| other.as_str() != alias)
+            .filter_map(|(_, e)| e.device.device_path.clone())
+            .collect();
+        Ok(PendingReconnect {
+            alias: alias.to_string(),
+            preferred,
+            vid: device.vid,
+            fingerprint: device.fingerprint(self.identity),
+            identity: self.identity,
+            baud: device.baud,
+            taken,
+            connector,
+            policy: self.reconnect_policy,
+        })
+    }
+
+    /// Attach the transport a [`PendingReconnect`] opened on `port`,
+    /// updating the device path if it moved.
+    pub fn finish_reconnect(
+        &mut self,
+        alias: &str,
+        port: &str,
+        transport: Arc<dyn Transport>,
+    ) -> anyhow::Result<()> {
         let entry = self
             .devices
             .get_mut(alias)
             .ok_or_else(|| anyhow::anyhow!("unknown device alias: {alias}"))?;
-
-        // Determine the port path — prefer the caller's override.
-        let port_path = match new_port {
-            Some(p) => {
-                // Update the device record with the new path.
-                let mut updated = (*entry.device).clone();
-                updated.device_path = Some(p.to_string());
-                entry.device = Arc::new(updated);
-                p.to_string()
-            }
-            None => entry
-                .device
-                .device_path
-                .clone()
-                .ok_or_else(|| anyhow::anyhow!("device {alias} has no port path"))?,
-        };
-
-        // Drop the stale transport.
-        entry.transport = None;
-
-        // Create a fresh transport and verify firmware is alive.
-        let transport = HardwareSerialTransport::new(&port_path, DEFAULT_BAUD);
-        if !transport.ping_handshake().await {
-            anyhow::bail!(
-                "ping handshake failed after reconnect on {port_path} — \
-                 firmware may not be running"
-            );
+        if entry.device.device_path.as_deref() != Some(port) {
+            let mut updated = (*entry.device).clone();
+            updated.device_path = Some(port.to_string());
+            entry.device = Arc::new(updated);
         }
-
-        entry.transport = Some(Arc::new(transport) as Arc<dyn super::transport::Transport>);
+        entry.set_transport(transport, self.redact_paths_in_logs);
         entry.capabilities.gpio = true;
-
-        tracing::info!(alias = %alias, port = %port_path, "device reconnected");
+        let port = self.log_port(alias, port);
+        tracing::info!(alias = %alias, port = %port, "device reconnected");
         Ok(())
     }
+
+    /// The installed connector, or the serial connector when none is set.
+    fn reconnect_connector(&self) -> anyhow::Result<Arc<dyn TransportConnector>> {
+        if let Some(connector) = &self.connector {
+            return Ok(connector.clone());
+        }
+        #[cfg(feature = "hardware")]
+        {
+            Ok(Arc::new(super::serial::SerialTransportConnector::new(
+                self.boards.clone(),
+            )))
+        }
+        #[cfg(not(feature = "hardware"))]
+        {
+            anyhow::bail!("no transport connector configured")
+        }
+    }
+}
+
+/// Reconnect `alias` in a shared registry, e.g. after its transport
+/// reported `Disconnected`.
+///
+/// The lock is taken only to plan the reconnect and to attach the fresh
+/// transport; the handshakes and backoff between attempts run without it,
+/// so other devices stay usable meanwhile.
+pub async fn reconnect_shared(
+    registry: &tokio::sync::RwLock<DeviceRegistry>,
+    alias: &str,
+) -> anyhow::Result<()> {
+    let pending = registry.read().await.prepare_reconnect(alias, None)?;
+    let (port, transport) = pending.connect().await?;
+    registry
+        .write()
+        .await
+        .finish_reconnect(alias, &port, transport)
 }
 
 impl Default for DeviceRegistry {
@@ -562,6 +1568,8 @@ fn alias_prefix(board_name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hardware::protocol::{ZcCommand, ZcResponse};
+    use crate::hardware::transport::{TransportError, TransportKind};
 
     #[test]
     fn alias_prefix_pico_variants() {
@@ -796,4 +1804,757 @@ mod tests {
         let reg = DeviceRegistry::new();
         assert_eq!(reg.summary(), "");
     }
+
+    struct VersionedTransport(Option<u32>);
+
+    #[async_trait]
+    impl Transport for VersionedTransport {
+        async fn send(&self, _cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            Ok(ZcResponse::success(serde_json::json!({})))
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn protocol_version(&self) -> Option<u32> {
+            self.0
+        }
+    }
+
+    /// Transport that records every command it is sent.
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: std::sync::Mutex<Vec<ZcCommand>>,
+    }
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send(&self, cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            self.sent.lock().unwrap().push(cmd.clone());
+            Ok(ZcResponse::success(serde_json::json!({})))
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn close_drives_configured_safe_state_then_drops_transports() {
+        use crate::config::PeripheralSafePin;
+
+        let transport = Arc::new(RecordingTransport::default());
+        let mut reg = DeviceRegistry::new();
+        reg.register(
+            "raspberry-pi-pico",
+            Some(0x2e8a),
+            None,
+            Some("/dev/ttyACM0".into()),
+            None,
+        );
+        reg.attach_transport(
+            "pico0",
+            transport.clone() as Arc<dyn Transport>,
+            DeviceCapabilities::default(),
+        )
+        .unwrap();
+        reg.set_boards(&[PeripheralBoardConfig {
+            board: "pico".into(),
+            path: Some("/dev/ttyACM0".into()),
+            commands: [("gpio_write".to_string(), "set_pin".to_string())].into(),
+            safe_state: vec![
+                PeripheralSafePin { pin: 5, value: 0 },
+                PeripheralSafePin { pin: 6, value: 1 },
+            ],
+            ..PeripheralBoardConfig::default()
+        }]);
+
+        reg.close().await;
+
+        let sent = transport.sent.lock().unwrap();
+        let wire: Vec<(&str, &serde_json::Value)> =
+            sent.iter().map(|c| (c.cmd.as_str(), &c.params)).collect();
+        assert_eq!(
+            wire,
+            vec![
+                ("set_pin", &serde_json::json!({"pin": 5, "value": 0})),
+                ("set_pin", &serde_json::json!({"pin": 6, "value": 1})),
+            ]
+        );
+        assert!(reg.context("pico0").is_none());
+        assert!(reg.get_device("pico0").is_some());
+    }
+
+    /// Connector whose handshake fails until the `ready_on`-th connect.
+    struct BootingConnector {
+        ready_on: usize,
+        connects: std::sync::atomic::AtomicUsize,
+        last_baud: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl TransportConnector for BootingConnector {
+        async fn connect(&self, port_path: &str, baud: u32) -> anyhow::Result<Arc<dyn Transport>> {
+            self.last_baud
+                .store(baud, std::sync::atomic::Ordering::SeqCst);
+            let n = self
+                .connects
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                + 1;
+            if n < self.ready_on {
+                anyhow::bail!("ping handshake failed on {port_path}");
+            }
+            Ok(Arc::new(VersionedTransport(None)))
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            Vec::new()
+        }
+    }
+
+    fn registry_with_booting_board(ready_on: usize) -> (DeviceRegistry, Arc<BootingConnector>) {
+        let connector = Arc::new(BootingConnector {
+            ready_on,
+            connects: std::sync::atomic::AtomicUsize::new(0),
+            last_baud: std::sync::atomic::AtomicU32::new(0),
+        });
+        let mut reg = DeviceRegistry::new();
+        reg.register(
+            "raspberry-pi-pico",
+            Some(0x2e8a),
+            None,
+            Some("/dev/ttyACM0".into()),
+            None,
+        );
+        reg.set_connector(connector.clone());
+        reg.set_reconnect_policy(ReconnectPolicy {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+        });
+        (reg, connector)
+    }
+
+    #[tokio::test]
+    async fn reconnect_succeeds_once_booting_board_answers() {
+        let (mut reg, connector) = registry_with_booting_board(3);
+        reg.reconnect("pico0", Some("/dev/ttyACM1")).await.unwrap();
+
+        assert_eq!(
+            connector.connects.load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+        let ctx = reg.context("pico0").expect("transport attached");
+        assert_eq!(ctx.device.device_path.as_deref(), Some("/dev/ttyACM1"));
+    }
+
+    #[tokio::test]
+    async fn reconnect_fails_after_exhausting_attempts() {
+        let (mut reg, connector) = registry_with_booting_board(4);
+        let err = reg.reconnect("pico0", None).await.unwrap_err();
+
+        assert_eq!(
+            connector.connects.load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+        assert!(
+            format!("{err:#}").contains("gave up reconnecting pico0 after 3 attempt(s)"),
+            "{err:#}"
+        );
+        assert!(reg.context("pico0").is_none());
+    }
+
+    /// Connector that answers the handshake after a per-port delay (so
+    /// probes finish out of scan order) and tracks peak concurrency.
+    struct SlowProbeConnector {
+        delays_ms: HashMap<String, u64>,
+        silent: Vec<String>,
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TransportConnector for SlowProbeConnector {
+        async fn connect(&self, port_path: &str, _baud: u32) -> anyhow::Result<Arc<dyn Transport>> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            let delay = self.delays_ms.get(port_path).copied().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if self.silent.iter().any(|p| p == port_path) {
+                anyhow::bail!("ping handshake failed on {port_path}");
+            }
+            Ok(Arc::new(VersionedTransport(Some(1))))
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            Vec::new()
+        }
+    }
+
+    fn scanned(port_path: &str, vid: u16, board_name: Option<&str>) -> ScannedPort {
+        ScannedPort {
+            port_path: port_path.to_string(),
+            vid,
+            pid: 0,
+            serial_number: None,
+            board_name: board_name.map(str::to_string),
+            architecture: None,
+            baud: DEFAULT_BAUD,
+        }
+    }
+
+    #[tokio::test]
+    async fn discover_ports_probes_concurrently_and_registers_in_scan_order() {
+        let ports = vec![
+            scanned("/dev/ttyUSB0", 0, None),
+            scanned("/dev/ttyACM0", 0x2e8a, Some("raspberry-pi-pico")),
+            scanned("/dev/ttyUSB1", 0, None),
+            scanned("/dev/ttyUSB2", 0, None),
+            scanned("/dev/ttyUSB3", 0, None),
+            scanned("/dev/ttyUSB4", 0, None),
+        ];
+        // Earlier ports answer last, so completion order is the reverse of
+        // scan order.
+        let delays_ms = [
+            ("/dev/ttyUSB0", 80),
+            ("/dev/ttyUSB1", 60),
+            ("/dev/ttyUSB2", 40),
+            ("/dev/ttyUSB3", 20),
+            ("/dev/ttyUSB4", 0),
+        ]
+        .into_iter()
+        .map(|(port, ms)| (port.to_string(), ms))
+        .collect();
+        let connector = Arc::new(SlowProbeConnector {
+            delays_ms,
+            silent: vec!["/dev/ttyUSB2".to_string()],
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            peak: std::sync::atomic::AtomicUsize::new(0),
+        });
+
+        let reg = DeviceRegistry::discover_ports(
+            ports,
+            connector.clone(),
+            |_, _| Arc::new(VersionedTransport(None)),
+            3,
+        )
+        .await;
+
+        let peak = connector.peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(
+            peak, 3,
+            "probes should run concurrently, bounded by parallelism"
+        );
+        assert_eq!(
+            reg.summary(),
+            "device0: unknown /dev/ttyUSB0\n\
+             device1: unknown /dev/ttyUSB1\n\
+             device2: unknown /dev/ttyUSB3\n\
+             device3: unknown /dev/ttyUSB4\n\
+             pico0: raspberry-pi-pico /dev/ttyACM0"
+        );
+        // Probed devices keep the handshake transport; known VIDs get `open`'s.
+        assert_eq!(
+            reg.context("device0").unwrap().device.protocol_version,
+            Some(1)
+        );
+        assert_eq!(reg.context("pico0").unwrap().device.protocol_version, None);
+        assert_eq!(
+            reg.context("pico0").unwrap().capabilities.max_pin,
+            DeviceKind::Pico.default_max_pin()
+        );
+    }
+
+    #[test]
+    fn board_baud_matches_configured_path_then_board_name() {
+        let board = |name: &str, path: Option<&str>, baud: u32| PeripheralBoardConfig {
+            board: name.into(),
+            transport: "serial".into(),
+            path: path.map(str::to_string),
+            baud,
+            post_write_delay_ms: 0,
+            health_command: "ping".into(),
+            commands: HashMap::new(),
+            safe_state: Vec::new(),
+        };
+        let boards = vec![
+            board("arduino-uno", None, 9600),
+            board("esp32", Some("/dev/ttyUSB0"), 921_600),
+        ];
+
+        assert_eq!(board_baud(&boards, "/dev/ttyUSB0", None), 921_600);
+        assert_eq!(
+            board_baud(&boards, "/dev/ttyACM0", Some("arduino-uno")),
+            9600
+        );
+        assert_eq!(
+            board_baud(&boards, "/dev/ttyACM1", Some("raspberry-pi-pico")),
+            DEFAULT_BAUD
+        );
+        assert_eq!(board_baud(&[], "/dev/ttyUSB0", None), DEFAULT_BAUD);
+    }
+
+    #[test]
+    fn board_post_write_delay_follows_board_config() {
+        let boards = vec![PeripheralBoardConfig {
+            board: "arduino-uno".into(),
+            post_write_delay_ms: 20,
+            ..PeripheralBoardConfig::default()
+        }];
+
+        assert_eq!(
+            board_post_write_delay(&boards, "/dev/ttyACM0", Some("arduino-uno")),
+            Duration::from_millis(20)
+        );
+        assert_eq!(
+            board_post_write_delay(&boards, "/dev/ttyACM0", None),
+            Duration::ZERO
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_baud_is_used_to_open_and_reconnect_the_transport() {
+        let connector = Arc::new(BootingConnector {
+            ready_on: 1,
+            connects: std::sync::atomic::AtomicUsize::new(0),
+            last_baud: std::sync::atomic::AtomicU32::new(0),
+        });
+        let opened = std::sync::Mutex::new(Vec::new());
+        let open = |port: &str, baud: u32| -> Arc<dyn Transport> {
+            opened.lock().unwrap().push((port.to_string(), baud));
+            Arc::new(VersionedTransport(None))
+        };
+        let uno = ScannedPort {
+            baud: 9600,
+            ..scanned("/dev/ttyACM0", 0x2341, Some("arduino-uno"))
+        };
+        let mut reg = DeviceRegistry::discover_ports(
+            vec![
+                uno,
+                scanned("/dev/ttyACM1", 0x2e8a, Some("raspberry-pi-pico")),
+            ],
+            connector.clone(),
+            open,
+            1,
+        )
+        .await;
+
+        assert_eq!(
+            *opened.lock().unwrap(),
+            vec![
+                ("/dev/ttyACM0".to_string(), 9600),
+                ("/dev/ttyACM1".to_string(), DEFAULT_BAUD)
+            ]
+        );
+        assert_eq!(reg.get_device("arduino0").unwrap().baud, 9600);
+        assert_eq!(reg.get_device("pico0").unwrap().baud, DEFAULT_BAUD);
+
+        reg.reconnect("arduino0", None).await.unwrap();
+        assert_eq!(
+            connector
+                .last_baud
+                .load(std::sync::atomic::Ordering::SeqCst),
+            9600
+        );
+    }
+
+    #[tokio::test]
+    async fn rescan_registers_new_ports_and_keeps_aliases_across_replug() {
+        let connector = Arc::new(SlowProbeConnector {
+            delays_ms: HashMap::new(),
+            silent: Vec::new(),
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            peak: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let open = |_: &str, _: u32| -> Arc<dyn Transport> { Arc::new(VersionedTransport(None)) };
+        let pico = |port: &str| scanned(port, 0x2e8a, Some("raspberry-pi-pico"));
+        let mut reg = DeviceRegistry::discover_ports(
+            vec![pico("/dev/ttyACM0"), scanned("/dev/ttyUSB0", 0, None)],
+            connector,
+            open,
+            2,
+        )
+        .await;
+
+        // Unplug pico0 and plug a second Pico in on a new path.
+        let report = reg
+            .rescan_ports(
+                vec![scanned("/dev/ttyUSB0", 0, None), pico("/dev/ttyACM1")],
+                open,
+            )
+            .await;
+        assert_eq!(
+            report,
+            RescanReport {
+                added: vec!["pico1".into()],
+                removed: vec!["pico0".into()],
+                reconnected: Vec::new(),
+            }
+        );
+        assert!(reg.is_marked_disconnected("pico0"));
+        assert!(reg.context("pico0").is_none());
+        assert!(reg.context("device0").is_some());
+
+        // pico0 comes back on its old path and keeps its alias.
+        let report = reg
+            .rescan_ports(
+                vec![
+                    pico("/dev/ttyACM0"),
+                    scanned("/dev/ttyUSB0", 0, None),
+                    pico("/dev/ttyACM1"),
+                ],
+                open,
+            )
+            .await;
+        assert_eq!(
+            report,
+            RescanReport {
+                reconnected: vec!["pico0".into()],
+                ..RescanReport::default()
+            }
+        );
+        assert!(!reg.is_marked_disconnected("pico0"));
+        assert!(reg.context("pico0").unwrap().capabilities.gpio);
+        assert_eq!(reg.len(), 3);
+    }
+
+    #[test]
+    fn fingerprint_uses_serial_number_and_falls_back_to_port() {
+        let board = |port: &str, serial: Option<&str>| ScannedPort {
+            pid: 0x000a,
+            serial_number: serial.map(str::to_string),
+            ..scanned(port, 0x2e8a, Some("raspberry-pi-pico"))
+        };
+        let serial = HardwareDeviceIdentity::Serial;
+
+        let a = board("/dev/ttyACM0", Some("E6614103"));
+        let b = board("/dev/ttyACM0", Some("E6614864"));
+        assert_eq!(a.fingerprint(serial), "2e8a:000a:E6614103");
+        assert_ne!(a.fingerprint(serial), b.fingerprint(serial));
+        assert_eq!(
+            a.fingerprint(serial),
+            board("/dev/ttyACM1", Some("E6614103")).fingerprint(serial)
+        );
+        assert_eq!(
+            board("/dev/ttyACM0", None).fingerprint(serial),
+            "2e8a:000a@/dev/ttyACM0"
+        );
+        assert_eq!(
+            a.fingerprint(HardwareDeviceIdentity::Port),
+            b.fingerprint(HardwareDeviceIdentity::Port)
+        );
+    }
+
+    #[tokio::test]
+    async fn identical_boards_keep_distinct_aliases_by_serial_across_port_swap() {
+        let connector = Arc::new(SlowProbeConnector {
+            delays_ms: HashMap::new(),
+            silent: Vec::new(),
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            peak: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let open = |_: &str, _: u32| -> Arc<dyn Transport> { Arc::new(VersionedTransport(None)) };
+        let pico = |port: &str, serial: &str| ScannedPort {
+            pid: 0x000a,
+            serial_number: Some(serial.to_string()),
+            ..scanned(port, 0x2e8a, Some("raspberry-pi-pico"))
+        };
+        let mut reg = DeviceRegistry::discover_ports(
+            vec![pico("/dev/ttyACM0", "AAAA"), pico("/dev/ttyACM1", "BBBB")],
+            connector,
+            open,
+            1,
+        )
+        .await;
+        let serial_of = |reg: &DeviceRegistry, alias: &str| {
+            reg.get_device(alias)
+                .and_then(|d| d.serial_number.clone())
+                .unwrap()
+        };
+        assert_eq!(serial_of(&reg, "pico0"), "AAAA");
+        assert_eq!(serial_of(&reg, "pico1"), "BBBB");
+
+        // Both boards replugged into each other's ports.
+        let report = reg
+            .rescan_ports(
+                vec![pico("/dev/ttyACM0", "BBBB"), pico("/dev/ttyACM1", "AAAA")],
+                open,
+            )
+            .await;
+        assert_eq!(
+            report,
+            RescanReport {
+                reconnected: vec!["pico1".into(), "pico0".into()],
+                ..RescanReport::default()
+            }
+        );
+        assert_eq!(reg.len(), 2);
+        let pico0 = reg.get_device("pico0").unwrap();
+        assert_eq!(pico0.serial_number.as_deref(), Some("AAAA"));
+        assert_eq!(pico0.port(), Some("/dev/ttyACM1"));
+        let pico1 = reg.get_device("pico1").unwrap();
+        assert_eq!(pico1.serial_number.as_deref(), Some("BBBB"));
+        assert_eq!(pico1.port(), Some("/dev/ttyACM0"));
+        assert!(!reg.is_marked_disconnected("pico0"));
+        assert!(!reg.is_marked_disconnected("pico1"));
+    }
+
+    /// Connector listing `ports` for every VID; the handshake fails on
+    /// `dead` paths. Records the paths it tried.
+    struct ScanningConnector {
+        ports: Vec<ScannedPort>,
+        dead: Vec<&'static str>,
+        tried: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl TransportConnector for ScanningConnector {
+        async fn connect(&self, port_path: &str, _baud: u32) -> anyhow::Result<Arc<dyn Transport>> {
+            self.tried.lock().unwrap().push(port_path.to_string());
+            if self.dead.contains(&port_path) {
+                anyhow::bail!("ping handshake failed on {port_path}");
+            }
+            Ok(Arc::new(VersionedTransport(None)))
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            self.ports.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn reconnect_only_moves_to_a_port_with_the_same_fingerprint() {
+        let pico = |port: &str, serial: Option<&str>| ScannedPort {
+            pid: 0x000a,
+            serial_number: serial.map(str::to_string),
+            ..scanned(port, 0x2e8a, Some("raspberry-pi-pico"))
+        };
+        let open = |_: &str, _: u32| -> Arc<dyn Transport> { Arc::new(VersionedTransport(None)) };
+        let reconnect = |registered: ScannedPort, ports: Vec<ScannedPort>| async move {
+            let connector = Arc::new(ScanningConnector {
+                ports,
+                dead: vec!["/dev/ttyACM0"],
+                tried: std::sync::Mutex::new(Vec::new()),
+            });
+            let mut reg =
+                DeviceRegistry::discover_ports(vec![registered], connector.clone(), open, 1).await;
+            reg.set_reconnect_policy(ReconnectPolicy {
+                attempts: 1,
+                ..ReconnectPolicy::default()
+            });
+            let result = reg.reconnect("pico0", None).await;
+            let port = reg.get_device("pico0").unwrap().port().map(str::to_string);
+            let tried = connector.tried.lock().unwrap().clone();
+            (result, port, tried)
+        };
+
+        // An identical board with another serial must not take the alias.
+        let (result, port, tried) = reconnect(
+            pico("/dev/ttyACM0", Some("BBBB")),
+            vec![
+                pico("/dev/ttyACM1", Some("AAAA")),
+                pico("/dev/ttyACM2", Some("BBBB")),
+            ],
+        )
+        .await;
+        result.unwrap();
+        assert_eq!(port.as_deref(), Some("/dev/ttyACM2"));
+        assert_eq!(tried, vec!["/dev/ttyACM0", "/dev/ttyACM2"]);
+
+        // Without a serial number the fingerprint is tied to the port.
+        let (result, port, tried) =
+            reconnect(pico("/dev/ttyACM0", None), vec![pico("/dev/ttyACM1", None)]).await;
+        assert!(result.is_err());
+        assert_eq!(port.as_deref(), Some("/dev/ttyACM0"));
+        assert_eq!(tried, vec!["/dev/ttyACM0"]);
+    }
+
+    #[test]
+    fn attach_transport_records_negotiated_protocol_version() {
+        let mut reg = DeviceRegistry::new();
+        let alias = reg.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+        assert_eq!(reg.get(&alias).unwrap().protocol_version, None);
+
+        reg.attach_transport(
+            &alias,
+            Arc::new(VersionedTransport(Some(1))),
+            DeviceCapabilities::default(),
+        )
+        .unwrap();
+        assert_eq!(reg.get(&alias).unwrap().protocol_version, Some(1));
+    }
+
+    #[test]
+    fn attach_transport_without_version_leaves_device_unversioned() {
+        let mut reg = DeviceRegistry::new();
+        let alias = reg.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+        reg.attach_transport(
+            &alias,
+            Arc::new(VersionedTransport(None)),
+            DeviceCapabilities::default(),
+        )
+        .unwrap();
+        assert_eq!(reg.get(&alias).unwrap().protocol_version, None);
+    }
+
+    fn gpio_caps() -> DeviceCapabilities {
+        DeviceCapabilities {
+            gpio: true,
+            ..DeviceCapabilities::default()
+        }
+    }
+
+    fn registry_with(boards: &[(&str, u16)]) -> DeviceRegistry {
+        let mut reg = DeviceRegistry::new();
+        for (board, vid) in boards {
+            let alias = reg.register(board, Some(*vid), None, None, None);
+            reg.attach_transport(&alias, Arc::new(VersionedTransport(None)), gpio_caps())
+                .unwrap();
+        }
+        reg
+    }
+
+    #[test]
+    fn resolve_by_kind_selects_sole_matching_device() {
+        let reg = registry_with(&[("raspberry-pi-pico", 0x2e8a), ("arduino-uno", 0x2341)]);
+
+        let (alias, ctx) = reg
+            .resolve_gpio_device(&serde_json::json!({"kind": "pico"}))
+            .unwrap();
+        assert_eq!(alias, "pico0");
+        assert_eq!(ctx.device.kind, DeviceKind::Pico);
+
+        let (alias, _) = reg
+            .resolve_device(&serde_json::json!({"kind": "Arduino"}))
+            .unwrap();
+        assert_eq!(alias, "arduino0");
+    }
+
+    #[test]
+    fn resolve_by_kind_errors_when_none_match() {
+        let reg = registry_with(&[("arduino-uno", 0x2341)]);
+        let err = reg
+            .resolve_gpio_device(&serde_json::json!({"kind": "pico"}))
+            .err()
+            .unwrap();
+        assert!(err.contains("of kind 'pico'"), "{err}");
+    }
+
+    #[test]
+    fn resolve_by_kind_errors_when_several_match() {
+        let reg = registry_with(&[
+            ("raspberry-pi-pico", 0x2e8a),
+            ("raspberry-pi-pico", 0x2e8a),
+            ("arduino-uno", 0x2341),
+        ]);
+        let err = reg
+            .resolve_gpio_device(&serde_json::json!({"kind": "pico"}))
+            .err()
+            .unwrap();
+        assert!(err.contains("multiple pico devices"), "{err}");
+        assert!(err.contains("pico0, pico1"), "{err}");
+    }
+
+    #[test]
+    fn preference_order_picks_default_among_several() {
+        let boards = [
+            ("raspberry-pi-pico", 0x2e8a),
+            ("raspberry-pi-pico", 0x2e8a),
+            ("arduino-uno", 0x2341),
+        ];
+        let mut reg = registry_with(&boards);
+        let err = reg
+            .resolve_gpio_device(&serde_json::json!({}))
+            .err()
+            .unwrap();
+        assert!(err.contains("multiple devices available"), "{err}");
+
+        reg.set_preference(vec!["esp32".into(), "arduino".into(), "pico1".into()]);
+        let (alias, _) = reg.resolve_gpio_device(&serde_json::json!({})).unwrap();
+        assert_eq!(alias, "arduino0");
+
+        let (alias, _) = reg
+            .resolve_gpio_device(&serde_json::json!({"kind": "pico"}))
+            .unwrap();
+        assert_eq!(alias, "pico1");
+    }
+
+    #[test]
+    fn explicit_alias_must_match_requested_kind() {
+        let reg = registry_with(&[("raspberry-pi-pico", 0x2e8a), ("arduino-uno", 0x2341)]);
+        let err = reg
+            .resolve_gpio_device(&serde_json::json!({"device": "arduino0", "kind": "pico"}))
+            .err()
+            .unwrap();
+        assert!(err.contains("not 'pico'"), "{err}");
+    }
+
+    #[test]
+    fn check_pin_uses_known_max_only() {
+        let caps = DeviceCapabilities {
+            max_pin: DeviceKind::Pico.default_max_pin(),
+            ..DeviceCapabilities::default()
+        };
+        assert!(caps.check_pin(28).is_ok());
+        assert!(caps.check_pin(29).is_err());
+        assert!(DeviceCapabilities::default().check_pin(9999).is_ok());
+        assert_eq!(DeviceKind::Arduino.default_max_pin(), None);
+    }
+
+    /// Transport that records the alias it was told to log under.
+    #[derive(Default)]
+    struct LabelledTransport {
+        log_alias: parking_lot::Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl Transport for LabelledTransport {
+        async fn send(&self, _cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            Err(TransportError::Disconnected)
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn set_log_alias(&self, alias: Option<&str>) {
+            *self.log_alias.lock() = alias.map(str::to_string);
+        }
+    }
+
+    #[test]
+    fn redacted_logs_name_devices_by_alias() {
+        let logs = crate::hardware::test_support::LogCapture::start();
+        let mut reg = DeviceRegistry::new();
+        reg.set_path_redaction(false, true);
+        let transport = Arc::new(LabelledTransport::default());
+        let alias = reg.register_scanned(
+            scanned("/dev/ttyACM7", 0x2e8a, Some("raspberry-pi-pico")),
+            transport.clone(),
+        );
+
+        let output = logs.contents();
+        assert!(output.contains("device registered"), "{output}");
+        assert!(output.contains(&format!("port={alias}")), "{output}");
+        assert!(!output.contains("/dev/ttyACM7"), "{output}");
+        assert_eq!(transport.log_alias.lock().as_deref(), Some(alias.as_str()));
+
+        reg.set_path_redaction(false, false);
+        assert!(transport.log_alias.lock().is_none());
+    }
 }
@@ -17,6 +17,8 @@ pub struct UsbDeviceInfo {
     pub vid: u16,
     pub pid: u16,
     pub product_string: Option<String>,
+    /// USB serial number (`iSerial`), when the device reports one.
+    pub serial_number: Option<String>,
     pub board_name: Option<String>,
     pub architecture: Option<String>,
 }
@@ -41,6 +43,7 @@ pub fn list_usb_devices() -> Result<Vec<UsbDeviceInfo>> {
             vid,
             pid,
             product_string: dev.product_string().map(String::from),
+            serial_number: dev.serial_number().map(String::from),
             board_name: board.map(|b| b.name.to_string()),
             architecture: board.and_then(|b| b.architecture.map(String::from)),
         });
@@ -60,6 +63,8 @@ pub struct SerialDeviceInfo {
     pub vid: u16,
     /// USB Product ID read from sysfs/IOKit. `0` if unknown.
     pub pid: u16,
+    /// USB serial number (`iSerial`), when the board reports one.
+    pub serial_number: Option<String>,
     /// Board name from the registry, if VID/PID was recognised.
     pub board_name: Option<String>,
     /// Architecture description from the registry.
@@ -111,13 +116,14 @@ fn scan_serial_devices_linux() -> Vec<SerialDeviceInfo> {
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            let (vid, pid) = vid_pid_from_sysfs(&port_name).unwrap_or((0, 0));
+            let (vid, pid, serial_number) = usb_ids_from_sysfs(&port_name).unwrap_or((0, 0, None));
             let board = registry::lookup_board(vid, pid);
 
             results.push(SerialDeviceInfo {
                 port_path,
                 vid,
                 pid,
+                serial_number,
                 board_name: board.map(|b| b.name.to_string()),
                 architecture: board.and_then(|b| b.architecture.map(String::from)),
             });
@@ -127,23 +133,22 @@ fn scan_serial_devices_linux() -> Vec<SerialDeviceInfo> {
     results
 }
 
-/// Read VID and PID for a tty port from Linux sysfs.
+/// Read VID, PID and USB serial number for a tty port from Linux sysfs.
 ///
 /// Follows the symlink chain:
 /// `/sys/class/tty/<port_name>/device` → canonicalised USB interface directory
-/// then climbs to parent (or grandparent) USB device to read `idVendor`/`idProduct`.
+/// then climbs to parent (or grandparent) USB device to read `idVendor`/`idProduct`
+/// and `serial`.
 #[cfg(all(feature = "hardware", target_os = "linux"))]
-fn vid_pid_from_sysfs(port_name: &str) -> Option<(u16, u16)> {
-    use std::path::Path;
-
+fn usb_ids_from_sysfs(port_name: &str) -> Option<(u16, u16, Option<String>)> {
     let device_link = format!("/sys/class/tty/{}/device", port_name);
     // Resolve the symlink chain to a real absolute path.
     let device_path = std::fs::canonicalize(device_link).ok()?;
 
     // ttyACM (CDC ACM): device_path = …/2-1:1.0 (interface)
     // idVendor is at the USB device level, one directory up.
-    if let Some((v, p)) = try_read_vid_pid(device_path.parent()?) {
-        return Some((v, p));
+    if let Some(ids) = try_read_usb_ids(device_path.parent()?) {
+        return Some(ids);
     }
 
     // ttyUSB (USB-serial chips like CH340, FTDI):
@@ -152,15 +157,20 @@ fn vid_pid_from_sysfs(port_name: &str) -> Option<(u16, u16)> {
     device_path
         .parent()
         .and_then(|p| p.parent())
-        .and_then(try_read_vid_pid)
+        .and_then(try_read_usb_ids)
 }
 
-/// Try to read `idVendor` and `idProduct` files from a directory.
+/// Try to read `idVendor` and `idProduct` files from a directory, plus the
+/// optional `serial` file.
 #[cfg(all(feature = "hardware", target_os = "linux"))]
-fn try_read_vid_pid(dir: &std::path::Path) -> Option<(u16, u16)> {
+fn try_read_usb_ids(dir: &std::path::Path) -> Option<(u16, u16, Option<String>)> {
     let vid = read_hex_u16(dir.join("idVendor"))?;
     let pid = read_hex_u16(dir.join("idProduct"))?;
-    Some((vid, pid))
+    let serial = std::fs::read_to_string(dir.join("serial"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some((vid, pid, serial))
 }
 
 /// Read a hex-formatted u16 from a sysfs file (e.g. `"2e8a\n"` → `0x2E8A`).
@@ -198,6 +208,7 @@ fn scan_serial_devices_macos() -> Vec<SerialDeviceInfo> {
                 port_path,
                 vid: 0,
                 pid: 0,
+                serial_number: None,
                 board_name: None,
                 architecture: None,
             });
@@ -0,0 +1,258 @@
+//! Hardware emergency stop — halts all actuation until explicitly cleared.
+//!
+//! While engaged, actuating hardware tools (`gpio_write`, `gpio_config`,
+//! `pwm_write`, and the peripheral `gpio_write` tools) refuse with [`ESTOP_ENGAGED_ERROR`]
+//! before anything reaches a device. Reads (`gpio_read`, `i2c_scan`,
+//! `device_capabilities`) are unaffected.
+//!
+//! The stop is engaged from the gateway (`POST /api/estop`, or a chat
+//! message equal to `hardware.estop_safe_word`) and cleared with
+//! `DELETE /api/estop`. On engage, each pin in `hardware.estop_safe_pins` is
+//! driven to its safe level. This is an in-process flag separate from the
+//! persisted `zeroclaw estop` controls in [`crate::security::estop`].
+
+use super::device::DeviceRegistry;
+use super::protocol::ZcCommand;
+use crate::config::{Config, HardwareConfig, HardwareSafePin};
+use crate::tools::traits::ToolResult;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use tokio::sync::RwLock;
+
+/// Error returned by actuating tools while the stop is engaged.
+pub const ESTOP_ENGAGED_ERROR: &str =
+    "emergency stop engaged: hardware actuation is halted until the stop is cleared";
+
+/// Process-wide actuation stop shared by the hardware tools.
+#[derive(Default)]
+pub struct HardwareEstop {
+    engaged: AtomicBool,
+    /// Registry of the most recent tool set, used to reach the safe pins.
+    registry: Mutex<Weak<RwLock<DeviceRegistry>>>,
+}
+
+impl HardwareEstop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stop shared by the gateway and the hardware tools.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<HardwareEstop>> = OnceLock::new();
+        Arc::clone(GLOBAL.get_or_init(|| Arc::new(Self::new())))
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        self.engaged.load(Ordering::SeqCst)
+    }
+
+    /// Engage the stop. Returns `true` when it was not already engaged.
+    pub fn engage(&self) -> bool {
+        let newly = !self.engaged.swap(true, Ordering::SeqCst);
+        if newly {
+            tracing::warn!("hardware emergency stop engaged; actuation halted");
+        }
+        newly
+    }
+
+    /// Clear the stop. Returns `true` when it was engaged.
+    pub fn clear(&self) -> bool {
+        let was = self.engaged.swap(false, Ordering::SeqCst);
+        if was {
+            tracing::warn!("hardware emergency stop cleared; actuation re-enabled");
+        }
+        was
+    }
+
+    /// Failed tool result for an actuating tool while engaged.
+    pub fn refusal(&self) -> Option<ToolResult> {
+        self.is_engaged().then(|| ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(ESTOP_ENGAGED_ERROR.to_string()),
+        })
+    }
+
+    /// Remember `registry` as the one to drive safe pins through. Only a weak
+    /// reference is kept, so the registry's ports close with its tools.
+    pub fn attach_registry(&self, registry: &Arc<RwLock<DeviceRegistry>>) {
+        *self.registry.lock() = Arc::downgrade(registry);
+    }
+
+    pub(crate) fn live_registry(&self) -> Option<Arc<RwLock<DeviceRegistry>>> {
+        self.registry.lock().upgrade()
+    }
+}
+
+/// Whether `message` is the configured safe word (whole message, trimmed,
+/// case-insensitive).
+pub fn matches_safe_word(config: &HardwareConfig, message: &str) -> bool {
+    config
+        .estop_safe_word
+        .as_deref()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .is_some_and(|word| word.eq_ignore_ascii_case(message.trim()))
+}
+
+/// Drive each of `pins` to its safe level, bypassing the stop. Returns one
+/// line per pin describing the outcome.
+pub async fn drive_safe_state(
+    registry: &RwLock<DeviceRegistry>,
+    pins: &[HardwareSafePin],
+) -> Vec<String> {
+    let mut report = Vec::with_capacity(pins.len());
+    for safe in pins {
+        let level = if safe.value == 0 { "LOW" } else { "HIGH" };
+        let Some(ctx) = registry.read().await.context(&safe.device) else {
+            report.push(format!(
+                "{} GPIO {}: device not connected",
+                safe.device, safe.pin
+            ));
+            continue;
+        };
+        let cmd = ZcCommand::new(
+            "gpio_write",
+            serde_json::json!({ "pin": safe.pin, "value": u8::from(safe.value != 0) }),
+        );
+        let line = match ctx.transport.send(&ctx.firmware_command(&cmd)).await {
+            Ok(resp) if resp.ok => format!("{} GPIO {} set {level}", safe.device, safe.pin),
+            Ok(resp) => format!(
+                "{} GPIO {}: {}",
+                safe.device,
+                safe.pin,
+                resp.error
+                    .unwrap_or_else(|| "device returned ok:false".into())
+            ),
+            Err(e) => format!("{} GPIO {}: transport error: {e}", safe.device, safe.pin),
+        };
+        report.push(line);
+    }
+    report
+}
+
+/// Engage the global stop and drive `hardware.estop_safe_pins` to their
+/// safe levels, through the live tool registry or, when none is live, a
+/// freshly loaded one. Under `gateway.dry_mode` the pins are only reported.
+/// Returns the per-pin report.
+pub async fn engage_from_config(config: &Config) -> Vec<String> {
+    let estop = HardwareEstop::global();
+    estop.engage();
+    let pins = &config.hardware.estop_safe_pins;
+    if config.gateway.dry_mode {
+        return pins
+            .iter()
+            .map(|safe| {
+                let level = if safe.value == 0 { "LOW" } else { "HIGH" };
+                format!(
+                    "[dry run] would set {} GPIO {} {level}",
+                    safe.device, safe.pin
+                )
+            })
+            .collect();
+    }
+    if pins.is_empty() {
+        return Vec::new();
+    }
+    let registry = match estop.live_registry() {
+        Some(registry) => registry,
+        None => Arc::new(RwLock::new(super::load_device_registry(config).await)),
+    };
+    let report = drive_safe_state(&registry, pins).await;
+    for line in &report {
+        tracing::warn!("hardware emergency stop safe state: {line}");
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::device::DeviceCapabilities;
+    use crate::hardware::protocol::ZcResponse;
+    use crate::hardware::transport::{Transport, TransportError, TransportKind};
+    use async_trait::async_trait;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: Mutex<Vec<ZcCommand>>,
+    }
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send(&self, cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            self.sent.lock().push(cmd.clone());
+            Ok(ZcResponse::success(serde_json::json!({})))
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn safe_word_matches_whole_message_case_insensitively() {
+        let mut config = HardwareConfig::default();
+        assert!(!matches_safe_word(&config, "stop"));
+
+        config.estop_safe_word = Some("Red Light".into());
+        assert!(matches_safe_word(&config, "  red light "));
+        assert!(!matches_safe_word(&config, "red light please"));
+    }
+
+    #[tokio::test]
+    async fn safe_pins_are_driven_while_engaged() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut reg = DeviceRegistry::new();
+        let alias = reg.register(
+            "raspberry-pi-pico",
+            Some(0x2e8a),
+            Some(0x000a),
+            Some("/dev/ttyACM0".to_string()),
+            None,
+        );
+        reg.attach_transport(
+            &alias,
+            transport.clone() as Arc<dyn Transport>,
+            DeviceCapabilities::default(),
+        )
+        .unwrap();
+        let registry = Arc::new(RwLock::new(reg));
+
+        let estop = HardwareEstop::new();
+        estop.attach_registry(&registry);
+        assert!(estop.engage());
+        assert!(!estop.engage());
+        let pins = vec![
+            HardwareSafePin {
+                device: alias.clone(),
+                pin: 5,
+                value: 0,
+            },
+            HardwareSafePin {
+                device: "missing0".into(),
+                pin: 2,
+                value: 1,
+            },
+        ];
+        let report = drive_safe_state(&estop.live_registry().unwrap(), &pins).await;
+
+        assert_eq!(
+            report,
+            vec![
+                format!("{alias} GPIO 5 set LOW"),
+                "missing0 GPIO 2: device not connected".to_string(),
+            ]
+        );
+        let sent = transport.sent.lock();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].params, serde_json::json!({"pin": 5, "value": 0}));
+        assert!(estop.clear());
+        assert!(!estop.is_engaged());
+    }
+}
@@ -0,0 +1,157 @@
+//! Firmware bundle registry — picks the UF2 image to flash for a board.
+//!
+//! Pico, Pico W and other RP2040 boards need different images, so
+//! `hardware.firmware_bundles` maps board names to UF2 files. The board comes
+//! from `hardware.bootsel_board` when set, otherwise from the `Board-ID` line
+//! of the BOOTSEL volume's `INFO_UF2.TXT`. `hardware.bootsel_auto_flash_uf2`
+//! is the fallback image when no bundles are configured.
+
+use crate::config::HardwareConfig;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// UF2 images keyed by board name (case-insensitive).
+#[derive(Debug, Clone, Default)]
+pub struct FirmwareBundles {
+    bundles: BTreeMap<String, PathBuf>,
+    fallback: Option<PathBuf>,
+}
+
+impl FirmwareBundles {
+    pub fn new(
+        bundles: impl IntoIterator<Item = (String, PathBuf)>,
+        fallback: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            bundles: bundles
+                .into_iter()
+                .map(|(board, uf2)| (normalize_board(&board), uf2))
+                .collect(),
+            fallback,
+        }
+    }
+
+    pub fn from_config(config: &HardwareConfig) -> Self {
+        Self::new(
+            config.firmware_bundles.clone(),
+            config.bootsel_auto_flash_uf2.clone(),
+        )
+    }
+
+    /// Whether there is anything to flash at all.
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty() && self.fallback.is_none()
+    }
+
+    /// Board names with a bundle, sorted.
+    pub fn boards(&self) -> Vec<&str> {
+        self.bundles.keys().map(String::as_str).collect()
+    }
+
+    /// UF2 image for `board`.
+    ///
+    /// With no bundles configured the fallback image is used for any board.
+    /// Otherwise `board` must match a bundle; an unknown or undetected board
+    /// is an error rather than a guess, since flashing the wrong image can
+    /// leave the board unusable until it is re-flashed by hand.
+    pub fn select(&self, board: Option<&str>) -> anyhow::Result<&Path> {
+        if self.bundles.is_empty() {
+            return self.fallback.as_deref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no firmware configured: set hardware.firmware_bundles or hardware.bootsel_auto_flash_uf2"
+                )
+            });
+        }
+
+        let Some(board) = board.map(str::trim).filter(|b| !b.is_empty()) else {
+            return self.fallback.as_deref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "board could not be detected; set hardware.bootsel_board to one of: {}",
+                    self.boards().join(", ")
+                )
+            });
+        };
+
+        self.bundles
+            .get(&normalize_board(board))
+            .map(PathBuf::as_path)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no firmware bundle for board '{board}' (available: {})",
+                    self.boards().join(", ")
+                )
+            })
+    }
+}
+
+fn normalize_board(board: &str) -> String {
+    board.trim().to_ascii_lowercase()
+}
+
+/// `Board-ID` from the contents of a UF2 volume's `INFO_UF2.TXT`
+/// (e.g. `RPI-RP2` for an RP2040).
+pub fn board_id_from_info_uf2(info: &str) -> Option<&str> {
+    info.lines()
+        .find_map(|line| line.strip_prefix("Board-ID:"))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundles() -> FirmwareBundles {
+        FirmwareBundles::new(
+            [
+                ("pico".to_string(), PathBuf::from("fw/pico.uf2")),
+                ("Pico-W".to_string(), PathBuf::from("fw/pico_w.uf2")),
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn selects_bundle_by_board_name_case_insensitively() {
+        let bundles = bundles();
+        assert_eq!(
+            bundles.select(Some("pico")).unwrap(),
+            Path::new("fw/pico.uf2")
+        );
+        assert_eq!(
+            bundles.select(Some(" PICO-w ")).unwrap(),
+            Path::new("fw/pico_w.uf2")
+        );
+    }
+
+    #[test]
+    fn unknown_or_missing_board_is_an_error_listing_available_boards() {
+        let bundles = bundles();
+        let err = bundles.select(Some("rp2350")).unwrap_err().to_string();
+        assert!(
+            err.contains("no firmware bundle for board 'rp2350'"),
+            "{err}"
+        );
+        assert!(err.contains("pico, pico-w"), "{err}");
+
+        let err = bundles.select(None).unwrap_err().to_string();
+        assert!(err.contains("hardware.bootsel_board"), "{err}");
+    }
+
+    #[test]
+    fn fallback_image_is_used_without_bundles() {
+        let bundles = FirmwareBundles::new([], Some(PathBuf::from("fw/any.uf2")));
+        assert_eq!(
+            bundles.select(Some("RPI-RP2")).unwrap(),
+            Path::new("fw/any.uf2")
+        );
+        assert!(FirmwareBundles::default().select(None).is_err());
+    }
+
+    #[test]
+    fn reads_board_id_from_info_uf2() {
+        let info = "UF2 Bootloader v3.0\nModel: Raspberry Pi RP2\nBoard-ID: RPI-RP2\n";
+        assert_eq!(board_id_from_info_uf2(info), Some("RPI-RP2"));
+        assert_eq!(board_id_from_info_uf2("Model: unknown\n"), None);
+    }
+}
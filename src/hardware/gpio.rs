@@ -1,4 +1,5 @@
-//! GPIO tools — `gpio_read` and `gpio_write` for LLM-driven hardware control.
+//! GPIO tools — `gpio_read`, `gpio_write`, and `gpio_config` for LLM-driven
+//! hardware control.
 //!
 //! These are the first built-in hardware tools. They implement the standard
 //! [`Tool`](crate::tools::Tool) trait so the LLM can call them via function
@@ -14,16 +15,181 @@
 //! gpio_read:
 //!   Host → Device:  {"cmd":"gpio_read","params":{"pin":25}}\n
 //!   Device → Host:  {"ok":true,"data":{"pin":25,"value":1,"state":"HIGH"}}\n
+//!
+//! gpio_config:
+//!   Host → Device:  {"cmd":"gpio_config","params":{"pins":[{"pin":25,"mode":"output"}]}}\n
+//!   Device → Host:  {"ok":true,"data":{"pins":[{"pin":25,"mode":"output"}]}}\n
 //! ```
-
-use super::device::DeviceRegistry;
-use super::protocol::ZcCommand;
+//!
+//! Sends follow the tool's [`HardwareSendOptions`]. When a send fails with
+//! [`TransportError::Disconnected`] the tools
+//! [reconnect](super::device::reconnect_shared) the device per the
+//! registry's reconnect policy and retry the command once, unless
+//! `reconnect_on_disconnect` is off. With an `audit` log, every command and
+//! its outcome is appended to the [`CommandAuditLog`]. With a `rate_limit`,
+//! commands over the device's [`CommandRateLimiter`] budget fail without
+//! being sent. With `dry_run`, `gpio_write` and `gpio_config` validate and
+//! resolve as usual but report what they would have done instead of
+//! touching the transport. While the [`HardwareEstop`] is engaged,
+//! `gpio_write` and `gpio_config` refuse before doing anything; `gpio_read`
+//! is unaffected.
+//!
+//! `gpio_read` and `gpio_write` report success as prose by default. With
+//! `with_output_format(Json)`, or a call's `"format": "json"`, the output is
+//! instead `{"device":"pico0","pin":25,"value":1,"state":"HIGH"}`.
+
+use super::audit::CommandAuditLog;
+use super::device::{reconnect_shared, DeviceContext, DeviceRegistry};
+use super::estop::HardwareEstop;
+use super::protocol::{ZcCommand, ZcResponse};
+use super::rate_limit::CommandRateLimiter;
+use super::transport::TransportError;
+use crate::config::HardwareGpioOutputFormat;
+use crate::security::SecurityPolicy;
 use crate::tools::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Send-path settings shared by every hardware tool, built once in
+/// [`build_hardware_tools`](super::build_hardware_tools).
+///
+/// `dry_run` and `estop` only apply to tools that actuate (`gpio_write`,
+/// `gpio_config`, `pwm_write`).
+#[derive(Clone)]
+pub struct HardwareSendOptions {
+    /// Reconnect and retry once on `Disconnected` (default on).
+    pub reconnect_on_disconnect: bool,
+    /// Record every command sent to a device (`None` = off).
+    pub audit: Option<Arc<CommandAuditLog>>,
+    /// Refuse commands over the per-device budget (`None` = off).
+    pub rate_limit: Option<Arc<CommandRateLimiter>>,
+    /// Report what would be sent instead of sending it (default off).
+    pub dry_run: bool,
+    /// Emergency stop to check, normally [`HardwareEstop::global`].
+    pub estop: Arc<HardwareEstop>,
+}
+
+impl Default for HardwareSendOptions {
+    fn default() -> Self {
+        Self {
+            reconnect_on_disconnect: true,
+            audit: None,
+            rate_limit: None,
+            dry_run: false,
+            estop: HardwareEstop::global(),
+        }
+    }
+}
+
+/// [`send_with_reconnect`] unless the rate limit of `options` refuses the
+/// command, then record the command and its outcome in its audit log (if
+/// set) on behalf of `tool`. Errors are passed through
+/// [`DeviceRegistry::redact_paths`] first.
+pub(super) async fn send_audited(
+    tool: &str,
+    options: &HardwareSendOptions,
+    registry: &RwLock<DeviceRegistry>,
+    alias: &str,
+    ctx: &DeviceContext,
+    cmd: &ZcCommand,
+) -> Result<ZcResponse, String> {
+    let limited = options
+        .rate_limit
+        .as_ref()
+        .map_or(Ok(()), |limiter| limiter.check(alias, cmd));
+    let outcome = match limited {
+        Err(e) => Err(e),
+        Ok(()) => {
+            let reconnect = options.reconnect_on_disconnect;
+            match send_with_reconnect(registry, alias, ctx, cmd, reconnect).await {
+                Err(e) => Err(registry.read().await.redact_paths(alias, &e)),
+                ok => ok,
+            }
+        }
+    };
+    if let Some(audit) = &options.audit {
+        audit.record(tool, alias, cmd, &outcome).await;
+    }
+    outcome
+}
+
+/// Send `cmd` over `ctx`, reconnecting the device once on `Disconnected`.
+///
+/// The command goes out under the device's firmware name for it (see
+/// [`DeviceContext::firmware_command`]). The reconnect holds the registry
+/// lock only to plan and to attach (see [`reconnect_shared`]); the retry
+/// goes out over the freshly attached transport.
+async fn send_with_reconnect(
+    registry: &RwLock<DeviceRegistry>,
+    alias: &str,
+    ctx: &DeviceContext,
+    cmd: &ZcCommand,
+    reconnect: bool,
+) -> Result<ZcResponse, String> {
+    match ctx.transport.send(&ctx.firmware_command(cmd)).await {
+        Err(TransportError::Disconnected) if reconnect => {
+            tracing::warn!(alias = %alias, cmd = %cmd.cmd, "device disconnected; reconnecting");
+            if let Err(e) = reconnect_shared(registry, alias).await {
+                return Err(format!(
+                    "transport error: {} (reconnect failed: {e:#})",
+                    TransportError::Disconnected
+                ));
+            }
+            let fresh = registry.read().await.context(alias);
+            let fresh = fresh.ok_or_else(|| format!("device '{alias}' has no transport"))?;
+            fresh
+                .transport
+                .send(&fresh.firmware_command(cmd))
+                .await
+                .map_err(|e| format!("transport error after reconnect: {e}"))
+        }
+        other => other.map_err(|e| format!("transport error: {e}")),
+    }
+}
+
+/// JSON schema of the `format` argument shared by `gpio_read` and `gpio_write`.
+fn format_schema() -> serde_json::Value {
+    json!({
+        "type": "string",
+        "enum": ["prose", "json"],
+        "description": "Output shape: prose sentence or JSON {device, pin, value, state} (default from config)"
+    })
+}
+
+/// The call's `format` argument, or `default` when absent.
+fn requested_format(
+    args: &serde_json::Value,
+    default: HardwareGpioOutputFormat,
+) -> Result<HardwareGpioOutputFormat, String> {
+    match args.get("format").and_then(|v| v.as_str()) {
+        None => Ok(default),
+        Some("prose") => Ok(HardwareGpioOutputFormat::Prose),
+        Some("json") => Ok(HardwareGpioOutputFormat::Json),
+        Some(other) => Err(format!(
+            "invalid format '{other}'; expected \"prose\" or \"json\""
+        )),
+    }
+}
+
+/// Success output for a pin state: `prose` as-is, or the JSON object.
+fn pin_state_output(
+    format: HardwareGpioOutputFormat,
+    device: &str,
+    pin: u64,
+    value: u64,
+    state: &str,
+    prose: String,
+) -> String {
+    match format {
+        HardwareGpioOutputFormat::Prose => prose,
+        HardwareGpioOutputFormat::Json => {
+            json!({ "device": device, "pin": pin, "value": value, "state": state }).to_string()
+        }
+    }
+}
+
 // ── GpioWriteTool ─────────────────────────────────────────────────────────────
 
 /// Tool: set a GPIO pin HIGH or LOW on a connected hardware device.
@@ -33,11 +199,31 @@ use tokio::sync::RwLock;
 /// and returns a human-readable result.
 pub struct GpioWriteTool {
     registry: Arc<RwLock<DeviceRegistry>>,
+    send_options: HardwareSendOptions,
+    output_format: HardwareGpioOutputFormat,
 }
 
 impl GpioWriteTool {
     pub fn new(registry: Arc<RwLock<DeviceRegistry>>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            send_options: HardwareSendOptions::default(),
+            output_format: HardwareGpioOutputFormat::default(),
+        }
+    }
+
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
+    }
+
+    /// Output used when a call does not pass `format` (default prose).
+    #[must_use]
+    pub fn with_output_format(mut self, format: HardwareGpioOutputFormat) -> Self {
+        self.output_format = format;
+        self
     }
 }
 
@@ -47,6 +233,11 @@ impl Tool for GpioWriteTool {
         "gpio_write"
     }
 
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     fn description(&self) -> &str {
         "Set a GPIO pin HIGH (1) or LOW (0) on a connected hardware device"
     }
@@ -59,6 +250,10 @@ impl Tool for GpioWriteTool {
                     "type": "string",
                     "description": "Device alias e.g. pico0, arduino0"
                 },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                },
                 "pin": {
                     "type": "integer",
                     "description": "GPIO pin number"
@@ -67,13 +262,17 @@ impl Tool for GpioWriteTool {
                     "type": "integer",
                     "enum": [0, 1],
                     "description": "1 = HIGH (on), 0 = LOW (off)"
-                }
+                },
+                "format": format_schema()
             },
             "required": ["pin", "value"]
         })
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = self.send_options.estop.refusal() {
+            return Ok(refused);
+        }
         let pin = match args.get("pin").and_then(|v| v.as_u64()) {
             Some(p) => p,
             None => {
@@ -102,6 +301,16 @@ impl Tool for GpioWriteTool {
                 error: Some("value must be 0 or 1".to_string()),
             });
         }
+        let format = match requested_format(&args, self.output_format) {
+            Ok(format) => format,
+            Err(msg) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(msg),
+                })
+            }
+        };
 
         // Resolve device alias and obtain an owned context (Arc-based) before
         // dropping the registry read guard — avoids holding the lock across async I/O.
@@ -120,18 +329,45 @@ impl Tool for GpioWriteTool {
             // registry read guard dropped here
         };
 
+        if let Err(msg) = ctx.capabilities.check_pin(pin) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(msg),
+            });
+        }
+
+        if self.send_options.dry_run {
+            let state = if value == 1 { "HIGH" } else { "LOW" };
+            return Ok(ToolResult {
+                success: true,
+                output: format!("[dry run] would set GPIO {pin} {state} on {device_alias}"),
+                error: None,
+            });
+        }
+
         let cmd = ZcCommand::new("gpio_write", json!({ "pin": pin, "value": value }));
 
-        match ctx.transport.send(&cmd).await {
+        match send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await
+        {
             Ok(resp) if resp.ok => {
                 let state = resp
                     .data
                     .get("state")
                     .and_then(|v| v.as_str())
                     .unwrap_or(if value == 1 { "HIGH" } else { "LOW" });
+                let prose = format!("GPIO {} set {} on {}", pin, state, device_alias);
                 Ok(ToolResult {
                     success: true,
-                    output: format!("GPIO {} set {} on {}", pin, state, device_alias),
+                    output: pin_state_output(format, &device_alias, pin, value, state, prose),
                     error: None,
                 })
             }
@@ -146,7 +382,7 @@ impl Tool for GpioWriteTool {
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("transport error: {}", e)),
+                error: Some(e),
             }),
         }
     }
@@ -160,11 +396,31 @@ impl Tool for GpioWriteTool {
 /// sends it via the device's transport, and returns the pin state.
 pub struct GpioReadTool {
     registry: Arc<RwLock<DeviceRegistry>>,
+    send_options: HardwareSendOptions,
+    output_format: HardwareGpioOutputFormat,
 }
 
 impl GpioReadTool {
     pub fn new(registry: Arc<RwLock<DeviceRegistry>>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            send_options: HardwareSendOptions::default(),
+            output_format: HardwareGpioOutputFormat::default(),
+        }
+    }
+
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
+    }
+
+    /// Output used when a call does not pass `format` (default prose).
+    #[must_use]
+    pub fn with_output_format(mut self, format: HardwareGpioOutputFormat) -> Self {
+        self.output_format = format;
+        self
     }
 }
 
@@ -186,10 +442,15 @@ impl Tool for GpioReadTool {
                     "type": "string",
                     "description": "Device alias e.g. pico0, arduino0"
                 },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                },
                 "pin": {
                     "type": "integer",
                     "description": "GPIO pin number to read"
-                }
+                },
+                "format": format_schema()
             },
             "required": ["pin"]
         })
@@ -206,6 +467,16 @@ impl Tool for GpioReadTool {
                 })
             }
         };
+        let format = match requested_format(&args, self.output_format) {
+            Ok(format) => format,
+            Err(msg) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(msg),
+                })
+            }
+        };
 
         // Resolve device alias and obtain an owned context (Arc-based) before
         // dropping the registry read guard — avoids holding the lock across async I/O.
@@ -224,9 +495,26 @@ impl Tool for GpioReadTool {
             // registry read guard dropped here
         };
 
+        if let Err(msg) = ctx.capabilities.check_pin(pin) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(msg),
+            });
+        }
+
         let cmd = ZcCommand::new("gpio_read", json!({ "pin": pin }));
 
-        match ctx.transport.send(&cmd).await {
+        match send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await
+        {
             Ok(resp) if resp.ok => {
                 let value = resp.data.get("value").and_then(|v| v.as_u64()).unwrap_or(0);
                 let state = resp
@@ -234,9 +522,10 @@ impl Tool for GpioReadTool {
                     .get("state")
                     .and_then(|v| v.as_str())
                     .unwrap_or(if value == 1 { "HIGH" } else { "LOW" });
+                let prose = format!("GPIO {} is {} ({}) on {}", pin, state, value, device_alias);
                 Ok(ToolResult {
                     success: true,
-                    output: format!("GPIO {} is {} ({}) on {}", pin, state, value, device_alias),
+                    output: pin_state_output(format, &device_alias, pin, value, state, prose),
                     error: None,
                 })
             }
@@ -251,103 +540,277 @@ impl Tool for GpioReadTool {
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("transport error: {}", e)),
+                error: Some(e),
             }),
         }
     }
 }
 
-// ── Factory ───────────────────────────────────────────────────────────────────
+// ── GpioConfigTool ────────────────────────────────────────────────────────────
 
-/// Create the built-in GPIO tools for a given device registry.
+/// Pin modes accepted by `gpio_config`.
+const GPIO_PIN_MODES: &[&str] = &["input", "output", "input_pullup", "input_pulldown"];
+
+/// Tool: configure the mode of one or more GPIO pins on a connected device.
 ///
-/// Returns `[GpioWriteTool, GpioReadTool]` ready for registration in the
-/// agent's tool list or a future `ToolRegistry`.
-pub fn gpio_tools(registry: Arc<RwLock<DeviceRegistry>>) -> Vec<Box<dyn Tool>> {
-    vec![
-        Box::new(GpioWriteTool::new(registry.clone())),
-        Box::new(GpioReadTool::new(registry)),
-    ]
+/// The LLM provides `device` (alias) and `pins`, a list of `{pin, mode}`
+/// entries. All entries are validated before anything is sent, so a single
+/// bad mode never leaves the board half-configured.
+pub struct GpioConfigTool {
+    registry: Arc<RwLock<DeviceRegistry>>,
+    security: Arc<SecurityPolicy>,
+    send_options: HardwareSendOptions,
 }
 
-// ── Tests ─────────────────────────────────────────────────────────────────────
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::hardware::{
-        device::{DeviceCapabilities, DeviceRegistry},
-        protocol::ZcResponse,
-        transport::{Transport, TransportError, TransportKind},
-    };
-    use std::sync::atomic::{AtomicBool, Ordering};
+impl GpioConfigTool {
+    pub fn new(registry: Arc<RwLock<DeviceRegistry>>, security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            registry,
+            security,
+            send_options: HardwareSendOptions::default(),
+        }
+    }
 
-    /// Mock transport that returns configurable responses.
-    struct MockTransport {
-        response: tokio::sync::Mutex<ZcResponse>,
-        connected: AtomicBool,
-        last_cmd: tokio::sync::Mutex<Option<ZcCommand>>,
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
     }
 
-    impl MockTransport {
-        fn new(response: ZcResponse) -> Self {
-            Self {
-                response: tokio::sync::Mutex::new(response),
-                connected: AtomicBool::new(true),
-                last_cmd: tokio::sync::Mutex::new(None),
+    /// Parse and validate the `pins` argument into `(pin, mode)` pairs.
+    fn parse_pins(args: &serde_json::Value) -> Result<Vec<(u64, String)>, String> {
+        let entries = args
+            .get("pins")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| "missing required parameter: pins".to_string())?;
+        if entries.is_empty() {
+            return Err("pins must contain at least one entry".to_string());
+        }
+
+        let mut pins = Vec::with_capacity(entries.len());
+        for (i, entry) in entries.iter().enumerate() {
+            let pin = entry
+                .get("pin")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| format!("pins[{i}]: missing or invalid pin"))?;
+            let mode = entry
+                .get("mode")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("pins[{i}]: missing mode"))?
+                .trim()
+                .to_ascii_lowercase();
+            if !GPIO_PIN_MODES.contains(&mode.as_str()) {
+                return Err(format!(
+                    "pins[{i}]: invalid mode '{mode}'; expected one of: {}",
+                    GPIO_PIN_MODES.join(", ")
+                ));
             }
+            pins.push((pin, mode));
         }
+        Ok(pins)
+    }
+}
+
+#[async_trait]
+impl Tool for GpioConfigTool {
+    fn name(&self) -> &str {
+        "gpio_config"
+    }
 
-        fn disconnected() -> Self {
-            let t = Self::new(ZcResponse::error("mock: disconnected"));
-            t.connected.store(false, Ordering::SeqCst);
-            t
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> &str {
+        "Configure GPIO pin modes (input, output, input_pullup, input_pulldown) on a connected device before reading or writing"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "device": {
+                    "type": "string",
+                    "description": "Device alias e.g. pico0, arduino0"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                },
+                "pins": {
+                    "type": "array",
+                    "description": "Pins to configure",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "pin": {
+                                "type": "integer",
+                                "description": "GPIO pin number"
+                            },
+                            "mode": {
+                                "type": "string",
+                                "enum": GPIO_PIN_MODES,
+                                "description": "Pin mode"
+                            }
+                        },
+                        "required": ["pin", "mode"]
+                    }
+                }
+            },
+            "required": ["pins"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = self.send_options.estop.refusal() {
+            return Ok(refused);
         }
+        let pins = match Self::parse_pins(&args) {
+            Ok(pins) => pins,
+            Err(msg) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(msg),
+                })
+            }
+        };
 
-        async fn last_command(&self) -> Option<ZcCommand> {
-            self.last_cmd.lock().await.clone()
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
         }
-    }
 
-    #[async_trait]
-    impl Transport for MockTransport {
-        async fn send(&self, cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
-            if !self.connected.load(Ordering::SeqCst) {
-                return Err(TransportError::Disconnected);
+        // Resolve device alias and obtain an owned context (Arc-based) before
+        // dropping the registry read guard — avoids holding the lock across async I/O.
+        let (device_alias, ctx) = {
+            let registry = self.registry.read().await;
+            match registry.resolve_gpio_device(&args) {
+                Ok(resolved) => resolved,
+                Err(msg) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(msg),
+                    });
+                }
             }
-            *self.last_cmd.lock().await = Some(cmd.clone());
-            Ok(self.response.lock().await.clone())
+            // registry read guard dropped here
+        };
+
+        if let Some(msg) = pins
+            .iter()
+            .find_map(|(pin, _)| ctx.capabilities.check_pin(*pin).err())
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(msg),
+            });
         }
 
-        fn kind(&self) -> TransportKind {
-            TransportKind::Serial
+        if self.send_options.dry_run {
+            let lines: Vec<String> = pins
+                .iter()
+                .map(|(pin, mode)| {
+                    format!("[dry run] would configure GPIO {pin} as {mode} on {device_alias}")
+                })
+                .collect();
+            return Ok(ToolResult {
+                success: true,
+                output: lines.join("\n"),
+                error: None,
+            });
         }
 
-        fn is_connected(&self) -> bool {
-            self.connected.load(Ordering::SeqCst)
+        let payload: Vec<serde_json::Value> = pins
+            .iter()
+            .map(|(pin, mode)| json!({ "pin": pin, "mode": mode }))
+            .collect();
+        let cmd = ZcCommand::new("gpio_config", json!({ "pins": payload }));
+
+        match send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await
+        {
+            Ok(resp) if resp.ok => {
+                let lines: Vec<String> = pins
+                    .iter()
+                    .map(|(pin, mode)| {
+                        format!("GPIO {} configured as {} on {}", pin, mode, device_alias)
+                    })
+                    .collect();
+                Ok(ToolResult {
+                    success: true,
+                    output: lines.join("\n"),
+                    error: None,
+                })
+            }
+            Ok(resp) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    resp.error
+                        .unwrap_or_else(|| "device returned ok:false".to_string()),
+                ),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e),
+            }),
         }
     }
+}
+
+// ── Factory ───────────────────────────────────────────────────────────────────
+
+/// Create the built-in GPIO tools for a given device registry.
+///
+/// Returns `[GpioWriteTool, GpioReadTool]` ready for registration in the
+/// agent's tool list or a future `ToolRegistry`.
+pub fn gpio_tools(registry: Arc<RwLock<DeviceRegistry>>) -> Vec<Box<dyn Tool>> {
+    vec![
+        Box::new(GpioWriteTool::new(registry.clone())),
+        Box::new(GpioReadTool::new(registry)),
+    ]
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{
+        device::{
+            DeviceCapabilities, DeviceRegistry, ReconnectPolicy, ScannedPort, TransportConnector,
+        },
+        estop::ESTOP_ENGAGED_ERROR,
+        protocol::ZcResponse,
+        test_support::{registry_with_transport, MockTransport},
+        transport::Transport,
+    };
 
     /// Helper: build a registry with one device + mock transport.
     fn registry_with_mock(transport: Arc<MockTransport>) -> Arc<RwLock<DeviceRegistry>> {
-        let mut reg = DeviceRegistry::new();
-        let alias = reg.register(
-            "raspberry-pi-pico",
-            Some(0x2e8a),
-            Some(0x000a),
-            Some("/dev/ttyACM0".to_string()),
-            Some("ARM Cortex-M0+".to_string()),
-        );
-        reg.attach_transport(
-            &alias,
-            transport as Arc<dyn Transport>,
+        registry_with_transport(
+            transport,
             DeviceCapabilities {
                 gpio: true,
                 ..Default::default()
             },
         )
-        .expect("alias was just registered");
-        Arc::new(RwLock::new(reg))
     }
 
     // ── GpioWriteTool tests ──────────────────────────────────────────────
@@ -376,6 +839,195 @@ mod tests {
         assert_eq!(cmd.params["value"], 1);
     }
 
+    #[tokio::test]
+    async fn remapped_command_names_are_sent_to_custom_firmware() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let reg = registry_with_mock(mock.clone());
+        reg.write()
+            .await
+            .set_boards(&[crate::config::PeripheralBoardConfig {
+                board: "pico".into(),
+                path: Some("/dev/ttyACM0".into()),
+                commands: [("gpio_write".to_string(), "set_pin".to_string())].into(),
+                ..Default::default()
+            }]);
+
+        let result = GpioWriteTool::new(reg.clone())
+            .with_send_options(HardwareSendOptions {
+                estop: Arc::new(HardwareEstop::new()),
+                ..HardwareSendOptions::default()
+            })
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.cmd, "set_pin");
+        assert_eq!(cmd.params, json!({"pin": 25, "value": 1}));
+
+        // Commands without a mapping keep their standard names.
+        GpioReadTool::new(reg)
+            .execute(json!({"device": "pico0", "pin": 25}))
+            .await
+            .unwrap();
+        assert_eq!(mock.last_command().await.unwrap().cmd, "gpio_read");
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_actuation_without_touching_the_transport() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_mock(mock.clone());
+
+        let write = GpioWriteTool::new(reg.clone())
+            .with_send_options(HardwareSendOptions {
+                dry_run: true,
+                ..HardwareSendOptions::default()
+            })
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+        assert!(write.success);
+        assert_eq!(write.output, "[dry run] would set GPIO 25 HIGH on pico0");
+
+        let config = GpioConfigTool::new(reg, Arc::new(SecurityPolicy::default()))
+            .with_send_options(HardwareSendOptions {
+                dry_run: true,
+                ..HardwareSendOptions::default()
+            })
+            .execute(json!({"device": "pico0", "pins": [{"pin": 25, "mode": "output"}]}))
+            .await
+            .unwrap();
+        assert!(config.success);
+        assert_eq!(
+            config.output,
+            "[dry run] would configure GPIO 25 as output on pico0"
+        );
+
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn estop_blocks_writes_until_cleared_and_leaves_reads_alone() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let reg = registry_with_mock(mock.clone());
+        let estop = Arc::new(HardwareEstop::new());
+        let write = GpioWriteTool::new(reg.clone()).with_send_options(HardwareSendOptions {
+            estop: estop.clone(),
+            ..HardwareSendOptions::default()
+        });
+        let config = GpioConfigTool::new(reg.clone(), Arc::new(SecurityPolicy::default()))
+            .with_send_options(HardwareSendOptions {
+                estop: estop.clone(),
+                ..HardwareSendOptions::default()
+            });
+        let read = GpioReadTool::new(reg);
+        let write_args = json!({"device": "pico0", "pin": 25, "value": 1});
+
+        estop.engage();
+        let refused = write.execute(write_args.clone()).await.unwrap();
+        assert!(!refused.success);
+        assert_eq!(refused.error.as_deref(), Some(ESTOP_ENGAGED_ERROR));
+        let refused = config
+            .execute(json!({"device": "pico0", "pins": [{"pin": 25, "mode": "output"}]}))
+            .await
+            .unwrap();
+        assert_eq!(refused.error.as_deref(), Some(ESTOP_ENGAGED_ERROR));
+        assert!(mock.last_command().await.is_none());
+
+        let reading = read
+            .execute(json!({"device": "pico0", "pin": 25}))
+            .await
+            .unwrap();
+        assert!(reading.success, "{:?}", reading.error);
+
+        estop.clear();
+        let written = write.execute(write_args).await.unwrap();
+        assert!(written.success, "{:?}", written.error);
+        assert_eq!(mock.last_command().await.unwrap().cmd, "gpio_write");
+    }
+
+    #[tokio::test]
+    async fn gpio_write_appends_audit_record() {
+        let tmp = tempfile::tempdir().unwrap();
+        let audit = Arc::new(CommandAuditLog::new(tmp.path().join("hw-audit.jsonl")));
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let tool =
+            GpioWriteTool::new(registry_with_mock(mock)).with_send_options(HardwareSendOptions {
+                audit: Some(audit),
+                ..HardwareSendOptions::default()
+            });
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let log = std::fs::read_to_string(tmp.path().join("hw-audit.jsonl")).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["tool"], "gpio_write");
+        assert_eq!(record["device"], "pico0");
+        assert_eq!(record["cmd"], "gpio_write");
+        assert_eq!(record["params"], json!({"pin": 25, "value": 1}));
+        assert_eq!(record["success"], true);
+        assert!(record["error"].is_null());
+        assert!(record["timestamp"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn gpio_write_over_rate_limit_is_refused_before_send() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let tool = GpioWriteTool::new(registry_with_mock(mock.clone())).with_send_options(
+            HardwareSendOptions {
+                rate_limit: Some(Arc::new(CommandRateLimiter::new(0, 1))),
+                ..HardwareSendOptions::default()
+            },
+        );
+
+        let first = tool
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+        assert!(first.success);
+
+        let second = tool
+            .execute(json!({"device": "pico0", "pin": 25, "value": 0}))
+            .await
+            .unwrap();
+        assert!(!second.success);
+        assert!(second
+            .error
+            .unwrap()
+            .contains("device command rate limit exceeded"));
+        // The refused write never reached the device.
+        assert_eq!(mock.last_command().await.unwrap().params["value"], 1);
+
+        // Reads have their own (here unlimited) budget.
+        let read =
+            GpioReadTool::new(registry_with_mock(mock)).with_send_options(HardwareSendOptions {
+                rate_limit: Some(Arc::new(CommandRateLimiter::new(0, 1))),
+                ..HardwareSendOptions::default()
+            });
+        for _ in 0..3 {
+            assert!(
+                read.execute(json!({"device": "pico0", "pin": 25}))
+                    .await
+                    .unwrap()
+                    .success
+            );
+        }
+    }
+
     #[tokio::test]
     async fn gpio_write_low() {
         let mock = Arc::new(MockTransport::new(ZcResponse::success(
@@ -410,6 +1062,70 @@ mod tests {
         assert_eq!(result.error.as_deref(), Some("pin 99 not available"));
     }
 
+    fn pico_caps() -> DeviceCapabilities {
+        DeviceCapabilities {
+            gpio: true,
+            max_pin: Some(28),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn gpio_write_rejects_pin_above_known_max() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_transport(mock.clone(), pico_caps());
+        let tool = GpioWriteTool::new(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 9999, "value": 1}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.as_deref().unwrap().contains("0..=28"));
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn gpio_write_sends_any_pin_when_max_unknown() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 9999, "value": 1, "state": "HIGH"}),
+        )));
+        let reg = registry_with_mock(mock.clone());
+        let tool = GpioWriteTool::new(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 9999, "value": 1}))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(mock.last_command().await.unwrap().params["pin"], 9999);
+    }
+
+    #[tokio::test]
+    async fn gpio_read_and_config_reject_out_of_range_pins() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_transport(mock.clone(), pico_caps());
+
+        let read = GpioReadTool::new(reg.clone())
+            .execute(json!({"device": "pico0", "pin": 29}))
+            .await
+            .unwrap();
+        assert!(!read.success);
+
+        let config = GpioConfigTool::new(reg, Arc::new(SecurityPolicy::default()))
+            .execute(json!({
+                "device": "pico0",
+                "pins": [{"pin": 25, "mode": "output"}, {"pin": 40, "mode": "input"}]
+            }))
+            .await
+            .unwrap();
+        assert!(!config.success);
+        assert!(config.error.as_deref().unwrap().contains("pin 40"));
+        assert!(mock.last_command().await.is_none());
+    }
+
     #[tokio::test]
     async fn gpio_write_transport_disconnected() {
         let mock = Arc::new(MockTransport::disconnected());
@@ -520,6 +1236,50 @@ mod tests {
         assert_eq!(cmd.params["pin"], 25);
     }
 
+    #[tokio::test]
+    async fn gpio_read_and_write_return_json_when_asked_or_configured() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let reg = registry_with_mock(mock);
+        let expected = json!({"device": "pico0", "pin": 25, "value": 1, "state": "HIGH"});
+
+        let read = GpioReadTool::new(reg.clone())
+            .execute(json!({"device": "pico0", "pin": 25, "format": "json"}))
+            .await
+            .unwrap();
+        assert!(read.success);
+        let output: serde_json::Value = serde_json::from_str(&read.output).unwrap();
+        assert_eq!(output, expected);
+
+        let write = GpioWriteTool::new(reg.clone())
+            .with_output_format(HardwareGpioOutputFormat::Json)
+            .with_send_options(HardwareSendOptions {
+                estop: Arc::new(HardwareEstop::new()),
+                ..HardwareSendOptions::default()
+            });
+        let result = write
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output, expected);
+
+        // A call can still ask for prose, and bad values are rejected.
+        let result = write
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1, "format": "prose"}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "GPIO 25 set HIGH on pico0");
+        let result = GpioReadTool::new(reg)
+            .execute(json!({"device": "pico0", "pin": 25, "format": "xml"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("invalid format 'xml'"));
+    }
+
     #[tokio::test]
     async fn gpio_read_low() {
         let mock = Arc::new(MockTransport::new(ZcResponse::success(
@@ -590,6 +1350,327 @@ mod tests {
         assert!(result.error.as_deref().unwrap_or("").contains("no GPIO"));
     }
 
+    // ── GpioConfigTool tests ─────────────────────────────────────────────
+
+    fn config_tool(reg: Arc<RwLock<DeviceRegistry>>) -> GpioConfigTool {
+        GpioConfigTool::new(reg, Arc::new(SecurityPolicy::default()))
+    }
+
+    #[tokio::test]
+    async fn gpio_config_multi_pin_success() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_mock(mock.clone());
+        let tool = config_tool(reg);
+
+        let result = tool
+            .execute(json!({
+                "device": "pico0",
+                "pins": [
+                    {"pin": 25, "mode": "output"},
+                    {"pin": 14, "mode": "input_pullup"}
+                ]
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            "GPIO 25 configured as output on pico0\nGPIO 14 configured as input_pullup on pico0"
+        );
+
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.cmd, "gpio_config");
+        assert_eq!(cmd.params["pins"][0]["pin"], 25);
+        assert_eq!(cmd.params["pins"][0]["mode"], "output");
+        assert_eq!(cmd.params["pins"][1]["mode"], "input_pullup");
+    }
+
+    #[tokio::test]
+    async fn gpio_config_rejects_invalid_mode() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_mock(mock.clone());
+        let tool = config_tool(reg);
+
+        let result = tool
+            .execute(json!({
+                "device": "pico0",
+                "pins": [
+                    {"pin": 25, "mode": "output"},
+                    {"pin": 14, "mode": "analog"}
+                ]
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("invalid mode 'analog'"));
+        // Nothing is sent when any entry is invalid.
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn gpio_config_device_error() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::error(
+            "pin 99 not available",
+        )));
+        let reg = registry_with_mock(mock);
+        let tool = config_tool(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pins": [{"pin": 99, "mode": "input"}]}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("pin 99 not available"));
+    }
+
+    #[tokio::test]
+    async fn gpio_config_blocked_in_read_only_mode() {
+        let mock = Arc::new(MockTransport::new(ZcResponse::success(json!({}))));
+        let reg = registry_with_mock(mock.clone());
+        let security = Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = GpioConfigTool::new(reg, security);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pins": [{"pin": 25, "mode": "output"}]}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.as_deref().unwrap().contains("read-only"));
+        assert!(mock.last_command().await.is_none());
+    }
+
+    // ── Reconnect tests ──────────────────────────────────────────────────
+
+    /// Mock connector: hands out `transport` on `port`, fails everywhere else.
+    struct MockConnector {
+        port: Option<String>,
+        transport: Arc<MockTransport>,
+        vid_ports: Vec<ScannedPort>,
+        attempts: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl TransportConnector for MockConnector {
+        async fn connect(&self, port_path: &str, _baud: u32) -> anyhow::Result<Arc<dyn Transport>> {
+            self.attempts.lock().unwrap().push(port_path.to_string());
+            if self.port.as_deref() == Some(port_path) {
+                Ok(self.transport.clone() as Arc<dyn Transport>)
+            } else {
+                anyhow::bail!("no device on {port_path}")
+            }
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            self.vid_ports.clone()
+        }
+    }
+
+    /// The test Pico, with a USB serial number, as a scan reports it on `port`.
+    fn pico_on(port: &str) -> ScannedPort {
+        ScannedPort {
+            port_path: port.to_string(),
+            vid: 0x2e8a,
+            pid: 0x000a,
+            serial_number: Some("E6614103".to_string()),
+            board_name: Some("raspberry-pi-pico".to_string()),
+            architecture: None,
+            baud: crate::hardware::DEFAULT_BAUD,
+        }
+    }
+
+    /// Reconnect policy with `attempts` tries and millisecond backoff.
+    fn quick_retries(attempts: u32) -> ReconnectPolicy {
+        ReconnectPolicy {
+            attempts,
+            initial_backoff: std::time::Duration::from_millis(1),
+            max_backoff: std::time::Duration::from_millis(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn gpio_write_reconnects_on_new_path_and_retries() {
+        let fresh = Arc::new(MockTransport::new(ZcResponse::success(
+            json!({"pin": 25, "value": 1, "state": "HIGH"}),
+        )));
+        let connector = Arc::new(MockConnector {
+            port: Some("/dev/ttyACM1".to_string()),
+            transport: fresh.clone(),
+            vid_ports: vec![pico_on("/dev/ttyACM1")],
+            attempts: std::sync::Mutex::new(Vec::new()),
+        });
+        let stale = Arc::new(MockTransport::disconnected());
+        let reg = Arc::new(RwLock::new(
+            DeviceRegistry::discover_ports(
+                vec![pico_on("/dev/ttyACM0")],
+                connector.clone(),
+                |_, _| stale.clone() as Arc<dyn Transport>,
+                1,
+            )
+            .await,
+        ));
+        let tool = GpioWriteTool::new(reg.clone());
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "GPIO 25 set HIGH on pico0");
+        assert_eq!(fresh.last_command().await.unwrap().cmd, "gpio_write");
+        // Same path first, then the same board (by serial) on the new path.
+        assert_eq!(
+            *connector.attempts.lock().unwrap(),
+            vec!["/dev/ttyACM0".to_string(), "/dev/ttyACM1".to_string()]
+        );
+        assert_eq!(
+            reg.read().await.get_device("pico0").unwrap().port(),
+            Some("/dev/ttyACM1")
+        );
+    }
+
+    #[tokio::test]
+    async fn gpio_read_gives_up_when_device_stays_down() {
+        let reg = registry_with_mock(Arc::new(MockTransport::disconnected()));
+        let connector = Arc::new(MockConnector {
+            port: None,
+            transport: Arc::new(MockTransport::disconnected()),
+            vid_ports: vec![pico_on("/dev/ttyACM0")],
+            attempts: std::sync::Mutex::new(Vec::new()),
+        });
+        {
+            let mut reg = reg.write().await;
+            reg.set_connector(connector.clone());
+            reg.set_reconnect_policy(quick_retries(2));
+        }
+        let tool = GpioReadTool::new(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 25}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        let err = result.error.unwrap();
+        assert!(err.contains("reconnect failed"), "{err}");
+        assert!(err.contains("after 2 attempt(s)"), "{err}");
+        assert_eq!(connector.attempts.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn transport_error_names_alias_not_path_when_redacting() {
+        let reg = registry_with_mock(Arc::new(MockTransport::disconnected()));
+        let connector = Arc::new(MockConnector {
+            port: None,
+            transport: Arc::new(MockTransport::disconnected()),
+            vid_ports: vec![pico_on("/dev/ttyACM7")],
+            attempts: std::sync::Mutex::new(Vec::new()),
+        });
+        {
+            let mut reg = reg.write().await;
+            reg.set_connector(connector);
+            reg.set_reconnect_policy(quick_retries(1));
+        }
+        let tool = GpioReadTool::new(reg.clone());
+        let args = json!({"device": "pico0", "pin": 25});
+
+        let err = tool.execute(args.clone()).await.unwrap().error.unwrap();
+        assert!(err.contains("/dev/ttyACM"), "{err}");
+
+        reg.write().await.set_path_redaction(true, false);
+        let err = tool.execute(args).await.unwrap().error.unwrap();
+        assert!(err.contains("reconnect failed"), "{err}");
+        assert!(err.contains("pico0"), "{err}");
+        assert!(!err.contains("/dev/"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn gpio_write_skips_reconnect_when_disabled() {
+        let reg = registry_with_mock(Arc::new(MockTransport::disconnected()));
+        let connector = Arc::new(MockConnector {
+            port: Some("/dev/ttyACM0".to_string()),
+            transport: Arc::new(MockTransport::new(ZcResponse::success(json!({})))),
+            vid_ports: Vec::new(),
+            attempts: std::sync::Mutex::new(Vec::new()),
+        });
+        reg.write().await.set_connector(connector.clone());
+        let tool = GpioWriteTool::new(reg).with_send_options(HardwareSendOptions {
+            reconnect_on_disconnect: false,
+            ..HardwareSendOptions::default()
+        });
+
+        let result = tool
+            .execute(json!({"device": "pico0", "pin": 25, "value": 1}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("transport error: transport disconnected")
+        );
+        assert!(connector.attempts.lock().unwrap().is_empty());
+    }
+
+    /// Connector whose handshake waits until `release` is notified.
+    struct SlowConnector {
+        started: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+        transport: Arc<MockTransport>,
+    }
+
+    #[async_trait]
+    impl TransportConnector for SlowConnector {
+        async fn connect(
+            &self,
+            _port_path: &str,
+            _baud: u32,
+        ) -> anyhow::Result<Arc<dyn Transport>> {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(self.transport.clone() as Arc<dyn Transport>)
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn reconnect_handshake_runs_without_registry_lock() {
+        let reg = registry_with_mock(Arc::new(MockTransport::disconnected()));
+        let connector = Arc::new(SlowConnector {
+            started: tokio::sync::Notify::new(),
+            release: tokio::sync::Notify::new(),
+            transport: Arc::new(MockTransport::new(ZcResponse::success(
+                json!({"pin": 25, "value": 0, "state": "LOW"}),
+            ))),
+        });
+        reg.write().await.set_connector(connector.clone());
+        let tool = GpioReadTool::new(reg.clone());
+        let call = tokio::spawn(async move { tool.execute(json!({"pin": 25})).await });
+
+        connector.started.notified().await;
+        // Mid-handshake, the registry is free for other devices and tools.
+        assert!(reg.try_write().is_ok());
+        connector.release.notify_one();
+
+        let result = call.await.unwrap().unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(reg.read().await.context("pico0").is_some());
+    }
+
     // ── Factory / spec tests ─────────────────────────────────────────────
 
     #[test]
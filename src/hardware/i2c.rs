@@ -0,0 +1,246 @@
+//! I2C scan tool — `i2c_scan` lists the addresses that answer on a device's
+//! I2C bus.
+//!
+//! Wire protocol (ZeroClaw serial JSON):
+//! ```text
+//! i2c_scan:
+//!   Host → Device:  {"cmd":"i2c_scan","params":{"bus":0}}\n
+//!   Device → Host:  {"ok":true,"data":{"bus":0,"addresses":[60,104]}}\n
+//! ```
+//!
+//! Only devices advertising `capabilities.i2c` are eligible. Sends go through
+//! the same reconnect, audit and rate-limit path as the GPIO tools.
+
+use super::device::DeviceRegistry;
+use super::gpio::{send_audited, HardwareSendOptions};
+use super::protocol::ZcCommand;
+use crate::tools::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Tool: scan an I2C bus on a connected device for responding addresses.
+///
+/// Read-only — the firmware only probes each address for an ACK.
+pub struct I2cScanTool {
+    registry: Arc<RwLock<DeviceRegistry>>,
+    send_options: HardwareSendOptions,
+}
+
+impl I2cScanTool {
+    pub fn new(registry: Arc<RwLock<DeviceRegistry>>) -> Self {
+        Self {
+            registry,
+            send_options: HardwareSendOptions::default(),
+        }
+    }
+
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
+    }
+}
+
+/// Render the `addresses` of an `i2c_scan` response, e.g.
+/// `Found 2 devices on bus 0: 0x3C, 0x68`.
+fn format_scan(bus: u64, data: &serde_json::Value) -> String {
+    let addresses: Vec<String> = data
+        .get("addresses")
+        .and_then(|v| v.as_array())
+        .map(|addrs| {
+            addrs
+                .iter()
+                .filter_map(|a| a.as_u64())
+                .map(|a| format!("0x{a:02X}"))
+                .collect()
+        })
+        .unwrap_or_default();
+    match addresses.len() {
+        0 => format!("No devices found on bus {bus}"),
+        1 => format!("Found 1 device on bus {bus}: {}", addresses[0]),
+        n => format!("Found {n} devices on bus {bus}: {}", addresses.join(", ")),
+    }
+}
+
+#[async_trait]
+impl Tool for I2cScanTool {
+    fn name(&self) -> &str {
+        "i2c_scan"
+    }
+
+    fn description(&self) -> &str {
+        "Scan an I2C bus on a connected device and list the addresses of devices that respond"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "device": {
+                    "type": "string",
+                    "description": "Device alias e.g. pico0, arduino0"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                },
+                "bus": {
+                    "type": "integer",
+                    "description": "I2C bus number (default 0)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let bus = match args.get("bus") {
+            None => 0,
+            Some(v) => match v.as_u64() {
+                Some(bus) => bus,
+                None => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("bus must be a non-negative integer".to_string()),
+                    })
+                }
+            },
+        };
+
+        // Resolve device alias and obtain an owned context (Arc-based) before
+        // dropping the registry read guard — avoids holding the lock across async I/O.
+        let (device_alias, ctx) = {
+            let registry = self.registry.read().await;
+            match registry.resolve_i2c_device(&args) {
+                Ok(resolved) => resolved,
+                Err(msg) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(msg),
+                    });
+                }
+            }
+        };
+
+        let cmd = ZcCommand::new("i2c_scan", json!({ "bus": bus }));
+
+        match send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await
+        {
+            Ok(resp) if resp.ok => Ok(ToolResult {
+                success: true,
+                output: format_scan(bus, &resp.data),
+                error: None,
+            }),
+            Ok(resp) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    resp.error
+                        .unwrap_or_else(|| "device returned ok:false".to_string()),
+                ),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{
+        device::DeviceCapabilities, protocol::ZcResponse, test_support::registry_with_mock,
+    };
+
+    fn i2c_caps() -> DeviceCapabilities {
+        DeviceCapabilities {
+            gpio: true,
+            i2c: true,
+            ..DeviceCapabilities::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_lists_found_addresses() {
+        let (reg, mock) = registry_with_mock(
+            ZcResponse::success(json!({"bus": 1, "addresses": [60, 104]})),
+            i2c_caps(),
+        );
+        let tool = I2cScanTool::new(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "bus": 1}))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "Found 2 devices on bus 1: 0x3C, 0x68");
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.cmd, "i2c_scan");
+        assert_eq!(cmd.params, json!({"bus": 1}));
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_defaults_to_bus_zero_and_reports_empty_bus() {
+        let (reg, mock) =
+            registry_with_mock(ZcResponse::success(json!({"addresses": []})), i2c_caps());
+        let tool = I2cScanTool::new(reg);
+
+        let result = tool.execute(json!({})).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "No devices found on bus 0");
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.params["bus"], 0);
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_requires_i2c_capability() {
+        let (reg, mock) = registry_with_mock(
+            ZcResponse::success(json!({"addresses": [60]})),
+            DeviceCapabilities {
+                gpio: true,
+                ..DeviceCapabilities::default()
+            },
+        );
+        let tool = I2cScanTool::new(reg);
+
+        let explicit = tool.execute(json!({"device": "pico0"})).await.unwrap();
+        assert!(!explicit.success);
+        assert!(explicit.error.unwrap().contains("does not support I2C"));
+
+        let auto = tool.execute(json!({})).await.unwrap();
+        assert!(!auto.success);
+        assert!(auto.error.unwrap().contains("no I2C-capable device found"));
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn i2c_scan_surfaces_device_error() {
+        let (reg, _mock) = registry_with_mock(ZcResponse::error("bus 3 not available"), i2c_caps());
+        let tool = I2cScanTool::new(reg);
+
+        let result = tool
+            .execute(json!({"device": "pico0", "bus": 3}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("bus 3 not available"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Serial keepalive — pings registered devices on an interval.
+//!
+//! Some boards reset or sleep after a period of serial inactivity, dropping
+//! the firmware state. With `hardware.keepalive_interval_secs` set, a
+//! background task sends `ping` to every serial device so it stays awake and
+//! a dead link is noticed before the next tool call. A failed ping marks the
+//! device disconnected; marked devices are skipped until a reconnect
+//! attaches a fresh transport.
+//!
+//! `hardware.probe_on_startup` runs one such round in the background right
+//! after discovery, reconnecting each unresponsive device before marking
+//! it, so the prompt summary does not report lazily opened boards as
+//! connected before anything has talked to them.
+//!
+//! The registry lock is never held across device I/O: pings, safe-state
+//! writes and reconnect handshakes run on contexts taken out beforehand.
+
+use super::device::{reconnect_shared, DeviceContext, DeviceRegistry};
+use super::protocol::ZcCommand;
+use super::transport::TransportKind;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Serial devices not already marked disconnected, by alias.
+async fn ping_targets(registry: &RwLock<DeviceRegistry>) -> Vec<(String, DeviceContext)> {
+    let registry = registry.read().await;
+    let mut aliases: Vec<&str> = registry.aliases();
+    aliases.sort_unstable();
+    aliases
+        .into_iter()
+        .filter(|alias| !registry.is_marked_disconnected(alias))
+        .filter_map(|alias| registry.context(alias).map(|ctx| (alias.to_string(), ctx)))
+        .filter(|(_, ctx)| ctx.transport.kind() == TransportKind::Serial)
+        .collect()
+}
+
+/// Send one `ping`, returning why the device did not answer.
+async fn ping(ctx: &DeviceContext) -> Result<(), String> {
+    let cmd = ZcCommand::new("ping", serde_json::json!({}));
+    match ctx.transport.send(&cmd).await {
+        Ok(resp) if resp.ok => Ok(()),
+        Ok(resp) => Err(resp.error.unwrap_or_else(|| "ping rejected".into())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Ping every serial device not already marked disconnected, once.
+///
+/// Returns the aliases newly marked disconnected by this round.
+pub async fn ping_devices(registry: &RwLock<DeviceRegistry>) -> Vec<String> {
+    let mut failed = Vec::new();
+    for (alias, ctx) in ping_targets(registry).await {
+        let Err(error) = ping(&ctx).await else {
+            continue;
+        };
+        if ctx.transport.is_connected() {
+            let safe = registry.read().await.safe_state(&alias);
+            if let Some(safe) = safe {
+                safe.apply().await;
+            }
+        }
+        if registry.write().await.mark_disconnected(&alias) {
+            tracing::warn!(alias = %alias, "keepalive ping failed; marking device disconnected: {error}");
+            failed.push(alias);
+        }
+    }
+    failed
+}
+
+/// Startup probe: ping every serial device once; a device that does not
+/// answer is reconnected per the registry's
+/// [`ReconnectPolicy`](super::device::ReconnectPolicy) and marked
+/// disconnected if that fails too.
+///
+/// Returns the aliases found unreachable.
+pub async fn probe_devices(registry: &RwLock<DeviceRegistry>) -> Vec<String> {
+    let mut unreachable = Vec::new();
+    for (alias, ctx) in ping_targets(registry).await {
+        let Err(error) = ping(&ctx).await else {
+            tracing::debug!(alias = %alias, "startup probe: device reachable");
+            continue;
+        };
+        match reconnect_shared(registry, &alias).await {
+            Ok(()) => tracing::info!(alias = %alias, "startup probe: device reconnected"),
+            Err(e) => {
+                registry.write().await.mark_disconnected(&alias);
+                tracing::warn!(
+                    alias = %alias,
+                    "startup probe: device unreachable ({error}); reconnect failed: {e:#}"
+                );
+                unreachable.push(alias);
+            }
+        }
+    }
+    unreachable
+}
+
+/// Run [`probe_devices`] in the background when a Tokio runtime is
+/// available, so startup never waits on an unreachable board.
+pub fn spawn_startup_probe(registry: &Arc<RwLock<DeviceRegistry>>) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::debug!("no async runtime; hardware startup probe not run");
+        return;
+    };
+    let registry = Arc::clone(registry);
+    handle.spawn(async move {
+        probe_devices(&registry).await;
+    });
+}
+
+/// Ping devices every `interval` until the registry is dropped.
+pub async fn run(registry: Weak<RwLock<DeviceRegistry>>, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let Some(registry) = registry.upgrade() else {
+            return;
+        };
+        ping_devices(&registry).await;
+    }
+}
+
+/// Start the keepalive task for `registry` when `interval_secs` is non-zero
+/// and a Tokio runtime is available.
+pub fn spawn(registry: &Arc<RwLock<DeviceRegistry>>, interval_secs: u64) {
+    if interval_secs == 0 {
+        return;
+    }
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::debug!("no async runtime; hardware keepalive not started");
+        return;
+    };
+    handle.spawn(run(
+        Arc::downgrade(registry),
+        Duration::from_secs(interval_secs),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::device::{DeviceCapabilities, ScannedPort};
+    use crate::hardware::protocol::ZcResponse;
+    use crate::hardware::transport::{Transport, TransportError};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct PingCounter {
+        pings: AtomicUsize,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Transport for PingCounter {
+        async fn send(&self, cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            assert_eq!(cmd.cmd, "ping");
+            self.pings.fetch_add(1, Ordering::SeqCst);
+            if self.fail.load(Ordering::SeqCst) {
+                Err(TransportError::Disconnected)
+            } else {
+                Ok(ZcResponse::success(serde_json::json!({})))
+            }
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+
+    fn registry_with(transport: Arc<PingCounter>) -> (Arc<RwLock<DeviceRegistry>>, String) {
+        let mut reg = DeviceRegistry::new();
+        let alias = reg.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+        reg.attach_transport(&alias, transport, DeviceCapabilities::default())
+            .unwrap();
+        (Arc::new(RwLock::new(reg)), alias)
+    }
+
+    #[tokio::test]
+    async fn keepalive_pings_at_interval_and_marks_failures_disconnected() {
+        let transport = Arc::new(PingCounter::default());
+        let (registry, alias) = registry_with(transport.clone());
+        let task = tokio::spawn(run(Arc::downgrade(&registry), Duration::from_millis(40)));
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let pings = transport.pings.load(Ordering::SeqCst);
+        assert!((2..=4).contains(&pings), "expected ~3 pings, got {pings}");
+        assert!(!registry.read().await.is_marked_disconnected(&alias));
+
+        transport.fail.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(registry.read().await.is_marked_disconnected(&alias));
+
+        // Marked devices are no longer pinged.
+        let after_mark = transport.pings.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(transport.pings.load(Ordering::SeqCst), after_mark);
+        task.abort();
+    }
+
+    fn status_of<'a>(summary: &'a str, alias: &str) -> &'a str {
+        summary
+            .lines()
+            .find(|line| line.trim_start().starts_with(alias))
+            .and_then(|line| line.rsplit('[').next())
+            .map(|status| status.trim_end_matches(']'))
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn startup_probe_marks_unreachable_boards_disconnected() {
+        let unreachable = Arc::new(PingCounter::default());
+        unreachable.fail.store(true, Ordering::SeqCst);
+        let mut reg = DeviceRegistry::new();
+        let live = reg.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+        let dead = reg.register("arduino-uno", Some(0x2341), None, None, None);
+        reg.attach_transport(
+            &live,
+            Arc::new(PingCounter::default()),
+            DeviceCapabilities::default(),
+        )
+        .unwrap();
+        reg.attach_transport(&dead, unreachable, DeviceCapabilities::default())
+            .unwrap();
+        let registry = RwLock::new(reg);
+        // Before probing, a lazily opened transport looks connected.
+        assert_eq!(
+            status_of(&registry.read().await.prompt_summary(), &dead),
+            "connected"
+        );
+
+        assert_eq!(probe_devices(&registry).await, vec![dead.clone()]);
+
+        let summary = registry.read().await.prompt_summary();
+        assert_eq!(status_of(&summary, &live), "connected", "{summary}");
+        assert_eq!(status_of(&summary, &dead), "disconnected", "{summary}");
+    }
+
+    struct FreshConnector;
+
+    #[async_trait]
+    impl crate::hardware::device::TransportConnector for FreshConnector {
+        async fn connect(
+            &self,
+            _port_path: &str,
+            _baud: u32,
+        ) -> anyhow::Result<Arc<dyn Transport>> {
+            Ok(Arc::new(PingCounter::default()))
+        }
+
+        fn ports_for_vid(&self, _vid: u16) -> Vec<ScannedPort> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn startup_probe_recovers_board_before_marking_it() {
+        let stale = Arc::new(PingCounter::default());
+        stale.fail.store(true, Ordering::SeqCst);
+        let mut reg = DeviceRegistry::new();
+        reg.set_connector(Arc::new(FreshConnector));
+        let alias = reg.register(
+            "raspberry-pi-pico",
+            Some(0x2e8a),
+            None,
+            Some("/dev/ttyACM0".into()),
+            None,
+        );
+        reg.attach_transport(&alias, stale, DeviceCapabilities::default())
+            .unwrap();
+        let registry = RwLock::new(reg);
+
+        assert!(probe_devices(&registry).await.is_empty());
+        let summary = registry.read().await.prompt_summary();
+        assert_eq!(status_of(&summary, &alias), "connected", "{summary}");
+    }
+
+    #[tokio::test]
+    async fn reattaching_transport_clears_disconnected_mark() {
+        let transport = Arc::new(PingCounter::default());
+        transport.fail.store(true, Ordering::SeqCst);
+        let (registry, alias) = registry_with(transport);
+
+        assert_eq!(ping_devices(&registry).await, vec![alias.clone()]);
+        assert!(ping_devices(&registry).await.is_empty());
+
+        registry
+            .write()
+            .await
+            .attach_transport(
+                &alias,
+                Arc::new(PingCounter::default()),
+                DeviceCapabilities::default(),
+            )
+            .unwrap();
+        assert!(!registry.read().await.is_marked_disconnected(&alias));
+    }
+}
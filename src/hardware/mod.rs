@@ -2,9 +2,17 @@
 //!
 //! See `docs/hardware-peripherals-design.md` for the full design.
 
+pub mod audit;
+pub mod capabilities;
 pub mod device;
+pub mod estop;
+pub mod firmware;
 pub mod gpio;
+pub mod i2c;
+pub mod keepalive;
 pub mod protocol;
+pub mod pwm;
+pub mod rate_limit;
 pub mod registry;
 pub mod transport;
 
@@ -20,37 +28,89 @@ pub mod discover;
 ))]
 pub mod introspect;
 
+#[cfg(feature = "hardware")]
+pub mod bootsel;
+
 #[cfg(feature = "hardware")]
 pub mod serial;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 use crate::config::Config;
+use crate::security::SecurityPolicy;
+use crate::tools::Tool;
 use anyhow::Result;
+use std::sync::Arc;
 
 // Re-export config types so wizard can use `hardware::HardwareConfig` etc.
-pub use crate::config::{HardwareConfig, HardwareTransport};
+pub use crate::config::{HardwareConfig, HardwareNoDeviceBehavior, HardwareTransport};
+#[allow(unused_imports)]
+pub use capabilities::DeviceCapabilitiesTool;
 #[allow(unused_imports)]
 pub use device::{
-    Device, DeviceCapabilities, DeviceContext, DeviceKind, DeviceRegistry, DeviceRuntime,
-    NO_HW_DEVICES_SUMMARY,
+    board_baud, board_post_write_delay, Device, DeviceCapabilities, DeviceContext, DeviceKind,
+    DeviceRegistry, DeviceRuntime, ReconnectPolicy, RescanReport, ScannedPort, TransportConnector,
+    DEFAULT_BAUD, NO_HW_DEVICES_SUMMARY,
 };
 #[allow(unused_imports)]
-pub use gpio::{gpio_tools, GpioReadTool, GpioWriteTool};
+pub use estop::{HardwareEstop, ESTOP_ENGAGED_ERROR};
+#[allow(unused_imports)]
+pub use gpio::{gpio_tools, GpioConfigTool, GpioReadTool, GpioWriteTool, HardwareSendOptions};
+#[allow(unused_imports)]
+pub use i2c::I2cScanTool;
 #[allow(unused_imports)]
 pub use protocol::{ZcCommand, ZcResponse};
 #[allow(unused_imports)]
+pub use pwm::PwmWriteTool;
+#[allow(unused_imports)]
 pub use transport::{Transport, TransportError, TransportKind};
 
 #[cfg(feature = "hardware")]
 #[allow(unused_imports)]
 pub use serial::HardwareSerialTransport;
 
-/// A hardware device discovered during auto-scan.
+/// A hardware device discovered during auto-scan, as shown by the setup
+/// wizard, `zeroclaw hardware discover` and the Android bridge.
 #[derive(Debug, Clone)]
 pub struct DiscoveredDevice {
+    /// Board name from the registry, or `vid:pid` when unrecognised.
     pub name: String,
+    /// Product string reported by the device.
     pub detail: Option<String>,
     pub device_path: Option<String>,
     pub transport: HardwareTransport,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+    /// Architecture from the board registry (e.g. `"ARM Cortex-M4"`), a hint
+    /// at what the board can do before it is connected.
+    pub capabilities_hint: Option<String>,
+}
+
+#[cfg(all(
+    feature = "hardware",
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+))]
+impl From<discover::UsbDeviceInfo> for DiscoveredDevice {
+    fn from(d: discover::UsbDeviceInfo) -> Self {
+        Self {
+            name: d
+                .board_name
+                .unwrap_or_else(|| format!("{:04x}:{:04x}", d.vid, d.pid)),
+            detail: d.product_string,
+            device_path: None,
+            transport: if d.architecture.as_deref() == Some("native") {
+                HardwareTransport::Native
+            } else {
+                HardwareTransport::Serial
+            },
+            vid: Some(d.vid),
+            pid: Some(d.pid),
+            serial_number: d.serial_number,
+            capabilities_hint: d.architecture,
+        }
+    }
 }
 
 /// Auto-discover connected hardware devices.
@@ -64,26 +124,157 @@ pub fn discover_hardware() -> Vec<DiscoveredDevice> {
     ))]
     {
         if let Ok(devices) = discover::list_usb_devices() {
-            return devices
-                .into_iter()
-                .map(|d| DiscoveredDevice {
-                    name: d
-                        .board_name
-                        .unwrap_or_else(|| format!("{:04x}:{:04x}", d.vid, d.pid)),
-                    detail: d.product_string,
-                    device_path: None,
-                    transport: if d.architecture.as_deref() == Some("native") {
-                        HardwareTransport::Native
-                    } else {
-                        HardwareTransport::Serial
-                    },
-                })
-                .collect();
+            return devices.into_iter().map(DiscoveredDevice::from).collect();
         }
     }
     Vec::new()
 }
 
+/// Build the device registry the agent's hardware tools dispatch through.
+///
+/// Runs serial discovery when hardware is enabled in a `hardware` build;
+/// otherwise returns an empty registry. Boards listed in
+/// `[peripherals].boards` are opened at their configured `baud`.
+#[cfg(feature = "hardware")]
+pub async fn load_device_registry(config: &Config) -> DeviceRegistry {
+    if config.hardware.enabled {
+        DeviceRegistry::discover(
+            config.hardware.discovery_parallelism,
+            &config.peripherals.boards,
+            config.hardware.redact_device_paths_in_logs,
+        )
+        .await
+    } else {
+        DeviceRegistry::new()
+    }
+}
+
+#[cfg(not(feature = "hardware"))]
+#[allow(clippy::unused_async)]
+pub async fn load_device_registry(_config: &Config) -> DeviceRegistry {
+    DeviceRegistry::new()
+}
+
+/// Build the registry-backed hardware tools (`gpio_write`, `gpio_read`,
+/// `gpio_config`, `device_capabilities`), plus `i2c_scan` and `pwm_write`
+/// when a registered device advertises I2C or PWM.
+///
+/// This also attaches the registry to the emergency stop and starts the
+/// keepalive and startup probe, so call it once per process (see
+/// [`shared_hardware_tools`]). With [`HardwareNoDeviceBehavior::Hide`] no
+/// tools are built when the registry is empty and no peripherals are
+/// configured, so software-only setups don't see tools that can only answer
+/// "no device found".
+pub fn build_hardware_tools(
+    mut registry: DeviceRegistry,
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+) -> Vec<Arc<dyn Tool>> {
+    let has_peripherals = config.peripherals.enabled && !config.peripherals.boards.is_empty();
+    if registry.is_empty()
+        && !has_peripherals
+        && config.hardware.no_device_behavior == HardwareNoDeviceBehavior::Hide
+    {
+        tracing::debug!("no hardware devices or peripherals; hardware tools hidden");
+        return Vec::new();
+    }
+
+    registry.set_preference(config.hardware.device_preference.clone());
+    registry.set_identity(config.hardware.device_identity);
+    registry.set_reconnect_policy(ReconnectPolicy {
+        attempts: config.hardware.reconnect_attempts,
+        initial_backoff: std::time::Duration::from_millis(config.hardware.reconnect_backoff_ms),
+        max_backoff: std::time::Duration::from_millis(config.hardware.reconnect_max_backoff_ms),
+    });
+    registry.set_path_redaction(
+        config.hardware.redact_device_paths,
+        config.hardware.redact_device_paths_in_logs,
+    );
+    let has_i2c = registry.any_capable(|c| c.i2c);
+    let has_pwm = registry.any_capable(|c| c.pwm);
+    let registry = Arc::new(tokio::sync::RwLock::new(registry));
+    HardwareEstop::global().attach_registry(&registry);
+    keepalive::spawn(&registry, config.hardware.keepalive_interval_secs);
+    if config.hardware.probe_on_startup {
+        keepalive::spawn_startup_probe(&registry);
+    }
+    let send_options = HardwareSendOptions {
+        audit: audit::CommandAuditLog::from_config(&config.hardware, &config.workspace_dir),
+        rate_limit: rate_limit::CommandRateLimiter::from_config(&config.hardware),
+        dry_run: config.gateway.dry_mode,
+        ..HardwareSendOptions::default()
+    };
+    let mut tools: Vec<Arc<dyn Tool>> = vec![
+        Arc::new(
+            GpioWriteTool::new(registry.clone())
+                .with_send_options(send_options.clone())
+                .with_output_format(config.hardware.gpio_output_format),
+        ),
+        Arc::new(
+            GpioReadTool::new(registry.clone())
+                .with_send_options(send_options.clone())
+                .with_output_format(config.hardware.gpio_output_format),
+        ),
+        Arc::new(
+            GpioConfigTool::new(registry.clone(), security.clone())
+                .with_send_options(send_options.clone()),
+        ),
+        Arc::new(
+            DeviceCapabilitiesTool::new(registry.clone()).with_send_options(send_options.clone()),
+        ),
+    ];
+    if has_pwm {
+        tools.push(Arc::new(
+            PwmWriteTool::new(registry.clone()).with_send_options(send_options.clone()),
+        ));
+    }
+    if has_i2c {
+        tools.push(Arc::new(
+            I2cScanTool::new(registry).with_send_options(send_options),
+        ));
+    }
+    tools
+}
+
+/// The process-wide hardware tools, built by the first caller.
+///
+/// The CLI loop and every channel or gateway message share this set, so
+/// discovery, the keepalive task and the command rate limiter are not
+/// rebuilt per message.
+pub async fn shared_hardware_tools(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+) -> Arc<Vec<Arc<dyn Tool>>> {
+    static SHARED: tokio::sync::OnceCell<Arc<Vec<Arc<dyn Tool>>>> =
+        tokio::sync::OnceCell::const_new();
+    SHARED
+        .get_or_init(|| async {
+            let registry = load_device_registry(config).await;
+            Arc::new(build_hardware_tools(registry, config, security))
+        })
+        .await
+        .clone()
+}
+
+/// Add `hardware` tools to `tools`. Tools whose names are already registered
+/// (e.g. peripheral GPIO tools) are skipped.
+pub fn merge_hardware_tools(tools: &mut Vec<Box<dyn Tool>>, hardware: &[Arc<dyn Tool>]) {
+    for tool in hardware {
+        if tools.iter().any(|existing| existing.name() == tool.name()) {
+            continue;
+        }
+        tools.push(crate::tools::ArcDelegatingTool::boxed(Arc::clone(tool)));
+    }
+}
+
+/// On shutdown, drive the live tool registry's devices to their configured
+/// `safe_state` and close their transports (see [`DeviceRegistry::close`]).
+pub async fn close_live_devices() {
+    if let Some(registry) = HardwareEstop::global().live_registry() {
+        registry.write().await.close().await;
+    }
+}
+
 /// Return the recommended default wizard choice index based on discovered devices.
 /// 0 = Native, 1 = Tethered/Serial, 2 = Debug Probe, 3 = Software Only
 pub fn recommended_wizard_default(devices: &[DiscoveredDevice]) -> usize {
@@ -161,7 +352,10 @@ pub fn handle_command(cmd: crate::HardwareCommands, _config: &Config) -> Result<
     any(target_os = "linux", target_os = "macos", target_os = "windows")
 ))]
 fn run_discover() -> Result<()> {
-    let devices = discover::list_usb_devices()?;
+    let devices: Vec<DiscoveredDevice> = discover::list_usb_devices()?
+        .into_iter()
+        .map(DiscoveredDevice::from)
+        .collect();
 
     if devices.is_empty() {
         println!("No USB devices found.");
@@ -173,12 +367,21 @@ fn run_discover() -> Result<()> {
     println!("USB devices:");
     println!();
     for d in &devices {
-        let board = d.board_name.as_deref().unwrap_or("(unknown)");
-        let arch = d.architecture.as_deref().unwrap_or("—");
-        let product = d.product_string.as_deref().unwrap_or("—");
+        let arch = d.capabilities_hint.as_deref().unwrap_or("—");
+        let product = d.detail.as_deref().unwrap_or("—");
+        let serial = d
+            .serial_number
+            .as_deref()
+            .map(|s| format!("  serial {s}"))
+            .unwrap_or_default();
         println!(
-            "  {:04x}:{:04x}  {}  {}  {}",
-            d.vid, d.pid, board, arch, product
+            "  {:04x}:{:04x}  {}  {}  {}{}",
+            d.vid.unwrap_or_default(),
+            d.pid.unwrap_or_default(),
+            d.name,
+            arch,
+            product,
+            serial
         );
     }
     println!();
@@ -287,3 +490,172 @@ fn info_via_probe(chip: &str) -> anyhow::Result<()> {
     println!("Info read via USB (SWD) — no firmware on target needed.");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PeripheralBoardConfig;
+    use std::collections::HashMap;
+
+    fn tool_names(tools: &[Box<dyn Tool>]) -> Vec<String> {
+        tools.iter().map(|t| t.name().to_string()).collect()
+    }
+
+    fn security() -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy::default())
+    }
+
+    fn merge_built(tools: &mut Vec<Box<dyn Tool>>, registry: DeviceRegistry, config: &Config) {
+        merge_hardware_tools(tools, &build_hardware_tools(registry, config, &security()));
+    }
+
+    #[test]
+    fn merge_hardware_tools_hides_tools_without_devices_or_config() {
+        let config = Config::default();
+        assert_eq!(
+            config.hardware.no_device_behavior,
+            HardwareNoDeviceBehavior::Hide
+        );
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(&mut tools, DeviceRegistry::new(), &config);
+        assert!(tools.is_empty(), "got {:?}", tool_names(&tools));
+    }
+
+    #[test]
+    fn merge_hardware_tools_keeps_tools_when_configured() {
+        let mut config = Config::default();
+        config.hardware.no_device_behavior = HardwareNoDeviceBehavior::Keep;
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(&mut tools, DeviceRegistry::new(), &config);
+        assert_eq!(
+            tool_names(&tools),
+            vec![
+                "gpio_write",
+                "gpio_read",
+                "gpio_config",
+                "device_capabilities"
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_hardware_tools_adds_tools_when_a_device_is_registered() {
+        let mut registry = DeviceRegistry::new();
+        registry.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(&mut tools, registry, &Config::default());
+        assert_eq!(tools.len(), 4);
+    }
+
+    /// Transport stub for tests that only need one attached.
+    struct IdleTransport;
+
+    #[async_trait::async_trait]
+    impl Transport for IdleTransport {
+        async fn send(&self, _cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+            Err(TransportError::Disconnected)
+        }
+
+        fn kind(&self) -> TransportKind {
+            TransportKind::Serial
+        }
+
+        fn is_connected(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn merge_hardware_tools_adds_i2c_scan_when_a_device_advertises_i2c() {
+        let mut registry = DeviceRegistry::new();
+        let alias = registry.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+        registry
+            .attach_transport(
+                &alias,
+                Arc::new(IdleTransport),
+                DeviceCapabilities {
+                    gpio: true,
+                    i2c: true,
+                    ..DeviceCapabilities::default()
+                },
+            )
+            .unwrap();
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(&mut tools, registry, &Config::default());
+        assert_eq!(
+            tool_names(&tools).last().map(String::as_str),
+            Some("i2c_scan")
+        );
+    }
+
+    #[test]
+    fn merge_hardware_tools_adds_pwm_write_only_when_a_device_advertises_pwm() {
+        let registry_with = |caps: DeviceCapabilities| {
+            let mut registry = DeviceRegistry::new();
+            let alias = registry.register("raspberry-pi-pico", Some(0x2e8a), None, None, None);
+            registry
+                .attach_transport(&alias, Arc::new(IdleTransport), caps)
+                .unwrap();
+            registry
+        };
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(
+            &mut tools,
+            registry_with(DeviceCapabilities {
+                gpio: true,
+                ..DeviceCapabilities::default()
+            }),
+            &Config::default(),
+        );
+        assert!(!tool_names(&tools).contains(&"pwm_write".to_string()));
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        merge_built(
+            &mut tools,
+            registry_with(DeviceCapabilities {
+                gpio: true,
+                pwm: true,
+                ..DeviceCapabilities::default()
+            }),
+            &Config::default(),
+        );
+        assert_eq!(
+            tool_names(&tools).last().map(String::as_str),
+            Some("pwm_write")
+        );
+    }
+
+    #[test]
+    fn merge_hardware_tools_skips_names_already_registered_by_peripherals() {
+        let mut config = Config::default();
+        config.peripherals.enabled = true;
+        config.peripherals.boards = vec![PeripheralBoardConfig {
+            board: "nucleo-f401re".into(),
+            transport: "serial".into(),
+            path: Some("/dev/ttyACM0".into()),
+            baud: 115_200,
+            post_write_delay_ms: 0,
+            health_command: "ping".into(),
+            commands: HashMap::new(),
+            safe_state: Vec::new(),
+        }];
+        let mut tools: Vec<Box<dyn Tool>> =
+            gpio_tools(Arc::new(tokio::sync::RwLock::new(DeviceRegistry::new())));
+
+        merge_built(&mut tools, DeviceRegistry::new(), &config);
+        assert_eq!(
+            tool_names(&tools),
+            vec![
+                "gpio_write",
+                "gpio_read",
+                "gpio_config",
+                "device_capabilities"
+            ]
+        );
+    }
+}
@@ -9,6 +9,15 @@
 //!
 //! Both sides MUST agree on these struct definitions. Any change here is a
 //! breaking firmware contract change.
+//!
+//! Correlation: the host may tag a command with a numeric `id`; firmware
+//! that echoes it back lets the host skip unsolicited lines (logs, late
+//! replies) until the matching response arrives. Responses without an `id`
+//! are taken as the reply to the last command.
+//!
+//! Versioning: the `ping` response may carry `data.protocol_version`. The
+//! host records it so transports can adapt to older firmware, and warns when
+//! the version is missing or newer than [`HOST_PROTOCOL_VERSION`].
 
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +31,10 @@ pub struct ZcCommand {
     /// Command parameters — schema depends on the command.
     #[serde(default)]
     pub params: serde_json::Value,
+    /// Request ID the device should echo in its response; assigned by the
+    /// transport when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
 }
 
 impl ZcCommand {
@@ -30,6 +43,7 @@ impl ZcCommand {
         Self {
             cmd: cmd.into(),
             params,
+            id: None,
         }
     }
 
@@ -38,8 +52,15 @@ impl ZcCommand {
         Self {
             cmd: cmd.into(),
             params: serde_json::Value::Object(serde_json::Map::new()),
+            id: None,
         }
     }
+
+    /// This command tagged with request ID `id`.
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = Some(id);
+        self
+    }
 }
 
 /// Device-to-host response.
@@ -55,6 +76,9 @@ pub struct ZcResponse {
     /// Human-readable error message when `ok` is false.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Echo of the command's `id`; older firmware omits it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
 }
 
 impl ZcResponse {
@@ -64,6 +88,7 @@ impl ZcResponse {
             ok: true,
             data,
             error: None,
+            id: None,
         }
     }
 
@@ -73,6 +98,65 @@ impl ZcResponse {
             ok: false,
             data: serde_json::Value::Null,
             error: Some(message.into()),
+            id: None,
+        }
+    }
+}
+
+/// Highest wire-protocol version this host understands.
+pub const HOST_PROTOCOL_VERSION: u32 = 1;
+
+/// Firmware identity reported in a `ping` response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandshakeInfo {
+    /// `data.firmware` (e.g. `"zeroclaw"`).
+    pub firmware: Option<String>,
+    /// `data.protocol_version`; older firmware omits it.
+    pub protocol_version: Option<u32>,
+}
+
+/// How a device's protocol version relates to [`HOST_PROTOCOL_VERSION`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolSupport {
+    /// Version at or below the host's; fully understood.
+    Supported(u32),
+    /// Device did not report a version (pre-versioning firmware).
+    Unknown,
+    /// Device speaks a newer protocol than this host.
+    Newer(u32),
+}
+
+impl HandshakeInfo {
+    /// Parse a `ping` response. `protocol_version` may be an integer or a
+    /// numeric string; anything else counts as missing.
+    pub fn from_ping_response(resp: &ZcResponse) -> Self {
+        let firmware = resp
+            .data
+            .get("firmware")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let protocol_version = resp.data.get("protocol_version").and_then(|v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+                .and_then(|n| u32::try_from(n).ok())
+        });
+        Self {
+            firmware,
+            protocol_version,
+        }
+    }
+
+    /// Whether the device identified itself as ZeroClaw firmware.
+    pub fn is_zeroclaw(&self) -> bool {
+        self.firmware.as_deref() == Some("zeroclaw")
+    }
+
+    /// Compare the reported version against [`HOST_PROTOCOL_VERSION`].
+    pub fn protocol_support(&self) -> ProtocolSupport {
+        match self.protocol_version {
+            None => ProtocolSupport::Unknown,
+            Some(v) if v > HOST_PROTOCOL_VERSION => ProtocolSupport::Newer(v),
+            Some(v) => ProtocolSupport::Supported(v),
         }
     }
 }
@@ -145,4 +229,45 @@ mod tests {
         assert!(resp.data.is_null());
         assert!(resp.error.is_none());
     }
+
+    #[test]
+    fn request_id_is_serialized_only_when_set() {
+        let v = serde_json::to_value(ZcCommand::simple("ping")).unwrap();
+        assert!(v.get("id").is_none());
+
+        let v = serde_json::to_value(ZcCommand::simple("ping").with_id(7)).unwrap();
+        assert_eq!(v["id"], 7);
+
+        let resp: ZcResponse = serde_json::from_str(r#"{"id":7,"ok":true}"#).unwrap();
+        assert_eq!(resp.id, Some(7));
+    }
+
+    #[test]
+    fn handshake_parses_protocol_version() {
+        let resp = ZcResponse::success(json!({"firmware": "zeroclaw", "protocol_version": 1}));
+        let info = HandshakeInfo::from_ping_response(&resp);
+        assert!(info.is_zeroclaw());
+        assert_eq!(info.protocol_version, Some(1));
+        assert_eq!(info.protocol_support(), ProtocolSupport::Supported(1));
+
+        let resp = ZcResponse::success(json!({"firmware": "zeroclaw", "protocol_version": "7"}));
+        let info = HandshakeInfo::from_ping_response(&resp);
+        assert_eq!(info.protocol_version, Some(7));
+        assert_eq!(info.protocol_support(), ProtocolSupport::Newer(7));
+    }
+
+    #[test]
+    fn handshake_without_protocol_version_is_unknown() {
+        let resp = ZcResponse::success(json!({"firmware": "zeroclaw"}));
+        let info = HandshakeInfo::from_ping_response(&resp);
+        assert!(info.is_zeroclaw());
+        assert_eq!(info.protocol_version, None);
+        assert_eq!(info.protocol_support(), ProtocolSupport::Unknown);
+
+        let resp = ZcResponse::success(json!({"firmware": "zeroclaw", "protocol_version": -1}));
+        assert_eq!(
+            HandshakeInfo::from_ping_response(&resp).protocol_version,
+            None
+        );
+    }
 }
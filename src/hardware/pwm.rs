@@ -0,0 +1,376 @@
+//! PWM tool — `pwm_write` sets a pin's duty cycle for servo or LED
+//! brightness control, where `gpio_write` can only switch it fully on or off.
+//!
+//! Wire protocol (ZeroClaw serial JSON):
+//! ```text
+//! pwm_write:
+//!   Host → Device:  {"cmd":"pwm_write","params":{"pin":15,"duty":0.25,"freq_hz":50}}\n
+//!   Device → Host:  {"ok":true,"data":{"pin":15,"duty":0.2502,"freq_hz":50}}\n
+//! ```
+//!
+//! `freq_hz` is only sent when the caller sets it; otherwise the firmware
+//! keeps the pin's current frequency. The device may round `duty` to its
+//! timer resolution, so the reported duty cycle is the one it sends back.
+//!
+//! Only devices advertising `capabilities.pwm` are eligible. Sends go through
+//! the same reconnect, audit and rate-limit path as the GPIO tools, and like
+//! `gpio_write` the tool honours dry-run and refuses while the
+//! [`HardwareEstop`] is engaged.
+
+use super::device::DeviceRegistry;
+use super::gpio::{send_audited, HardwareSendOptions};
+use super::protocol::ZcCommand;
+use crate::tools::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Tool: set the PWM duty cycle of a pin on a connected device.
+///
+/// The LLM provides `device` (alias), `pin`, `duty` (0.0–1.0) and optionally
+/// `freq_hz`, and gets back the duty cycle the device applied.
+pub struct PwmWriteTool {
+    registry: Arc<RwLock<DeviceRegistry>>,
+    send_options: HardwareSendOptions,
+}
+
+impl PwmWriteTool {
+    pub fn new(registry: Arc<RwLock<DeviceRegistry>>) -> Self {
+        Self {
+            registry,
+            send_options: HardwareSendOptions::default(),
+        }
+    }
+
+    /// Use `options` for every command this tool sends.
+    #[must_use]
+    pub fn with_send_options(mut self, options: HardwareSendOptions) -> Self {
+        self.send_options = options;
+        self
+    }
+}
+
+/// Parse and validate `pin`, `duty` and the optional `freq_hz`.
+fn parse_args(args: &serde_json::Value) -> Result<(u64, f64, Option<u64>), String> {
+    let pin = args
+        .get("pin")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "missing required parameter: pin".to_string())?;
+    let duty = args
+        .get("duty")
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| "missing required parameter: duty".to_string())?;
+    if !(0.0..=1.0).contains(&duty) {
+        return Err("duty must be between 0.0 and 1.0".to_string());
+    }
+    let freq_hz = match args.get("freq_hz") {
+        None => None,
+        Some(v) => match v.as_u64() {
+            Some(freq) if freq > 0 => Some(freq),
+            _ => return Err("freq_hz must be a positive integer".to_string()),
+        },
+    };
+    Ok((pin, duty, freq_hz))
+}
+
+/// A duty cycle and, when known, its frequency, e.g. `25.0% duty at 50 Hz`.
+fn duty_phrase(duty: f64, freq_hz: Option<u64>) -> String {
+    let percent = duty * 100.0;
+    match freq_hz {
+        Some(freq) => format!("{percent:.1}% duty at {freq} Hz"),
+        None => format!("{percent:.1}% duty"),
+    }
+}
+
+#[async_trait]
+impl Tool for PwmWriteTool {
+    fn name(&self) -> &str {
+        "pwm_write"
+    }
+
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> &str {
+        "Set the PWM duty cycle (0.0–1.0) of a pin on a connected device, e.g. for servo position or LED brightness"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "device": {
+                    "type": "string",
+                    "description": "Device alias e.g. pico0, arduino0"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Board kind (pico, arduino, esp32, nucleo, generic); selects the only device of that kind when no alias is given"
+                },
+                "pin": {
+                    "type": "integer",
+                    "description": "PWM-capable pin number"
+                },
+                "duty": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "description": "Duty cycle: 0.0 = always off, 1.0 = always on"
+                },
+                "freq_hz": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "PWM frequency in Hz (e.g. 50 for hobby servos); keeps the current frequency when omitted"
+                }
+            },
+            "required": ["pin", "duty"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = self.send_options.estop.refusal() {
+            return Ok(refused);
+        }
+        let (pin, duty, freq_hz) = match parse_args(&args) {
+            Ok(parsed) => parsed,
+            Err(msg) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(msg),
+                })
+            }
+        };
+
+        // Resolve device alias and obtain an owned context (Arc-based) before
+        // dropping the registry read guard — avoids holding the lock across async I/O.
+        let (device_alias, ctx) = {
+            let registry = self.registry.read().await;
+            match registry.resolve_pwm_device(&args) {
+                Ok(resolved) => resolved,
+                Err(msg) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(msg),
+                    });
+                }
+            }
+        };
+
+        if let Err(msg) = ctx.capabilities.check_pin(pin) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(msg),
+            });
+        }
+
+        if self.send_options.dry_run {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "[dry run] would set PWM on GPIO {pin} to {} on {device_alias}",
+                    duty_phrase(duty, freq_hz)
+                ),
+                error: None,
+            });
+        }
+
+        let mut params = json!({ "pin": pin, "duty": duty });
+        if let Some(freq) = freq_hz {
+            params["freq_hz"] = json!(freq);
+        }
+        let cmd = ZcCommand::new("pwm_write", params);
+
+        match send_audited(
+            self.name(),
+            &self.send_options,
+            &self.registry,
+            &device_alias,
+            &ctx,
+            &cmd,
+        )
+        .await
+        {
+            Ok(resp) if resp.ok => {
+                let applied = resp
+                    .data
+                    .get("duty")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(duty);
+                let freq = resp
+                    .data
+                    .get("freq_hz")
+                    .and_then(|v| v.as_u64())
+                    .or(freq_hz);
+                Ok(ToolResult {
+                    success: true,
+                    output: format!(
+                        "PWM on GPIO {pin} set to {} on {device_alias}",
+                        duty_phrase(applied, freq)
+                    ),
+                    error: None,
+                })
+            }
+            Ok(resp) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    resp.error
+                        .unwrap_or_else(|| "device returned ok:false".to_string()),
+                ),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{
+        device::DeviceCapabilities,
+        estop::{HardwareEstop, ESTOP_ENGAGED_ERROR},
+        protocol::ZcResponse,
+        test_support::registry_with_mock,
+    };
+
+    fn pwm_caps() -> DeviceCapabilities {
+        DeviceCapabilities {
+            gpio: true,
+            pwm: true,
+            ..DeviceCapabilities::default()
+        }
+    }
+
+    fn tool(reg: Arc<RwLock<DeviceRegistry>>) -> PwmWriteTool {
+        PwmWriteTool::new(reg).with_send_options(HardwareSendOptions {
+            estop: Arc::new(HardwareEstop::new()),
+            ..HardwareSendOptions::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn pwm_write_sends_duty_and_reports_applied_value() {
+        let (reg, mock) = registry_with_mock(
+            ZcResponse::success(json!({"pin": 15, "duty": 0.2502, "freq_hz": 50})),
+            pwm_caps(),
+        );
+
+        let result = tool(reg)
+            .execute(json!({"device": "pico0", "pin": 15, "duty": 0.25, "freq_hz": 50}))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            "PWM on GPIO 15 set to 25.0% duty at 50 Hz on pico0"
+        );
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.cmd, "pwm_write");
+        assert_eq!(cmd.params, json!({"pin": 15, "duty": 0.25, "freq_hz": 50}));
+    }
+
+    #[tokio::test]
+    async fn pwm_write_omits_frequency_when_not_given() {
+        let (reg, mock) = registry_with_mock(ZcResponse::success(json!({})), pwm_caps());
+
+        let result = tool(reg)
+            .execute(json!({"pin": 2, "duty": 1}))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "PWM on GPIO 2 set to 100.0% duty on pico0");
+        let cmd = mock.last_command().await.unwrap();
+        assert_eq!(cmd.params, json!({"pin": 2, "duty": 1.0}));
+    }
+
+    #[tokio::test]
+    async fn pwm_write_rejects_out_of_range_arguments() {
+        let (reg, mock) = registry_with_mock(ZcResponse::success(json!({})), pwm_caps());
+        let tool = tool(reg);
+
+        let result = tool.execute(json!({"pin": 2, "duty": 1.5})).await.unwrap();
+        assert_eq!(
+            result.error.as_deref(),
+            Some("duty must be between 0.0 and 1.0")
+        );
+
+        let result = tool
+            .execute(json!({"pin": 2, "duty": 0.5, "freq_hz": 0}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.error.as_deref(),
+            Some("freq_hz must be a positive integer")
+        );
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn pwm_write_requires_pwm_capability() {
+        let (reg, mock) = registry_with_mock(
+            ZcResponse::success(json!({})),
+            DeviceCapabilities {
+                gpio: true,
+                ..DeviceCapabilities::default()
+            },
+        );
+        let tool = tool(reg);
+
+        let explicit = tool
+            .execute(json!({"device": "pico0", "pin": 2, "duty": 0.5}))
+            .await
+            .unwrap();
+        assert!(!explicit.success);
+        assert!(explicit.error.unwrap().contains("does not support PWM"));
+
+        let auto = tool.execute(json!({"pin": 2, "duty": 0.5})).await.unwrap();
+        assert!(!auto.success);
+        assert!(auto.error.unwrap().contains("no PWM-capable device found"));
+        assert!(mock.last_command().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn pwm_write_dry_run_and_estop_send_nothing() {
+        let (reg, mock) = registry_with_mock(ZcResponse::success(json!({})), pwm_caps());
+
+        let dry = PwmWriteTool::new(reg.clone())
+            .with_send_options(HardwareSendOptions {
+                dry_run: true,
+                estop: Arc::new(HardwareEstop::new()),
+                ..HardwareSendOptions::default()
+            })
+            .execute(json!({"pin": 15, "duty": 0.5}))
+            .await
+            .unwrap();
+        assert!(dry.success);
+        assert_eq!(
+            dry.output,
+            "[dry run] would set PWM on GPIO 15 to 50.0% duty on pico0"
+        );
+
+        let estop = Arc::new(HardwareEstop::new());
+        estop.engage();
+        let refused = PwmWriteTool::new(reg)
+            .with_send_options(HardwareSendOptions {
+                estop,
+                ..HardwareSendOptions::default()
+            })
+            .execute(json!({"pin": 15, "duty": 0.5}))
+            .await
+            .unwrap();
+        assert_eq!(refused.error.as_deref(), Some(ESTOP_ENGAGED_ERROR));
+        assert!(mock.last_command().await.is_none());
+    }
+}
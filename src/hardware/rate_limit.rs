@@ -0,0 +1,172 @@
+//! Per-device command rate limit for the hardware tools.
+//!
+//! A model stuck in a loop can toggle a pin many times a second, which
+//! chatters relays and floods the serial link. With
+//! `hardware.max_write_commands_per_sec` / `max_read_commands_per_sec` set,
+//! each device accepts at most that many commands of the kind within any
+//! one-second window; the rest fail before reaching the transport.
+
+use super::protocol::ZcCommand;
+use crate::config::HardwareConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Commands that only query the device; everything else counts as a write.
+const READ_COMMANDS: &[&str] = &["gpio_read", "capabilities", "i2c_scan", "ping"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    Read,
+    Write,
+}
+
+impl CommandKind {
+    pub fn of(cmd: &ZcCommand) -> Self {
+        if READ_COMMANDS.contains(&cmd.cmd.as_str()) {
+            Self::Read
+        } else {
+            Self::Write
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Sliding one-second window of accepted commands per device and kind.
+pub struct CommandRateLimiter {
+    reads_per_sec: u32,
+    writes_per_sec: u32,
+    sent: Mutex<HashMap<(String, CommandKind), VecDeque<Instant>>>,
+}
+
+impl CommandRateLimiter {
+    /// `0` leaves that kind of command unlimited.
+    pub fn new(reads_per_sec: u32, writes_per_sec: u32) -> Self {
+        Self {
+            reads_per_sec,
+            writes_per_sec,
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build the limiter from config; `None` when both limits are off.
+    pub fn from_config(config: &HardwareConfig) -> Option<Arc<Self>> {
+        if config.max_read_commands_per_sec == 0 && config.max_write_commands_per_sec == 0 {
+            return None;
+        }
+        Some(Arc::new(Self::new(
+            config.max_read_commands_per_sec,
+            config.max_write_commands_per_sec,
+        )))
+    }
+
+    /// Count `cmd` against `alias`'s budget, or explain why it is refused.
+    pub fn check(&self, alias: &str, cmd: &ZcCommand) -> Result<(), String> {
+        self.check_at(alias, CommandKind::of(cmd), Instant::now())
+    }
+
+    fn check_at(&self, alias: &str, kind: CommandKind, now: Instant) -> Result<(), String> {
+        let limit = match kind {
+            CommandKind::Read => self.reads_per_sec,
+            CommandKind::Write => self.writes_per_sec,
+        };
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        let window = sent.entry((alias.to_string(), kind)).or_default();
+        while window
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= WINDOW)
+        {
+            window.pop_front();
+        }
+        if window.len() >= limit as usize {
+            tracing::warn!(alias = %alias, kind = kind.as_str(), limit, "device command rate limit exceeded");
+            return Err(format!(
+                "device command rate limit exceeded: '{alias}' allows {limit} {} command(s) per second; wait before retrying",
+                kind.as_str()
+            ));
+        }
+        window.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rapid_writes_are_throttled_and_spaced_writes_pass() {
+        let limiter = CommandRateLimiter::new(0, 2);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("pico0", CommandKind::Write, start).is_ok());
+        assert!(limiter
+            .check_at(
+                "pico0",
+                CommandKind::Write,
+                start + Duration::from_millis(10)
+            )
+            .is_ok());
+        let err = limiter
+            .check_at(
+                "pico0",
+                CommandKind::Write,
+                start + Duration::from_millis(20),
+            )
+            .unwrap_err();
+        assert!(err.contains("device command rate limit exceeded"), "{err}");
+
+        // Once the burst has aged out, writes spaced under the limit all pass.
+        for i in 1..=5 {
+            assert!(limiter
+                .check_at(
+                    "pico0",
+                    CommandKind::Write,
+                    start + Duration::from_millis(500 + 600 * i)
+                )
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn limits_are_per_device_and_per_kind() {
+        let limiter = CommandRateLimiter::new(0, 1);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("pico0", CommandKind::Write, now).is_ok());
+        assert!(limiter.check_at("pico0", CommandKind::Write, now).is_err());
+        assert!(limiter.check_at("pico1", CommandKind::Write, now).is_ok());
+        for _ in 0..10 {
+            assert!(limiter.check_at("pico0", CommandKind::Read, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn classifies_queries_as_reads() {
+        let read = ZcCommand::new("gpio_read", json!({ "pin": 25 }));
+        let write = ZcCommand::new("gpio_write", json!({ "pin": 25, "value": 1 }));
+        assert_eq!(CommandKind::of(&read), CommandKind::Read);
+        assert_eq!(
+            CommandKind::of(&ZcCommand::simple("capabilities")),
+            CommandKind::Read
+        );
+        assert_eq!(CommandKind::of(&write), CommandKind::Write);
+        assert_eq!(
+            CommandKind::of(&ZcCommand::simple("gpio_config")),
+            CommandKind::Write
+        );
+    }
+}
@@ -7,29 +7,43 @@
 //!
 //! Wire protocol (ZeroClaw serial JSON):
 //! ```text
-//! Host → Device:  {"cmd":"gpio_write","params":{"pin":25,"value":1}}\n
-//! Device → Host:  {"ok":true,"data":{"pin":25,"value":1,"state":"HIGH"}}\n
+//! Host → Device:  {"cmd":"gpio_write","params":{"pin":25,"value":1},"id":3}\n
+//! Device → Host:  {"ok":true,"data":{"pin":25,"value":1,"state":"HIGH"},"id":3}\n
 //! ```
 //!
+//! Each command carries an `id` from a per-transport counter. Lines before
+//! the response with that `id` (debug logs, stale replies) are traced and
+//! skipped, up to [`MAX_SKIPPED_LINES`]. Firmware that does not echo `id` is
+//! answered by its first response line, as before.
+//!
+//! A per-board `post_write_delay_ms` is slept between flushing a command and
+//! reading its response, for firmware that is not ready to answer at once.
+//!
 //! All I/O is wrapped in `tokio::time::timeout` — no blocking reads.
 
+pub use super::device::DEFAULT_BAUD;
 use super::{
-    protocol::{ZcCommand, ZcResponse},
+    device::{board_post_write_delay, scanned_serial_ports, ScannedPort, TransportConnector},
+    protocol::{HandshakeInfo, ProtocolSupport, ZcCommand, ZcResponse, HOST_PROTOCOL_VERSION},
     transport::{Transport, TransportError, TransportKind},
 };
+use crate::config::PeripheralBoardConfig;
 use async_trait::async_trait;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio_serial::SerialPortBuilderExt;
 
 /// Default timeout for a single send→receive round-trip (seconds).
 const SEND_TIMEOUT_SECS: u64 = 5;
 
-/// Default baud rate for ZeroClaw serial devices.
-pub const DEFAULT_BAUD: u32 = 115_200;
-
 /// Timeout for the ping handshake during device discovery (milliseconds).
 const PING_TIMEOUT_MS: u64 = 300;
 
+/// Unmatched lines skipped while waiting for a command's response.
+const MAX_SKIPPED_LINES: usize = 8;
+
 /// Allowed serial device path prefixes — reject arbitrary paths for security.
 /// Uses the shared allowlist from `crate::util`.
 use crate::util::is_serial_path_allowed as is_path_allowed;
@@ -42,6 +56,13 @@ use crate::util::is_serial_path_allowed as is_path_allowed;
 pub struct HardwareSerialTransport {
     port_path: String,
     baud_rate: u32,
+    protocol_version: parking_lot::Mutex<Option<u32>>,
+    /// Next request ID to tag a command with.
+    next_id: AtomicU64,
+    /// Pause between flushing a command and reading the response.
+    post_write_delay: Duration,
+    /// Device alias logged in place of `port_path`, when paths are redacted.
+    log_alias: parking_lot::Mutex<Option<String>>,
 }
 
 impl HardwareSerialTransport {
@@ -52,9 +73,20 @@ impl HardwareSerialTransport {
         Self {
             port_path: port_path.into(),
             baud_rate,
+            protocol_version: parking_lot::Mutex::new(None),
+            next_id: AtomicU64::new(1),
+            post_write_delay: Duration::ZERO,
+            log_alias: parking_lot::Mutex::new(None),
         }
     }
 
+    /// Wait `delay` after each command before reading the response.
+    #[must_use]
+    pub fn with_post_write_delay(mut self, delay: Duration) -> Self {
+        self.post_write_delay = delay;
+        self
+    }
+
     /// Create with the default baud rate (115 200).
     pub fn with_default_baud(port_path: impl Into<String>) -> Self {
         Self::new(port_path, DEFAULT_BAUD)
@@ -65,38 +97,105 @@ impl HardwareSerialTransport {
         &self.port_path
     }
 
+    /// `cmd` with a fresh request ID, unless the caller already set one.
+    fn tagged(&self, cmd: &ZcCommand) -> ZcCommand {
+        let mut tagged = cmd.clone();
+        if tagged.id.is_none() {
+            tagged.id = Some(self.next_id.fetch_add(1, Ordering::Relaxed));
+        }
+        tagged
+    }
+
     /// Attempt a ping handshake to verify ZeroClaw firmware is running.
     ///
-    /// Opens the port, sends `{"cmd":"ping","params":{}}`, waits up to
-    /// `PING_TIMEOUT_MS` for a response with `data.firmware == "zeroclaw"`.
-    ///
     /// Returns `true` if a ZeroClaw device responds, `false` otherwise.
     /// This method never returns an error — discovery must not hang on failure.
     pub async fn ping_handshake(&self) -> bool {
-        let ping = ZcCommand::simple("ping");
-        let json = match serde_json::to_string(&ping) {
-            Ok(j) => j,
-            Err(_) => return false,
-        };
-        let result = tokio::time::timeout(
+        self.handshake()
+            .await
+            .is_some_and(|info| info.is_zeroclaw())
+    }
+
+    /// Ping the device and negotiate the protocol version.
+    ///
+    /// Opens the port, sends `{"cmd":"ping","params":{}}`, and waits up to
+    /// `PING_TIMEOUT_MS` for a response. The reported `protocol_version` is
+    /// recorded on the transport; a missing or newer-than-supported version
+    /// is logged as a warning. Returns `None` when the device does not answer.
+    pub async fn handshake(&self) -> Option<HandshakeInfo> {
+        let ping = self.tagged(&ZcCommand::simple("ping"));
+        let json = serde_json::to_string(&ping).ok()?;
+        let resp = tokio::time::timeout(
             std::time::Duration::from_millis(PING_TIMEOUT_MS),
-            do_send(&self.port_path, self.baud_rate, &json),
+            do_send(
+                &self.port_path,
+                self.baud_rate,
+                &json,
+                ping.id,
+                self.post_write_delay,
+            ),
         )
-        .await;
-
-        match result {
-            Ok(Ok(resp)) => {
-                // Accept if firmware field is "zeroclaw" (in data or top-level)
-                resp.ok
-                    && resp
-                        .data
-                        .get("firmware")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s == "zeroclaw")
-                        .unwrap_or(false)
+        .await
+        .ok()?
+        .ok()?;
+        if !resp.ok {
+            return None;
+        }
+
+        let info = HandshakeInfo::from_ping_response(&resp);
+        if info.is_zeroclaw() {
+            match info.protocol_support() {
+                ProtocolSupport::Supported(_) => {}
+                ProtocolSupport::Unknown => tracing::warn!(
+                    port = %self.port_path,
+                    "device did not report a protocol version; assuming legacy firmware"
+                ),
+                ProtocolSupport::Newer(v) => tracing::warn!(
+                    port = %self.port_path,
+                    device = v,
+                    host = HOST_PROTOCOL_VERSION,
+                    "device protocol version is newer than this host supports"
+                ),
             }
-            _ => false,
         }
+        *self.protocol_version.lock() = info.protocol_version;
+        Some(info)
+    }
+}
+
+/// [`TransportConnector`] for ZeroClaw serial devices.
+///
+/// Opens a [`HardwareSerialTransport`] and requires a successful ping
+/// handshake; candidate ports come from `discover::scan_serial_devices()`.
+/// A port configured in `boards` by path gets that board's post-write delay.
+#[derive(Default)]
+pub struct SerialTransportConnector {
+    boards: Vec<PeripheralBoardConfig>,
+}
+
+impl SerialTransportConnector {
+    /// Connector applying the post-write delays configured in `boards`.
+    pub fn new(boards: Vec<PeripheralBoardConfig>) -> Self {
+        Self { boards }
+    }
+}
+
+#[async_trait]
+impl TransportConnector for SerialTransportConnector {
+    async fn connect(&self, port_path: &str, baud: u32) -> anyhow::Result<Arc<dyn Transport>> {
+        let transport = HardwareSerialTransport::new(port_path, baud)
+            .with_post_write_delay(board_post_write_delay(&self.boards, port_path, None));
+        if !transport.ping_handshake().await {
+            anyhow::bail!("ping handshake failed on {port_path} — firmware may not be running");
+        }
+        Ok(Arc::new(transport))
+    }
+
+    fn ports_for_vid(&self, vid: u16) -> Vec<ScannedPort> {
+        scanned_serial_ports(&self.boards)
+            .into_iter()
+            .filter(|info| info.vid == vid)
+            .collect()
     }
 }
 
@@ -110,14 +209,26 @@ impl Transport for HardwareSerialTransport {
             )));
         }
 
-        let json = serde_json::to_string(cmd)
+        let cmd = self.tagged(cmd);
+        let json = serde_json::to_string(&cmd)
             .map_err(|e| TransportError::Protocol(format!("failed to serialize command: {e}")))?;
         // Log command name only — never log the full payload (may contain large or sensitive data).
-        tracing::info!(port = %self.port_path, cmd = %cmd.cmd, "serial send");
+        let port = self
+            .log_alias
+            .lock()
+            .clone()
+            .unwrap_or_else(|| self.port_path.clone());
+        tracing::info!(port = %port, cmd = %cmd.cmd, "serial send");
 
         tokio::time::timeout(
             std::time::Duration::from_secs(SEND_TIMEOUT_SECS),
-            do_send(&self.port_path, self.baud_rate, &json),
+            do_send(
+                &self.port_path,
+                self.baud_rate,
+                &json,
+                cmd.id,
+                self.post_write_delay,
+            ),
         )
         .await
         .map_err(|_| TransportError::Timeout(SEND_TIMEOUT_SECS))?
@@ -131,15 +242,29 @@ impl Transport for HardwareSerialTransport {
         // Lightweight connectivity check: the device file must exist.
         std::path::Path::new(&self.port_path).exists()
     }
+
+    fn protocol_version(&self) -> Option<u32> {
+        *self.protocol_version.lock()
+    }
+
+    fn set_log_alias(&self, alias: Option<&str>) {
+        *self.log_alias.lock() = alias.map(str::to_string);
+    }
 }
 
-/// Open the port, write the command, read one response line, return the parsed response.
+/// Open the port, write the command, read until its response, return the parsed response.
 ///
 /// This is the inner function wrapped with `tokio::time::timeout` by the caller.
 /// Do NOT add a timeout here — the outer caller owns the deadline.
-async fn do_send(path: &str, baud: u32, json: &str) -> Result<ZcResponse, TransportError> {
+async fn do_send(
+    path: &str,
+    baud: u32,
+    json: &str,
+    id: Option<u64>,
+    post_write_delay: Duration,
+) -> Result<ZcResponse, TransportError> {
     // Open port lazily — released when this function returns
-    let mut port = tokio_serial::new(path, baud)
+    let port = tokio_serial::new(path, baud)
         .open_native_async()
         .map_err(|e| {
             // Match on the error kind for robust cross-platform disconnect detection.
@@ -151,37 +276,80 @@ async fn do_send(path: &str, baud: u32, json: &str) -> Result<ZcResponse, Transp
                 _ => TransportError::Other(format!("failed to open {path}: {e}")),
             }
         })?;
+    exchange(port, json, id, post_write_delay).await
+}
 
+/// Write the command line to `port`, wait `post_write_delay`, then read its
+/// response.
+async fn exchange<S>(
+    mut port: S,
+    json: &str,
+    id: Option<u64>,
+    post_write_delay: Duration,
+) -> Result<ZcResponse, TransportError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     // Write command line
     port.write_all(format!("{json}\n").as_bytes())
         .await
         .map_err(TransportError::Io)?;
     port.flush().await.map_err(TransportError::Io)?;
+    if !post_write_delay.is_zero() {
+        tokio::time::sleep(post_write_delay).await;
+    }
 
-    // Read response line — port is moved into BufReader; write phase complete
-    let mut reader = BufReader::new(port);
-    let mut response_line = String::new();
-    reader
-        .read_line(&mut response_line)
-        .await
-        .map_err(|e: std::io::Error| {
-            if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                TransportError::Disconnected
-            } else {
-                TransportError::Io(e)
-            }
-        })?;
+    // Read response lines — port is moved into BufReader; write phase complete
+    read_response(&mut BufReader::new(port), id).await
+}
 
-    let trimmed = response_line.trim();
-    if trimmed.is_empty() {
-        return Err(TransportError::Protocol(
-            "empty response from device".to_string(),
-        ));
-    }
+/// Read lines until the response to request `id`.
+///
+/// A response echoing `id`, or carrying no `id` at all (firmware without
+/// request IDs), is accepted. Responses for other IDs and lines that are not
+/// responses are traced and skipped, at most [`MAX_SKIPPED_LINES`] of them.
+async fn read_response<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    id: Option<u64>,
+) -> Result<ZcResponse, TransportError> {
+    let mut last_error = "empty response from device".to_string();
+    for _ in 0..=MAX_SKIPPED_LINES {
+        let mut response_line = String::new();
+        let read = reader
+            .read_line(&mut response_line)
+            .await
+            .map_err(|e: std::io::Error| {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    TransportError::Disconnected
+                } else {
+                    TransportError::Io(e)
+                }
+            })?;
+        if read == 0 {
+            break;
+        }
 
-    serde_json::from_str(trimmed).map_err(|e| {
-        TransportError::Protocol(format!("invalid JSON response: {e} — got: {trimmed:?}"))
-    })
+        let trimmed = response_line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<ZcResponse>(trimmed) {
+            Ok(resp) if resp.id.is_none() || resp.id == id => return Ok(resp),
+            Ok(resp) => {
+                tracing::debug!(
+                    expected = ?id,
+                    got = ?resp.id,
+                    "skipping serial response for another request"
+                );
+                last_error = format!("no response for request {id:?}");
+            }
+            Err(e) => {
+                tracing::debug!(line = %trimmed, "skipping non-response serial line");
+                last_error = format!("invalid JSON response: {e} — got: {trimmed:?}");
+            }
+        }
+    }
+    Err(TransportError::Protocol(last_error))
 }
 
 #[cfg(test)]
@@ -195,6 +363,28 @@ mod tests {
         assert_eq!(t.baud_rate, 115_200);
     }
 
+    #[tokio::test]
+    async fn send_logs_alias_instead_of_path_once_set() {
+        #[cfg(target_os = "linux")]
+        let path = "/dev/ttyACM987";
+        #[cfg(target_os = "macos")]
+        let path = "/dev/tty.usbmodemfake987";
+        #[cfg(target_os = "windows")]
+        let path = "COM987";
+        #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+        let path = "/dev/ttyACM987";
+
+        let logs = crate::hardware::test_support::LogCapture::start();
+        let t = HardwareSerialTransport::new(path, 115_200);
+        t.set_log_alias(Some("pico0"));
+        let _ = t.send(&ZcCommand::simple("ping")).await;
+
+        let output = logs.contents();
+        assert!(output.contains("serial send"), "{output}");
+        assert!(output.contains("port=pico0"), "{output}");
+        assert!(!output.contains(path), "{output}");
+    }
+
     #[test]
     fn serial_transport_default_baud() {
         let t = HardwareSerialTransport::with_default_baud("/dev/ttyACM0");
@@ -249,6 +439,75 @@ mod tests {
         assert!(!is_path_allowed(""));
     }
 
+    #[test]
+    fn commands_are_tagged_with_increasing_ids() {
+        let t = HardwareSerialTransport::with_default_baud("/dev/ttyACM0");
+        let first = t.tagged(&ZcCommand::simple("ping"));
+        let second = t.tagged(&ZcCommand::simple("ping"));
+        assert!(second.id > first.id);
+        assert_eq!(t.tagged(&first).id, first.id);
+    }
+
+    #[tokio::test]
+    async fn read_response_skips_logs_and_other_ids() {
+        let lines = b"boot: gpio ready\n\n{\"ok\":true,\"id\":4}\n{\"ok\":true,\"data\":{\"value\":1},\"id\":5}\n";
+        let resp = read_response(&mut BufReader::new(&lines[..]), Some(5))
+            .await
+            .unwrap();
+        assert_eq!(resp.id, Some(5));
+        assert_eq!(resp.data["value"], 1);
+    }
+
+    #[tokio::test]
+    async fn read_response_takes_first_response_without_id() {
+        let lines = b"log line\n{\"ok\":true,\"data\":{\"value\":0}}\n";
+        let resp = read_response(&mut BufReader::new(&lines[..]), Some(9))
+            .await
+            .unwrap();
+        assert_eq!(resp.data["value"], 0);
+    }
+
+    #[tokio::test]
+    async fn read_response_gives_up_after_too_many_skipped_lines() {
+        let lines = "noise\n".repeat(MAX_SKIPPED_LINES + 1);
+        let result = read_response(&mut BufReader::new(lines.as_bytes()), Some(1)).await;
+        assert!(matches!(result, Err(TransportError::Protocol(_))));
+
+        let result = read_response(&mut BufReader::new(&b""[..]), Some(1)).await;
+        assert!(
+            matches!(result, Err(TransportError::Protocol(ref msg)) if msg.contains("empty response"))
+        );
+    }
+
+    #[tokio::test]
+    async fn exchange_waits_post_write_delay_before_reading() {
+        let (host, mut device) = tokio::io::duplex(256);
+        let firmware = tokio::spawn(async move {
+            let mut line = String::new();
+            BufReader::new(&mut device)
+                .read_line(&mut line)
+                .await
+                .unwrap();
+            device
+                .write_all(b"{\"ok\":true,\"data\":{\"value\":1},\"id\":7}\n")
+                .await
+                .unwrap();
+            line
+        });
+
+        let delay = Duration::from_millis(50);
+        let started = std::time::Instant::now();
+        let resp = exchange(host, r#"{"cmd":"gpio_read","id":7}"#, Some(7), delay)
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= delay);
+        assert_eq!(resp.data["value"], 1);
+        assert_eq!(
+            firmware.await.unwrap().trim(),
+            r#"{"cmd":"gpio_read","id":7}"#
+        );
+    }
+
     #[tokio::test]
     async fn send_rejects_disallowed_path() {
         let t = HardwareSerialTransport::new("/dev/sda", 115_200);
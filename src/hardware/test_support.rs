@@ -0,0 +1,123 @@
+//! Test fixtures shared by the hardware tool tests.
+
+use super::device::{DeviceCapabilities, DeviceRegistry};
+use super::protocol::{ZcCommand, ZcResponse};
+use super::transport::{Transport, TransportError, TransportKind};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Mock transport that returns a fixed response and records the last command.
+pub(crate) struct MockTransport {
+    response: ZcResponse,
+    connected: AtomicBool,
+    last_cmd: tokio::sync::Mutex<Option<ZcCommand>>,
+}
+
+impl MockTransport {
+    pub(crate) fn new(response: ZcResponse) -> Self {
+        Self {
+            response,
+            connected: AtomicBool::new(true),
+            last_cmd: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// A transport whose every send fails with `Disconnected`.
+    pub(crate) fn disconnected() -> Self {
+        let t = Self::new(ZcResponse::error("mock: disconnected"));
+        t.connected.store(false, Ordering::SeqCst);
+        t
+    }
+
+    pub(crate) async fn last_command(&self) -> Option<ZcCommand> {
+        self.last_cmd.lock().await.clone()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, cmd: &ZcCommand) -> Result<ZcResponse, TransportError> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(TransportError::Disconnected);
+        }
+        *self.last_cmd.lock().await = Some(cmd.clone());
+        Ok(self.response.clone())
+    }
+
+    fn kind(&self) -> TransportKind {
+        TransportKind::Serial
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+}
+
+/// Registry with one Pico (`pico0`) attached to `transport`.
+pub(crate) fn registry_with_transport(
+    transport: Arc<MockTransport>,
+    caps: DeviceCapabilities,
+) -> Arc<RwLock<DeviceRegistry>> {
+    let mut reg = DeviceRegistry::new();
+    let alias = reg.register(
+        "raspberry-pi-pico",
+        Some(0x2e8a),
+        Some(0x000a),
+        Some("/dev/ttyACM0".to_string()),
+        Some("ARM Cortex-M0+".to_string()),
+    );
+    reg.attach_transport(&alias, transport as Arc<dyn Transport>, caps)
+        .expect("alias was just registered");
+    Arc::new(RwLock::new(reg))
+}
+
+/// Like [`registry_with_transport`] with a fresh mock answering `response`.
+pub(crate) fn registry_with_mock(
+    response: ZcResponse,
+    caps: DeviceCapabilities,
+) -> (Arc<RwLock<DeviceRegistry>>, Arc<MockTransport>) {
+    let mock = Arc::new(MockTransport::new(response));
+    (registry_with_transport(Arc::clone(&mock), caps), mock)
+}
+
+/// Captures the logs emitted on the current thread while it is alive.
+pub(crate) struct LogCapture {
+    buf: Arc<std::sync::Mutex<Vec<u8>>>,
+    _guard: tracing::subscriber::DefaultGuard,
+}
+
+impl LogCapture {
+    pub(crate) fn start() -> Self {
+        let buf = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&buf);
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || LogSink(Arc::clone(&sink)))
+            .finish();
+        Self {
+            buf,
+            _guard: tracing::subscriber::set_default(subscriber),
+        }
+    }
+
+    /// Everything logged so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buf.lock().unwrap()).into_owned()
+    }
+}
+
+struct LogSink(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for LogSink {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -75,6 +75,19 @@ pub trait Transport: Send + Sync {
 
     /// Whether the transport is currently connected to a device.
     fn is_connected(&self) -> bool;
+
+    /// Wire-protocol version negotiated with the device, if known.
+    ///
+    /// Set by the handshake; `None` until then or for firmware that predates
+    /// versioning.
+    fn protocol_version(&self) -> Option<u32> {
+        None
+    }
+
+    /// Name the device `alias` instead of its path in this transport's
+    /// info/warn logs; `None` logs the path. A no-op for transports whose
+    /// logs carry no path.
+    fn set_log_alias(&self, _alias: Option<&str>) {}
 }
 
 #[cfg(test)]
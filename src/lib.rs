@@ -79,6 +79,7 @@ pub mod update;
 pub(crate) mod util;
 
 pub use config::Config;
+pub use hardware::{discover_hardware, DiscoveredDevice};
 
 /// Service management subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -1,20 +1,175 @@
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use anyhow::Result;
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Default interval between Qdrant re-probes while running SQLite-only.
+const DEFAULT_QDRANT_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Qdrant candidates fetched per requested result, leaving room for
+/// candidates that are filtered out or missing from SQLite.
+const QDRANT_CANDIDATE_FACTOR: usize = 3;
+
+/// Consecutive failed semantic recalls before running SQLite-only, so one
+/// transient error does not switch off semantic recall for a whole
+/// re-check interval.
+const RECALL_FAILURES_BEFORE_DEGRADED: u32 = 3;
 
 /// Composite memory backend:
 /// - SQLite remains authoritative for metadata/content/filtering.
 /// - Qdrant provides semantic ranking candidates.
+///
+/// Qdrant is probed at construction (see [`Self::with_startup_probe`]) or
+/// else before the first operation. If it is unreachable the backend either
+/// fails (`strict_startup`) or runs SQLite-only, skipping semantic recall
+/// until a periodic re-probe succeeds. Repeated recall failures also switch
+/// it to SQLite-only.
 pub struct SqliteQdrantHybridMemory {
     sqlite: Arc<dyn Memory>,
     qdrant: Arc<dyn Memory>,
+    strict_startup: bool,
+    recheck_interval: Duration,
+    max_recall_limit: usize,
+    /// Category names (as displayed) stored without a Qdrant embedding.
+    skip_embedding_categories: HashSet<String>,
+    startup_checked: OnceCell<()>,
+    degraded: AtomicBool,
+    last_probe: Mutex<Option<Instant>>,
+    /// Semantic recalls failed in a row since the last success.
+    recall_failures: AtomicU32,
 }
 
 impl SqliteQdrantHybridMemory {
     pub fn new(sqlite: Arc<dyn Memory>, qdrant: Arc<dyn Memory>) -> Self {
-        Self { sqlite, qdrant }
+        Self {
+            sqlite,
+            qdrant,
+            strict_startup: false,
+            recheck_interval: DEFAULT_QDRANT_RECHECK_INTERVAL,
+            max_recall_limit: super::DEFAULT_MAX_RECALL_LIMIT,
+            skip_embedding_categories: HashSet::new(),
+            startup_checked: OnceCell::new(),
+            degraded: AtomicBool::new(false),
+            last_probe: Mutex::new(None),
+            recall_failures: AtomicU32::new(0),
+        }
+    }
+
+    /// Fail instead of degrading when Qdrant is down at startup, and set how
+    /// often a degraded backend re-probes Qdrant.
+    #[must_use]
+    pub fn with_qdrant_policy(mut self, strict_startup: bool, recheck_interval: Duration) -> Self {
+        self.strict_startup = strict_startup;
+        self.recheck_interval = recheck_interval;
+        self
+    }
+
+    /// Cap the results per recall, and with it the Qdrant candidate query
+    /// size (`0` = no cap).
+    #[must_use]
+    pub fn with_max_recall_limit(mut self, max: usize) -> Self {
+        self.max_recall_limit = max;
+        self
+    }
+
+    /// Keep entries in these categories in SQLite only, without embedding them
+    /// into Qdrant. Recall still finds them through the SQLite fallback.
+    #[must_use]
+    pub fn with_skip_embedding_categories(
+        mut self,
+        categories: impl IntoIterator<Item = String>,
+    ) -> Self {
+        self.skip_embedding_categories = categories
+            .into_iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        self
+    }
+
+    fn skips_embedding(&self, category: &MemoryCategory) -> bool {
+        !self.skip_embedding_categories.is_empty()
+            && self
+                .skip_embedding_categories
+                .contains(&category.to_string())
+    }
+
+    /// True while semantic recall is skipped because Qdrant is unreachable.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    /// Settle the startup check with a probe taken at construction, e.g.
+    /// [`super::QdrantMemory::probe_blocking`] from the synchronous factory,
+    /// so strict mode fails there instead of on first use.
+    pub fn with_startup_probe(self, qdrant_reachable: bool) -> Result<Self> {
+        if !qdrant_reachable {
+            if self.strict_startup {
+                anyhow::bail!("Qdrant is unreachable and memory.qdrant.strict_startup is enabled");
+            }
+            self.mark_degraded("startup health check failed");
+        }
+        let _ = self.startup_checked.set(());
+        Ok(self)
+    }
+
+    /// Probe Qdrant once before first use.
+    ///
+    /// In strict mode an unreachable Qdrant is an error (re-probed on the next
+    /// call); otherwise the backend switches to SQLite-only.
+    pub async fn startup_check(&self) -> Result<()> {
+        self.startup_checked
+            .get_or_try_init(|| async {
+                if self.qdrant.health_check().await {
+                    return Ok(());
+                }
+                if self.strict_startup {
+                    anyhow::bail!(
+                        "Qdrant is unreachable and memory.qdrant.strict_startup is enabled"
+                    );
+                }
+                self.mark_degraded("startup health check failed");
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Switch to SQLite-only, logging only on the transition.
+    fn mark_degraded(&self, reason: &str) {
+        *self.last_probe.lock() = Some(Instant::now());
+        if !self.degraded.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                reason,
+                "Qdrant unreachable; hybrid memory running SQLite-only until it recovers"
+            );
+        }
+    }
+
+    /// Whether to consult Qdrant now, re-probing a degraded backend once per
+    /// `recheck_interval`.
+    async fn qdrant_ready(&self) -> bool {
+        if !self.is_degraded() {
+            return true;
+        }
+        {
+            let mut last_probe = self.last_probe.lock();
+            if last_probe.is_some_and(|at| at.elapsed() < self.recheck_interval) {
+                return false;
+            }
+            *last_probe = Some(Instant::now());
+        }
+        if !self.qdrant.health_check().await {
+            return false;
+        }
+        self.degraded.store(false, Ordering::Relaxed);
+        tracing::info!("Qdrant reachable again; hybrid memory resuming semantic recall");
+        true
     }
 }
 
@@ -31,11 +186,17 @@ impl Memory for SqliteQdrantHybridMemory {
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> Result<()> {
+        self.startup_check().await?;
+
         // SQLite is authoritative. Fail only if local persistence fails.
         self.sqlite
             .store(key, content, category.clone(), session_id)
             .await?;
 
+        if self.skips_embedding(&category) {
+            return Ok(());
+        }
+
         // Best-effort vector sync to Qdrant.
         if let Err(err) = self.qdrant.store(key, content, category, session_id).await {
             tracing::warn!(
@@ -54,23 +215,40 @@ impl Memory for SqliteQdrantHybridMemory {
         limit: usize,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
+        self.startup_check().await?;
+
+        let limit = super::clamp_recall_limit(limit, self.max_recall_limit);
         let trimmed_query = query.trim();
-        if trimmed_query.is_empty() {
+        if trimmed_query.is_empty() || !self.qdrant_ready().await {
             return self.sqlite.recall(query, limit, session_id).await;
         }
 
         let qdrant_candidates = match self
             .qdrant
-            .recall(trimmed_query, limit.max(1).saturating_mul(3), session_id)
+            .recall(
+                trimmed_query,
+                limit.max(1).saturating_mul(QDRANT_CANDIDATE_FACTOR),
+                session_id,
+            )
             .await
         {
-            Ok(candidates) => candidates,
+            Ok(candidates) => {
+                self.recall_failures.store(0, Ordering::Relaxed);
+                candidates
+            }
             Err(err) => {
-                tracing::warn!(
-                    query = trimmed_query,
-                    error = %err,
-                    "Hybrid memory semantic recall failed; falling back to SQLite recall"
-                );
+                let failures = self.recall_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if failures >= RECALL_FAILURES_BEFORE_DEGRADED {
+                    self.recall_failures.store(0, Ordering::Relaxed);
+                    self.mark_degraded(&format!(
+                        "{failures} semantic recalls failed in a row, last: {err}"
+                    ));
+                } else {
+                    tracing::warn!(
+                        error = %err,
+                        "Hybrid memory semantic recall failed; using SQLite for this query"
+                    );
+                }
                 return self.sqlite.recall(trimmed_query, limit, session_id).await;
             }
         };
@@ -144,6 +322,25 @@ impl Memory for SqliteQdrantHybridMemory {
         Ok(removed)
     }
 
+    async fn forget_where(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        force: bool,
+    ) -> Result<usize> {
+        let removed = self
+            .sqlite
+            .forget_where(category, session_id, force)
+            .await?;
+        if let Err(err) = self.qdrant.forget_where(category, session_id, force).await {
+            tracing::warn!(
+                error = %err,
+                "Hybrid memory bulk vector delete failed; SQLite delete result preserved"
+            );
+        }
+        Ok(removed)
+    }
+
     async fn count(&self) -> Result<usize> {
         self.sqlite.count().await
     }
@@ -154,8 +351,12 @@ impl Memory for SqliteQdrantHybridMemory {
             return false;
         }
 
-        if !self.qdrant.health_check().await {
-            tracing::warn!("Hybrid memory Qdrant health check failed; SQLite remains available");
+        if let Err(err) = self.startup_check().await {
+            tracing::warn!(error = %err, "Hybrid memory health check failed");
+            return false;
+        }
+        if !self.qdrant_ready().await {
+            tracing::debug!("Hybrid memory running SQLite-only; Qdrant still unreachable");
         }
         true
     }
@@ -172,7 +373,12 @@ mod tests {
         recall_results: Vec<MemoryEntry>,
         fail_store: bool,
         fail_recall: bool,
+        store_calls: Mutex<Vec<String>>,
         forget_calls: Mutex<Vec<String>>,
+        forget_where_calls: Mutex<Vec<(Option<MemoryCategory>, Option<String>)>>,
+        healthy: AtomicBool,
+        recall_calls: std::sync::atomic::AtomicUsize,
+        last_recall_limit: std::sync::atomic::AtomicUsize,
     }
 
     impl StubQdrantMemory {
@@ -181,9 +387,20 @@ mod tests {
                 recall_results,
                 fail_store,
                 fail_recall,
+                store_calls: Mutex::new(Vec::new()),
                 forget_calls: Mutex::new(Vec::new()),
+                forget_where_calls: Mutex::new(Vec::new()),
+                healthy: AtomicBool::new(true),
+                recall_calls: std::sync::atomic::AtomicUsize::new(0),
+                last_recall_limit: std::sync::atomic::AtomicUsize::new(0),
             }
         }
+
+        fn unreachable() -> Self {
+            let stub = Self::new(Vec::new(), true, true);
+            stub.healthy.store(false, Ordering::SeqCst);
+            stub
+        }
     }
 
     #[async_trait]
@@ -194,11 +411,15 @@ mod tests {
 
         async fn store(
             &self,
-            _key: &str,
+            key: &str,
             _content: &str,
             _category: MemoryCategory,
             _session_id: Option<&str>,
         ) -> Result<()> {
+            self.store_calls
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(key.to_string());
             if self.fail_store {
                 anyhow::bail!("simulated qdrant store failure");
             }
@@ -208,9 +429,11 @@ mod tests {
         async fn recall(
             &self,
             _query: &str,
-            _limit: usize,
+            limit: usize,
             _session_id: Option<&str>,
         ) -> Result<Vec<MemoryEntry>> {
+            self.recall_calls.fetch_add(1, Ordering::SeqCst);
+            self.last_recall_limit.store(limit, Ordering::SeqCst);
             if self.fail_recall {
                 anyhow::bail!("simulated qdrant recall failure");
             }
@@ -237,12 +460,25 @@ mod tests {
             Ok(true)
         }
 
+        async fn forget_where(
+            &self,
+            category: Option<&MemoryCategory>,
+            session_id: Option<&str>,
+            _force: bool,
+        ) -> Result<usize> {
+            self.forget_where_calls
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push((category.cloned(), session_id.map(String::from)));
+            Ok(0)
+        }
+
         async fn count(&self) -> Result<usize> {
             Ok(self.recall_results.len())
         }
 
         async fn health_check(&self) -> bool {
-            true
+            self.healthy.load(Ordering::SeqCst)
         }
     }
 
@@ -279,6 +515,42 @@ mod tests {
         assert!(stored.is_some(), "SQLite should remain authoritative");
     }
 
+    #[tokio::test]
+    async fn skipped_category_stays_out_of_qdrant_but_is_recalled_from_sqlite() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant = Arc::new(StubQdrantMemory::new(Vec::new(), false, false));
+        let hybrid = SqliteQdrantHybridMemory::new(
+            Arc::clone(&sqlite),
+            Arc::clone(&qdrant) as Arc<dyn Memory>,
+        )
+        .with_skip_embedding_categories(["conversation".to_string()]);
+
+        hybrid
+            .store("fact", "prefers green tea", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        hybrid
+            .store(
+                "chat_1",
+                "ephemeral chatter about lunch",
+                MemoryCategory::Conversation,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            *qdrant.store_calls.lock().unwrap(),
+            vec!["fact".to_string()]
+        );
+
+        let recalled = hybrid.recall("lunch", 5, None).await.unwrap();
+        assert!(
+            recalled.iter().any(|entry| entry.key == "chat_1"),
+            "SQLite fallback should recall entries that were never embedded"
+        );
+        assert_eq!(qdrant.recall_calls.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn recall_joins_qdrant_ranking_with_sqlite_rows() {
         let (_tmp, sqlite) = temp_sqlite();
@@ -307,6 +579,39 @@ mod tests {
         assert_eq!(recalled[1].score, Some(0.72));
     }
 
+    #[tokio::test]
+    async fn oversized_recall_limit_bounds_qdrant_candidate_query() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant = Arc::new(StubQdrantMemory::new(Vec::new(), false, false));
+        let hybrid = SqliteQdrantHybridMemory::new(
+            Arc::clone(&sqlite),
+            Arc::clone(&qdrant) as Arc<dyn Memory>,
+        )
+        .with_max_recall_limit(10);
+
+        hybrid.recall("anything", usize::MAX, None).await.unwrap();
+        assert_eq!(qdrant.last_recall_limit.load(Ordering::SeqCst), 30);
+
+        hybrid.recall("anything", 4, None).await.unwrap();
+        assert_eq!(qdrant.last_recall_limit.load(Ordering::SeqCst), 12);
+    }
+
+    #[tokio::test]
+    async fn default_recall_limit_cap_applies_without_config() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant = Arc::new(StubQdrantMemory::new(Vec::new(), false, false));
+        let hybrid = SqliteQdrantHybridMemory::new(
+            Arc::clone(&sqlite),
+            Arc::clone(&qdrant) as Arc<dyn Memory>,
+        );
+
+        hybrid.recall("anything", 1_000_000, None).await.unwrap();
+        assert_eq!(
+            qdrant.last_recall_limit.load(Ordering::SeqCst),
+            crate::memory::DEFAULT_MAX_RECALL_LIMIT * QDRANT_CANDIDATE_FACTOR
+        );
+    }
+
     #[tokio::test]
     async fn recall_falls_back_to_sqlite_when_qdrant_fails() {
         let (_tmp, sqlite) = temp_sqlite();
@@ -329,4 +634,124 @@ mod tests {
             "SQLite fallback should provide recall results when Qdrant is unavailable"
         );
     }
+
+    #[tokio::test]
+    async fn forget_where_prunes_sqlite_rows_and_qdrant_vectors() {
+        let (_tmp, sqlite) = temp_sqlite();
+        sqlite
+            .store("chat_1", "hello", MemoryCategory::Conversation, Some("s1"))
+            .await
+            .unwrap();
+        sqlite
+            .store("fact", "likes tea", MemoryCategory::Core, Some("s1"))
+            .await
+            .unwrap();
+
+        let qdrant = Arc::new(StubQdrantMemory::new(Vec::new(), false, false));
+        let hybrid = SqliteQdrantHybridMemory::new(
+            Arc::clone(&sqlite),
+            Arc::clone(&qdrant) as Arc<dyn Memory>,
+        );
+
+        let removed = hybrid
+            .forget_where(Some(&MemoryCategory::Conversation), Some("s1"), false)
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(sqlite.get("chat_1").await.unwrap().is_none());
+        assert!(sqlite.get("fact").await.unwrap().is_some());
+        assert_eq!(
+            *qdrant.forget_where_calls.lock().unwrap(),
+            vec![(Some(MemoryCategory::Conversation), Some("s1".to_string()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn strict_startup_fails_when_qdrant_unreachable() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant: Arc<dyn Memory> = Arc::new(StubQdrantMemory::unreachable());
+        let hybrid = SqliteQdrantHybridMemory::new(sqlite, qdrant)
+            .with_qdrant_policy(true, DEFAULT_QDRANT_RECHECK_INTERVAL);
+
+        let err = hybrid.recall("anything", 5, None).await.unwrap_err();
+        assert!(err.to_string().contains("strict_startup"));
+        assert!(!hybrid.health_check().await);
+        assert!(!hybrid.is_degraded());
+    }
+
+    #[tokio::test]
+    async fn startup_probe_settles_startup_check() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant: Arc<dyn Memory> = Arc::new(StubQdrantMemory::unreachable());
+        let err = SqliteQdrantHybridMemory::new(Arc::clone(&sqlite), Arc::clone(&qdrant))
+            .with_qdrant_policy(true, DEFAULT_QDRANT_RECHECK_INTERVAL)
+            .with_startup_probe(false)
+            .err()
+            .expect("strict mode should fail on an unreachable probe");
+        assert!(err.to_string().contains("strict_startup"));
+
+        let hybrid = SqliteQdrantHybridMemory::new(sqlite, qdrant)
+            .with_startup_probe(false)
+            .unwrap();
+        assert!(hybrid.is_degraded());
+        assert!(hybrid.startup_checked.initialized());
+    }
+
+    #[tokio::test]
+    async fn repeated_recall_failures_switch_to_sqlite_only() {
+        let (_tmp, sqlite) = temp_sqlite();
+        let qdrant = Arc::new(StubQdrantMemory::new(Vec::new(), false, true));
+        let hybrid = SqliteQdrantHybridMemory::new(sqlite, Arc::clone(&qdrant) as Arc<dyn Memory>)
+            .with_qdrant_policy(false, Duration::from_secs(3600));
+
+        for _ in 1..RECALL_FAILURES_BEFORE_DEGRADED {
+            hybrid.recall("anything", 5, None).await.unwrap();
+            assert!(!hybrid.is_degraded());
+        }
+        hybrid.recall("anything", 5, None).await.unwrap();
+        assert!(hybrid.is_degraded());
+
+        let calls = qdrant.recall_calls.load(Ordering::SeqCst);
+        hybrid.recall("anything", 5, None).await.unwrap();
+        assert_eq!(qdrant.recall_calls.load(Ordering::SeqCst), calls);
+    }
+
+    #[tokio::test]
+    async fn degraded_mode_skips_qdrant_until_recheck_succeeds() {
+        let (_tmp, sqlite) = temp_sqlite();
+        sqlite
+            .store(
+                "topic",
+                "degraded recall finds this",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        let qdrant = Arc::new(StubQdrantMemory::new(
+            vec![make_qdrant_entry("topic", 0.9)],
+            false,
+            false,
+        ));
+        qdrant.healthy.store(false, Ordering::SeqCst);
+        let hybrid = SqliteQdrantHybridMemory::new(
+            Arc::clone(&sqlite),
+            Arc::clone(&qdrant) as Arc<dyn Memory>,
+        )
+        .with_qdrant_policy(false, Duration::from_secs(3600));
+
+        let recalled = hybrid.recall("degraded", 5, None).await.unwrap();
+        assert!(recalled.iter().any(|e| e.key == "topic"));
+        assert!(hybrid.is_degraded());
+        hybrid.recall("degraded", 5, None).await.unwrap();
+        assert_eq!(qdrant.recall_calls.load(Ordering::SeqCst), 0);
+
+        // Qdrant recovers; the next re-probe (forced here) resumes semantic recall.
+        qdrant.healthy.store(true, Ordering::SeqCst);
+        *hybrid.last_probe.lock() = None;
+        let recalled = hybrid.recall("degraded", 5, None).await.unwrap();
+        assert!(!hybrid.is_degraded());
+        assert_eq!(qdrant.recall_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(recalled[0].score, Some(0.9));
+    }
 }
@@ -93,6 +93,18 @@ pub fn effective_memory_backend_name(
     memory_backend.trim().to_ascii_lowercase()
 }
 
+/// Default cap on results per recall (`memory.max_recall_limit`).
+pub const DEFAULT_MAX_RECALL_LIMIT: usize = 100;
+
+/// Clamp a caller-supplied recall `limit` to `max` (`0` = no cap).
+pub fn clamp_recall_limit(limit: usize, max: usize) -> usize {
+    if max == 0 {
+        limit
+    } else {
+        limit.min(max)
+    }
+}
+
 /// Legacy auto-save key used for model-authored assistant summaries.
 /// These entries are treated as untrusted context and should not be re-injected.
 pub fn is_assistant_autosave_key(key: &str) -> bool {
@@ -371,8 +383,22 @@ pub fn create_memory_with_storage_and_routes(
             workspace_dir,
             &resolved_embedding,
         )?);
-        let qdrant: Arc<dyn Memory> = Arc::new(build_qdrant_memory(config, &resolved_embedding)?);
-        return Ok(Box::new(SqliteQdrantHybridMemory::new(sqlite, qdrant)));
+        let qdrant = build_qdrant_memory(config, &resolved_embedding)?;
+        // Probe now so strict mode fails here rather than on first use.
+        let qdrant_reachable = qdrant.probe_blocking();
+        let qdrant: Arc<dyn Memory> = Arc::new(qdrant);
+        return Ok(Box::new(
+            SqliteQdrantHybridMemory::new(sqlite, qdrant)
+                .with_qdrant_policy(
+                    config.qdrant.strict_startup,
+                    std::time::Duration::from_secs(config.qdrant.recheck_interval_secs),
+                )
+                .with_max_recall_limit(config.max_recall_limit)
+                .with_skip_embedding_categories(
+                    config.qdrant.skip_embedding_categories.iter().cloned(),
+                )
+                .with_startup_probe(qdrant_reachable)?,
+        ));
     }
 
     create_memory_with_builders(
@@ -512,6 +538,25 @@ mod tests {
         assert_eq!(mem.name(), "sqlite_qdrant_hybrid");
     }
 
+    #[test]
+    fn factory_hybrid_strict_startup_fails_fast_when_qdrant_unreachable() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = MemoryConfig {
+            backend: "sqlite_qdrant_hybrid".into(),
+            qdrant: crate::config::QdrantConfig {
+                url: Some("http://127.0.0.1:9".into()),
+                strict_startup: true,
+                ..crate::config::QdrantConfig::default()
+            },
+            ..MemoryConfig::default()
+        };
+
+        let error = create_memory(&cfg, tmp.path(), None)
+            .err()
+            .expect("strict hybrid backend should fail at construction");
+        assert!(error.to_string().contains("strict_startup"));
+    }
+
     #[test]
     fn factory_none_uses_noop_memory() {
         let tmp = TempDir::new().unwrap();
@@ -1,13 +1,17 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{ensure_forget_scope, Memory, MemoryCategory, MemoryEntry};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::OnceCell;
 use uuid::Uuid;
 
+/// How long the synchronous startup probe waits for Qdrant to answer.
+const STARTUP_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Qdrant vector database memory backend.
 ///
 /// Uses Qdrant's REST API for vector storage and semantic search.
@@ -68,6 +72,30 @@ impl QdrantMemory {
         }
     }
 
+    /// Check from synchronous code that Qdrant answers, like `health_check`.
+    ///
+    /// Runs on its own thread with a throwaway client, so it works inside a
+    /// Tokio runtime and leaves no pooled connection tied to a foreign one.
+    pub fn probe_blocking(&self) -> bool {
+        let url = format!("{}/", self.base_url);
+        let api_key = self.api_key.clone();
+        std::thread::spawn(move || {
+            let Ok(client) = reqwest::blocking::Client::builder()
+                .timeout(STARTUP_PROBE_TIMEOUT)
+                .build()
+            else {
+                return false;
+            };
+            let mut req = client.get(url);
+            if let Some(key) = api_key {
+                req = req.header("api-key", key);
+            }
+            matches!(req.send(), Ok(r) if r.status().is_success())
+        })
+        .join()
+        .unwrap_or(false)
+    }
+
     /// Ensure the collection is initialized (called lazily on first operation).
     async fn ensure_initialized(&self) -> Result<()> {
         self.initialized
@@ -537,6 +565,72 @@ impl Memory for QdrantMemory {
         Ok(true)
     }
 
+    async fn forget_where(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        force: bool,
+    ) -> Result<usize> {
+        ensure_forget_scope(category, session_id, force)?;
+        self.ensure_initialized().await?;
+
+        let mut must_conditions = Vec::new();
+        if let Some(cat) = category {
+            must_conditions.push(serde_json::json!({
+                "key": "category",
+                "match": { "value": Self::category_to_str(cat) }
+            }));
+        }
+        if let Some(sid) = session_id {
+            must_conditions.push(serde_json::json!({
+                "key": "session_id",
+                "match": { "value": sid }
+            }));
+        }
+        let filter = serde_json::json!({ "must": must_conditions });
+
+        // The delete endpoint does not report how many points it removed,
+        // so count the matches first.
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/count", self.collection),
+            )
+            .json(&serde_json::json!({ "filter": filter, "exact": true }))
+            .send()
+            .await
+            .context("failed to count Qdrant points")?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant count failed ({status}): {text}");
+        }
+        let json: serde_json::Value = resp.json().await?;
+        let matched = json
+            .get("result")
+            .and_then(|r| r.get("count"))
+            .and_then(|c| c.as_u64())
+            .unwrap_or(0);
+
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/delete", self.collection),
+            )
+            .query(&[("wait", "true")])
+            .json(&serde_json::json!({ "filter": filter }))
+            .send()
+            .await
+            .context("failed to delete from Qdrant")?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant delete failed ({status}): {text}");
+        }
+
+        usize::try_from(matched).context("Qdrant returned a points count that exceeds usize")
+    }
+
     async fn count(&self) -> Result<usize> {
         self.ensure_initialized().await?;
 
@@ -1,5 +1,5 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{ensure_forget_scope, Memory, MemoryCategory, MemoryEntry};
 use super::vector;
 use anyhow::Context;
 use async_trait::async_trait;
@@ -793,6 +793,31 @@ impl Memory for SqliteMemory {
         .await?
     }
 
+    async fn forget_where(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        force: bool,
+    ) -> anyhow::Result<usize> {
+        ensure_forget_scope(category, session_id, force)?;
+
+        let conn = self.conn.clone();
+        let category = category.map(Self::category_to_str);
+        let sid = session_id.map(String::from);
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+            let conn = conn.lock();
+            let affected = conn.execute(
+                "DELETE FROM memories
+                 WHERE (?1 IS NULL OR category = ?1)
+                   AND (?2 IS NULL OR session_id = ?2)",
+                params![category, sid],
+            )?;
+            Ok(affected)
+        })
+        .await?
+    }
+
     async fn count(&self) -> anyhow::Result<usize> {
         let conn = self.conn.clone();
 
@@ -1853,6 +1878,78 @@ mod tests {
         assert_eq!(results[0].key, "k1");
     }
 
+    #[tokio::test]
+    async fn forget_where_by_category() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store(
+            "c1",
+            "chat one",
+            MemoryCategory::Conversation,
+            Some("sess-a"),
+        )
+        .await
+        .unwrap();
+        mem.store("c2", "chat two", MemoryCategory::Conversation, None)
+            .await
+            .unwrap();
+        mem.store("f1", "durable fact", MemoryCategory::Core, Some("sess-a"))
+            .await
+            .unwrap();
+
+        let removed = mem
+            .forget_where(Some(&MemoryCategory::Conversation), None, false)
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(mem.count().await.unwrap(), 1);
+        assert!(mem.get("f1").await.unwrap().is_some());
+        assert!(mem.recall("chat", 10, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn forget_where_by_session() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("k1", "a1", MemoryCategory::Core, Some("sess-a"))
+            .await
+            .unwrap();
+        mem.store("k2", "a2", MemoryCategory::Conversation, Some("sess-a"))
+            .await
+            .unwrap();
+        mem.store("k3", "b1", MemoryCategory::Conversation, Some("sess-b"))
+            .await
+            .unwrap();
+
+        let removed = mem.forget_where(None, Some("sess-a"), false).await.unwrap();
+        assert_eq!(removed, 2);
+        let left = mem.list(None, None).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].key, "k3");
+
+        let removed = mem
+            .forget_where(Some(&MemoryCategory::Core), Some("sess-b"), false)
+            .await
+            .unwrap();
+        assert_eq!(removed, 0);
+    }
+
+    #[tokio::test]
+    async fn forget_where_unscoped_requires_force() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("k1", "a1", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("k2", "a2", MemoryCategory::Daily, Some("sess-a"))
+            .await
+            .unwrap();
+
+        let err = mem.forget_where(None, None, false).await.unwrap_err();
+        assert!(err.to_string().contains("refusing"));
+        assert_eq!(mem.count().await.unwrap(), 2);
+
+        assert_eq!(mem.forget_where(None, None, true).await.unwrap(), 2);
+        assert_eq!(mem.count().await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn schema_migration_idempotent_on_reopen() {
         let tmp = TempDir::new().unwrap();
@@ -51,6 +51,21 @@ impl std::fmt::Display for MemoryCategory {
     }
 }
 
+/// Guard for [`Memory::forget_where`]: refuse an unscoped delete unless forced.
+pub fn ensure_forget_scope(
+    category: Option<&MemoryCategory>,
+    session_id: Option<&str>,
+    force: bool,
+) -> anyhow::Result<()> {
+    if category.is_none() && session_id.is_none() && !force {
+        anyhow::bail!(
+            "refusing to forget all memories without a category or session filter; \
+             pass force to confirm"
+        );
+    }
+    Ok(())
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -87,6 +102,30 @@ pub trait Memory: Send + Sync {
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
+    /// Remove every memory matching `category` and/or `session_id`.
+    /// Returns the number of memories removed.
+    ///
+    /// A call with neither filter would wipe the whole store, so it is
+    /// refused unless `force` is set.
+    ///
+    /// The default implementation lists matches and forgets them one by one;
+    /// backends that can delete by filter should override it.
+    async fn forget_where(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        force: bool,
+    ) -> anyhow::Result<usize> {
+        ensure_forget_scope(category, session_id, force)?;
+        let mut removed = 0;
+        for entry in self.list(category, session_id).await? {
+            if self.forget(&entry.key).await? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
     /// Count total memories
     async fn count(&self) -> anyhow::Result<usize>;
 
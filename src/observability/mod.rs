@@ -5,6 +5,7 @@ pub mod noop;
 #[cfg(feature = "observability-otel")]
 pub mod otel;
 pub mod prometheus;
+pub mod provider_debug;
 pub mod runtime_trace;
 pub mod traits;
 pub mod verbose;
@@ -459,6 +459,7 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         auto_hydrate: true,
         sqlite_open_timeout_secs: None,
         sqlite_journal_mode: "wal".to_string(),
+        max_recall_limit: 100,
         qdrant: crate::config::QdrantConfig::default(),
     }
 }
@@ -27,6 +27,11 @@ impl Tool for ArduinoUploadTool {
         "arduino_upload"
     }
 
+    /// Flashing takes over the board's serial port; run in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     fn description(&self) -> &str {
         "Generate Arduino sketch code and upload it to the connected Arduino. Use when: user asks to 'make a heart', 'blink LED', or run any custom pattern on Arduino. You MUST write the full .ino sketch code (setup + loop). Arduino Uno: pin 13 = built-in LED. Saves to temp dir, runs arduino-cli compile and upload. Requires arduino-cli installed."
     }
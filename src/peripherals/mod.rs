@@ -31,6 +31,7 @@ use crate::peripherals::traits::Peripheral;
 use crate::tools::HardwareMemoryMapTool;
 use crate::tools::Tool;
 use anyhow::Result;
+use std::collections::HashMap;
 
 /// List configured boards from config (no connection yet).
 pub fn list_configured_boards(config: &PeripheralsConfig) -> Vec<&PeripheralBoardConfig> {
@@ -94,6 +95,10 @@ pub async fn handle_command(cmd: crate::PeripheralCommands, config: &Config) ->
                 transport: transport.to_string(),
                 path: path_opt,
                 baud: 115_200,
+                post_write_delay_ms: 0,
+                health_command: "ping".into(),
+                commands: HashMap::new(),
+                safe_state: Vec::new(),
             });
             cfg.save().await?;
             println!("Added {} at {}. Restart daemon to apply.", board, path);
@@ -247,6 +252,10 @@ mod tests {
                 transport: "serial".into(),
                 path: Some("/dev/ttyACM0".into()),
                 baud: 115_200,
+                post_write_delay_ms: 0,
+                health_command: "ping".into(),
+                commands: HashMap::new(),
+                safe_state: Vec::new(),
             }],
             datasheet_dir: None,
         };
@@ -267,12 +276,20 @@ mod tests {
                     transport: "serial".into(),
                     path: Some("/dev/ttyACM0".into()),
                     baud: 115_200,
+                    post_write_delay_ms: 0,
+                    health_command: "ping".into(),
+                    commands: HashMap::new(),
+                    safe_state: Vec::new(),
                 },
                 PeripheralBoardConfig {
                     board: "rpi-gpio".into(),
                     transport: "native".into(),
                     path: None,
                     baud: 115_200,
+                    post_write_delay_ms: 0,
+                    health_command: "ping".into(),
+                    commands: HashMap::new(),
+                    safe_state: Vec::new(),
                 },
             ],
             datasheet_dir: None,
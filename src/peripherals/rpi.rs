@@ -120,6 +120,11 @@ impl Tool for RpiGpioWriteTool {
         "gpio_write"
     }
 
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     fn description(&self) -> &str {
         "Set a GPIO pin high (1) or low (0) on Raspberry Pi. Uses BCM pin numbers."
     }
@@ -142,6 +147,9 @@ impl Tool for RpiGpioWriteTool {
     }
 
     async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = crate::hardware::HardwareEstop::global().refusal() {
+            return Ok(refused);
+        }
         let pin = args
             .get("pin")
             .and_then(|v| v.as_u64())
@@ -9,9 +9,11 @@ use crate::config::PeripheralBoardConfig;
 use crate::tools::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::Mutex;
 use tokio_serial::{SerialPortBuilderExt, SerialStream};
 
@@ -31,7 +33,18 @@ fn is_path_allowed(path: &str) -> bool {
 }
 
 /// JSON request/response over serial.
-async fn send_request(port: &mut SerialStream, cmd: &str, args: Value) -> anyhow::Result<Value> {
+///
+/// `post_write_delay` is slept between flushing the request and reading the
+/// reply, for firmware that is not ready to answer immediately.
+async fn send_request<S>(
+    port: &mut S,
+    cmd: &str,
+    args: Value,
+    post_write_delay: Duration,
+) -> anyhow::Result<Value>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     static ID: AtomicU64 = AtomicU64::new(0);
     let id = ID.fetch_add(1, Ordering::Relaxed);
     let id_str = id.to_string();
@@ -45,6 +58,9 @@ async fn send_request(port: &mut SerialStream, cmd: &str, args: Value) -> anyhow
 
     port.write_all(line.as_bytes()).await?;
     port.flush().await?;
+    if !post_write_delay.is_zero() {
+        tokio::time::sleep(post_write_delay).await;
+    }
 
     let mut buf = Vec::new();
     let mut b = [0u8; 1];
@@ -63,9 +79,22 @@ async fn send_request(port: &mut SerialStream, cmd: &str, args: Value) -> anyhow
     Ok(resp)
 }
 
+/// Send `cmd` and report whether the board answered `ok:true`.
+async fn probe_health<S>(port: &mut S, cmd: &str, post_write_delay: Duration) -> bool
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    send_request(port, cmd, json!({}), post_write_delay)
+        .await
+        .is_ok_and(|resp| resp["ok"].as_bool() == Some(true))
+}
+
 /// Shared serial transport for tools. Pub(crate) for capabilities tool.
 pub(crate) struct SerialTransport {
     port: Mutex<SerialStream>,
+    post_write_delay: Duration,
+    /// Firmware names replacing standard command names (board `commands`).
+    commands: HashMap<String, String>,
 }
 
 /// Timeout for serial request/response (seconds).
@@ -73,10 +102,11 @@ const SERIAL_TIMEOUT_SECS: u64 = 5;
 
 impl SerialTransport {
     async fn request(&self, cmd: &str, args: Value) -> anyhow::Result<ToolResult> {
+        let cmd = self.commands.get(cmd).map_or(cmd, String::as_str);
         let mut port = self.port.lock().await;
         let resp = tokio::time::timeout(
             std::time::Duration::from_secs(SERIAL_TIMEOUT_SECS),
-            send_request(&mut port, cmd, args),
+            send_request(&mut *port, cmd, args, self.post_write_delay),
         )
         .await
         .map_err(|_| {
@@ -97,6 +127,17 @@ impl SerialTransport {
         })
     }
 
+    /// Liveness check using the board's configured health command.
+    async fn health(&self, cmd: &str) -> bool {
+        let mut port = self.port.lock().await;
+        tokio::time::timeout(
+            Duration::from_secs(SERIAL_TIMEOUT_SECS),
+            probe_health(&mut *port, cmd, self.post_write_delay),
+        )
+        .await
+        .unwrap_or(false)
+    }
+
     /// Phase C: fetch capabilities from device (gpio pins, led_pin).
     pub async fn capabilities(&self) -> anyhow::Result<ToolResult> {
         self.request("capabilities", json!({})).await
@@ -107,6 +148,7 @@ impl SerialTransport {
 pub struct SerialPeripheral {
     name: String,
     board_type: String,
+    health_command: String,
     transport: Arc<SerialTransport>,
 }
 
@@ -126,18 +168,27 @@ impl SerialPeripheral {
             );
         }
 
+        config
+            .validate_baud()
+            .map_err(|e| anyhow::anyhow!("Serial peripheral {}: {}", config.board, e))?;
+
         let port = tokio_serial::new(path, config.baud)
             .open_native_async()
-            .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path, e))?;
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to open {} at {} baud: {}", path, config.baud, e)
+            })?;
 
         let name = format!("{}-{}", config.board, path.replace('/', "_"));
         let transport = Arc::new(SerialTransport {
             port: Mutex::new(port),
+            post_write_delay: Duration::from_millis(config.post_write_delay_ms),
+            commands: config.commands.clone(),
         });
 
         Ok(Self {
             name: name.clone(),
             board_type: config.board.clone(),
+            health_command: config.health_command.clone(),
             transport,
         })
     }
@@ -162,11 +213,7 @@ impl Peripheral for SerialPeripheral {
     }
 
     async fn health_check(&self) -> bool {
-        self.transport
-            .request("ping", json!({}))
-            .await
-            .map(|r| r.success)
-            .unwrap_or(false)
+        self.transport.health(&self.health_command).await
     }
 
     fn tools(&self) -> Vec<Box<dyn Tool>> {
@@ -238,6 +285,11 @@ impl Tool for GpioWriteTool {
         "gpio_write"
     }
 
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     fn description(&self) -> &str {
         "Set a GPIO pin high (1) or low (0) on a connected peripheral (e.g. turn on/off LED)"
     }
@@ -260,6 +312,9 @@ impl Tool for GpioWriteTool {
     }
 
     async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = crate::hardware::HardwareEstop::global().refusal() {
+            return Ok(refused);
+        }
         let pin = args
             .get("pin")
             .and_then(|v| v.as_u64())
@@ -273,3 +328,189 @@ impl Tool for GpioWriteTool {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Instant;
+    use tokio::io::ReadBuf;
+
+    /// Device that answers with line noise until `ready_after` has elapsed
+    /// since the request was flushed, then echoes a valid response.
+    struct SlowDevice {
+        request: Vec<u8>,
+        flushed_at: Option<Instant>,
+        ready_after: Duration,
+        reply: Option<Vec<u8>>,
+    }
+
+    impl SlowDevice {
+        fn new(ready_after: Duration) -> Self {
+            Self {
+                request: Vec::new(),
+                flushed_at: None,
+                ready_after,
+                reply: None,
+            }
+        }
+    }
+
+    impl AsyncWrite for SlowDevice {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.request.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.flushed_at = Some(Instant::now());
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncRead for SlowDevice {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if self.reply.is_none() {
+                let ready = self
+                    .flushed_at
+                    .is_some_and(|t| t.elapsed() >= self.ready_after);
+                let reply = if ready {
+                    let req: Value = serde_json::from_slice(self.request.trim_ascii()).unwrap();
+                    format!(
+                        "{}\n",
+                        json!({"id": req["id"], "ok": true, "result": "done"})
+                    )
+                } else {
+                    "\u{fffd}\u{fffd}\n".to_string()
+                };
+                self.reply = Some(reply.into_bytes());
+            }
+            let reply = self.reply.as_mut().unwrap();
+            let n = buf.remaining().min(reply.len());
+            buf.put_slice(&reply[..n]);
+            reply.drain(..n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_without_guard_delay_gets_garbage() {
+        let mut device = SlowDevice::new(Duration::from_millis(30));
+        let result = send_request(&mut device, "ping", json!({}), Duration::ZERO).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn guard_delay_lets_slow_device_respond() {
+        let mut device = SlowDevice::new(Duration::from_millis(30));
+        let resp = send_request(&mut device, "ping", json!({}), Duration::from_millis(40))
+            .await
+            .unwrap();
+        assert_eq!(resp["ok"], true);
+        assert_eq!(resp["result"], "done");
+    }
+
+    /// Firmware that only understands `supported`; anything else gets
+    /// `ok:false`.
+    struct CommandDevice {
+        supported: &'static str,
+        request: Vec<u8>,
+        reply: Option<Vec<u8>>,
+    }
+
+    impl CommandDevice {
+        fn new(supported: &'static str) -> Self {
+            Self {
+                supported,
+                request: Vec::new(),
+                reply: None,
+            }
+        }
+    }
+
+    impl AsyncWrite for CommandDevice {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.request.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncRead for CommandDevice {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if self.reply.is_none() {
+                let req: Value = serde_json::from_slice(self.request.trim_ascii()).unwrap();
+                let resp = if req["cmd"] == self.supported {
+                    json!({"id": req["id"], "ok": true, "result": "alive"})
+                } else {
+                    json!({"id": req["id"], "ok": false, "error": "unknown command"})
+                };
+                self.reply = Some(format!("{resp}\n").into_bytes());
+            }
+            let reply = self.reply.as_mut().unwrap();
+            let n = buf.remaining().min(reply.len());
+            buf.put_slice(&reply[..n]);
+            reply.drain(..n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_health_command_reports_healthy_where_ping_fails() {
+        let mut device = CommandDevice::new("status");
+        assert!(!probe_health(&mut device, "ping", Duration::ZERO).await);
+
+        let mut device = CommandDevice::new("status");
+        assert!(probe_health(&mut device, "status", Duration::ZERO).await);
+    }
+
+    #[test]
+    fn health_command_defaults_to_ping() {
+        let config: PeripheralBoardConfig = toml::from_str(r#"board = "esp32""#).unwrap();
+        assert_eq!(config.health_command, "ping");
+        assert_eq!(PeripheralBoardConfig::default().health_command, "ping");
+    }
+
+    #[tokio::test]
+    async fn connect_rejects_zero_baud_before_opening_port() {
+        let config = PeripheralBoardConfig {
+            board: "nucleo-f401re".into(),
+            path: Some("/dev/ttyACM0".into()),
+            baud: 0,
+            ..PeripheralBoardConfig::default()
+        };
+        let err = SerialPeripheral::connect(&config).await.err().unwrap();
+        let msg = err.to_string();
+        assert!(msg.contains("baud must be between 1"), "{msg}");
+        assert!(msg.contains("nucleo-f401re"), "{msg}");
+    }
+}
@@ -95,6 +95,11 @@ impl Tool for UnoQGpioWriteTool {
         "gpio_write"
     }
 
+    /// Calls drive shared device state; run them in order.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     fn description(&self) -> &str {
         "Set GPIO pin high (1) or low (0) on Arduino Uno Q. Requires zeroclaw-uno-q-bridge app running."
     }
@@ -117,6 +122,9 @@ impl Tool for UnoQGpioWriteTool {
     }
 
     async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(refused) = crate::hardware::HardwareEstop::global().refusal() {
+            return Ok(refused);
+        }
         let pin = args
             .get("pin")
             .and_then(|v| v.as_u64())
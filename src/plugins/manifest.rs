@@ -5,8 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path};
 
 use super::traits::PluginCapability;
 
@@ -23,6 +24,30 @@ pub struct PluginToolManifest {
     pub description: String,
     #[serde(default = "default_plugin_tool_parameters")]
     pub parameters: Value,
+    /// How the tool's output is read back (`[tools.exec]`).
+    #[serde(default)]
+    pub exec: PluginToolExecManifest,
+}
+
+/// Output protocol of a plugin tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginToolExecManifest {
+    /// Output is JSON lines: `{"progress": "..."}` lines are logged and the
+    /// first `ToolResult` line is the result. Off: the whole output is one
+    /// `ToolResult` (or plain text).
+    #[serde(default)]
+    pub streaming: bool,
+    /// Extra environment passed to the tool with each call.
+    /// `ZEROCLAW_WORKSPACE` is always set to the workspace directory.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory for the tool, relative to the plugin's directory.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Time limit for one call, in seconds (capped at 300). Unset or `0`
+    /// uses the runtime's invoke timeout.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 fn default_plugin_tool_parameters() -> Value {
@@ -159,6 +184,14 @@ pub fn validate_manifest(manifest: &PluginManifest) -> anyhow::Result<()> {
         if tool.description.trim().is_empty() {
             anyhow::bail!("plugin tool description cannot be empty");
         }
+        if let Some(cwd) = &tool.exec.cwd {
+            if !stays_inside_plugin_root(cwd) {
+                anyhow::bail!(
+                    "plugin tool '{}' cwd '{cwd}' escapes the plugin directory",
+                    tool.name
+                );
+            }
+        }
     }
     for provider in &manifest.providers {
         if provider.trim().is_empty() {
@@ -168,12 +201,75 @@ pub fn validate_manifest(manifest: &PluginManifest) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// A relative path without `..`, so it resolves inside the plugin directory.
+fn stays_inside_plugin_root(cwd: &str) -> bool {
+    Path::new(cwd)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
 impl PluginManifest {
     pub fn is_valid(&self) -> bool {
         validate_manifest(self).is_ok()
     }
 }
 
+/// Restrict tool-call `args` to the keys declared in a tool's `parameters`.
+///
+/// Undeclared keys are dropped. Every key listed in `required` must be present,
+/// and declared arguments must match their `type` when one is given.
+pub fn filter_declared_tool_args(parameters: &Value, args: &Value) -> anyhow::Result<Value> {
+    let Some(args) = args.as_object() else {
+        anyhow::bail!("tool arguments must be a JSON object");
+    };
+    let properties = parameters
+        .get("properties")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut filtered = serde_json::Map::new();
+    for (key, value) in args {
+        let Some(schema) = properties.get(key) else {
+            tracing::debug!("dropping undeclared plugin tool argument `{key}`");
+            continue;
+        };
+        if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+            if !json_type_matches(expected, value) {
+                anyhow::bail!("argument `{key}` must be of type {expected}");
+            }
+        }
+        filtered.insert(key.clone(), value.clone());
+    }
+
+    let required = parameters
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for key in required {
+        if !filtered.contains_key(key) {
+            anyhow::bail!("missing required argument `{key}`");
+        }
+    }
+
+    Ok(Value::Object(filtered))
+}
+
+fn json_type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,4 +385,34 @@ id = "  "
         };
         assert!(validate_manifest(&manifest).is_err());
     }
+
+    fn greet_parameters() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "times": { "type": "integer" }
+            },
+            "required": ["name"]
+        })
+    }
+
+    #[test]
+    fn strict_args_drop_undeclared_keys() {
+        let args = serde_json::json!({ "name": "ada", "times": 2, "sudo": true });
+        let filtered = filter_declared_tool_args(&greet_parameters(), &args).unwrap();
+        assert_eq!(filtered, serde_json::json!({ "name": "ada", "times": 2 }));
+    }
+
+    #[test]
+    fn strict_args_reject_missing_or_mistyped_required() {
+        let err =
+            filter_declared_tool_args(&greet_parameters(), &serde_json::json!({ "times": 1 }))
+                .unwrap_err();
+        assert!(err.to_string().contains("missing required argument `name`"));
+
+        let err = filter_declared_tool_args(&greet_parameters(), &serde_json::json!({ "name": 7 }))
+            .unwrap_err();
+        assert!(err.to_string().contains("`name` must be of type string"));
+    }
 }
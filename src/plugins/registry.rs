@@ -7,7 +7,7 @@ use std::collections::{HashMap, HashSet};
 use crate::hooks::HookHandler;
 use crate::tools::traits::Tool;
 
-use super::manifest::{PluginManifest, PluginToolManifest};
+use super::manifest::{PluginManifest, PluginToolExecManifest, PluginToolManifest};
 
 /// Status of a loaded plugin.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -141,6 +141,19 @@ impl PluginRegistry {
         self.manifest_providers.contains(name)
     }
 
+    /// The `exec` table declared for `tool`.
+    pub fn tool_exec(&self, tool: &str) -> Option<&PluginToolExecManifest> {
+        self.manifest_tools
+            .iter()
+            .find(|t| t.name == tool)
+            .map(|t| &t.exec)
+    }
+
+    /// Whether `tool` declares `exec.streaming` output.
+    pub fn tool_is_streaming(&self, tool: &str) -> bool {
+        self.tool_exec(tool).is_some_and(|exec| exec.streaming)
+    }
+
     pub fn tool_module_path(&self, tool: &str) -> Option<&str> {
         self.tool_modules.get(tool).map(String::as_str)
     }
@@ -219,6 +232,7 @@ mod tests {
                     "type": "object",
                     "properties": {}
                 }),
+                exec: Default::default(),
             }],
             providers: vec![provider.to_string()],
         }
@@ -9,7 +9,7 @@ use tokio::sync::Semaphore;
 use tokio::time::{timeout, Duration};
 use wasmtime::{Engine, Extern, Instance, Memory, Module, Store, TypedFunc};
 
-use super::manifest::PluginManifest;
+use super::manifest::{PluginManifest, PluginToolExecManifest};
 use super::registry::PluginRegistry;
 use crate::config::PluginsConfig;
 use crate::tools::ToolResult;
@@ -19,6 +19,9 @@ const ABI_PROVIDER_CHAT_FN: &str = "zeroclaw_provider_chat";
 const ABI_ALLOC_FN: &str = "alloc";
 const ABI_DEALLOC_FN: &str = "dealloc";
 const MAX_WASM_PAYLOAD_BYTES_FALLBACK: usize = 4 * 1024 * 1024;
+/// Upper bound on a tool's `exec.timeout_secs`, so a hung plugin can't
+/// block the agent indefinitely.
+const MAX_TOOL_TIMEOUT_SECS: u64 = 300;
 type WasmAbiModule = (
     Store<()>,
     Instance,
@@ -43,10 +46,28 @@ impl PluginRuntime {
     }
 
     pub fn load_registry_from_config(&self, config: &PluginsConfig) -> Result<PluginRegistry> {
+        self.load_registry_with_reader(config, read_manifest_file)
+    }
+
+    /// Scan `config.load_paths`, reading each manifest with `read_manifest`.
+    ///
+    /// A manifest that fails to read, parse or validate within
+    /// `plugins.load_timeout_ms` is skipped with a warning, and scanning stops
+    /// once `plugins.max_plugins` manifests are registered.
+    fn load_registry_with_reader<F>(
+        &self,
+        config: &PluginsConfig,
+        read_manifest: F,
+    ) -> Result<PluginRegistry>
+    where
+        F: Fn(&Path) -> Result<PluginManifest> + Send + Sync + 'static,
+    {
         let mut registry = PluginRegistry::default();
         if !config.enabled {
             return Ok(registry);
         }
+        let read_manifest = Arc::new(read_manifest);
+        let load_timeout = Duration::from_millis(config.load_timeout_ms);
         for dir in &config.load_paths {
             let path = Path::new(dir);
             if !path.exists() {
@@ -66,26 +87,68 @@ impl PluginRuntime {
                 if !(file_name.ends_with(".plugin.toml") || file_name.ends_with(".plugin.json")) {
                     continue;
                 }
-                let raw = std::fs::read_to_string(&path).with_context(|| {
-                    format!("failed to read plugin manifest {}", path.display())
-                })?;
-                let manifest: PluginManifest = if file_name.ends_with(".plugin.toml") {
-                    toml::from_str(&raw).with_context(|| {
-                        format!("failed to parse plugin TOML manifest {}", path.display())
-                    })?
-                } else {
-                    serde_json::from_str(&raw).with_context(|| {
-                        format!("failed to parse plugin JSON manifest {}", path.display())
-                    })?
-                };
-                let manifest = self.load_manifest(manifest)?;
-                registry.register(manifest);
+                if config.max_plugins > 0 && registry.len() >= config.max_plugins {
+                    tracing::warn!(
+                        "plugin limit reached ({}); skipping remaining plugin manifests",
+                        config.max_plugins
+                    );
+                    return Ok(registry);
+                }
+                let manifest =
+                    read_manifest_with_timeout(Arc::clone(&read_manifest), &path, load_timeout)
+                        .and_then(|manifest| self.load_manifest(manifest));
+                match manifest {
+                    Ok(manifest) => registry.register(manifest),
+                    Err(error) => {
+                        tracing::warn!("skipping plugin manifest {}: {error:#}", path.display());
+                    }
+                }
             }
         }
         Ok(registry)
     }
 }
 
+/// Read and parse a `*.plugin.toml` / `*.plugin.json` manifest.
+fn read_manifest_file(path: &Path) -> Result<PluginManifest> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read plugin manifest {}", path.display()))?;
+    if path.to_string_lossy().ends_with(".plugin.toml") {
+        toml::from_str(&raw)
+            .with_context(|| format!("failed to parse plugin TOML manifest {}", path.display()))
+    } else {
+        serde_json::from_str(&raw)
+            .with_context(|| format!("failed to parse plugin JSON manifest {}", path.display()))
+    }
+}
+
+/// Run `read_manifest` on its own thread and give up after `load_timeout`.
+/// A read that hangs is left to finish in the background; its result is
+/// discarded.
+fn read_manifest_with_timeout<F>(
+    read_manifest: Arc<F>,
+    path: &Path,
+    load_timeout: Duration,
+) -> Result<PluginManifest>
+where
+    F: Fn(&Path) -> Result<PluginManifest> + Send + Sync + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    let owned_path = path.to_path_buf();
+    std::thread::Builder::new()
+        .name("plugin-manifest-load".into())
+        .spawn(move || {
+            let _ = tx.send(read_manifest(&owned_path));
+        })
+        .context("failed to spawn plugin manifest reader")?;
+    rx.recv_timeout(load_timeout).unwrap_or_else(|_| {
+        Err(anyhow::anyhow!(
+            "timed out after {}ms loading plugin manifest",
+            load_timeout.as_millis()
+        ))
+    })
+}
+
 #[derive(Debug, Serialize)]
 struct ProviderPluginRequest<'a> {
     provider: &'a str,
@@ -211,10 +274,19 @@ fn current_limits() -> PluginExecutionLimits {
     guard.limits
 }
 
+/// Invoke timeout for one call to `tool`: its `exec.timeout_secs`, clamped
+/// to [`MAX_TOOL_TIMEOUT_SECS`], or `default_ms` when unset or `0`.
+fn tool_invoke_timeout_ms(exec: Option<&PluginToolExecManifest>, default_ms: u64) -> u64 {
+    exec.and_then(|exec| exec.timeout_secs)
+        .filter(|&secs| secs > 0)
+        .map_or(default_ms, |secs| secs.min(MAX_TOOL_TIMEOUT_SECS) * 1_000)
+}
+
 async fn call_wasm_json_limited(
     module_path: String,
     fn_name: &'static str,
     payload: String,
+    timeout_ms: Option<u64>,
 ) -> Result<String> {
     let limits = current_limits();
     let semaphore = semaphore_cell()
@@ -227,7 +299,8 @@ async fn call_wasm_json_limited(
         anyhow::bail!("plugin payload exceeds configured memory limit");
     }
 
-    run_blocking_with_timeout(semaphore, limits.invoke_timeout_ms, move || {
+    let timeout_ms = timeout_ms.unwrap_or(limits.invoke_timeout_ms);
+    run_blocking_with_timeout(semaphore, timeout_ms, move || {
         call_wasm_json(&module_path, fn_name, &payload)
     })
     .await
@@ -258,17 +331,31 @@ where
     }
 }
 
-pub async fn execute_plugin_tool(tool_name: &str, args: &Value) -> Result<ToolResult> {
+pub async fn execute_plugin_tool(
+    tool_name: &str,
+    args: &Value,
+    workspace_dir: &Path,
+) -> Result<ToolResult> {
     let registry = current_registry();
     let module_path = registry
         .tool_module_path(tool_name)
         .ok_or_else(|| anyhow::anyhow!("plugin tool '{tool_name}' not found in registry"))?
         .to_string();
-    let payload = serde_json::json!({
-        "tool": tool_name,
-        "args": args,
-    });
-    let output = call_wasm_json_limited(module_path, ABI_TOOL_EXEC_FN, payload.to_string()).await?;
+    let payload = tool_exec_payload(&registry, tool_name, args, workspace_dir);
+    let timeout_ms = tool_invoke_timeout_ms(
+        registry.tool_exec(tool_name),
+        current_limits().invoke_timeout_ms,
+    );
+    let output = call_wasm_json_limited(
+        module_path,
+        ABI_TOOL_EXEC_FN,
+        payload.to_string(),
+        Some(timeout_ms),
+    )
+    .await?;
+    if registry.tool_is_streaming(tool_name) {
+        return parse_streaming_tool_output(tool_name, &output);
+    }
     if let Ok(parsed) = serde_json::from_str::<ToolResult>(&output) {
         return Ok(parsed);
     }
@@ -279,6 +366,50 @@ pub async fn execute_plugin_tool(tool_name: &str, args: &Value) -> Result<ToolRe
     })
 }
 
+/// JSON handed to `zeroclaw_tool_execute`: the call plus the tool's
+/// `exec.env` (with `ZEROCLAW_WORKSPACE`) and `exec.cwd`.
+fn tool_exec_payload(
+    registry: &PluginRegistry,
+    tool_name: &str,
+    args: &Value,
+    workspace_dir: &Path,
+) -> Value {
+    let exec = registry.tool_exec(tool_name);
+    let mut env = exec.map(|exec| exec.env.clone()).unwrap_or_default();
+    env.insert(
+        "ZEROCLAW_WORKSPACE".into(),
+        workspace_dir.to_string_lossy().into_owned(),
+    );
+    serde_json::json!({
+        "tool": tool_name,
+        "args": args,
+        "env": env,
+        "cwd": exec.and_then(|exec| exec.cwd.as_deref()),
+    })
+}
+
+/// Read `exec.streaming` output: `{"progress": "..."}` lines are logged and
+/// the first `ToolResult` line ends the read. The invoke timeout still covers
+/// the whole call, since a WASM export returns its output at once.
+fn parse_streaming_tool_output(tool_name: &str, output: &str) -> Result<ToolResult> {
+    for line in output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let value: Value = serde_json::from_str(line).with_context(|| {
+            format!("plugin tool '{tool_name}' emitted a non-JSON streaming line")
+        })?;
+        if let Some(progress) = value.get("progress").and_then(Value::as_str) {
+            tracing::info!(tool = tool_name, "plugin tool progress: {progress}");
+            continue;
+        }
+        return serde_json::from_value(value)
+            .with_context(|| format!("plugin tool '{tool_name}' emitted an invalid result line"));
+    }
+    anyhow::bail!("plugin tool '{tool_name}' ended without a result line")
+}
+
 pub async fn execute_plugin_provider_chat(
     provider_name: &str,
     system_prompt: Option<&str>,
@@ -302,6 +433,7 @@ pub async fn execute_plugin_provider_chat(
         module_path,
         ABI_PROVIDER_CHAT_FN,
         serde_json::to_string(&request)?,
+        None,
     )
     .await?;
     if let Ok(parsed) = serde_json::from_str::<ProviderPluginResponse>(&output) {
@@ -518,6 +650,185 @@ description = "{tool} description"
         assert!(reg.provider_module_path("demo-provider").is_some());
     }
 
+    #[test]
+    fn exec_payload_carries_env_cwd_and_workspace() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("envy.plugin.toml"),
+            r#"
+id = "envy"
+version = "1.0.0"
+module_path = "plugins/envy.wasm"
+wit_packages = ["zeroclaw:tools@1.0.0"]
+
+[[tools]]
+name = "envy_tool"
+description = "Reads its environment"
+
+[tools.exec]
+cwd = "data"
+env = { API_REGION = "eu" }
+"#,
+        )
+        .expect("write manifest");
+        let cfg = PluginsConfig {
+            enabled: true,
+            load_paths: vec![dir.path().to_string_lossy().to_string()],
+            ..PluginsConfig::default()
+        };
+        let reg = PluginRuntime::new()
+            .load_registry_from_config(&cfg)
+            .expect("load registry");
+
+        let payload = tool_exec_payload(
+            &reg,
+            "envy_tool",
+            &serde_json::json!({"q": 1}),
+            Path::new("/work/space"),
+        );
+        assert_eq!(payload["args"]["q"], 1);
+        assert_eq!(payload["cwd"], "data");
+        assert_eq!(payload["env"]["API_REGION"], "eu");
+        assert_eq!(payload["env"]["ZEROCLAW_WORKSPACE"], "/work/space");
+    }
+
+    #[test]
+    fn manifest_with_escaping_cwd_is_rejected() {
+        let dir = TempDir::new().expect("temp dir");
+        for (id, cwd) in [("up", "../outside"), ("abs", "/etc")] {
+            std::fs::write(
+                dir.path().join(format!("{id}.plugin.toml")),
+                format!(
+                    r#"
+id = "{id}"
+version = "1.0.0"
+module_path = "plugins/{id}.wasm"
+
+[[tools]]
+name = "{id}_tool"
+description = "Escapes"
+
+[tools.exec]
+cwd = "{cwd}"
+"#
+                ),
+            )
+            .expect("write manifest");
+        }
+        let cfg = PluginsConfig {
+            enabled: true,
+            load_paths: vec![dir.path().to_string_lossy().to_string()],
+            ..PluginsConfig::default()
+        };
+        let reg = PluginRuntime::new()
+            .load_registry_from_config(&cfg)
+            .expect("load registry");
+        assert_eq!(reg.len(), 0);
+    }
+
+    #[test]
+    fn loading_stops_at_max_plugins() {
+        let dir = TempDir::new().expect("temp dir");
+        for i in 0..5 {
+            write_manifest(
+                dir.path(),
+                &format!("p{i}"),
+                &format!("provider-{i}"),
+                &format!("tool_{i}"),
+            );
+        }
+        let cfg = PluginsConfig {
+            enabled: true,
+            load_paths: vec![dir.path().to_string_lossy().to_string()],
+            max_plugins: 3,
+            ..PluginsConfig::default()
+        };
+        let reg = PluginRuntime::new()
+            .load_registry_from_config(&cfg)
+            .expect("load registry");
+        assert_eq!(reg.len(), 3);
+    }
+
+    #[test]
+    fn slow_and_invalid_manifests_are_skipped_within_timeout() {
+        let dir = TempDir::new().expect("temp dir");
+        write_manifest(dir.path(), "good", "good-provider", "good_tool");
+        write_manifest(dir.path(), "slow", "slow-provider", "slow_tool");
+        std::fs::write(dir.path().join("broken.plugin.toml"), "id = [").expect("write");
+        let cfg = PluginsConfig {
+            enabled: true,
+            load_paths: vec![dir.path().to_string_lossy().to_string()],
+            load_timeout_ms: 50,
+            ..PluginsConfig::default()
+        };
+
+        let started = std::time::Instant::now();
+        let reg = PluginRuntime::new()
+            .load_registry_with_reader(&cfg, |path: &Path| {
+                if path.ends_with("slow.plugin.toml") {
+                    std::thread::sleep(std::time::Duration::from_secs(2));
+                }
+                read_manifest_file(path)
+            })
+            .expect("load registry");
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(reg.len(), 1);
+        assert!(reg.has_provider("good-provider"));
+    }
+
+    #[test]
+    fn streaming_output_skips_progress_and_stops_at_result_line() {
+        let output = concat!(
+            "{\"progress\":\"indexing\"}\n",
+            "\n",
+            "{\"progress\":\"50%\"}\n",
+            "{\"success\":true,\"output\":\"done\",\"error\":null}\n",
+            "{\"progress\":\"ignored\"}\n",
+        );
+        let result = parse_streaming_tool_output("demo_tool", output).expect("result line");
+        assert!(result.success);
+        assert_eq!(result.output, "done");
+
+        let err =
+            parse_streaming_tool_output("demo_tool", "{\"progress\":\"working\"}\n").unwrap_err();
+        assert!(err.to_string().contains("without a result line"));
+    }
+
+    #[test]
+    fn streaming_flag_is_read_from_tool_exec_table() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("stream.plugin.toml"),
+            r#"
+id = "stream"
+version = "1.0.0"
+module_path = "plugins/stream.wasm"
+wit_packages = ["zeroclaw:tools@1.0.0"]
+
+[[tools]]
+name = "stream_tool"
+description = "Streams progress"
+
+[tools.exec]
+streaming = true
+"#,
+        )
+        .expect("write manifest");
+        write_manifest(dir.path(), "plain", "plain-provider", "plain_tool");
+
+        let cfg = PluginsConfig {
+            enabled: true,
+            load_paths: vec![dir.path().to_string_lossy().to_string()],
+            ..PluginsConfig::default()
+        };
+        let reg = PluginRuntime::new()
+            .load_registry_from_config(&cfg)
+            .expect("load registry");
+        assert!(reg.tool_is_streaming("stream_tool"));
+        assert!(!reg.tool_is_streaming("plain_tool"));
+    }
+
     #[test]
     fn unpack_ptr_len_roundtrip() {
         let ptr: u32 = 0x1234_5678;
@@ -565,6 +876,47 @@ description = "{tool} description"
         assert!(!reg_b.has_provider("reload-provider-a-for-runtime-test"));
     }
 
+    #[tokio::test]
+    async fn tool_timeout_secs_cuts_off_a_slow_call() {
+        let exec: PluginToolExecManifest =
+            toml::from_str("timeout_secs = 1").expect("parse exec table");
+        let timeout_ms = tool_invoke_timeout_ms(Some(&exec), 2_000);
+        assert_eq!(timeout_ms, 1_000);
+
+        let started = std::time::Instant::now();
+        let result = run_blocking_with_timeout(
+            Arc::new(Semaphore::new(1)),
+            timeout_ms,
+            || -> anyhow::Result<()> {
+                std::thread::sleep(std::time::Duration::from_secs(5));
+                Ok(())
+            },
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    fn tool_timeout_falls_back_to_default_and_is_clamped() {
+        assert_eq!(tool_invoke_timeout_ms(None, 2_000), 2_000);
+        let exec = PluginToolExecManifest::default();
+        assert_eq!(tool_invoke_timeout_ms(Some(&exec), 2_000), 2_000);
+        let exec = PluginToolExecManifest {
+            timeout_secs: Some(0),
+            ..PluginToolExecManifest::default()
+        };
+        assert_eq!(tool_invoke_timeout_ms(Some(&exec), 2_000), 2_000);
+        let exec = PluginToolExecManifest {
+            timeout_secs: Some(86_400),
+            ..PluginToolExecManifest::default()
+        };
+        assert_eq!(
+            tool_invoke_timeout_ms(Some(&exec), 2_000),
+            MAX_TOOL_TIMEOUT_SECS * 1_000
+        );
+    }
+
     #[tokio::test]
     async fn timeout_path_releases_semaphore_permit() {
         let semaphore = Arc::new(Semaphore::new(1));
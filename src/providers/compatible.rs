@@ -263,31 +263,10 @@ impl OpenAiCompatibleProvider {
     /// and prepend to the first `user` message. Drop all system messages.
     /// Used for providers (e.g. MiniMax) that reject `role: system`.
     fn flatten_system_messages(messages: &[ChatMessage]) -> Vec<ChatMessage> {
-        let system_content: String = messages
-            .iter()
-            .filter(|m| m.role == "system")
-            .map(|m| m.content.as_str())
-            .collect::<Vec<_>>()
-            .join("\n\n");
-
-        if system_content.is_empty() {
-            return messages.to_vec();
-        }
-
-        let mut result: Vec<ChatMessage> = messages
-            .iter()
-            .filter(|m| m.role != "system")
-            .cloned()
-            .collect();
-
-        if let Some(first_user) = result.iter_mut().find(|m| m.role == "user") {
-            first_user.content = format!("{system_content}\n\n{}", first_user.content);
-        } else {
-            // No user message found: insert a synthetic user message with system content
-            result.insert(0, ChatMessage::user(&system_content));
-        }
-
-        result
+        crate::providers::traits::place_system_prompt(
+            messages,
+            crate::config::SystemPromptPlacement::FirstUserMessage,
+        )
     }
 
     fn http_client(&self) -> Client {
@@ -1887,6 +1866,14 @@ impl Provider for OpenAiCompatibleProvider {
         }
     }
 
+    fn system_prompt_placement(&self) -> crate::config::SystemPromptPlacement {
+        if self.merge_system_into_user {
+            crate::config::SystemPromptPlacement::FirstUserMessage
+        } else {
+            crate::config::SystemPromptPlacement::SystemMessage
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -44,6 +44,7 @@ pub use traits::{
 };
 
 use crate::auth::AuthService;
+use crate::config::SystemPromptPlacement;
 use crate::plugins;
 use compatible::{AuthStyle, CompatibleApiMode, OpenAiCompatibleProvider};
 use reliable::ReliableProvider;
@@ -744,6 +745,7 @@ pub struct ProviderRuntimeOptions {
     pub custom_provider_api_mode: Option<CompatibleApiMode>,
     pub max_tokens_override: Option<u32>,
     pub model_support_vision: Option<bool>,
+    pub system_prompt_placement: Option<SystemPromptPlacement>,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -759,6 +761,7 @@ impl Default for ProviderRuntimeOptions {
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            system_prompt_placement: None,
         }
     }
 }
@@ -1622,7 +1625,8 @@ pub fn create_resilient_provider_with_options(
     )
     .with_api_keys(reliability.api_keys.clone())
     .with_model_fallbacks(reliability.model_fallbacks.clone())
-    .with_vision_override(options.model_support_vision);
+    .with_vision_override(options.model_support_vision)
+    .with_system_prompt_placement_override(options.system_prompt_placement);
 
     Ok(Box::new(reliable))
 }
@@ -1791,7 +1795,8 @@ pub fn create_routed_provider_with_options(
                 default_model.to_string()
             },
         )
-        .with_vision_override(options.model_support_vision),
+        .with_vision_override(options.model_support_vision)
+        .with_system_prompt_placement_override(options.system_prompt_placement),
     ))
 }
 
@@ -1593,6 +1593,7 @@ data: [DONE]
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            system_prompt_placement: None,
         };
         let provider =
             OpenAiCodexProvider::new(&options, None).expect("provider should initialize");
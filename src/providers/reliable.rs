@@ -2,6 +2,7 @@ use super::traits::{
     ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
 };
 use super::Provider;
+use crate::config::SystemPromptPlacement;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::{HashMap, HashSet};
@@ -185,6 +186,71 @@ fn parse_retry_after_ms(err: &anyhow::Error) -> Option<u64> {
     None
 }
 
+/// Whether `err` is a provider outage worth retrying against another
+/// provider: a network failure or timeout, a 5xx, or a retryable 429.
+///
+/// Client errors, context-window overflow and errors that did not come from
+/// a provider call (turn budget, tools, config) are not; neither is a
+/// [`ProvidersExhausted`] with any non-retryable attempt.
+pub fn is_provider_outage(err: &anyhow::Error) -> bool {
+    if let Some(exhausted) = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ProvidersExhausted>())
+    {
+        return exhausted.is_transient();
+    }
+    if is_context_window_exceeded(err) || is_non_retryable_rate_limit(err) {
+        return false;
+    }
+    if let Some(reqwest_err) = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+    {
+        return match reqwest_err.status() {
+            Some(status) => status.is_server_error() || status.as_u16() == 429,
+            None => reqwest_err.is_timeout() || reqwest_err.is_connect(),
+        };
+    }
+    // Providers report HTTP failures as `"<provider> API error (<status>): …"`.
+    err.chain().any(|cause| {
+        let msg = cause.to_string();
+        msg.split_once("API error (").is_some_and(|(_, rest)| {
+            rest.get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .is_some_and(|code| code >= 500 || code == 429)
+        })
+    })
+}
+
+/// Returned by [`ReliableProvider`] when every provider/model attempt failed.
+#[derive(Debug)]
+pub struct ProvidersExhausted {
+    failures: Vec<String>,
+}
+
+impl ProvidersExhausted {
+    /// Whether every attempt failed for a reason retries can fix.
+    pub fn is_transient(&self) -> bool {
+        !self.failures.is_empty()
+            && self
+                .failures
+                .iter()
+                .all(|failure| !failure.contains("non_retryable;"))
+    }
+}
+
+impl std::fmt::Display for ProvidersExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "All providers/models failed. Attempts:\n{}",
+            self.failures.join("\n")
+        )
+    }
+}
+
+impl std::error::Error for ProvidersExhausted {}
+
 fn failure_reason(rate_limited: bool, non_retryable: bool) -> &'static str {
     if rate_limited && non_retryable {
         "rate_limited_non_retryable"
@@ -242,6 +308,8 @@ pub struct ReliableProvider {
     provider_model_fallbacks: HashMap<String, Vec<String>>,
     /// Vision support override from config (`None` = defer to provider).
     vision_override: Option<bool>,
+    /// System-prompt placement override from config (`None` = defer to provider).
+    system_prompt_override: Option<SystemPromptPlacement>,
 }
 
 impl ReliableProvider {
@@ -259,6 +327,7 @@ impl ReliableProvider {
             model_fallbacks: HashMap::new(),
             provider_model_fallbacks: HashMap::new(),
             vision_override: None,
+            system_prompt_override: None,
         }
     }
 
@@ -295,6 +364,15 @@ impl ReliableProvider {
         self
     }
 
+    /// Set system-prompt placement override from runtime config.
+    pub fn with_system_prompt_placement_override(
+        mut self,
+        placement: Option<SystemPromptPlacement>,
+    ) -> Self {
+        self.system_prompt_override = placement;
+        self
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -492,10 +570,7 @@ impl Provider for ReliableProvider {
             }
         }
 
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+        Err(ProvidersExhausted { failures }.into())
     }
 
     async fn chat_with_history(
@@ -607,10 +682,7 @@ impl Provider for ReliableProvider {
             }
         }
 
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+        Err(ProvidersExhausted { failures }.into())
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -628,6 +700,15 @@ impl Provider for ReliableProvider {
         })
     }
 
+    fn system_prompt_placement(&self) -> SystemPromptPlacement {
+        self.system_prompt_override.unwrap_or_else(|| {
+            self.providers
+                .first()
+                .map(|(_, p)| p.system_prompt_placement())
+                .unwrap_or_default()
+        })
+    }
+
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -738,10 +819,7 @@ impl Provider for ReliableProvider {
             }
         }
 
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+        Err(ProvidersExhausted { failures }.into())
     }
 
     async fn chat(
@@ -862,10 +940,7 @@ impl Provider for ReliableProvider {
             }
         }
 
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+        Err(ProvidersExhausted { failures }.into())
     }
 
     fn supports_streaming(&self) -> bool {
@@ -1176,6 +1251,45 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn provider_outage_covers_only_server_and_rate_limit_failures() {
+        assert!(is_provider_outage(&anyhow::anyhow!(
+            "OpenAI API error (503 Service Unavailable): overloaded"
+        )));
+        assert!(is_provider_outage(&anyhow::anyhow!(
+            "OpenAI API error (429 Too Many Requests): slow down"
+        )));
+        assert!(!is_provider_outage(&anyhow::anyhow!(
+            "OpenAI API error (400 Bad Request): invalid tools"
+        )));
+        assert!(!is_provider_outage(&anyhow::anyhow!(
+            "OpenAI API error (429 Too Many Requests): insufficient quota"
+        )));
+        assert!(!is_provider_outage(&anyhow::anyhow!(
+            "prompt is too long: 210000 tokens"
+        )));
+        assert!(!is_provider_outage(&anyhow::anyhow!(
+            "tool gpio_write failed on port 4031"
+        )));
+
+        let exhausted = |reason: &str| -> anyhow::Error {
+            ProvidersExhausted {
+                failures: vec![format!(
+                    "provider=p1 model=m attempt 1/1: {reason}; error=boom"
+                )],
+            }
+            .into()
+        };
+        assert!(is_provider_outage(&exhausted("retryable")));
+        assert!(is_provider_outage(
+            &exhausted("rate_limited").context("turn failed")
+        ));
+        assert!(!is_provider_outage(&exhausted("non_retryable")));
+        assert!(!is_provider_outage(&exhausted(
+            "rate_limited_non_retryable"
+        )));
+    }
+
     #[tokio::test]
     async fn context_window_error_aborts_retries_and_model_fallbacks() {
         let calls = Arc::new(AtomicUsize::new(0));
@@ -1,5 +1,6 @@
 use super::traits::{ChatMessage, ChatRequest, ChatResponse};
 use super::Provider;
+use crate::config::SystemPromptPlacement;
 use async_trait::async_trait;
 use std::collections::HashMap;
 
@@ -25,6 +26,8 @@ pub struct RouterProvider {
     default_model: String,
     /// Vision support override from config (`None` = defer to providers).
     vision_override: Option<bool>,
+    /// System-prompt placement override from config (`None` = defer to provider).
+    system_prompt_override: Option<SystemPromptPlacement>,
 }
 
 impl RouterProvider {
@@ -81,6 +84,7 @@ impl RouterProvider {
             default_index,
             default_model,
             vision_override: None,
+            system_prompt_override: None,
         }
     }
 
@@ -90,6 +94,15 @@ impl RouterProvider {
         self
     }
 
+    /// Set system-prompt placement override from runtime config.
+    pub fn with_system_prompt_placement_override(
+        mut self,
+        placement: Option<SystemPromptPlacement>,
+    ) -> Self {
+        self.system_prompt_override = placement;
+        self
+    }
+
     /// Resolve a model parameter to a (provider, actual_model) pair.
     ///
     /// If the model starts with "hint:", look up the hint in the route table.
@@ -188,6 +201,15 @@ impl Provider for RouterProvider {
         })
     }
 
+    fn system_prompt_placement(&self) -> SystemPromptPlacement {
+        self.system_prompt_override.unwrap_or_else(|| {
+            self.providers
+                .get(self.default_index)
+                .map(|(_, p)| p.system_prompt_placement())
+                .unwrap_or_default()
+        })
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
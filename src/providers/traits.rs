@@ -1,8 +1,10 @@
+use crate::config::SystemPromptPlacement;
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
+use std::time::Duration;
 
 /// A single message in a conversation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,6 +221,63 @@ pub enum StreamError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Stream timed out: {0}")]
+    Timeout(String),
+}
+
+/// Apply a first-token and a total deadline to a provider stream.
+///
+/// If no non-empty delta arrives within `first_token`, or the stream is
+/// still running after `total`, a single [`StreamError::Timeout`] is yielded
+/// and the stream ends. `None` disables the corresponding limit.
+pub fn with_stream_timeouts(
+    inner: stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    first_token: Option<Duration>,
+    total: Option<Duration>,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+    if first_token.is_none() && total.is_none() {
+        return inner;
+    }
+    let started = tokio::time::Instant::now();
+    let total = total.map(|limit| {
+        (
+            started + limit,
+            format!("response not complete within {limit:?}"),
+        )
+    });
+    let first_token = first_token.map(|limit| {
+        (
+            started + limit,
+            format!("no content received within {limit:?}"),
+        )
+    });
+
+    stream::unfold(Some((inner, first_token)), move |state| {
+        let total = total.clone();
+        async move {
+            let (mut inner, first_token) = state?;
+            let deadline = match (&first_token, &total) {
+                (Some(first), Some(total)) => Some(if first.0 <= total.0 { first } else { total }),
+                (first, total) => first.as_ref().or(total.as_ref()),
+            };
+            let next = match deadline {
+                Some((at, reason)) => {
+                    let Ok(next) = tokio::time::timeout_at(*at, inner.next()).await else {
+                        return Some((Err(StreamError::Timeout(reason.clone())), None));
+                    };
+                    next
+                }
+                None => inner.next().await,
+            }?;
+            let first_token = match &next {
+                Ok(chunk) if !chunk.delta.is_empty() => None,
+                _ => first_token,
+            };
+            Some((next, Some((inner, first_token))))
+        }
+    })
+    .boxed()
 }
 
 /// Structured error returned when a requested capability is not supported.
@@ -402,6 +461,12 @@ pub trait Provider: Send + Sync {
         self.capabilities().vision
     }
 
+    /// Where this provider expects the system prompt.
+    /// Default is a leading `system` message.
+    fn system_prompt_placement(&self) -> SystemPromptPlacement {
+        SystemPromptPlacement::SystemMessage
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -472,6 +537,43 @@ pub trait Provider: Send + Sync {
     }
 }
 
+/// Arrange `messages` for a provider that wants the system prompt at
+/// `placement`.
+///
+/// For [`SystemPromptPlacement::FirstUserMessage`] all `system` messages are
+/// joined and prepended to the first user message (or sent as a user message
+/// when there is none); otherwise `messages` is returned unchanged.
+pub fn place_system_prompt(
+    messages: &[ChatMessage],
+    placement: SystemPromptPlacement,
+) -> Vec<ChatMessage> {
+    if placement == SystemPromptPlacement::SystemMessage {
+        return messages.to_vec();
+    }
+
+    let system_content: String = messages
+        .iter()
+        .filter(|m| m.role == ROLE_SYSTEM)
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if system_content.is_empty() {
+        return messages.to_vec();
+    }
+
+    let mut result: Vec<ChatMessage> = messages
+        .iter()
+        .filter(|m| m.role != ROLE_SYSTEM)
+        .cloned()
+        .collect();
+    if let Some(first_user) = result.iter_mut().find(|m| m.role == ROLE_USER) {
+        first_user.content = format!("{system_content}\n\n{}", first_user.content);
+    } else {
+        result.insert(0, ChatMessage::user(system_content));
+    }
+    result
+}
+
 /// Build tool instructions text for prompt-guided tool calling.
 ///
 /// Generates a formatted text block describing available tools and how to
@@ -970,4 +1072,51 @@ mod tests {
 
         assert!(message.contains("non-prompt-guided"));
     }
+
+    fn delayed_stream(
+        first_delay: Duration,
+        deltas: &[&'static str],
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let chunks: Vec<_> = deltas
+            .iter()
+            .map(|delta| StreamChunk::delta(*delta))
+            .chain(std::iter::once(StreamChunk::final_chunk()))
+            .collect();
+        stream::once(tokio::time::sleep(first_delay))
+            .flat_map(move |()| stream::iter(chunks.clone().into_iter().map(Ok)))
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn first_token_timeout_fails_without_partial_content() {
+        let slow = delayed_stream(Duration::from_millis(200), &["late", " reply"]);
+        let items: Vec<_> = with_stream_timeouts(
+            slow,
+            Some(Duration::from_millis(30)),
+            Some(Duration::from_secs(10)),
+        )
+        .collect()
+        .await;
+
+        assert_eq!(items.len(), 1);
+        let Err(StreamError::Timeout(reason)) = &items[0] else {
+            panic!("expected timeout, got {:?}", items[0]);
+        };
+        assert!(reason.contains("no content received"));
+    }
+
+    #[tokio::test]
+    async fn stream_within_first_token_window_passes_through() {
+        let fast = delayed_stream(Duration::from_millis(5), &["hello", " world"]);
+        let items: Vec<_> = with_stream_timeouts(fast, Some(Duration::from_millis(500)), None)
+            .collect()
+            .await;
+
+        let text: String = items
+            .iter()
+            .map(|item| item.as_ref().unwrap().delta.as_str())
+            .collect();
+        assert_eq!(text, "hello world");
+        assert!(items.last().unwrap().as_ref().unwrap().is_final);
+    }
 }
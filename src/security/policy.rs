@@ -100,6 +100,11 @@ impl ActionTracker {
         actions.retain(|t| *t > cutoff);
         actions.len()
     }
+
+    /// Forget all recorded actions.
+    pub fn reset(&self) {
+        self.actions.lock().clear();
+    }
 }
 
 impl Clone for ActionTracker {
@@ -1427,7 +1432,9 @@ impl SecurityPolicy {
                 }
 
                 if !self.record_action() {
-                    return Err("Rate limit exceeded: action budget exhausted".to_string());
+                    return Err(
+                        self.rate_limit_error("Rate limit exceeded: action budget exhausted")
+                    );
                 }
 
                 Ok(())
@@ -1447,6 +1454,33 @@ impl SecurityPolicy {
         self.tracker.count() >= self.max_actions_per_hour as usize
     }
 
+    /// Actions recorded in the current one-hour window.
+    pub fn actions_used(&self) -> u32 {
+        u32::try_from(self.tracker.count()).unwrap_or(u32::MAX)
+    }
+
+    /// Actions left in the current window before requests are rate-limited.
+    pub fn actions_remaining(&self) -> u32 {
+        self.max_actions_per_hour
+            .saturating_sub(self.actions_used())
+    }
+
+    /// Clear the action window, restoring the full hourly budget.
+    pub fn reset_action_window(&self) {
+        self.tracker.reset();
+    }
+
+    /// `reason` followed by the current budget usage, for rate-limit errors.
+    pub fn rate_limit_error(&self, reason: &str) -> String {
+        let used = self.actions_used();
+        format!(
+            "{reason} ({}/{} actions used in the last hour, {} remaining)",
+            used.min(self.max_actions_per_hour),
+            self.max_actions_per_hour,
+            self.max_actions_per_hour.saturating_sub(used)
+        )
+    }
+
     /// Build from config sections
     /// Produce a concise security-constraint summary suitable for periodic
     /// re-injection into the conversation (safety heartbeat).
@@ -2143,6 +2177,44 @@ mod tests {
         assert!(p.is_rate_limited());
     }
 
+    #[test]
+    fn recording_actions_decrements_remaining_and_reset_restores_it() {
+        let p = SecurityPolicy {
+            max_actions_per_hour: 3,
+            ..SecurityPolicy::default()
+        };
+        assert_eq!(p.actions_remaining(), 3);
+        p.record_action();
+        p.record_action();
+        assert_eq!(p.actions_used(), 2);
+        assert_eq!(p.actions_remaining(), 1);
+        p.record_action();
+        p.record_action();
+        assert_eq!(p.actions_remaining(), 0);
+        assert!(p.is_rate_limited());
+
+        p.reset_action_window();
+        assert_eq!(p.actions_used(), 0);
+        assert_eq!(p.actions_remaining(), 3);
+        assert!(!p.is_rate_limited());
+    }
+
+    #[test]
+    fn rate_limit_error_reports_budget() {
+        let p = SecurityPolicy {
+            max_actions_per_hour: 2,
+            ..SecurityPolicy::default()
+        };
+        for _ in 0..3 {
+            p.record_action();
+        }
+        assert_eq!(
+            p.rate_limit_error("Rate limit exceeded: action budget exhausted"),
+            "Rate limit exceeded: action budget exhausted \
+             (2/2 actions used in the last hour, 0 remaining)"
+        );
+    }
+
     #[test]
     fn action_tracker_clone_is_independent() {
         let tracker = ActionTracker::new();
@@ -6,7 +6,9 @@
 //! Computer-use (OS-level) actions are supported via an optional sidecar endpoint.
 
 use super::traits::{Tool, ToolResult};
-use super::url_validation::{validate_url as validate_network_url, DomainPolicy, UrlSchemePolicy};
+use super::url_validation::{
+    validate_url as validate_network_url, AllowlistMatcher, DomainPolicy, UrlSchemePolicy,
+};
 use crate::config::UrlAccessConfig;
 use crate::security::SecurityPolicy;
 use anyhow::Context;
@@ -64,7 +66,7 @@ impl Default for ComputerUseConfig {
 /// Browser automation tool using pluggable backends.
 pub struct BrowserTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
+    allowed_domains: AllowlistMatcher,
     url_access: UrlAccessConfig,
     session_name: Option<String>,
     backend: String,
@@ -278,7 +280,7 @@ impl BrowserTool {
     ) -> Self {
         Self {
             security,
-            allowed_domains: normalize_domains(allowed_domains),
+            allowed_domains: AllowlistMatcher::new(normalize_domains(allowed_domains)),
             url_access,
             session_name,
             backend,
@@ -522,12 +524,13 @@ impl BrowserTool {
             url,
             &DomainPolicy {
                 allowed_domains: &self.allowed_domains,
-                blocked_domains: &[],
+                blocked_domains: &AllowlistMatcher::default(),
                 allowed_field_name: "browser.allowed_domains",
                 blocked_field_name: None,
                 empty_allowed_message: "Browser tool enabled but no allowed_domains configured. Add [browser].allowed_domains in config.toml",
                 scheme_policy: UrlSchemePolicy::HttpsOnly,
                 ipv6_error_context: "browser",
+                allow_ipv6_literals: false,
                 url_access: Some(&self.url_access),
             },
         )?;
@@ -982,7 +985,7 @@ impl BrowserTool {
             "action": action,
             "params": params,
             "policy": {
-                "allowed_domains": self.allowed_domains,
+                "allowed_domains": self.allowed_domains.patterns(),
                 "window_allowlist": self.computer_use.window_allowlist,
                 "max_coordinate_x": self.computer_use.max_coordinate_x,
                 "max_coordinate_y": self.computer_use.max_coordinate_y,
@@ -1255,7 +1258,10 @@ impl Tool for BrowserTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -1,6 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use super::url_validation::{
-    normalize_allowed_domains, validate_url, DomainPolicy, UrlSchemePolicy,
+    normalize_allowed_domains, validate_url, AllowlistMatcher, DomainPolicy, UrlSchemePolicy,
 };
 use crate::config::UrlAccessConfig;
 use crate::security::SecurityPolicy;
@@ -55,7 +55,7 @@ impl BrowserChoice {
 /// Open approved HTTPS URLs in the configured browser (no scraping, no DOM automation).
 pub struct BrowserOpenTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
+    allowed_domains: AllowlistMatcher,
     url_access: UrlAccessConfig,
     browser: BrowserChoice,
 }
@@ -69,7 +69,7 @@ impl BrowserOpenTool {
     ) -> Self {
         Self {
             security,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
+            allowed_domains: AllowlistMatcher::new(normalize_allowed_domains(allowed_domains)),
             url_access,
             browser,
         }
@@ -80,12 +80,13 @@ impl BrowserOpenTool {
             raw_url,
             &DomainPolicy {
                 allowed_domains: &self.allowed_domains,
-                blocked_domains: &[],
+                blocked_domains: &AllowlistMatcher::default(),
                 allowed_field_name: "browser.allowed_domains",
                 blocked_field_name: None,
                 empty_allowed_message: "Browser tool is enabled but no allowed_domains are configured. Add [browser].allowed_domains in config.toml",
                 scheme_policy: UrlSchemePolicy::HttpsOnly,
                 ipv6_error_context: "browser_open",
+                allow_ipv6_literals: false,
                 url_access: Some(&self.url_access),
             },
         )
@@ -133,7 +134,10 @@ impl Tool for BrowserOpenTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -87,7 +87,10 @@ impl ChannelAckConfigTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -166,7 +166,10 @@ impl Tool for ContentSearchTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -202,7 +205,10 @@ impl Tool for ContentSearchTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
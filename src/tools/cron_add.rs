@@ -33,7 +33,10 @@ impl CronAddTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".to_string()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -41,7 +44,10 @@ impl CronAddTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".to_string()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
@@ -31,7 +31,10 @@ impl CronRemoveTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".to_string()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -39,7 +42,10 @@ impl CronRemoveTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".to_string()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
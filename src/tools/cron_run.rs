@@ -79,7 +79,10 @@ impl Tool for CronRunTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -111,7 +114,10 @@ impl Tool for CronRunTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
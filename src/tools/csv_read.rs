@@ -0,0 +1,501 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+/// Maximum CSV file size (50 MB).
+const MAX_CSV_BYTES: u64 = 50 * 1024 * 1024;
+/// Default number of data rows rendered for the LLM.
+const DEFAULT_MAX_ROWS: usize = 100;
+/// Hard ceiling on rendered rows regardless of what the caller requests.
+const MAX_ROWS_LIMIT: usize = 10_000;
+/// Hard ceiling on returned characters.
+const MAX_OUTPUT_CHARS: usize = 200_000;
+
+/// Read a CSV file in the workspace and render it as compact text.
+pub struct CsvReadTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl CsvReadTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+/// Parse RFC 4180 CSV into records.
+///
+/// Fields may be quoted with `"`, with `""` as an escaped quote; quoted
+/// fields may span lines. Records end at `\n` or `\r\n`. A stray quote,
+/// an unterminated quoted field, or a record whose field count differs from
+/// the first record is an error naming the line it starts on.
+fn parse_csv(text: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1usize;
+    let mut record_line = 1usize;
+    // Whether the current field started with a quote and is still open.
+    let mut in_quotes = false;
+    // Whether the current field was quoted (and has been closed).
+    let mut quoted = false;
+
+    let mut finish_record = |record: &mut Vec<String>, record_line: usize| -> anyhow::Result<()> {
+        let record = std::mem::take(record);
+        // A blank line is not a record.
+        if record.len() == 1 && record[0].is_empty() {
+            return Ok(());
+        }
+        if let Some(first) = records.first() {
+            if record.len() != first.len() {
+                anyhow::bail!(
+                    "line {record_line}: record has {} fields, expected {}",
+                    record.len(),
+                    first.len()
+                );
+            }
+        }
+        records.push(record);
+        Ok(())
+    };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push('\n');
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => anyhow::bail!("line {line}: unexpected quote in field"),
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+                finish_record(&mut record, record_line)?;
+                line += 1;
+                record_line = line;
+            }
+            _ if quoted => {
+                anyhow::bail!("line {line}: unexpected character after closing quote")
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        anyhow::bail!("line {record_line}: unterminated quoted field");
+    }
+    if quoted || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        finish_record(&mut record, record_line)?;
+    }
+    Ok(records)
+}
+
+/// Keep a cell on one line and free of the column separator.
+fn render_cell(cell: &str) -> String {
+    cell.replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+        .replace('\t', " ")
+}
+
+/// Render `records` (the first being the header) as a summary line followed
+/// by tab-separated rows, showing at most `max_rows` data rows.
+fn render_table(records: &[Vec<String>], max_rows: usize) -> String {
+    let Some((header, rows)) = records.split_first() else {
+        return "CSV contains no rows".into();
+    };
+    let mut out = format!("Rows: {}, columns: {}\n", rows.len(), header.len());
+    for row in std::iter::once(header).chain(rows.iter().take(max_rows)) {
+        let cells: Vec<String> = row.iter().map(|cell| render_cell(cell)).collect();
+        out.push_str(&cells.join("\t"));
+        out.push('\n');
+    }
+    if rows.len() > max_rows {
+        let _ = writeln!(out, "... [showing {max_rows} of {} rows]", rows.len());
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+fn parse_max_rows(args: &serde_json::Value) -> anyhow::Result<usize> {
+    let Some(value) = args.get("max_rows") else {
+        return Ok(DEFAULT_MAX_ROWS);
+    };
+
+    let serde_json::Value::Number(number) = value else {
+        anyhow::bail!("Invalid 'max_rows': expected a positive integer");
+    };
+    let Some(raw) = number.as_u64() else {
+        anyhow::bail!("Invalid 'max_rows': expected a positive integer");
+    };
+    if raw == 0 {
+        anyhow::bail!("Invalid 'max_rows': must be >= 1");
+    }
+
+    Ok(usize::try_from(raw)
+        .unwrap_or(MAX_ROWS_LIMIT)
+        .min(MAX_ROWS_LIMIT))
+}
+
+#[async_trait]
+impl Tool for CsvReadTool {
+    fn name(&self) -> &str {
+        "csv_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a CSV file in the workspace. Returns the row and column counts, \
+         the header, and up to max_rows data rows as tab-separated values."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the CSV file. Relative paths resolve from workspace."
+                },
+                "max_rows": {
+                    "type": "integer",
+                    "description": "Maximum data rows to return after the header (default: 100, max: 10000)",
+                    "minimum": 1,
+                    "maximum": 10_000
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+
+        let max_rows = match parse_max_rows(&args) {
+            Ok(value) => value,
+            Err(err) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(err.to_string()),
+                })
+            }
+        };
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
+            });
+        }
+
+        if !self.security.is_path_allowed(path) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Path not allowed by security policy: {path}")),
+            });
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
+            });
+        }
+
+        let full_path = self.security.workspace_dir.join(path);
+
+        let resolved_path = match tokio::fs::canonicalize(&full_path).await {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to resolve file path: {e}")),
+                });
+            }
+        };
+
+        if !self.security.is_resolved_path_allowed(&resolved_path) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    self.security
+                        .resolved_path_violation_message(&resolved_path),
+                ),
+            });
+        }
+
+        tracing::debug!("Reading CSV: {}", resolved_path.display());
+
+        match tokio::fs::metadata(&resolved_path).await {
+            Ok(meta) => {
+                if meta.len() > MAX_CSV_BYTES {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "CSV too large: {} bytes (limit: {MAX_CSV_BYTES} bytes)",
+                            meta.len()
+                        )),
+                    });
+                }
+            }
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to read file metadata: {e}")),
+                });
+            }
+        }
+
+        let bytes = match tokio::fs::read(&resolved_path).await {
+            Ok(b) => b,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to read CSV file: {e}")),
+                });
+            }
+        };
+
+        let records = match tokio::task::spawn_blocking(move || {
+            let text =
+                String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("file is not valid UTF-8"))?;
+            parse_csv(&text)
+        })
+        .await
+        {
+            Ok(Ok(records)) => records,
+            Ok(Err(e)) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Malformed CSV: {e}")),
+                });
+            }
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("CSV parsing task panicked: {e}")),
+                });
+            }
+        };
+
+        let text = render_table(&records, max_rows);
+        let output = if text.chars().count() > MAX_OUTPUT_CHARS {
+            let mut truncated: String = text.chars().take(MAX_OUTPUT_CHARS).collect();
+            let _ = write!(truncated, "\n\n... [truncated at {MAX_OUTPUT_CHARS} chars]");
+            truncated
+        } else {
+            text
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use tempfile::TempDir;
+
+    fn test_security(workspace: std::path::PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn test_security_with_limit(
+        workspace: std::path::PathBuf,
+        max_actions: u32,
+    ) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            max_actions_per_hour: max_actions,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    #[test]
+    fn schema_has_path_required() {
+        let tool = CsvReadTool::new(test_security(std::env::temp_dir()));
+        assert_eq!(tool.name(), "csv_read");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["path"].is_object());
+        assert!(schema["properties"]["max_rows"].is_object());
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&json!("path")));
+    }
+
+    #[test]
+    fn quoted_fields_keep_commas_quotes_and_newlines() {
+        let records =
+            parse_csv("name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\r\nLee,\"\"\n")
+                .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["name".to_string(), "note".to_string()],
+                vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+                vec!["Lee".to_string(), String::new()],
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_csv_is_an_error_naming_the_line() {
+        let err = parse_csv("a,b\n1,\"open\n2,3\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unterminated quoted field");
+        let err = parse_csv("a,b\n1,2,3\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: record has 3 fields, expected 2");
+        let err = parse_csv("a,b\n1,x\"y\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unexpected quote in field");
+    }
+
+    #[tokio::test]
+    async fn renders_counts_header_and_limited_rows() {
+        let tmp = TempDir::new().unwrap();
+        tokio::fs::write(
+            tmp.path().join("data.csv"),
+            "city,note\nParis,\"two\nlines\"\nOslo,cold\nRome,warm\n",
+        )
+        .await
+        .unwrap();
+
+        let tool = CsvReadTool::new(test_security(tmp.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "data.csv", "max_rows": 2}))
+            .await
+            .unwrap();
+        assert!(result.success, "error: {:?}", result.error);
+        assert_eq!(
+            result.output,
+            "Rows: 3, columns: 2\n\
+             city\tnote\n\
+             Paris\ttwo\\nlines\n\
+             Oslo\tcold\n\
+             ... [showing 2 of 3 rows]"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_file_returns_tool_error() {
+        let tmp = TempDir::new().unwrap();
+        tokio::fs::write(tmp.path().join("bad.csv"), "a,b\n\"unterminated\n")
+            .await
+            .unwrap();
+
+        let tool = CsvReadTool::new(test_security(tmp.path().to_path_buf()));
+        let result = tool.execute(json!({"path": "bad.csv"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap_or("")
+            .contains("Malformed CSV"));
+    }
+
+    #[tokio::test]
+    async fn path_traversal_and_rate_limit_are_enforced() {
+        let tmp = TempDir::new().unwrap();
+        let tool = CsvReadTool::new(test_security(tmp.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "../../../etc/passwd"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap_or("")
+            .contains("not allowed"));
+
+        let limited = CsvReadTool::new(test_security_with_limit(tmp.path().to_path_buf(), 0));
+        let result = limited.execute(json!({"path": "any.csv"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.as_deref().unwrap_or("").contains("Rate limit"));
+    }
+
+    #[tokio::test]
+    async fn invalid_max_rows_returns_tool_error() {
+        let tool = CsvReadTool::new(test_security(std::env::temp_dir()));
+        let result = tool
+            .execute(json!({"path": "data.csv", "max_rows": 0}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.as_deref().unwrap_or("").contains("max_rows"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn symlink_escape_is_blocked() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new().unwrap();
+        let workspace = root.path().join("workspace");
+        let outside = root.path().join("outside");
+        tokio::fs::create_dir_all(&workspace).await.unwrap();
+        tokio::fs::create_dir_all(&outside).await.unwrap();
+        tokio::fs::write(outside.join("secret.csv"), "a,b\n1,2\n")
+            .await
+            .unwrap();
+        symlink(outside.join("secret.csv"), workspace.join("link.csv")).unwrap();
+
+        let tool = CsvReadTool::new(test_security(workspace));
+        let result = tool.execute(json!({"path": "link.csv"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap_or("")
+            .contains("escapes workspace"));
+    }
+}
@@ -753,6 +753,10 @@ impl Tool for ToolArcRef {
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         self.inner.execute(args).await
     }
+
+    fn parallel_safe(&self) -> bool {
+        self.inner.parallel_safe()
+    }
 }
 
 struct NoopObserver;
@@ -1,8 +1,12 @@
+use super::extraction_limit::{
+    extraction_semaphore, run_limited_extraction, DEFAULT_EXTRACTION_PERMITS,
+};
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// Maximum DOCX file size (50 MB).
 const MAX_DOCX_BYTES: u64 = 50 * 1024 * 1024;
@@ -10,15 +14,28 @@ const MAX_DOCX_BYTES: u64 = 50 * 1024 * 1024;
 const DEFAULT_MAX_CHARS: usize = 50_000;
 /// Hard ceiling regardless of what the caller requests.
 const MAX_OUTPUT_CHARS: usize = 200_000;
+/// Upper bound for the uncompressed `word/document.xml`.
+const MAX_DOCUMENT_XML_BYTES: u64 = 16 * 1024 * 1024;
 
 /// Extract plain text from a DOCX file in the workspace.
 pub struct DocxReadTool {
     security: Arc<SecurityPolicy>,
+    extraction_permits: Arc<Semaphore>,
 }
 
 impl DocxReadTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            extraction_permits: extraction_semaphore(DEFAULT_EXTRACTION_PERMITS),
+        }
+    }
+
+    /// Share an extraction semaphore with the other document tools.
+    #[must_use]
+    pub fn with_extraction_permits(mut self, permits: Arc<Semaphore>) -> Self {
+        self.extraction_permits = permits;
+        self
     }
 }
 
@@ -27,6 +44,13 @@ impl DocxReadTool {
 /// DOCX is a ZIP archive containing `word/document.xml`.
 /// Text lives inside `<w:t>` elements; paragraphs are delimited by `<w:p>`.
 fn extract_docx_text(bytes: &[u8]) -> anyhow::Result<String> {
+    extract_docx_text_with_limits(bytes, MAX_DOCUMENT_XML_BYTES)
+}
+
+fn extract_docx_text_with_limits(
+    bytes: &[u8],
+    max_document_xml_bytes: u64,
+) -> anyhow::Result<String> {
     use quick_xml::events::Event;
     use quick_xml::Reader;
     use std::io::Read;
@@ -34,11 +58,24 @@ fn extract_docx_text(bytes: &[u8]) -> anyhow::Result<String> {
     let cursor = std::io::Cursor::new(bytes);
     let mut archive = zip::ZipArchive::new(cursor)?;
 
-    let mut xml_content = String::new();
-    archive
+    let document = archive
         .by_name("word/document.xml")
-        .map_err(|e| anyhow::anyhow!("Not a valid DOCX (missing word/document.xml): {e}"))?
+        .map_err(|e| anyhow::anyhow!("Not a valid DOCX (missing word/document.xml): {e}"))?;
+    if document.size() > max_document_xml_bytes {
+        anyhow::bail!(
+            "Document XML payload too large: {} bytes (limit: {} bytes)",
+            document.size(),
+            max_document_xml_bytes
+        );
+    }
+    // The declared size can lie; never read more than the limit.
+    let mut xml_content = String::new();
+    document
+        .take(max_document_xml_bytes + 1)
         .read_to_string(&mut xml_content)?;
+    if xml_content.len() as u64 > max_document_xml_bytes {
+        anyhow::bail!("Document XML payload too large: more than {max_document_xml_bytes} bytes");
+    }
 
     let mut reader = Reader::from_str(&xml_content);
     let mut text = String::new();
@@ -123,7 +160,10 @@ impl Tool for DocxReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -139,7 +179,10 @@ impl Tool for DocxReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
@@ -202,7 +245,11 @@ impl Tool for DocxReadTool {
             }
         };
 
-        let text = match tokio::task::spawn_blocking(move || extract_docx_text(&bytes)).await {
+        let text = match run_limited_extraction(&self.extraction_permits, move || {
+            extract_docx_text(&bytes)
+        })
+        .await
+        {
             Ok(Ok(t)) => t,
             Ok(Err(e)) => {
                 return Ok(ToolResult {
@@ -470,6 +517,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn document_xml_limit_is_enforced() {
+        let bytes = minimal_docx_bytes("Alpha");
+        let error = extract_docx_text_with_limits(&bytes, 64).unwrap_err();
+        assert!(error.to_string().contains("Document XML payload too large"));
+        assert!(extract_docx_text_with_limits(&bytes, 4096)
+            .unwrap()
+            .contains("Alpha"));
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn symlink_escape_is_blocked() {
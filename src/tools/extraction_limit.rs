@@ -0,0 +1,104 @@
+//! Bounded concurrency for blocking document extraction.
+//!
+//! DOCX/PPTX parsing runs on `spawn_blocking`. Reading several large documents
+//! in one turn would otherwise occupy that many blocking threads at once,
+//! which saturates the pool on small devices. The document tools share one
+//! [`Semaphore`] and hold a permit for as long as the blocking task runs.
+
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Default number of document extractions allowed to run at once.
+pub const DEFAULT_EXTRACTION_PERMITS: usize = 2;
+
+/// Create an extraction semaphore; `0` falls back to the default.
+pub fn extraction_semaphore(permits: usize) -> Arc<Semaphore> {
+    let permits = if permits == 0 {
+        DEFAULT_EXTRACTION_PERMITS
+    } else {
+        permits
+    };
+    Arc::new(Semaphore::new(permits))
+}
+
+/// Run `extract` on the blocking pool once a permit is available.
+///
+/// The permit moves into the blocking closure, so it is released when the
+/// extraction finishes — on success, error, or panic — even if the caller's
+/// future was dropped in the meantime.
+pub async fn run_limited_extraction<T, F>(
+    permits: &Arc<Semaphore>,
+    extract: F,
+) -> Result<T, tokio::task::JoinError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    // The semaphore is never closed; if it were, run unbounded rather than fail.
+    let permit = permits.clone().acquire_owned().await.ok();
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        extract()
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn only_permitted_extractions_run_concurrently() {
+        let permits = extraction_semaphore(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let permits = permits.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    run_limited_extraction(&permits, move || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(50));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                    })
+                    .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(permits.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn permit_is_released_when_extraction_fails() {
+        let permits = extraction_semaphore(1);
+
+        let result: Result<anyhow::Result<String>, _> =
+            run_limited_extraction(&permits, || anyhow::bail!("corrupt archive")).await;
+        assert!(result.unwrap().is_err());
+        assert_eq!(permits.available_permits(), 1);
+
+        let panicked = run_limited_extraction(&permits, || -> u32 { panic!("parser bug") }).await;
+        assert!(panicked.is_err());
+        assert_eq!(permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn zero_permits_falls_back_to_default() {
+        assert_eq!(
+            extraction_semaphore(0).available_permits(),
+            DEFAULT_EXTRACTION_PERMITS
+        );
+    }
+}
@@ -543,7 +543,10 @@ impl Tool for GitOperationsTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -1,6 +1,7 @@
 use super::traits::{Tool, ToolResult};
 use super::url_validation::{
-    normalize_allowed_domains, validate_url, DomainPolicy, UrlSchemePolicy,
+    normalize_allowed_domains, validate_url_pinned, AllowlistMatcher, DomainPolicy, PinnedAddr,
+    UrlSchemePolicy,
 };
 use crate::config::{HttpRequestCredentialProfile, UrlAccessConfig};
 use crate::security::SecurityPolicy;
@@ -14,7 +15,7 @@ use std::time::Duration;
 /// Supports GET, POST, PUT, DELETE methods with configurable security.
 pub struct HttpRequestTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
+    allowed_domains: AllowlistMatcher,
     url_access: UrlAccessConfig,
     max_response_size: usize,
     timeout_secs: u64,
@@ -34,7 +35,7 @@ impl HttpRequestTool {
     ) -> Self {
         Self {
             security,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
+            allowed_domains: AllowlistMatcher::new(normalize_allowed_domains(allowed_domains)),
             url_access,
             max_response_size,
             timeout_secs,
@@ -46,20 +47,24 @@ impl HttpRequestTool {
         }
     }
 
-    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
-        validate_url(
+    /// Validated URL, plus the address to connect to when
+    /// `security.url_access.pin_resolved_ip` is set.
+    fn validate_url(&self, raw_url: &str) -> anyhow::Result<(String, Option<PinnedAddr>)> {
+        let (url, pinned) = validate_url_pinned(
             raw_url,
             &DomainPolicy {
                 allowed_domains: &self.allowed_domains,
-                blocked_domains: &[],
+                blocked_domains: &AllowlistMatcher::default(),
                 allowed_field_name: "http_request.allowed_domains",
                 blocked_field_name: None,
                 empty_allowed_message: "HTTP request tool is enabled but no allowed_domains are configured. Add [http_request].allowed_domains in config.toml",
                 scheme_policy: UrlSchemePolicy::HttpOrHttps,
                 ipv6_error_context: "http_request",
+                allow_ipv6_literals: false,
                 url_access: Some(&self.url_access),
             },
-        )
+        )?;
+        Ok((url, pinned.filter(|_| self.url_access.pin_resolved_ip)))
     }
 
     fn validate_method(&self, method: &str) -> anyhow::Result<reqwest::Method> {
@@ -198,6 +203,7 @@ impl HttpRequestTool {
     async fn execute_request(
         &self,
         url: &str,
+        pinned: Option<&PinnedAddr>,
         method: reqwest::Method,
         headers: Vec<(String, String)>,
         body: Option<&str>,
@@ -213,6 +219,12 @@ impl HttpRequestTool {
             .connect_timeout(Duration::from_secs(10))
             .redirect(reqwest::redirect::Policy::none())
             .user_agent(self.user_agent.as_str());
+        // Connect to the address the private-host check saw rather than
+        // resolving again, so a rebinding DNS record cannot swap it out.
+        let builder = match pinned {
+            Some(pinned) => builder.resolve(&pinned.host, pinned.addr),
+            None => builder,
+        };
         let builder = crate::config::apply_runtime_proxy_to_builder(builder, "tool.http_request");
         let client = builder.build()?;
 
@@ -320,11 +332,14 @@ impl Tool for HttpRequestTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
-        let url = match self.validate_url(url) {
+        let (url, pinned) = match self.validate_url(url) {
             Ok(v) => v,
             Err(e) => {
                 return Ok(ToolResult {
@@ -379,7 +394,7 @@ impl Tool for HttpRequestTool {
         }
 
         match self
-            .execute_request(&url, method, request_headers, body)
+            .execute_request(&url, pinned.as_ref(), method, request_headers, body)
             .await
         {
             Ok(response) => {
@@ -483,8 +498,9 @@ mod tests {
     #[test]
     fn validate_accepts_exact_domain() {
         let tool = test_tool(vec!["example.com"]);
-        let got = tool.validate_url("https://example.com/docs").unwrap();
+        let (got, pinned) = tool.validate_url("https://example.com/docs").unwrap();
         assert_eq!(got, "https://example.com/docs");
+        assert!(pinned.is_none());
     }
 
     #[test]
@@ -964,6 +980,93 @@ mod tests {
         assert_eq!(tool.name(), "http_request");
     }
 
+    #[tokio::test]
+    async fn pinned_request_connects_to_pinned_addr_without_resolving() {
+        use axum::{http::HeaderMap, routing::get, Router};
+
+        let app = Router::new().route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                headers
+                    .get("host")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test server");
+        let addr = listener.local_addr().expect("server local addr");
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve test app");
+        });
+
+        // `.invalid` never resolves, so reaching the server at all means the
+        // client used the pinned address.
+        let pinned = PinnedAddr {
+            host: "pinned.invalid".into(),
+            addr,
+        };
+        let tool = test_tool(vec!["pinned.invalid"]);
+        let response = tool
+            .execute_request(
+                &format!("http://pinned.invalid:{}/", addr.port()),
+                Some(&pinned),
+                reqwest::Method::GET,
+                vec![],
+                None,
+            )
+            .await
+            .expect("pinned request should reach the test server");
+        assert!(response.status().is_success());
+        assert_eq!(
+            response.text().await.unwrap(),
+            format!("pinned.invalid:{}", addr.port())
+        );
+
+        server.abort();
+        let _ = server.await;
+    }
+
+    #[test]
+    fn pin_is_only_returned_when_enabled() {
+        let security = Arc::new(SecurityPolicy::default());
+        let tool = |pin_resolved_ip| {
+            HttpRequestTool::new(
+                security.clone(),
+                vec!["*".into()],
+                UrlAccessConfig {
+                    pin_resolved_ip,
+                    allow_loopback: true,
+                    ..UrlAccessConfig::default()
+                },
+                1_000_000,
+                30,
+                "test".to_string(),
+                HashMap::new(),
+            )
+        };
+
+        let (_, pinned) = tool(true)
+            .validate_url("http://localhost:8080/status")
+            .unwrap();
+        let pinned = pinned.expect("resolved host should be pinned");
+        assert_eq!(pinned.host, "localhost");
+        assert!(pinned.addr.ip().is_loopback());
+        assert_eq!(pinned.addr.port(), 8080);
+        let (_, pinned) = tool(false)
+            .validate_url("http://localhost:8080/status")
+            .unwrap();
+        assert!(pinned.is_none());
+
+        // IP literals are checked without a lookup, so there is nothing to pin.
+        let (_, pinned) = tool(true).validate_url("https://8.8.8.8/").unwrap();
+        assert!(pinned.is_none());
+        let (_, pinned) = tool(false).validate_url("https://8.8.8.8/").unwrap();
+        assert!(pinned.is_none());
+    }
+
     // ── §1.4 DNS rebinding / SSRF defense-in-depth tests ─────
 
     #[test]
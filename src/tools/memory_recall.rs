@@ -8,11 +8,23 @@ use std::sync::Arc;
 /// Let the agent search its own memory
 pub struct MemoryRecallTool {
     memory: Arc<dyn Memory>,
+    max_limit: usize,
 }
 
 impl MemoryRecallTool {
     pub fn new(memory: Arc<dyn Memory>) -> Self {
-        Self { memory }
+        Self {
+            memory,
+            max_limit: crate::memory::DEFAULT_MAX_RECALL_LIMIT,
+        }
+    }
+
+    /// Clamp the requested `limit` to `max` (`memory.max_recall_limit`;
+    /// `0` = no cap).
+    #[must_use]
+    pub fn with_max_limit(mut self, max: usize) -> Self {
+        self.max_limit = max;
+        self
     }
 }
 
@@ -54,6 +66,7 @@ impl Tool for MemoryRecallTool {
             .get("limit")
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
+        let limit = crate::memory::clamp_recall_limit(limit, self.max_limit);
 
         match self.memory.recall(query, limit, None).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
@@ -149,6 +162,29 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_clamps_oversized_limit() {
+        let (_tmp, mem) = seeded_mem();
+        for i in 0..10 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("Rust fact {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let tool = MemoryRecallTool::new(mem).with_max_limit(2);
+        let result = tool
+            .execute(json!({"query": "Rust", "limit": 1000}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 2"), "{}", result.output);
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
@@ -31,9 +31,11 @@ pub mod cron_remove;
 pub mod cron_run;
 pub mod cron_runs;
 pub mod cron_update;
+pub mod csv_read;
 pub mod delegate;
 pub mod delegate_coordination_status;
 pub mod docx_read;
+pub mod extraction_limit;
 #[cfg(feature = "channel-lark")]
 pub mod feishu_doc;
 pub mod file_edit;
@@ -100,6 +102,7 @@ pub use cron_remove::CronRemoveTool;
 pub use cron_run::CronRunTool;
 pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
+pub use csv_read::CsvReadTool;
 pub use delegate::DelegateTool;
 pub use delegate_coordination_status::DelegateCoordinationStatusTool;
 pub use docx_read::DocxReadTool;
@@ -143,7 +146,7 @@ pub use subagent_spawn::SubAgentSpawnTool;
 pub use task_plan::TaskPlanTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{openai_tool_specs, ToolResult, ToolSpec};
 pub use wasm_module::WasmModuleTool;
 pub use web_access_config::WebAccessConfigTool;
 pub use web_fetch::WebFetchTool;
@@ -164,12 +167,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Clone)]
-struct ArcDelegatingTool {
+pub(crate) struct ArcDelegatingTool {
     inner: Arc<dyn Tool>,
 }
 
 impl ArcDelegatingTool {
-    fn boxed(inner: Arc<dyn Tool>) -> Box<dyn Tool> {
+    pub(crate) fn boxed(inner: Arc<dyn Tool>) -> Box<dyn Tool> {
         Box::new(Self { inner })
     }
 }
@@ -191,6 +194,10 @@ impl Tool for ArcDelegatingTool {
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         self.inner.execute(args).await
     }
+
+    fn parallel_safe(&self) -> bool {
+        self.inner.parallel_safe()
+    }
 }
 
 fn boxed_registry_from_arcs(tools: Vec<Arc<dyn Tool>>) -> Vec<Box<dyn Tool>> {
@@ -216,11 +223,24 @@ pub fn add_bg_tools(tools: Vec<Box<dyn Tool>>) -> (Vec<Box<dyn Tool>>, BgJobStor
 #[derive(Clone)]
 struct PluginManifestTool {
     spec: ToolSpec,
+    strict_args: bool,
+    workspace_dir: std::path::PathBuf,
 }
 
 impl PluginManifestTool {
-    fn new(spec: ToolSpec) -> Self {
-        Self { spec }
+    fn new(spec: ToolSpec, workspace_dir: std::path::PathBuf) -> Self {
+        Self {
+            spec,
+            strict_args: false,
+            workspace_dir,
+        }
+    }
+
+    /// Forward only manifest-declared argument keys to the plugin.
+    #[must_use]
+    fn with_strict_args(mut self, strict_args: bool) -> Self {
+        self.strict_args = strict_args;
+        self
     }
 }
 
@@ -239,7 +259,23 @@ impl Tool for PluginManifestTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        match plugins::runtime::execute_plugin_tool(&self.spec.name, &args).await {
+        let args = if self.strict_args {
+            match plugins::manifest::filter_declared_tool_args(&self.spec.parameters, &args) {
+                Ok(filtered) => filtered,
+                Err(error) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(error.to_string()),
+                    })
+                }
+            }
+        } else {
+            args
+        };
+        match plugins::runtime::execute_plugin_tool(&self.spec.name, &args, &self.workspace_dir)
+            .await
+        {
             Ok(result) => Ok(result),
             Err(error) => Ok(ToolResult {
                 success: false,
@@ -250,6 +286,61 @@ impl Tool for PluginManifestTool {
     }
 }
 
+/// Default priority for `agent.max_tools`; higher is kept first.
+fn default_tool_priority(name: &str) -> i32 {
+    match name {
+        "shell" | "file_read" | "file_write" | "file_edit" | "apply_patch" | "glob_search"
+        | "content_search" => 100,
+        "memory_store" | "memory_observe" | "memory_recall" | "memory_forget" => 90,
+        "web_search_tool" | "web_fetch" | "http_request" | "browser" | "browser_open" => 80,
+        "gpio_read" | "gpio_write" | "gpio_config" | "pwm_write" | "device_capabilities" => 60,
+        _ => 0,
+    }
+}
+
+/// Keep at most `agent.max_tools` tools, dropping the lowest-priority ones
+/// (ties keep registration order) and warning with the names dropped.
+pub fn limit_tools(tools: &mut Vec<Box<dyn Tool>>, agent: &crate::config::AgentConfig) {
+    if agent.max_tools == 0 || tools.len() <= agent.max_tools {
+        return;
+    }
+
+    let priority = |tool: &dyn Tool| {
+        agent
+            .tool_priorities
+            .get(tool.name())
+            .copied()
+            .unwrap_or_else(|| default_tool_priority(tool.name()))
+    };
+    let mut ranked: Vec<(usize, i32)> = tools
+        .iter()
+        .enumerate()
+        .map(|(idx, tool)| (idx, priority(tool.as_ref())))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let keep: std::collections::HashSet<usize> = ranked
+        .iter()
+        .take(agent.max_tools)
+        .map(|(idx, _)| *idx)
+        .collect();
+
+    let mut omitted = Vec::new();
+    let mut idx = 0;
+    tools.retain(|tool| {
+        let kept = keep.contains(&idx);
+        if !kept {
+            omitted.push(tool.name().to_string());
+        }
+        idx += 1;
+        kept
+    });
+    tracing::warn!(
+        max_tools = agent.max_tools,
+        "tool count exceeds agent.max_tools; omitted: {}",
+        omitted.join(", ")
+    );
+}
+
 /// Create the default tool registry
 pub fn default_tools(security: Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
     default_tools_with_runtime(security, Arc::new(NativeRuntime::new()))
@@ -354,7 +445,10 @@ pub fn all_tools_with_runtime(
         Arc::new(CronRunsTool::new(config.clone())),
         Arc::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Arc::new(MemoryObserveTool::new(memory.clone(), security.clone())),
-        Arc::new(MemoryRecallTool::new(memory.clone())),
+        Arc::new(
+            MemoryRecallTool::new(memory.clone())
+                .with_max_limit(root_config.memory.max_recall_limit),
+        ),
         Arc::new(MemoryForgetTool::new(memory, security.clone())),
         Arc::new(ScheduleTool::new(security.clone(), root_config.clone())),
         Arc::new(TaskPlanTool::new(security.clone())),
@@ -462,18 +556,30 @@ pub fn all_tools_with_runtime(
     }
 
     if web_fetch_config.enabled {
-        tool_arcs.push(Arc::new(WebFetchTool::new(
-            security.clone(),
-            web_fetch_config.provider.clone(),
-            web_fetch_config.api_key.clone(),
-            web_fetch_config.api_url.clone(),
-            web_fetch_config.allowed_domains.clone(),
-            web_fetch_config.blocked_domains.clone(),
-            root_config.security.url_access.clone(),
-            web_fetch_config.max_response_size,
-            web_fetch_config.timeout_secs,
-            web_fetch_config.user_agent.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            WebFetchTool::new(
+                security.clone(),
+                web_fetch_config.provider.clone(),
+                web_fetch_config.api_key.clone(),
+                web_fetch_config.api_url.clone(),
+                web_fetch_config.allowed_domains.clone(),
+                web_fetch_config.blocked_domains.clone(),
+                root_config.security.url_access.clone(),
+                web_fetch_config.max_response_size,
+                web_fetch_config.timeout_secs,
+                web_fetch_config.user_agent.clone(),
+            )
+            .with_min_content_chars(web_fetch_config.min_content_chars)
+            .with_json_pretty(web_fetch_config.json_pretty)
+            .with_html_fallback(web_fetch_config.html_fallback)
+            .with_explain_blocked(web_fetch_config.explain_blocked)
+            .with_api_max_redirects(web_fetch_config.api_max_redirects)
+            .with_content_hash(web_fetch_config.content_hash)
+            .with_cache(
+                web_fetch_config.cache_capacity,
+                web_fetch_config.cache_ttl_secs,
+            ),
+        ));
     }
 
     // Web search tool (enabled by default for GLM and other models)
@@ -508,15 +614,22 @@ pub fn all_tools_with_runtime(
     // PDF extraction (feature-gated at compile time via rag-pdf)
     tool_arcs.push(Arc::new(PdfReadTool::new(security.clone())));
 
-    // DOCX text extraction
-    tool_arcs.push(Arc::new(DocxReadTool::new(security.clone())));
-
-    // PPTX text extraction
-    tool_arcs.push(Arc::new(PptxReadTool::new(security.clone())));
+    // DOCX/PPTX text extraction share one bound on concurrent blocking parses
+    let extraction_permits =
+        extraction_limit::extraction_semaphore(root_config.agent.max_document_extractions);
+    tool_arcs.push(Arc::new(
+        DocxReadTool::new(security.clone()).with_extraction_permits(extraction_permits.clone()),
+    ));
+    tool_arcs.push(Arc::new(
+        PptxReadTool::new(security.clone()).with_extraction_permits(extraction_permits),
+    ));
 
     // XLSX text extraction
     tool_arcs.push(Arc::new(XlsxReadTool::new(security.clone())));
 
+    // CSV reading
+    tool_arcs.push(Arc::new(CsvReadTool::new(security.clone())));
+
     // Vision tools are always available
     tool_arcs.push(Arc::new(ScreenshotTool::new(security.clone())));
     tool_arcs.push(Arc::new(ImageInfoTool::new(security.clone())));
@@ -557,6 +670,7 @@ pub fn all_tools_with_runtime(
                 .map(|mode| mode.as_compatible_mode()),
             max_tokens_override: None,
             model_support_vision: root_config.model_support_vision,
+            system_prompt_placement: root_config.provider.system_prompt_placement,
         };
         let parent_tools = Arc::new(tool_arcs.clone());
         let mut delegate_tool = DelegateTool::new_with_options(
@@ -667,11 +781,17 @@ pub fn all_tools_with_runtime(
     if config.plugins.enabled {
         let registry = plugins::runtime::current_registry();
         for tool in registry.tools() {
-            tool_arcs.push(Arc::new(PluginManifestTool::new(ToolSpec {
-                name: tool.name.clone(),
-                description: tool.description.clone(),
-                parameters: tool.parameters.clone(),
-            })));
+            tool_arcs.push(Arc::new(
+                PluginManifestTool::new(
+                    ToolSpec {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        parameters: tool.parameters.clone(),
+                    },
+                    security.workspace_dir.clone(),
+                )
+                .with_strict_args(config.plugins.strict_tool_args),
+            ));
         }
     }
 
@@ -857,6 +977,7 @@ mod tests {
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"docx_read"));
         assert!(names.contains(&"pdf_read"));
+        assert!(names.contains(&"csv_read"));
     }
 
     #[test]
@@ -951,6 +1072,27 @@ mod tests {
         assert!(names.contains(&"content_search"));
     }
 
+    #[test]
+    fn openai_tool_specs_export_builtin_shell_tool() {
+        let security = Arc::new(SecurityPolicy::default());
+        let specs: Vec<ToolSpec> = default_tools(security).iter().map(|t| t.spec()).collect();
+        let exported = openai_tool_specs(&specs);
+
+        let shell = exported
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["function"]["name"] == "shell")
+            .expect("shell tool exported");
+        assert_eq!(shell["type"], "function");
+        assert!(!shell["function"]["description"]
+            .as_str()
+            .unwrap()
+            .is_empty());
+        assert_eq!(shell["function"]["parameters"]["type"], "object");
+        assert!(shell["function"]["parameters"]["properties"]["command"].is_object());
+    }
+
     #[test]
     fn default_tools_all_have_descriptions() {
         let security = Arc::new(SecurityPolicy::default());
@@ -1169,4 +1311,55 @@ mod tests {
         assert!(names.contains(&"delegate"));
         assert!(!names.contains(&"delegate_coordination_status"));
     }
+
+    struct NamedTool(&'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: String::new(),
+                error: None,
+            })
+        }
+    }
+
+    #[test]
+    fn limit_tools_keeps_highest_priority_tools() {
+        let mut tools: Vec<Box<dyn Tool>> =
+            ["pushover", "mcp_lookup", "file_read", "gpio_read", "shell"]
+                .into_iter()
+                .map(|name| Box::new(NamedTool(name)) as Box<dyn Tool>)
+                .collect();
+        let agent = crate::config::AgentConfig {
+            max_tools: 3,
+            tool_priorities: HashMap::from([("mcp_lookup".to_string(), 200)]),
+            ..crate::config::AgentConfig::default()
+        };
+
+        limit_tools(&mut tools, &agent);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["mcp_lookup", "file_read", "shell"]);
+    }
+
+    #[test]
+    fn limit_tools_is_a_no_op_without_cap() {
+        let mut tools: Vec<Box<dyn Tool>> =
+            vec![Box::new(NamedTool("a")), Box::new(NamedTool("b"))];
+        limit_tools(&mut tools, &crate::config::AgentConfig::default());
+        assert_eq!(tools.len(), 2);
+    }
 }
@@ -1,3 +1,6 @@
+use super::extraction_limit::{
+    extraction_semaphore, run_limited_extraction, DEFAULT_EXTRACTION_PERMITS,
+};
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -5,6 +8,7 @@ use serde_json::json;
 use std::collections::{HashMap, HashSet};
 use std::path::{Component, Path};
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// Maximum PPTX file size (50 MB).
 const MAX_PPTX_BYTES: u64 = 50 * 1024 * 1024;
@@ -18,11 +22,22 @@ const MAX_TOTAL_SLIDE_XML_BYTES: u64 = 16 * 1024 * 1024;
 /// Extract plain text from a PPTX file in the workspace.
 pub struct PptxReadTool {
     security: Arc<SecurityPolicy>,
+    extraction_permits: Arc<Semaphore>,
 }
 
 impl PptxReadTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            extraction_permits: extraction_semaphore(DEFAULT_EXTRACTION_PERMITS),
+        }
+    }
+
+    /// Share an extraction semaphore with the other document tools.
+    #[must_use]
+    pub fn with_extraction_permits(mut self, permits: Arc<Semaphore>) -> Self {
+        self.extraction_permits = permits;
+        self
     }
 }
 
@@ -31,17 +46,20 @@ impl PptxReadTool {
 /// PPTX is a ZIP archive containing `ppt/slides/slide*.xml`.
 /// Text lives inside `<a:t>` elements; paragraphs are delimited by `<a:p>`.
 fn extract_pptx_text(bytes: &[u8]) -> anyhow::Result<String> {
-    extract_pptx_text_with_limits(bytes, MAX_TOTAL_SLIDE_XML_BYTES)
+    Ok(extract_pptx_slides(bytes, false)?.concat())
+}
+
+/// Extract the text of each non-empty slide, in presentation order. With
+/// `include_notes`, each slide's speaker notes follow its text.
+fn extract_pptx_slides(bytes: &[u8], include_notes: bool) -> anyhow::Result<Vec<String>> {
+    extract_pptx_slides_with_limits(bytes, include_notes, MAX_TOTAL_SLIDE_XML_BYTES)
 }
 
-fn extract_pptx_text_with_limits(
+fn extract_pptx_slides_with_limits(
     bytes: &[u8],
+    include_notes: bool,
     max_total_slide_xml_bytes: u64,
-) -> anyhow::Result<String> {
-    use quick_xml::events::Event;
-    use quick_xml::Reader;
-    use std::io::Read;
-
+) -> anyhow::Result<Vec<String>> {
     let cursor = std::io::Cursor::new(bytes);
     let mut archive = zip::ZipArchive::new(cursor)?;
 
@@ -82,73 +100,134 @@ fn extract_pptx_text_with_limits(
         }
     }
 
-    let mut text = String::new();
+    let mut slides = Vec::new();
     let mut total_slide_xml_bytes = 0u64;
 
     for slide_name in &ordered_slide_names {
-        let mut slide_file = archive
-            .by_name(slide_name)
-            .map_err(|e| anyhow::anyhow!("Failed to read {slide_name}: {e}"))?;
-        let slide_xml_size = slide_file.size();
-        total_slide_xml_bytes = total_slide_xml_bytes
-            .checked_add(slide_xml_size)
-            .ok_or_else(|| anyhow::anyhow!("Slide XML payload size overflow"))?;
-        if total_slide_xml_bytes > max_total_slide_xml_bytes {
-            anyhow::bail!(
-                "Slide XML payload too large: {} bytes (limit: {} bytes)",
-                total_slide_xml_bytes,
-                max_total_slide_xml_bytes
-            );
+        let xml_content = read_slide_xml(
+            &mut archive,
+            slide_name,
+            &mut total_slide_xml_bytes,
+            max_total_slide_xml_bytes,
+        )?
+        .ok_or_else(|| anyhow::anyhow!("Failed to read {slide_name}: file not found"))?;
+        let mut text = extract_paragraph_text(&xml_content)?;
+
+        if include_notes {
+            let notes_text = match notes_slide_name(slide_name) {
+                Some(notes_name) => read_slide_xml(
+                    &mut archive,
+                    &notes_name,
+                    &mut total_slide_xml_bytes,
+                    max_total_slide_xml_bytes,
+                )?
+                .map(|xml| extract_paragraph_text(&xml))
+                .transpose()?,
+                None => None,
+            };
+            if let Some(notes) = notes_text.filter(|notes| !notes.trim().is_empty()) {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str("--- Notes ---\n");
+                text.push_str(&notes);
+            }
         }
 
-        let mut xml_content = String::new();
-        slide_file.read_to_string(&mut xml_content)?;
-
-        let mut reader = Reader::from_str(&xml_content);
-        let mut in_text = false;
-        let slide_start = text.len();
-
-        loop {
-            match reader.read_event() {
-                Ok(Event::Start(e)) => {
-                    let name = e.name();
-                    if name.as_ref() == b"a:t" {
-                        in_text = true;
-                    } else if name.as_ref() == b"a:p" && text.len() > slide_start {
-                        text.push('\n');
-                    }
+        // Separate slides with a blank line.
+        if !text.is_empty() {
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            slides.push(text);
+        }
+    }
+
+    Ok(slides)
+}
+
+/// Read `name` from the archive, adding its uncompressed size to `total` and
+/// failing once `total` passes `max_total`. Returns `None` when the archive
+/// has no such file.
+fn read_slide_xml<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+    total: &mut u64,
+    max_total: u64,
+) -> anyhow::Result<Option<String>> {
+    use std::io::Read;
+
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => anyhow::bail!("Failed to read {name}: {e}"),
+    };
+    *total = total
+        .checked_add(file.size())
+        .ok_or_else(|| anyhow::anyhow!("Slide XML payload size overflow"))?;
+    if *total > max_total {
+        anyhow::bail!(
+            "Slide XML payload too large: {} bytes (limit: {} bytes)",
+            total,
+            max_total
+        );
+    }
+
+    let mut xml_content = String::new();
+    file.read_to_string(&mut xml_content)?;
+    Ok(Some(xml_content))
+}
+
+/// Text of the `<a:t>` runs in a slide or notes part, one line per `<a:p>`.
+fn extract_paragraph_text(xml_content: &str) -> anyhow::Result<String> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_str(xml_content);
+    let mut in_text = false;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = e.name();
+                if name.as_ref() == b"a:t" {
+                    in_text = true;
+                } else if name.as_ref() == b"a:p" && !text.is_empty() {
+                    text.push('\n');
                 }
-                Ok(Event::Empty(e)) => {
-                    // Self-closing <a:t/> contains no text and must not flip `in_text`.
-                    if e.name().as_ref() == b"a:p" && text.len() > slide_start {
-                        text.push('\n');
-                    }
+            }
+            Ok(Event::Empty(e)) => {
+                // Self-closing <a:t/> contains no text and must not flip `in_text`.
+                if e.name().as_ref() == b"a:p" && !text.is_empty() {
+                    text.push('\n');
                 }
-                Ok(Event::End(e)) => {
-                    if e.name().as_ref() == b"a:t" {
-                        in_text = false;
-                    }
+            }
+            Ok(Event::End(e)) => {
+                if e.name().as_ref() == b"a:t" {
+                    in_text = false;
                 }
-                Ok(Event::Text(e)) => {
-                    if in_text {
-                        text.push_str(&e.unescape()?);
-                    }
+            }
+            Ok(Event::Text(e)) => {
+                if in_text {
+                    text.push_str(&e.unescape()?);
                 }
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(e.into()),
-                _ => {}
             }
-        }
-
-        // Separate slides with a blank line.
-        if text.len() > slide_start && !text.ends_with('\n') {
-            text.push('\n');
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(e.into()),
+            _ => {}
         }
     }
 
     Ok(text)
 }
 
+/// `ppt/slides/slideN.xml` -> `ppt/notesSlides/notesSlideN.xml`, numbered
+/// the same way slides are.
+fn notes_slide_name(slide_path: &str) -> Option<String> {
+    slide_numeric_index(slide_path).map(|index| format!("ppt/notesSlides/notesSlide{index}.xml"))
+}
+
 fn slide_numeric_index(slide_path: &str) -> Option<u32> {
     let stem = Path::new(slide_path).file_stem()?.to_string_lossy();
     let digits = stem.strip_prefix("slide")?;
@@ -304,6 +383,95 @@ fn parse_max_chars(args: &serde_json::Value) -> anyhow::Result<usize> {
         .min(MAX_OUTPUT_CHARS))
 }
 
+/// Where over-long output is cut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TruncateMode {
+    /// After the last slide that fits; falls back to [`Self::Word`] when not
+    /// even the first slide fits.
+    Slide,
+    /// At the last whitespace at or below `max_chars`.
+    Word,
+    /// At exactly `max_chars`.
+    Hard,
+}
+
+fn parse_truncate_mode(args: &serde_json::Value) -> anyhow::Result<TruncateMode> {
+    match args.get("truncate").and_then(serde_json::Value::as_str) {
+        None if args.get("truncate").is_none() => Ok(TruncateMode::Slide),
+        Some("slide") => Ok(TruncateMode::Slide),
+        Some("word") => Ok(TruncateMode::Word),
+        Some("hard") => Ok(TruncateMode::Hard),
+        _ => anyhow::bail!("Invalid 'truncate': expected \"slide\", \"word\" or \"hard\""),
+    }
+}
+
+fn parse_include_notes(args: &serde_json::Value) -> anyhow::Result<bool> {
+    match args.get("include_notes") {
+        None => Ok(false),
+        Some(serde_json::Value::Bool(include)) => Ok(*include),
+        Some(_) => anyhow::bail!("Invalid 'include_notes': expected a boolean"),
+    }
+}
+
+/// Join `slides`, cutting to at most `max_chars` characters per `mode` and
+/// appending a note with how many slides were left out.
+fn truncate_slides(slides: &[String], max_chars: usize, mode: TruncateMode) -> String {
+    use std::fmt::Write as _;
+
+    let text = slides.concat();
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+
+    if mode == TruncateMode::Hard {
+        let mut truncated: String = text.chars().take(max_chars).collect();
+        let _ = write!(truncated, "\n\n... [truncated at {max_chars} chars]");
+        return truncated;
+    }
+
+    // Char offset at which each slide starts.
+    let mut slide_starts = Vec::with_capacity(slides.len());
+    let mut offset = 0;
+    for slide in slides {
+        slide_starts.push(offset);
+        offset += slide.chars().count();
+    }
+
+    let slide_cut = slide_starts
+        .iter()
+        .skip(1)
+        .copied()
+        .take_while(|start| *start <= max_chars)
+        .last();
+    let cut = match (mode, slide_cut) {
+        (TruncateMode::Slide, Some(cut)) => cut,
+        _ => word_boundary_cut(&text, max_chars),
+    };
+
+    let omitted = slide_starts.iter().filter(|start| **start >= cut).count();
+    let mut truncated: String = text.chars().take(cut).collect();
+    truncated.truncate(truncated.trim_end().len());
+    let _ = write!(
+        truncated,
+        "\n\n... [truncated at {max_chars} chars; {omitted} of {} slides omitted]",
+        slides.len()
+    );
+    truncated
+}
+
+/// Largest char count `<= max_chars` that ends before a whitespace character,
+/// or `max_chars` when the first `max_chars` characters contain none.
+fn word_boundary_cut(text: &str, max_chars: usize) -> usize {
+    text.chars()
+        .take(max_chars + 1)
+        .enumerate()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(idx, _)| idx)
+        .filter(|idx| *idx > 0)
+        .last()
+        .unwrap_or(max_chars)
+}
+
 #[async_trait]
 impl Tool for PptxReadTool {
     fn name(&self) -> &str {
@@ -328,6 +496,15 @@ impl Tool for PptxReadTool {
                     "description": "Maximum characters to return (default: 50000, max: 200000)",
                     "minimum": 1,
                     "maximum": 200_000
+                },
+                "truncate": {
+                    "type": "string",
+                    "enum": ["slide", "word", "hard"],
+                    "description": "How to cut output longer than max_chars: 'slide' (default) ends at the last whole slide, 'word' at the last word boundary, 'hard' at exactly max_chars"
+                },
+                "include_notes": {
+                    "type": "boolean",
+                    "description": "Append each slide's speaker notes after its text in a '--- Notes ---' block (default: false)"
                 }
             },
             "required": ["path"]
@@ -340,22 +517,32 @@ impl Tool for PptxReadTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
 
-        let max_chars = match parse_max_chars(&args) {
-            Ok(value) => value,
-            Err(err) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(err.to_string()),
-                })
-            }
-        };
+        let (max_chars, truncate, include_notes) =
+            match parse_max_chars(&args).and_then(|max_chars| {
+                Ok((
+                    max_chars,
+                    parse_truncate_mode(&args)?,
+                    parse_include_notes(&args)?,
+                ))
+            }) {
+                Ok(value) => value,
+                Err(err) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(err.to_string()),
+                    })
+                }
+            };
 
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: too many actions in the last hour"),
+                ),
             });
         }
 
@@ -371,7 +558,10 @@ impl Tool for PptxReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Rate limit exceeded: action budget exhausted"),
+                ),
             });
         }
 
@@ -434,8 +624,12 @@ impl Tool for PptxReadTool {
             }
         };
 
-        let text = match tokio::task::spawn_blocking(move || extract_pptx_text(&bytes)).await {
-            Ok(Ok(t)) => t,
+        let slides = match run_limited_extraction(&self.extraction_permits, move || {
+            extract_pptx_slides(&bytes, include_notes)
+        })
+        .await
+        {
+            Ok(Ok(slides)) => slides,
             Ok(Err(e)) => {
                 return Ok(ToolResult {
                     success: false,
@@ -452,7 +646,7 @@ impl Tool for PptxReadTool {
             }
         };
 
-        if text.trim().is_empty() {
+        if slides.iter().all(|slide| slide.trim().is_empty()) {
             return Ok(ToolResult {
                 success: true,
                 output: "PPTX contains no extractable text".into(),
@@ -460,14 +654,7 @@ impl Tool for PptxReadTool {
             });
         }
 
-        let output = if text.chars().count() > max_chars {
-            let mut truncated: String = text.chars().take(max_chars).collect();
-            use std::fmt::Write as _;
-            let _ = write!(truncated, "\n\n... [truncated at {max_chars} chars]");
-            truncated
-        } else {
-            text
-        };
+        let output = truncate_slides(&slides, max_chars, truncate);
 
         Ok(ToolResult {
             success: true,
@@ -800,6 +987,47 @@ mod tests {
         assert!(result.output.contains("truncated"));
     }
 
+    #[tokio::test]
+    async fn slide_truncation_ends_on_slide_boundary_and_reports_omitted_slides() {
+        let tmp = TempDir::new().unwrap();
+        let pptx_path = tmp.path().join("deck.pptx");
+        tokio::fs::write(
+            &pptx_path,
+            two_slide_pptx_bytes("First slide text", "Second slide has more words"),
+        )
+        .await
+        .unwrap();
+
+        let tool = PptxReadTool::new(test_security(tmp.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "deck.pptx", "max_chars": 30}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            "First slide text\n\n... [truncated at 30 chars; 1 of 2 slides omitted]"
+        );
+
+        let result = tool
+            .execute(json!({"path": "deck.pptx", "max_chars": 30, "truncate": "hard"}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("First slide text\nSecond slide "));
+        assert!(result.output.ends_with("[truncated at 30 chars]"));
+    }
+
+    #[test]
+    fn word_truncation_does_not_split_words() {
+        let slides = vec!["alpha beta gamma\n".to_string()];
+        assert_eq!(
+            truncate_slides(&slides, 13, TruncateMode::Word),
+            "alpha beta\n\n... [truncated at 13 chars; 0 of 1 slides omitted]"
+        );
+        // A single slide too long to fit falls back to a word cut.
+        assert!(truncate_slides(&slides, 13, TruncateMode::Slide).starts_with("alpha beta\n\n"));
+    }
+
     #[tokio::test]
     async fn invalid_max_chars_returns_tool_error() {
         let tmp = TempDir::new().unwrap();
@@ -838,10 +1066,72 @@ mod tests {
     #[test]
     fn cumulative_slide_xml_limit_is_enforced() {
         let bytes = two_slide_pptx_bytes("Alpha", "Beta");
-        let error = extract_pptx_text_with_limits(&bytes, 64).unwrap_err();
+        let error = extract_pptx_slides_with_limits(&bytes, false, 64).unwrap_err();
         assert!(error.to_string().contains("Slide XML payload too large"));
     }
 
+    #[tokio::test]
+    async fn include_notes_appends_notes_after_each_slide() {
+        use std::io::Write;
+
+        let notes_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notes xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+         xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld>
+    <p:spTree>
+      <p:sp>
+        <p:txBody>
+          <a:p><a:r><a:t>Mention the Q3 numbers</a:t></a:r></a:p>
+        </p:txBody>
+      </p:sp>
+    </p:spTree>
+  </p:cSld>
+</p:notes>"#;
+
+        // Slide 1 has no notes part; slide 2 does.
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(two_slide_pptx_bytes(
+            "Intro", "Results",
+        )))
+        .unwrap();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for i in 0..archive.len() {
+            zip.raw_copy_file(archive.by_index(i).unwrap()).unwrap();
+        }
+        zip.start_file("ppt/notesSlides/notesSlide2.xml", options)
+            .unwrap();
+        zip.write_all(notes_xml.as_bytes()).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let tmp = TempDir::new().unwrap();
+        tokio::fs::write(tmp.path().join("deck.pptx"), &bytes)
+            .await
+            .unwrap();
+        let tool = PptxReadTool::new(test_security(tmp.path().to_path_buf()));
+
+        let plain = tool.execute(json!({"path": "deck.pptx"})).await.unwrap();
+        assert!(plain.success);
+        assert!(!plain.output.contains("Q3"));
+
+        let result = tool
+            .execute(json!({"path": "deck.pptx", "include_notes": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            "Intro\nResults\n--- Notes ---\nMention the Q3 numbers\n"
+        );
+
+        let invalid = tool
+            .execute(json!({"path": "deck.pptx", "include_notes": "yes"}))
+            .await
+            .unwrap();
+        assert!(!invalid.success);
+        assert!(invalid.error.unwrap().contains("include_notes"));
+    }
+
     #[test]
     fn empty_text_tag_does_not_leak_in_text_state() {
         use std::io::Write;
@@ -17,6 +17,26 @@ pub struct ToolSpec {
     pub parameters: serde_json::Value,
 }
 
+impl ToolSpec {
+    /// This spec in OpenAI `tools` format:
+    /// `{"type":"function","function":{"name","description","parameters"}}`.
+    pub fn to_openai_tool(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name,
+                "description": self.description,
+                "parameters": self.parameters,
+            }
+        })
+    }
+}
+
+/// Export `specs` as an OpenAI `tools` array, for external orchestrators.
+pub fn openai_tool_specs(specs: &[ToolSpec]) -> serde_json::Value {
+    serde_json::Value::Array(specs.iter().map(ToolSpec::to_openai_tool).collect())
+}
+
 /// Core tool trait — implement for any capability
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -32,6 +52,13 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Whether calls may run concurrently with other tool calls from the
+    /// same model response. Tools that actuate hardware or otherwise mutate
+    /// shared state return `false`, which runs that whole batch in order.
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -91,6 +118,17 @@ mod tests {
         assert_eq!(spec.parameters["properties"]["value"]["type"], "string");
     }
 
+    #[test]
+    fn openai_tool_specs_wrap_each_spec_as_function() {
+        let exported = openai_tool_specs(&[DummyTool.spec()]);
+        let tool = &exported.as_array().unwrap()[0];
+
+        assert_eq!(tool["type"], "function");
+        assert_eq!(tool["function"]["name"], "dummy_tool");
+        assert_eq!(tool["function"]["description"], "A deterministic test tool");
+        assert_eq!(tool["function"]["parameters"]["type"], "object");
+    }
+
     #[tokio::test]
     async fn execute_returns_expected_output() {
         let tool = DummyTool;
@@ -1,6 +1,7 @@
 use crate::config::UrlAccessConfig;
 use anyhow::{Context, Result};
-use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, ToSocketAddrs};
 
 #[derive(Debug, Clone, Copy)]
 pub enum UrlSchemePolicy {
@@ -10,17 +11,115 @@ pub enum UrlSchemePolicy {
 
 #[derive(Debug, Clone)]
 pub struct DomainPolicy<'a> {
-    pub allowed_domains: &'a [String],
-    pub blocked_domains: &'a [String],
+    pub allowed_domains: &'a AllowlistMatcher,
+    pub blocked_domains: &'a AllowlistMatcher,
     pub allowed_field_name: &'a str,
     pub blocked_field_name: Option<&'a str>,
     pub empty_allowed_message: &'a str,
     pub scheme_policy: UrlSchemePolicy,
     pub ipv6_error_context: &'a str,
+    /// Accept bracketed IPv6 literals (`https://[2606:4700::1111]/`). The
+    /// bare address then goes through the same allowlist and private-host
+    /// checks as any other host.
+    pub allow_ipv6_literals: bool,
     pub url_access: Option<&'a UrlAccessConfig>,
 }
 
+/// Address a host name resolved to when `validate_url` checked it.
+///
+/// Handing this to the HTTP client (`reqwest::ClientBuilder::resolve`) makes
+/// it connect to the checked address instead of resolving the host again,
+/// which would let a DNS-rebinding record swap in a private address between
+/// check and connect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinnedAddr {
+    /// Host as the HTTP client sees it in the URL.
+    pub host: String,
+    pub addr: SocketAddr,
+}
+
+/// Each check `validate_url` made and its outcome, for operators debugging
+/// allow/block lists. Only ever logged; the model sees the terse error.
+/// `Default` records nothing.
+#[derive(Debug, Default)]
+pub struct DecisionTrail {
+    enabled: bool,
+    steps: Vec<String>,
+}
+
+impl DecisionTrail {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            steps: Vec::new(),
+        }
+    }
+
+    fn note(&mut self, step: impl FnOnce() -> String) {
+        if self.enabled {
+            self.steps.push(step());
+        }
+    }
+
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+}
+
+impl std::fmt::Display for DecisionTrail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.steps.join("; "))
+    }
+}
+
 pub fn validate_url(raw_url: &str, policy: &DomainPolicy<'_>) -> Result<String> {
+    validate_url_resolving(raw_url, policy, &mut DecisionTrail::default()).map(|(url, _)| url)
+}
+
+/// Like [`validate_url`], recording every check in `trail`, ending with the
+/// rule that rejected the URL.
+pub fn validate_url_traced(
+    raw_url: &str,
+    policy: &DomainPolicy<'_>,
+    trail: &mut DecisionTrail,
+) -> Result<String> {
+    let result = validate_url_resolving(raw_url, policy, trail).map(|(url, _)| url);
+    match &result {
+        Ok(_) => trail.note(|| "allowed".to_string()),
+        Err(e) => trail.note(|| format!("rejected: {e}")),
+    }
+    result
+}
+
+/// Like [`validate_url`], but also returns the address the host resolved to
+/// during the private-host check so the caller can pin its connection to it.
+///
+/// Returns no pin for IP literals and whenever the check skipped DNS
+/// (`block_private_ip = false`, or the host matches `allow_domains`).
+pub fn validate_url_pinned(
+    raw_url: &str,
+    policy: &DomainPolicy<'_>,
+) -> Result<(String, Option<PinnedAddr>)> {
+    let (url, resolved) = validate_url_resolving(raw_url, policy, &mut DecisionTrail::default())?;
+    let Some(ip) = resolved.first().copied() else {
+        return Ok((url, None));
+    };
+    let parsed = reqwest::Url::parse(&url).with_context(|| format!("Invalid URL: {url}"))?;
+    let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+        return Ok((url, None));
+    };
+    let pinned = PinnedAddr {
+        host: host.to_string(),
+        addr: SocketAddr::new(ip, port),
+    };
+    Ok((url, Some(pinned)))
+}
+
+fn validate_url_resolving(
+    raw_url: &str,
+    policy: &DomainPolicy<'_>,
+    trail: &mut DecisionTrail,
+) -> Result<(String, Vec<IpAddr>)> {
     let url = raw_url.trim();
 
     if url.is_empty() {
@@ -31,26 +130,60 @@ pub fn validate_url(raw_url: &str, policy: &DomainPolicy<'_>) -> Result<String>
         anyhow::bail!("URL cannot contain whitespace");
     }
 
-    if policy.allowed_domains.is_empty() {
+    let allow_all_domains = policy.url_access.is_some_and(|cfg| cfg.allow_all_domains);
+    if policy.allowed_domains.is_empty() && !allow_all_domains {
         anyhow::bail!("{}", policy.empty_allowed_message);
     }
 
-    let host = extract_host(url, policy.scheme_policy, policy.ipv6_error_context)?;
+    trail.note(|| format!("scheme policy {:?}", policy.scheme_policy));
+    let host = if policy.allow_ipv6_literals {
+        extract_host_allowing_ipv6(url, policy.scheme_policy)?
+    } else {
+        extract_host(url, policy.scheme_policy, policy.ipv6_error_context)?
+    };
+    trail.note(|| format!("host '{host}'"));
 
     if let Some(blocked_field_name) = policy.blocked_field_name {
-        if host_matches_allowlist(&host, policy.blocked_domains) {
+        let blocked = policy.blocked_domains.matches(&host);
+        trail.note(|| {
+            format!(
+                "{blocked_field_name} {:?}: {}",
+                policy.blocked_domains.patterns(),
+                if blocked { "matched" } else { "no match" }
+            )
+        });
+        if blocked {
             anyhow::bail!("Host '{host}' is in {blocked_field_name}");
         }
     }
 
-    if !host_matches_allowlist(&host, policy.allowed_domains) {
+    // `allow_all_domains` only widens an empty allowlist; a non-empty list is
+    // still authoritative.
+    let allowlist_open = policy.allowed_domains.is_empty() && allow_all_domains;
+    let allowed = allowlist_open || policy.allowed_domains.matches(&host);
+    trail.note(|| {
+        let outcome = if allowlist_open {
+            "open (security.url_access.allow_all_domains)"
+        } else if allowed {
+            "matched"
+        } else {
+            "no match"
+        };
+        format!(
+            "{} {:?}: {outcome}",
+            policy.allowed_field_name,
+            policy.allowed_domains.patterns()
+        )
+    });
+    if !allowed {
         anyhow::bail!("Host '{host}' is not in {}", policy.allowed_field_name);
     }
 
     enforce_global_domain_access_policy(&host, policy.url_access)?;
-    enforce_private_host_policy(&host, policy.url_access)?;
+    trail.note(|| "security.url_access domain rules: passed".to_string());
+    let resolved = enforce_private_host_policy(&host, policy.url_access, trail)?;
 
-    Ok(url.to_string())
+    Ok((url.to_string(), resolved))
 }
 
 fn enforce_global_domain_access_policy(
@@ -86,22 +219,30 @@ fn enforce_global_domain_access_policy(
     Ok(())
 }
 
-fn enforce_private_host_policy(host: &str, url_access: Option<&UrlAccessConfig>) -> Result<()> {
+/// Returns the addresses a host name resolved to, empty when no lookup was
+/// needed.
+fn enforce_private_host_policy(
+    host: &str,
+    url_access: Option<&UrlAccessConfig>,
+    trail: &mut DecisionTrail,
+) -> Result<Vec<IpAddr>> {
     let config = url_access.cloned().unwrap_or_default();
     if !config.block_private_ip {
-        return Ok(());
+        trail.note(|| "private-IP check skipped (block_private_ip = false)".to_string());
+        return Ok(Vec::new());
     }
 
     // Domain allowlist has highest priority for private/local blocking.
     if host_matches_allowlist(host, &config.allow_domains) {
-        return Ok(());
+        trail.note(|| "private-IP check skipped (host in allow_domains)".to_string());
+        return Ok(Vec::new());
     }
 
     if let Ok(ip) = host.parse::<IpAddr>() {
         if is_non_global_ip(ip) && !is_ip_explicitly_allowed(ip, &config) {
             anyhow::bail!("Blocked local/private host: {host}");
         }
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     if is_local_hostname(host) && !config.allow_loopback {
@@ -121,13 +262,14 @@ fn enforce_private_host_policy(host: &str, url_access: Option<&UrlAccessConfig>)
         }
     }
 
-    for ip in resolved {
+    trail.note(|| format!("resolved IPs {resolved:?}"));
+    for &ip in &resolved {
         if is_non_global_ip(ip) && !is_ip_explicitly_allowed(ip, &config) {
             anyhow::bail!("Blocked local/private host after DNS resolution: {host} -> {ip}");
         }
     }
 
-    Ok(())
+    Ok(resolved)
 }
 
 fn is_ip_explicitly_allowed(ip: IpAddr, config: &UrlAccessConfig) -> bool {
@@ -222,6 +364,23 @@ pub fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
     normalized
 }
 
+/// Like [`normalize_allowed_domains`], but rejects the global `"*"` wildcard
+/// when `forbid_wildcard` is set (`security.url_access.forbid_wildcard_domains`).
+pub fn normalize_allowed_domains_checked(
+    domains: Vec<String>,
+    forbid_wildcard: bool,
+    field_name: &str,
+) -> Result<Vec<String>> {
+    let normalized = normalize_allowed_domains(domains);
+    if forbid_wildcard && normalized.iter().any(|d| d == "*") {
+        anyhow::bail!(
+            "{field_name} contains \"*\", but security.url_access.forbid_wildcard_domains = true; \
+             list the allowed domains explicitly"
+        );
+    }
+    Ok(normalized)
+}
+
 pub fn normalize_domain(raw: &str) -> Option<String> {
     let mut d = raw.trim().to_lowercase();
     if d.is_empty() {
@@ -240,6 +399,16 @@ pub fn normalize_domain(raw: &str) -> Option<String> {
 
     d = d.trim_start_matches('.').trim_end_matches('.').to_string();
 
+    // IPv6 literals, bracketed or bare, are kept in canonical form so they
+    // compare equal to the host `extract_host_allowing_ipv6` returns.
+    let bracketed = d
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(literal, _)| literal);
+    if let Ok(v6) = bracketed.unwrap_or(&d).parse::<Ipv6Addr>() {
+        return Some(v6.to_string());
+    }
+
     if let Some((host, _)) = d.split_once(':') {
         d = host.to_string();
     }
@@ -256,6 +425,36 @@ pub fn extract_host(
     scheme_policy: UrlSchemePolicy,
     ipv6_error_context: &str,
 ) -> anyhow::Result<String> {
+    let authority = url_authority(url, scheme_policy)?;
+    if authority.starts_with('[') {
+        anyhow::bail!("IPv6 hosts are not supported in {ipv6_error_context}");
+    }
+    host_from_authority(authority)
+}
+
+/// Like [`extract_host`], but accepts a bracketed IPv6 literal and returns
+/// the address without brackets, in canonical form.
+pub fn extract_host_allowing_ipv6(
+    url: &str,
+    scheme_policy: UrlSchemePolicy,
+) -> anyhow::Result<String> {
+    let authority = url_authority(url, scheme_policy)?;
+    let Some(rest) = authority.strip_prefix('[') else {
+        return host_from_authority(authority);
+    };
+    let (literal, after) = rest
+        .split_once(']')
+        .ok_or_else(|| anyhow::anyhow!("Invalid IPv6 host: missing ']'"))?;
+    if !after.is_empty() && !after.starts_with(':') {
+        anyhow::bail!("Invalid IPv6 host: unexpected '{after}' after ']'");
+    }
+    let v6 = literal
+        .parse::<Ipv6Addr>()
+        .map_err(|_| anyhow::anyhow!("Invalid IPv6 host: '{literal}'"))?;
+    Ok(v6.to_string())
+}
+
+fn url_authority(url: &str, scheme_policy: UrlSchemePolicy) -> anyhow::Result<&str> {
     let rest = match scheme_policy {
         UrlSchemePolicy::HttpsOnly => url
             .strip_prefix("https://")
@@ -279,10 +478,10 @@ pub fn extract_host(
         anyhow::bail!("URL userinfo is not allowed");
     }
 
-    if authority.starts_with('[') {
-        anyhow::bail!("IPv6 hosts are not supported in {ipv6_error_context}");
-    }
+    Ok(authority)
+}
 
+fn host_from_authority(authority: &str) -> anyhow::Result<String> {
     let host = authority
         .split(':')
         .next()
@@ -312,6 +511,64 @@ pub fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
     })
 }
 
+/// Domain allowlist prebuilt for fast host lookups.
+///
+/// Exact (`example.com`) and wildcard (`*.example.com`) patterns both match
+/// the domain itself and any subdomain, so a lookup walks the host's parent
+/// domains through a hash set instead of scanning every pattern. Matches
+/// exactly what [`host_matches_allowlist`] would return for the same list.
+#[derive(Debug, Clone, Default)]
+pub struct AllowlistMatcher {
+    patterns: Vec<String>,
+    suffixes: HashSet<String>,
+    match_all: bool,
+}
+
+impl AllowlistMatcher {
+    pub fn new(patterns: Vec<String>) -> Self {
+        let mut suffixes = HashSet::with_capacity(patterns.len());
+        let mut match_all = false;
+        for pattern in &patterns {
+            if pattern == "*" {
+                match_all = true;
+            } else {
+                let suffix = pattern.strip_prefix("*.").unwrap_or(pattern);
+                suffixes.insert(suffix.to_string());
+            }
+        }
+        Self {
+            patterns,
+            suffixes,
+            match_all,
+        }
+    }
+
+    /// The patterns this matcher was built from.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn matches(&self, host: &str) -> bool {
+        if self.match_all {
+            return true;
+        }
+        let mut candidate = host;
+        loop {
+            if self.suffixes.contains(candidate) {
+                return true;
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
 pub fn is_private_or_local_host(host: &str) -> bool {
     let bare = host
         .strip_prefix('[')
@@ -363,6 +620,34 @@ fn is_non_global_v6(v6: std::net::Ipv6Addr) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn validate_url_pinned_skips_pin_without_dns_lookup() {
+        let allowed = AllowlistMatcher::new(vec!["*".into()]);
+        let blocked = AllowlistMatcher::default();
+        let policy = |url_access| DomainPolicy {
+            allowed_domains: &allowed,
+            blocked_domains: &blocked,
+            allowed_field_name: "allowed",
+            blocked_field_name: None,
+            empty_allowed_message: "empty",
+            scheme_policy: UrlSchemePolicy::HttpOrHttps,
+            ipv6_error_context: "test",
+            allow_ipv6_literals: false,
+            url_access,
+        };
+
+        let (url, pinned) = validate_url_pinned(" https://8.8.8.8/x ", &policy(None)).unwrap();
+        assert_eq!(url, "https://8.8.8.8/x");
+        assert!(pinned.is_none());
+
+        let open = UrlAccessConfig {
+            block_private_ip: false,
+            ..UrlAccessConfig::default()
+        };
+        let (_, pinned) = validate_url_pinned("https://example.com", &policy(Some(&open))).unwrap();
+        assert!(pinned.is_none());
+    }
+
     #[test]
     fn normalize_domain_strips_scheme_and_path() {
         let got = normalize_domain("https://Docs.Example.com/path").unwrap();
@@ -494,8 +779,8 @@ mod tests {
     }
 
     fn policy<'a>(
-        allowed_domains: &'a [String],
-        blocked_domains: &'a [String],
+        allowed_domains: &'a AllowlistMatcher,
+        blocked_domains: &'a AllowlistMatcher,
     ) -> DomainPolicy<'a> {
         DomainPolicy {
             allowed_domains,
@@ -505,14 +790,15 @@ mod tests {
             empty_allowed_message: "allowed domains must be configured",
             scheme_policy: UrlSchemePolicy::HttpOrHttps,
             ipv6_error_context: "web_fetch",
+            allow_ipv6_literals: false,
             url_access: None,
         }
     }
 
     #[test]
     fn validate_url_accepts_public_allowed_host() {
-        let allowed = vec!["example.com".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["example.com".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let got =
             validate_url("https://docs.example.com/path", &policy(&allowed, &blocked)).unwrap();
         assert_eq!(got, "https://docs.example.com/path");
@@ -520,8 +806,8 @@ mod tests {
 
     #[test]
     fn validate_url_rejects_blocked_host() {
-        let allowed = vec!["*".to_string()];
-        let blocked = vec!["example.com".to_string()];
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::new(vec!["example.com".to_string()]);
         let err = validate_url("https://example.com", &policy(&allowed, &blocked))
             .unwrap_err()
             .to_string();
@@ -530,18 +816,73 @@ mod tests {
 
     #[test]
     fn validate_url_rejects_private_host() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let err = validate_url("https://127.0.0.1", &policy(&allowed, &blocked))
             .unwrap_err()
             .to_string();
         assert!(err.contains("local/private"));
     }
 
+    #[test]
+    fn ipv6_literal_passes_when_public_and_is_rejected_when_local() {
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
+        let policy = DomainPolicy {
+            allow_ipv6_literals: true,
+            ..policy(&allowed, &blocked)
+        };
+
+        let got = validate_url("https://[2606:4700::1111]:8443/dns", &policy).unwrap();
+        assert_eq!(got, "https://[2606:4700::1111]:8443/dns");
+
+        for local in [
+            "https://[fd00::1]/",
+            "https://[fe80::1]/",
+            "http://[::1]:8080/",
+        ] {
+            let err = validate_url(local, &policy).unwrap_err().to_string();
+            assert!(err.contains("local/private"), "{local}: {err}");
+        }
+        let err = validate_url("https://[not-an-ip]/", &policy)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid IPv6 host"), "{err}");
+    }
+
+    #[test]
+    fn ipv6_literal_matches_allowlist_and_blocklist_entries() {
+        assert_eq!(
+            normalize_domain("https://[2606:4700:0::1111]:443/").as_deref(),
+            Some("2606:4700::1111")
+        );
+        let allowed = AllowlistMatcher::new(normalize_allowed_domains(vec![
+            "[2606:4700::1111]".into(),
+            "2001:4860:4860::8888".into(),
+        ]));
+        let blocked = AllowlistMatcher::new(normalize_allowed_domains(vec![
+            "2001:4860:4860:0:0:0:0:8888".into(),
+        ]));
+        let policy = DomainPolicy {
+            allow_ipv6_literals: true,
+            ..policy(&allowed, &blocked)
+        };
+
+        assert!(validate_url("https://[2606:4700::1111]/", &policy).is_ok());
+        let err = validate_url("https://[2001:4860:4860::8888]/", &policy)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("blocked_domains"), "{err}");
+        let err = validate_url("https://[2606:4700::1001]/", &policy)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("allowed_domains"), "{err}");
+    }
+
     #[test]
     fn validate_url_rejects_allowlist_miss() {
-        let allowed = vec!["example.com".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["example.com".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let err = validate_url("https://google.com", &policy(&allowed, &blocked))
             .unwrap_err()
             .to_string();
@@ -550,8 +891,8 @@ mod tests {
 
     #[test]
     fn validate_url_rejects_empty_allowlist() {
-        let allowed: Vec<String> = Vec::new();
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::default();
+        let blocked = AllowlistMatcher::default();
         let err = validate_url("https://example.com", &policy(&allowed, &blocked))
             .unwrap_err()
             .to_string();
@@ -560,8 +901,8 @@ mod tests {
 
     #[test]
     fn validate_url_allows_private_ip_when_cidr_allowlisted() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             allow_cidrs: vec!["10.0.0.0/8".to_string()],
             ..UrlAccessConfig::default()
@@ -576,8 +917,8 @@ mod tests {
 
     #[test]
     fn validate_url_allows_localhost_when_domain_allowlisted() {
-        let allowed = vec!["localhost".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["localhost".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             allow_domains: vec!["localhost".to_string()],
             ..UrlAccessConfig::default()
@@ -590,10 +931,49 @@ mod tests {
         assert_eq!(got, "https://localhost:8080");
     }
 
+    #[test]
+    fn traced_validation_records_resolved_ips() {
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
+        let url_access = UrlAccessConfig {
+            allow_loopback: true,
+            ..UrlAccessConfig::default()
+        };
+        let policy = DomainPolicy {
+            url_access: Some(&url_access),
+            ..policy(&allowed, &blocked)
+        };
+        let mut trail = DecisionTrail::new();
+        validate_url_traced("http://localhost:8080", &policy, &mut trail).unwrap();
+
+        let resolved = trail
+            .steps()
+            .iter()
+            .find(|step| step.starts_with("resolved IPs"))
+            .expect("trail should list resolved IPs");
+        assert!(
+            resolved.contains("127.0.0.1") || resolved.contains("::1"),
+            "{resolved}"
+        );
+        assert_eq!(trail.steps().last().map(String::as_str), Some("allowed"));
+    }
+
+    #[test]
+    fn untraced_validation_records_nothing() {
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
+        let mut trail = DecisionTrail::default();
+        assert!(
+            validate_url_traced("https://localhost", &policy(&allowed, &blocked), &mut trail)
+                .is_err()
+        );
+        assert!(trail.steps().is_empty());
+    }
+
     #[test]
     fn validate_url_rejects_localhost_when_not_allowlisted() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let err = validate_url("https://localhost:8080", &policy(&allowed, &blocked))
             .unwrap_err()
             .to_string();
@@ -602,8 +982,8 @@ mod tests {
 
     #[test]
     fn validate_url_rejects_domain_blocklist_match() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             domain_blocklist: vec!["example.com".to_string()],
             ..UrlAccessConfig::default()
@@ -620,8 +1000,8 @@ mod tests {
 
     #[test]
     fn validate_url_enforce_global_allowlist_rejects_miss() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             enforce_domain_allowlist: true,
             domain_allowlist: vec!["rust-lang.org".to_string()],
@@ -639,8 +1019,8 @@ mod tests {
 
     #[test]
     fn validate_url_requires_first_visit_approval_for_unseen_domain() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             require_first_visit_approval: true,
             ..UrlAccessConfig::default()
@@ -657,8 +1037,8 @@ mod tests {
 
     #[test]
     fn validate_url_allows_first_visit_when_domain_is_preapproved() {
-        let allowed = vec!["*".to_string()];
-        let blocked: Vec<String> = Vec::new();
+        let allowed = AllowlistMatcher::new(vec!["*".to_string()]);
+        let blocked = AllowlistMatcher::default();
         let url_access = UrlAccessConfig {
             require_first_visit_approval: true,
             approved_domains: vec!["docs.rs".to_string()],
@@ -671,4 +1051,134 @@ mod tests {
         let got = validate_url("https://docs.rs", &policy).unwrap();
         assert_eq!(got, "https://docs.rs");
     }
+
+    #[test]
+    fn validate_url_allow_all_domains_accepts_empty_allowlist() {
+        let allowed = AllowlistMatcher::default();
+        let blocked = AllowlistMatcher::default();
+        let url_access = UrlAccessConfig {
+            allow_all_domains: true,
+            ..UrlAccessConfig::default()
+        };
+        let policy = DomainPolicy {
+            url_access: Some(&url_access),
+            ..policy(&allowed, &blocked)
+        };
+        let got = validate_url("https://93.184.216.34/page", &policy).unwrap();
+        assert_eq!(got, "https://93.184.216.34/page");
+    }
+
+    #[test]
+    fn validate_url_allow_all_domains_still_blocks_private_hosts() {
+        let allowed = AllowlistMatcher::default();
+        let blocked = AllowlistMatcher::default();
+        let url_access = UrlAccessConfig {
+            allow_all_domains: true,
+            ..UrlAccessConfig::default()
+        };
+        let policy = DomainPolicy {
+            url_access: Some(&url_access),
+            ..policy(&allowed, &blocked)
+        };
+        for url in [
+            "https://127.0.0.1",
+            "https://192.168.1.10",
+            "https://localhost",
+        ] {
+            let err = validate_url(url, &policy).unwrap_err().to_string();
+            assert!(err.contains("local/private"), "{url}: {err}");
+        }
+    }
+
+    #[test]
+    fn validate_url_allow_all_domains_keeps_non_empty_allowlist_authoritative() {
+        let allowed = AllowlistMatcher::new(vec!["example.com".to_string()]);
+        let blocked = AllowlistMatcher::default();
+        let url_access = UrlAccessConfig {
+            allow_all_domains: true,
+            ..UrlAccessConfig::default()
+        };
+        let policy = DomainPolicy {
+            url_access: Some(&url_access),
+            ..policy(&allowed, &blocked)
+        };
+        let err = validate_url("https://google.com", &policy)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("allowed_domains"));
+    }
+
+    #[test]
+    fn normalize_allowed_domains_checked_rejects_wildcard_when_forbidden() {
+        let err = normalize_allowed_domains_checked(
+            vec!["example.com".into(), " * ".into()],
+            true,
+            "web_fetch.allowed_domains",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("web_fetch.allowed_domains"));
+        assert!(err.contains("forbid_wildcard_domains"));
+
+        let got =
+            normalize_allowed_domains_checked(vec!["*".into()], false, "web_fetch.allowed_domains")
+                .unwrap();
+        assert_eq!(got, vec!["*".to_string()]);
+
+        let got = normalize_allowed_domains_checked(
+            vec!["*.example.com".into()],
+            true,
+            "web_fetch.allowed_domains",
+        )
+        .unwrap();
+        assert_eq!(got, vec!["*.example.com".to_string()]);
+    }
+
+    #[test]
+    fn allowlist_matcher_agrees_with_linear_scan() {
+        let patterns: Vec<String> = [
+            "example.com",
+            "*.rust-lang.org",
+            "docs.rs",
+            "localhost",
+            "*.svc.local",
+            "10.0.0.1",
+        ]
+        .iter()
+        .map(|p| (*p).to_string())
+        .chain((0..500).map(|i| format!("site{i}.test")))
+        .collect();
+        let matcher = AllowlistMatcher::new(patterns.clone());
+
+        for host in [
+            "example.com",
+            "api.example.com",
+            "deep.api.example.com",
+            "notexample.com",
+            "example.com.evil.net",
+            "rust-lang.org",
+            "blog.rust-lang.org",
+            "docs.rs",
+            "evil-docs.rs",
+            "localhost",
+            "db.svc.local",
+            "svc.local",
+            "10.0.0.1",
+            "10.0.0.10",
+            "site42.test",
+            "www.site499.test",
+            "site500.test",
+            "com",
+        ] {
+            assert_eq!(
+                matcher.matches(host),
+                host_matches_allowlist(host, &patterns),
+                "{host}"
+            );
+        }
+
+        let all = AllowlistMatcher::new(vec!["*".into()]);
+        assert!(all.matches("anything.example"));
+        assert!(!AllowlistMatcher::default().matches("example.com"));
+    }
 }
@@ -1,6 +1,7 @@
 use super::traits::{Tool, ToolResult};
 use super::url_validation::{
-    normalize_allowed_domains, validate_url, DomainPolicy, UrlSchemePolicy,
+    normalize_allowed_domains, normalize_allowed_domains_checked, validate_url, AllowlistMatcher,
+    DomainPolicy, UrlSchemePolicy,
 };
 use crate::config::{Config, UrlAccessConfig};
 use crate::security::SecurityPolicy;
@@ -52,7 +53,10 @@ impl WebAccessConfigTool {
             return Some(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -131,6 +135,10 @@ impl WebAccessConfigTool {
             "domain_allowlist": cfg.domain_allowlist,
             "domain_blocklist": cfg.domain_blocklist,
             "approved_domains": cfg.approved_domains,
+            "forbid_wildcard_domains": cfg.forbid_wildcard_domains,
+            "pin_resolved_ip": cfg.pin_resolved_ip,
+            "respect_robots": cfg.respect_robots,
+            "connect_timeout_secs": cfg.connect_timeout_secs,
         })
     }
 
@@ -150,15 +158,16 @@ impl WebAccessConfigTool {
             .ok_or_else(|| anyhow::anyhow!("Missing required field: url"))?;
 
         let cfg = self.load_config_without_env()?;
-        let wildcard = vec!["*".to_string()];
+        let wildcard = AllowlistMatcher::new(vec!["*".to_string()]);
         let policy = DomainPolicy {
             allowed_domains: &wildcard,
-            blocked_domains: &[],
+            blocked_domains: &AllowlistMatcher::default(),
             allowed_field_name: "web_access_config.check_url.allowed_domains",
             blocked_field_name: None,
             empty_allowed_message: "internal error: wildcard allowlist missing",
             scheme_policy: UrlSchemePolicy::HttpOrHttps,
             ipv6_error_context: "web_access_config.check_url",
+            allow_ipv6_literals: false,
             url_access: Some(&cfg.security.url_access),
         };
 
@@ -269,6 +278,22 @@ impl WebAccessConfigTool {
             );
         }
 
+        if policy.forbid_wildcard_domains {
+            for (field, domains) in [
+                ("security.url_access.allow_domains", &policy.allow_domains),
+                (
+                    "security.url_access.domain_allowlist",
+                    &policy.domain_allowlist,
+                ),
+                (
+                    "security.url_access.approved_domains",
+                    &policy.approved_domains,
+                ),
+            ] {
+                normalize_allowed_domains_checked(domains.clone(), true, field)?;
+            }
+        }
+
         cfg.save().await?;
 
         Ok(ToolResult {
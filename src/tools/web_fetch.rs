@@ -1,21 +1,254 @@
 use super::traits::{Tool, ToolResult};
 use super::url_validation::{
-    normalize_allowed_domains, validate_url, DomainPolicy, UrlSchemePolicy,
+    normalize_allowed_domains, validate_url_traced, AllowlistMatcher, DecisionTrail, DomainPolicy,
+    UrlSchemePolicy,
 };
 use crate::config::UrlAccessConfig;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Canonical provider list for error messages and the tool description.
 /// `fast_html2md` is kept as a deprecated alias for `nanohtml2text`.
 const WEB_FETCH_PROVIDER_HELP: &str =
-    "Supported providers: 'nanohtml2text' (default), 'firecrawl', 'tavily'. \
+    "Supported providers: 'nanohtml2text' (default), 'readability', 'firecrawl', 'tavily'. \
      Deprecated alias: 'fast_html2md' (maps to 'nanohtml2text').";
 
+/// Error for the `readability` provider in builds without its feature.
+#[cfg(not(feature = "web-fetch-readability"))]
+const READABILITY_FEATURE_ERROR: &str =
+    "web_fetch provider 'readability' requires Cargo feature 'web-fetch-readability'";
+
+/// Minimum text length of a `<p>` counted as body text when a page has no
+/// `<article>` or `<main>` to score.
+#[cfg(feature = "web-fetch-readability")]
+const READABILITY_MIN_PARAGRAPH_CHARS: usize = 80;
+
+/// Elements stripped before HTML conversion. The first `SCRIPT_TAG_COUNT`
+/// never carry readable text and are also stripped by the fallback pass.
+const NOISE_TAGS: &[&str] = &[
+    "script", "style", "nav", "header", "footer", "aside", "noscript", "form", "button",
+];
+const SCRIPT_TAG_COUNT: usize = 2;
+
+/// In-memory LRU of successful fetch outputs, keyed by validated URL.
+struct FetchCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<String, (Instant, String)>,
+    /// Keys from least to most recently used.
+    order: VecDeque<String>,
+}
+
+impl FetchCache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.order.retain(|k| k != key);
+        self.order.push_back(key.to_string());
+    }
+
+    fn get(&mut self, key: &str) -> Option<String> {
+        let (stored_at, output) = self.entries.get(key)?;
+        if stored_at.elapsed() > self.ttl {
+            self.entries.remove(key);
+            self.order.retain(|k| k != key);
+            return None;
+        }
+        let output = output.clone();
+        self.touch(key);
+        Some(output)
+    }
+
+    fn insert(&mut self, key: String, output: String) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.touch(&key);
+        self.entries.insert(key, (Instant::now(), output));
+    }
+}
+
+/// Overall request timeout used when `web_fetch.timeout_secs` is 0.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Connect timeout used when `security.url_access.connect_timeout_secs` is 0.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Resolves `(connect, overall)` timeouts in seconds, substituting the
+/// defaults for 0. The connect phase is part of the overall request, so a
+/// connect timeout longer than the overall timeout is rejected.
+pub fn resolve_timeouts(
+    connect_timeout_secs: u64,
+    timeout_secs: u64,
+) -> anyhow::Result<(u64, u64)> {
+    let connect = match connect_timeout_secs {
+        0 => DEFAULT_CONNECT_TIMEOUT_SECS,
+        secs => secs,
+    };
+    let overall = match timeout_secs {
+        0 => DEFAULT_TIMEOUT_SECS,
+        secs => secs,
+    };
+    if connect > overall {
+        anyhow::bail!(
+            "web_fetch.timeout_secs ({overall}) must be at least \
+             security.url_access.connect_timeout_secs ({connect})"
+        );
+    }
+    Ok((connect, overall))
+}
+
+/// Redirects followed on a cloud provider's API endpoint by default.
+const DEFAULT_API_MAX_REDIRECTS: u32 = 3;
+
+/// Hex digits of the SHA-256 digest kept as a page's `content_hash`.
+const CONTENT_HASH_HEX_CHARS: usize = 16;
+
+/// Largest `robots.txt` body parsed; RFC 9309 lets crawlers ignore the rest.
+const ROBOTS_MAX_BYTES: usize = 500 * 1024;
+
+/// Allow/Disallow rules that `robots.txt` applies to our user agent.
+#[derive(Debug, Clone, Default)]
+struct RobotsRules {
+    /// `(allow, path pattern)` pairs; an empty list allows everything.
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parses `body`, keeping the group that names our product token
+    /// (`ZeroClaw` in `ZeroClaw/1.0`) or, failing that, the `*` group.
+    fn parse(body: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let mut named = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut names_us = false;
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match field.as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group.
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    let agent = value.to_ascii_lowercase();
+                    names_us |= !token.is_empty() && agent == token;
+                    group_agents.push(agent);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty `Disallow:` allows everything; it adds no rule.
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    if !token.is_empty() && group_agents.contains(&token) {
+                        named.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|agent| agent == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if names_us { named } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (with query) may be fetched: the longest matching rule
+    /// wins and `Allow` wins a tie, as in RFC 9309.
+    fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if !Self::pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            if best.is_none_or(|(best_len, best_allow)| {
+                len > best_len || (len == best_len && *allow && !best_allow)
+            }) {
+                best = Some((len, *allow));
+            }
+        }
+        best.map_or(true, |(_, allow)| allow)
+    }
+
+    /// Prefix match supporting the `*` wildcard and a trailing `$` anchor.
+    fn pattern_matches(pattern: &str, path: &str) -> bool {
+        let (pattern, anchored) = match pattern.strip_suffix('$') {
+            Some(stripped) => (stripped, true),
+            None => (pattern, false),
+        };
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = path.strip_prefix(first) else {
+            return false;
+        };
+        let parts: Vec<&str> = parts.collect();
+        for (i, part) in parts.iter().enumerate() {
+            let is_last = i + 1 == parts.len();
+            if anchored && is_last {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(at) => rest = &rest[at + part.len()..],
+                None => return false,
+            }
+        }
+        !anchored || rest.is_empty()
+    }
+}
+
+/// How fetched content is shaped before it is returned.
+#[derive(Debug, Clone, Copy)]
+struct OutputOptions {
+    json_pretty: bool,
+    html_fallback: bool,
+    content_hash: bool,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            json_pretty: false,
+            html_fallback: true,
+            content_hash: false,
+        }
+    }
+}
+
 /// Web fetch tool: fetches a web page and returns text/markdown content for LLM consumption.
 ///
 /// Providers:
@@ -28,12 +261,20 @@ pub struct WebFetchTool {
     provider: String,
     api_keys: Vec<String>,
     api_url: Option<String>,
-    allowed_domains: Vec<String>,
-    blocked_domains: Vec<String>,
+    allowed_domains: AllowlistMatcher,
+    blocked_domains: AllowlistMatcher,
     url_access: UrlAccessConfig,
     max_response_size: usize,
     timeout_secs: u64,
+    connect_timeout_secs: u64,
     user_agent: String,
+    min_content_chars: usize,
+    output: OutputOptions,
+    explain_blocked: bool,
+    api_max_redirects: u32,
+    cache: Option<Mutex<FetchCache>>,
+    /// Parsed `robots.txt` per origin, kept for the process lifetime.
+    robots_cache: Arc<Mutex<HashMap<String, RobotsRules>>>,
     key_index: Arc<AtomicUsize>,
 }
 
@@ -72,16 +313,66 @@ impl WebFetchTool {
             },
             api_keys,
             api_url,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
-            blocked_domains: normalize_allowed_domains(blocked_domains),
+            allowed_domains: AllowlistMatcher::new(normalize_allowed_domains(allowed_domains)),
+            blocked_domains: AllowlistMatcher::new(normalize_allowed_domains(blocked_domains)),
+            connect_timeout_secs: url_access.connect_timeout_secs,
             url_access,
             max_response_size,
             timeout_secs,
             user_agent,
+            min_content_chars: 0,
+            output: OutputOptions::default(),
+            explain_blocked: false,
+            api_max_redirects: DEFAULT_API_MAX_REDIRECTS,
+            cache: None,
+            robots_cache: Arc::new(Mutex::new(HashMap::new())),
             key_index: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Treat extracted text shorter than `min_chars` as a failed fetch (0 = off).
+    pub fn with_min_content_chars(mut self, min_chars: usize) -> Self {
+        self.min_content_chars = min_chars;
+        self
+    }
+
+    /// Pretty-print JSON responses instead of returning the raw body.
+    pub fn with_json_pretty(mut self, pretty: bool) -> Self {
+        self.output.json_pretty = pretty;
+        self
+    }
+
+    /// Retry empty HTML conversions with the alternate converter (default on).
+    pub fn with_html_fallback(mut self, enabled: bool) -> Self {
+        self.output.html_fallback = enabled;
+        self
+    }
+
+    /// Log the full decision trail when a URL is blocked (log-only).
+    pub fn with_explain_blocked(mut self, enabled: bool) -> Self {
+        self.explain_blocked = enabled;
+        self
+    }
+
+    /// Follow up to `max` same-host redirects on the cloud provider API endpoint.
+    pub fn with_api_max_redirects(mut self, max: u32) -> Self {
+        self.api_max_redirects = max;
+        self
+    }
+
+    /// Append a `content_hash` of the extracted text and honour `if_changed_since_hash`.
+    pub fn with_content_hash(mut self, enabled: bool) -> Self {
+        self.output.content_hash = enabled;
+        self
+    }
+
+    /// Cache up to `capacity` successful fetches for `ttl_secs` (0 capacity = off).
+    pub fn with_cache(mut self, capacity: usize, ttl_secs: u64) -> Self {
+        self.cache = (capacity > 0)
+            .then(|| Mutex::new(FetchCache::new(capacity, Duration::from_secs(ttl_secs))));
+        self
+    }
+
     /// Returns the next API key from the rotation pool using round-robin, or `None` if unconfigured.
     fn get_next_api_key(&self) -> Option<String> {
         if self.api_keys.is_empty() {
@@ -93,7 +384,22 @@ impl WebFetchTool {
 
     /// Validates and normalises a URL against the allowlist, blocklist, and SSRF policy.
     fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
-        validate_url(
+        let mut trail = if self.explain_blocked {
+            DecisionTrail::new()
+        } else {
+            DecisionTrail::default()
+        };
+        self.validate_url_traced(raw_url, &mut trail)
+    }
+
+    /// [`Self::validate_url`], logging `trail` when `explain_blocked` is set
+    /// and the URL is rejected.
+    fn validate_url_traced(
+        &self,
+        raw_url: &str,
+        trail: &mut DecisionTrail,
+    ) -> anyhow::Result<String> {
+        let result = validate_url_traced(
             raw_url,
             &DomainPolicy {
                 allowed_domains: &self.allowed_domains,
@@ -103,18 +409,38 @@ impl WebFetchTool {
                 empty_allowed_message: "web_fetch tool is enabled but no allowed_domains are configured. Add [web_fetch].allowed_domains in config.toml",
                 scheme_policy: UrlSchemePolicy::HttpOrHttps,
                 ipv6_error_context: "web_fetch",
+                allow_ipv6_literals: true,
                 url_access: Some(&self.url_access),
             },
-        )
+            trail,
+        );
+        if result.is_err() && self.explain_blocked {
+            tracing::info!(url = raw_url, trail = %trail, "web_fetch: URL blocked");
+        }
+        result
     }
 
-    /// Truncates text to `max_response_size` characters and appends a marker if trimmed.
-    fn truncate_response(&self, text: &str) -> String {
-        if text.len() > self.max_response_size {
-            let mut truncated = text
-                .chars()
-                .take(self.max_response_size)
-                .collect::<String>();
+    /// Resolves the optional `max_chars` argument, with `max_response_size`
+    /// as both the default and the ceiling.
+    fn parse_max_chars(&self, args: &serde_json::Value) -> anyhow::Result<usize> {
+        let Some(value) = args.get("max_chars") else {
+            return Ok(self.max_response_size);
+        };
+        let Some(raw) = value.as_u64() else {
+            anyhow::bail!("Invalid 'max_chars': expected a positive integer");
+        };
+        if raw == 0 {
+            anyhow::bail!("Invalid 'max_chars': must be >= 1");
+        }
+        Ok(usize::try_from(raw)
+            .unwrap_or(self.max_response_size)
+            .min(self.max_response_size))
+    }
+
+    /// Truncates text to `max_chars` characters and appends a marker if trimmed.
+    fn truncate_response(&self, text: &str, max_chars: usize) -> String {
+        if text.chars().count() > max_chars {
+            let mut truncated = text.chars().take(max_chars).collect::<String>();
             truncated.push_str("\n\n... [Response truncated due to size limit] ...");
             truncated
         } else {
@@ -122,19 +448,131 @@ impl WebFetchTool {
         }
     }
 
+    /// Builds the tool result for fetched text, failing near-empty pages.
+    ///
+    /// A soft 404 or JS-only page often "succeeds" with almost no text; reporting
+    /// that as success invites the model to invent the page contents.
+    fn content_result(&self, text: &str, max_chars: usize) -> ToolResult {
+        let chars = text.trim().chars().count();
+        if chars < self.min_content_chars {
+            return ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Fetched page has only {chars} characters of text (minimum {}). \
+                     The page may be empty, a soft 404, or require JavaScript to render.",
+                    self.min_content_chars
+                )),
+            };
+        }
+        ToolResult {
+            success: true,
+            output: self.truncate_response(text, max_chars),
+            error: None,
+        }
+    }
+
+    /// [`Self::content_result`], with a `[content_hash: …]` footer over the
+    /// extracted text when `content_hash` is enabled.
+    fn fetched_result(&self, text: &str, max_chars: usize) -> ToolResult {
+        let mut result = self.content_result(text, max_chars);
+        if self.output.content_hash && result.success {
+            result.output.push_str("\n\n");
+            result
+                .output
+                .push_str(&content_hash_footer(&content_hash(text)));
+        }
+        result
+    }
+
+    /// Replaces a full result with a short note when its `content_hash`
+    /// footer matches the caller's `if_changed_since_hash`.
+    fn unless_unchanged(&self, result: ToolResult, if_changed_since: Option<&str>) -> ToolResult {
+        let Some(prior) = if_changed_since
+            .map(|hash| hash.trim().to_ascii_lowercase())
+            .filter(|hash| !hash.is_empty())
+        else {
+            return result;
+        };
+        if !self.output.content_hash
+            || !result.success
+            || !result.output.ends_with(&content_hash_footer(&prior))
+        {
+            return result;
+        }
+        ToolResult {
+            success: true,
+            output: format!(
+                "Page unchanged since the last fetch. {}",
+                content_hash_footer(&prior)
+            ),
+            error: None,
+        }
+    }
+
+    /// Applies `json_pointer` (RFC 6901) and pretty-printing to a JSON body.
+    ///
+    /// With neither requested the raw body is returned untouched. A selected
+    /// string is returned as plain text.
+    fn extract_json(&self, body: &str, json_pointer: Option<&str>) -> anyhow::Result<String> {
+        if json_pointer.is_none() && !self.output.json_pretty {
+            return Ok(body.to_string());
+        }
+        let value: serde_json::Value = serde_json::from_str(body)
+            .map_err(|e| anyhow::anyhow!("Response is not valid JSON: {e}"))?;
+        let selected = match json_pointer {
+            Some(pointer) => {
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    anyhow::bail!(
+                        "Invalid json_pointer '{pointer}': must be empty or start with '/' (e.g. /data/items/0)"
+                    );
+                }
+                value.pointer(pointer).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "json_pointer '{pointer}' does not match any value in the response"
+                    )
+                })?
+            }
+            None => &value,
+        };
+        if let Some(text) = selected.as_str() {
+            return Ok(text.to_string());
+        }
+        let rendered = if self.output.json_pretty {
+            serde_json::to_string_pretty(selected)?
+        } else {
+            serde_json::to_string(selected)?
+        };
+        Ok(rendered)
+    }
+
     /// Returns the configured timeout, substituting a safe 30 s default if zero is set.
     fn effective_timeout_secs(&self) -> u64 {
         if self.timeout_secs == 0 {
             tracing::warn!("web_fetch: timeout_secs is 0, using safe default of 30s");
-            30
+            DEFAULT_TIMEOUT_SECS
         } else {
             self.timeout_secs
         }
     }
 
+    /// `(connect, overall)` timeouts for every client this tool builds; see
+    /// [`resolve_timeouts`].
+    fn http_timeouts(&self) -> anyhow::Result<(Duration, Duration)> {
+        let (connect, overall) =
+            resolve_timeouts(self.connect_timeout_secs, self.effective_timeout_secs())?;
+        Ok((Duration::from_secs(connect), Duration::from_secs(overall)))
+    }
+
     /// Strips noisy structural HTML elements (nav, scripts, footers, etc.) before text
     /// extraction to reduce boilerplate in the LLM output.
     fn strip_noise_elements(html: &str) -> anyhow::Result<String> {
+        Self::strip_elements(html, false)
+    }
+
+    /// Removes `NOISE_TAGS` elements, or only `<script>`/`<style>` when
+    /// `scripts_only` is set.
+    fn strip_elements(html: &str, scripts_only: bool) -> anyhow::Result<String> {
         // Rust regex does not support backreferences, so run one pass per tag.
         // OnceLock stores Result<_, String> so that a compile failure is surfaced as an
         // error rather than a panic. String is used instead of anyhow::Error because it
@@ -143,19 +581,21 @@ impl WebFetchTool {
         static NOISE_RES: OnceLock<Result<Vec<regex::Regex>, String>> = OnceLock::new();
         let regexes = NOISE_RES
             .get_or_init(|| {
-                [
-                    "script", "style", "nav", "header", "footer", "aside", "noscript", "form",
-                    "button",
-                ]
-                .iter()
-                .map(|tag| {
-                    regex::Regex::new(&format!(r"(?si)<{tag}[^>]*>.*?</{tag}>"))
-                        .map_err(|e| e.to_string())
-                })
-                .collect::<Result<Vec<_>, _>>()
+                NOISE_TAGS
+                    .iter()
+                    .map(|tag| {
+                        regex::Regex::new(&format!(r"(?si)<{tag}[^>]*>.*?</{tag}>"))
+                            .map_err(|e| e.to_string())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
             })
             .as_ref()
             .map_err(|e| anyhow::anyhow!("noise regex init failed: {e}"))?;
+        let regexes = if scripts_only {
+            &regexes[..SCRIPT_TAG_COUNT]
+        } else {
+            &regexes[..]
+        };
         let mut result = html.to_string();
         for re in regexes {
             result = re.replace_all(&result, " ").into_owned();
@@ -166,43 +606,272 @@ impl WebFetchTool {
     /// Strips noise elements then converts HTML to plain text using the configured provider.
     /// `fast_html2md` is a deprecated alias that maps to `nanohtml2text` when the
     /// `web-fetch-html2md` feature is not compiled in.
+    ///
+    /// With `html_fallback`, whitespace-only output is retried with the
+    /// alternate converter over the page with only `<script>`/`<style>`
+    /// removed, since the noise filter itself (`<noscript>`, `<header>`,
+    /// `<form>`, ...) is a common reason a page comes out empty.
     fn convert_html_to_output(&self, body: &str) -> anyhow::Result<String> {
         let cleaned = Self::strip_noise_elements(body)?;
-        match self.provider.as_str() {
+        let text = Self::convert_html(&self.provider, &cleaned)?;
+        if !self.output.html_fallback || !text.trim().is_empty() {
+            return Ok(text);
+        }
+
+        let alternate = if self.provider == "nanohtml2text" {
+            "fast_html2md"
+        } else {
+            "nanohtml2text"
+        };
+        let lenient = Self::strip_elements(body, true)?;
+        let text = Self::convert_html(alternate, &lenient)?;
+        if text.trim().is_empty() {
+            anyhow::bail!("page produced no extractable text (may require JavaScript)");
+        }
+        tracing::debug!(
+            provider = %self.provider,
+            alternate,
+            "web_fetch: primary HTML conversion was empty; used fallback"
+        );
+        Ok(text)
+    }
+
+    fn convert_html(provider: &str, html: &str) -> anyhow::Result<String> {
+        match provider {
             "fast_html2md" => {
                 #[cfg(feature = "web-fetch-html2md")]
                 {
-                    Ok(html2md::rewrite_html(&cleaned, false))
+                    Ok(html2md::rewrite_html(html, false))
                 }
                 #[cfg(not(feature = "web-fetch-html2md"))]
                 {
                     // Feature not compiled in; fall through to nanohtml2text.
-                    Ok(nanohtml2text::html2text(&cleaned))
+                    Ok(nanohtml2text::html2text(html))
+                }
+            }
+            "nanohtml2text" => Ok(nanohtml2text::html2text(html)),
+            "readability" => {
+                #[cfg(feature = "web-fetch-readability")]
+                {
+                    let main = Self::extract_main_content(html)?;
+                    Self::convert_html("fast_html2md", &main)
+                }
+                #[cfg(not(feature = "web-fetch-readability"))]
+                {
+                    anyhow::bail!(READABILITY_FEATURE_ERROR)
                 }
             }
-            "nanohtml2text" => Ok(nanohtml2text::html2text(&cleaned)),
             _ => anyhow::bail!(
                 "Unknown web_fetch provider: '{}'. {}",
-                self.provider,
+                provider,
                 WEB_FETCH_PROVIDER_HELP
             ),
         }
     }
 
-    /// Builds a `reqwest::Client` with the configured timeout, user-agent, and proxy settings.
+    /// Keeps the main content of a page for the `readability` provider.
+    ///
+    /// Runs after the noise filter has dropped `<nav>`, `<header>`,
+    /// `<footer>` and `<aside>`. Candidates are each `<article>` and
+    /// `<main>` block plus the page's dense paragraphs (`<p>` with at least
+    /// `READABILITY_MIN_PARAGRAPH_CHARS` characters of text); each is scored
+    /// by the text in its paragraphs and the best one wins. Pages with no
+    /// paragraph text are returned unchanged.
+    #[cfg(feature = "web-fetch-readability")]
+    fn extract_main_content(html: &str) -> anyhow::Result<String> {
+        use std::sync::OnceLock;
+        static RES: OnceLock<Result<[regex::Regex; 4], String>> = OnceLock::new();
+        let [article_re, main_re, paragraph_re, tag_re] = RES
+            .get_or_init(|| {
+                let compile = |pattern: &str| regex::Regex::new(pattern).map_err(|e| e.to_string());
+                Ok([
+                    compile(r"(?si)<article\b[^>]*>.*?</article>")?,
+                    compile(r"(?si)<main\b[^>]*>.*?</main>")?,
+                    compile(r"(?si)<p\b[^>]*>.*?</p>")?,
+                    compile(r"<[^>]*>")?,
+                ])
+            })
+            .as_ref()
+            .map_err(|e| anyhow::anyhow!("readability regex init failed: {e}"))?;
+
+        let text_chars = |fragment: &str| tag_re.replace_all(fragment, "").trim().chars().count();
+        let score = |block: &str| -> usize {
+            paragraph_re
+                .find_iter(block)
+                .map(|p| text_chars(p.as_str()))
+                .sum()
+        };
+
+        let dense: String = paragraph_re
+            .find_iter(html)
+            .map(|p| p.as_str())
+            .filter(|p| text_chars(p) >= READABILITY_MIN_PARAGRAPH_CHARS)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let candidates = article_re
+            .find_iter(html)
+            .chain(main_re.find_iter(html))
+            .map(|m| m.as_str())
+            .chain(std::iter::once(dense.as_str()));
+        // The first candidate wins ties, so an article beats equal dense text.
+        let (mut best_score, mut best) = (0, html);
+        for block in candidates {
+            let block_score = score(block);
+            if block_score > best_score {
+                (best_score, best) = (block_score, block);
+            }
+        }
+        Ok(best.to_string())
+    }
+
+    /// Builds a `reqwest::Client` with the configured timeouts, user-agent, and proxy settings.
+    /// Used for page, `robots.txt`, and cloud-provider requests alike.
     fn build_http_client(&self) -> anyhow::Result<reqwest::Client> {
+        let (connect_timeout, timeout) = self.http_timeouts()?;
         let builder = reqwest::Client::builder()
-            .timeout(Duration::from_secs(self.effective_timeout_secs()))
-            .connect_timeout(Duration::from_secs(10))
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
             .redirect(reqwest::redirect::Policy::none())
             .user_agent(self.user_agent.as_str());
         let builder = crate::config::apply_runtime_proxy_to_builder(builder, "tool.web_fetch");
         Ok(builder.build()?)
     }
 
+    /// Refuses `url` when `respect_robots` is set and the host's `robots.txt`
+    /// disallows its path for our user agent.
+    ///
+    /// `robots.txt` is requested through [`Self::validate_url`] like any other
+    /// fetch. A 4xx (or redirect) means no rules; a 5xx or network error
+    /// refuses the fetch and is not cached, so the next call retries.
+    async fn check_robots(&self, url: &str) -> anyhow::Result<()> {
+        if !self.url_access.respect_robots {
+            return Ok(());
+        }
+        let parsed = reqwest::Url::parse(url)?;
+        let origin = parsed.origin().ascii_serialization();
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        let cached = self.robots_cache.lock().get(&origin).cloned();
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let rules = self.fetch_robots(&origin).await?;
+                self.robots_cache
+                    .lock()
+                    .insert(origin.clone(), rules.clone());
+                rules
+            }
+        };
+        if !rules.is_allowed(&path) {
+            anyhow::bail!(
+                "robots.txt at {origin} disallows '{path}' for user agent '{}'",
+                self.user_agent
+            );
+        }
+        Ok(())
+    }
+
+    async fn fetch_robots(&self, origin: &str) -> anyhow::Result<RobotsRules> {
+        let robots_url = self.validate_url(&format!("{origin}/robots.txt"))?;
+        let response = self
+            .build_http_client()?
+            .get(&robots_url)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Could not fetch {robots_url}: {e}"))?;
+        let status = response.status();
+        if status.is_server_error() {
+            anyhow::bail!(
+                "Could not fetch {robots_url}: HTTP {}; refusing to fetch while robots.txt is unavailable",
+                status.as_u16()
+            );
+        }
+        if !status.is_success() {
+            return Ok(RobotsRules::default());
+        }
+        // Stop reading at the cap instead of buffering an oversized body.
+        let mut response = response;
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let room = ROBOTS_MAX_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if body.len() == ROBOTS_MAX_BYTES {
+                break;
+            }
+        }
+        Ok(RobotsRules::parse(
+            &String::from_utf8_lossy(&body),
+            &self.user_agent,
+        ))
+    }
+
+    /// POSTs `body` to a cloud provider API `endpoint`, following at most
+    /// `api_max_redirects` redirects. Each hop re-sends the same request and
+    /// must stay on the endpoint's host, so the API key never leaves it.
+    async fn post_provider_api(
+        &self,
+        provider: &str,
+        endpoint: &str,
+        bearer_token: Option<&str>,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<reqwest::Response> {
+        let client = self.build_http_client()?;
+        let mut target = reqwest::Url::parse(endpoint)
+            .map_err(|e| anyhow::anyhow!("Invalid {provider} API URL '{endpoint}': {e}"))?;
+        let host = target.host_str().unwrap_or_default().to_ascii_lowercase();
+        let mut redirects = 0;
+        loop {
+            let mut request = client.post(target.clone()).json(body);
+            if let Some(token) = bearer_token {
+                request = request.header(reqwest::header::AUTHORIZATION, format!("Bearer {token}"));
+            }
+            let response = request.send().await?;
+            if !response.status().is_redirection() {
+                return Ok(response);
+            }
+            if redirects == self.api_max_redirects {
+                anyhow::bail!(
+                    "{provider} API endpoint redirected more than {} time(s) \
+                     (web_fetch.api_max_redirects)",
+                    self.api_max_redirects
+                );
+            }
+            redirects += 1;
+
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("{provider} API redirect response missing Location header")
+                })?;
+            let next = target
+                .join(location)
+                .map_err(|e| anyhow::anyhow!("Invalid {provider} API redirect Location: {e}"))?;
+            let same_host = next
+                .host_str()
+                .is_some_and(|next_host| next_host.eq_ignore_ascii_case(&host));
+            if !matches!(next.scheme(), "http" | "https") || !same_host {
+                anyhow::bail!(
+                    "{provider} API endpoint redirected off '{host}' to '{next}'; \
+                     set web_fetch.api_url to the final endpoint"
+                );
+            }
+            target = next;
+        }
+    }
+
     /// Fetches `url` with reqwest, handles one redirect (re-validated), and converts the
     /// response body to text via the configured HTML provider.
-    async fn fetch_with_http_provider(&self, url: &str) -> anyhow::Result<String> {
+    async fn fetch_with_http_provider(
+        &self,
+        url: &str,
+        json_pointer: Option<&str>,
+    ) -> anyhow::Result<String> {
         let client = self.build_http_client()?;
         let response = client.get(url).send().await?;
 
@@ -242,10 +911,16 @@ impl WebFetchTool {
 
         let body = response.text().await?;
 
-        if content_type.contains("text/plain")
-            || content_type.contains("text/markdown")
-            || content_type.contains("application/json")
-        {
+        if content_type.contains("application/json") {
+            return self.extract_json(&body, json_pointer);
+        }
+        if json_pointer.is_some() {
+            anyhow::bail!(
+                "json_pointer requires an application/json response (got '{content_type}')"
+            );
+        }
+
+        if content_type.contains("text/plain") || content_type.contains("text/markdown") {
             return Ok(body);
         }
 
@@ -258,6 +933,27 @@ impl WebFetchTool {
         )
     }
 
+    /// Fetches `url` over HTTP and keeps only the page's main content; see
+    /// [`Self::extract_main_content`].
+    #[cfg(feature = "web-fetch-readability")]
+    async fn fetch_with_readability(
+        &self,
+        url: &str,
+        json_pointer: Option<&str>,
+    ) -> anyhow::Result<String> {
+        self.fetch_with_http_provider(url, json_pointer).await
+    }
+
+    #[cfg(not(feature = "web-fetch-readability"))]
+    #[allow(clippy::unused_async)]
+    async fn fetch_with_readability(
+        &self,
+        _url: &str,
+        _json_pointer: Option<&str>,
+    ) -> anyhow::Result<String> {
+        anyhow::bail!(READABILITY_FEATURE_ERROR)
+    }
+
     /// Fetches `url` via the Firecrawl scrape API and returns the extracted markdown content.
     #[cfg(feature = "firecrawl")]
     async fn fetch_with_firecrawl(&self, url: &str) -> anyhow::Result<String> {
@@ -275,20 +971,14 @@ impl WebFetchTool {
             .unwrap_or("https://api.firecrawl.dev");
         let endpoint = format!("{}/v1/scrape", api_url.trim_end_matches('/'));
 
+        let request = json!({
+            "url": url,
+            "formats": ["markdown"],
+            "onlyMainContent": true,
+            "timeout": (self.effective_timeout_secs() * 1000) as u64
+        });
         let response = self
-            .build_http_client()?
-            .post(endpoint)
-            .header(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", auth_token),
-            )
-            .json(&json!({
-                "url": url,
-                "formats": ["markdown"],
-                "onlyMainContent": true,
-                "timeout": (self.effective_timeout_secs() * 1000) as u64
-            }))
-            .send()
+            .post_provider_api("Firecrawl", &endpoint, Some(&auth_token), &request)
             .await?;
         let status = response.status();
         let body = response.text().await?;
@@ -355,14 +1045,12 @@ impl WebFetchTool {
             .unwrap_or("https://api.tavily.com");
         let endpoint = format!("{}/extract", api_url.trim_end_matches('/'));
 
+        let request = json!({
+            "api_key": api_key,
+            "urls": [url]
+        });
         let response = self
-            .build_http_client()?
-            .post(endpoint)
-            .json(&json!({
-                "api_key": api_key,
-                "urls": [url]
-            }))
-            .send()
+            .post_provider_api("Tavily", &endpoint, None, &request)
             .await?;
         let status = response.status();
         let body = response.text().await?;
@@ -406,6 +1094,17 @@ impl WebFetchTool {
     }
 }
 
+/// Truncated SHA-256 hex digest of extracted page text.
+fn content_hash(text: &str) -> String {
+    let mut hash = hex::encode(Sha256::digest(text.as_bytes()));
+    hash.truncate(CONTENT_HASH_HEX_CHARS);
+    hash
+}
+
+fn content_hash_footer(hash: &str) -> String {
+    format!("[content_hash: {hash}]")
+}
+
 #[async_trait]
 impl Tool for WebFetchTool {
     fn name(&self) -> &str {
@@ -417,16 +1116,32 @@ impl Tool for WebFetchTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        json!({
+        let mut schema = json!({
             "type": "object",
             "properties": {
                 "url": {
                     "type": "string",
                     "description": "The HTTP or HTTPS URL to fetch"
+                },
+                "json_pointer": {
+                    "type": "string",
+                    "description": "For JSON responses: RFC 6901 pointer selecting only the relevant subtree (e.g. /data/items/0)"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters to return (default and ceiling: the configured max_response_size)",
+                    "minimum": 1
                 }
             },
             "required": ["url"]
-        })
+        });
+        if self.output.content_hash {
+            schema["properties"]["if_changed_since_hash"] = json!({
+                "type": "string",
+                "description": "content_hash from an earlier fetch of this URL; if the page text is unchanged, a short 'unchanged' result is returned instead of the full content"
+            });
+        }
+        schema
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -447,7 +1162,10 @@ impl Tool for WebFetchTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(
+                    self.security
+                        .rate_limit_error("Action blocked: rate limit exceeded"),
+                ),
             });
         }
 
@@ -462,8 +1180,59 @@ impl Tool for WebFetchTool {
             }
         };
 
+        let max_chars = match self.parse_max_chars(&args) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+        let json_pointer = args.get("json_pointer").and_then(|v| v.as_str());
+        let if_changed_since = args.get("if_changed_since_hash").and_then(|v| v.as_str());
+        // Keyed after validation so SSRF/allowlist checks run on every call.
+        let cache_key = format!("{url}\n{}\n{max_chars}", json_pointer.unwrap_or_default());
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.lock().get(&cache_key)) {
+            return Ok(self.unless_unchanged(
+                ToolResult {
+                    success: true,
+                    output: cached,
+                    error: None,
+                },
+                if_changed_since,
+            ));
+        }
+        if json_pointer.is_some()
+            && !matches!(
+                self.provider.as_str(),
+                "fast_html2md" | "nanohtml2text" | "readability"
+            )
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "json_pointer is not supported by the '{}' provider",
+                    self.provider
+                )),
+            });
+        }
+
+        if let Err(e) = self.check_robots(&url).await {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            });
+        }
+
         let result = match self.provider.as_str() {
-            "fast_html2md" | "nanohtml2text" => self.fetch_with_http_provider(&url).await,
+            "fast_html2md" | "nanohtml2text" => {
+                self.fetch_with_http_provider(&url, json_pointer).await
+            }
+            "readability" => self.fetch_with_readability(&url, json_pointer).await,
             "firecrawl" => self.fetch_with_firecrawl(&url).await,
             "tavily" => self.fetch_with_tavily(&url).await,
             _ => Err(anyhow::anyhow!(
@@ -474,11 +1243,13 @@ impl Tool for WebFetchTool {
         };
 
         match result {
-            Ok(output) => Ok(ToolResult {
-                success: true,
-                output: self.truncate_response(&output),
-                error: None,
-            }),
+            Ok(output) => {
+                let result = self.fetched_result(&output, max_chars);
+                if let (true, Some(cache)) = (result.success, self.cache.as_ref()) {
+                    cache.lock().insert(cache_key, result.output.clone());
+                }
+                Ok(self.unless_unchanged(result, if_changed_since))
+            }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -530,6 +1301,162 @@ mod tests {
         )
     }
 
+    #[test]
+    fn content_below_min_chars_fails() {
+        let tool = test_tool(vec!["example.com"]).with_min_content_chars(50);
+        let result = tool.content_result("  Loading...  ", 1000);
+        assert!(!result.success);
+        assert!(result.output.is_empty());
+        assert!(result.error.unwrap().contains("require JavaScript"));
+    }
+
+    #[test]
+    fn content_above_min_chars_passes() {
+        let tool = test_tool(vec!["example.com"]).with_min_content_chars(10);
+        let result = tool.content_result("A real article with plenty of text.", 1000);
+        assert!(result.success);
+        assert_eq!(result.output, "A real article with plenty of text.");
+
+        let unchecked = test_tool(vec!["example.com"]).content_result("", 1000);
+        assert!(unchecked.success);
+    }
+
+    #[test]
+    fn json_pointer_extracts_nested_field() {
+        let tool = test_tool(vec!["example.com"]);
+        let body = r#"{"data":{"items":[{"id":1,"tags":["a","b"]}],"total":1},"meta":{"big":"x"}}"#;
+
+        let items = tool.extract_json(body, Some("/data/items/0")).unwrap();
+        assert_eq!(items, r#"{"id":1,"tags":["a","b"]}"#);
+        let tag = tool
+            .extract_json(body, Some("/data/items/0/tags/1"))
+            .unwrap();
+        assert_eq!(tag, "b");
+        assert_eq!(tool.extract_json(body, None).unwrap(), body);
+
+        let pretty = test_tool(vec!["example.com"]).with_json_pretty(true);
+        assert_eq!(pretty.extract_json(body, Some("/data/total")).unwrap(), "1");
+        assert!(pretty
+            .extract_json(body, None)
+            .unwrap()
+            .contains("\n  \"data\""));
+    }
+
+    #[test]
+    fn invalid_json_pointer_errors() {
+        let tool = test_tool(vec!["example.com"]);
+        let body = r#"{"data":{"items":[]}}"#;
+
+        let err = tool.extract_json(body, Some("data/items")).unwrap_err();
+        assert!(err.to_string().contains("must be empty or start with '/'"));
+        let err = tool.extract_json(body, Some("/data/missing")).unwrap_err();
+        assert!(err.to_string().contains("does not match any value"));
+        let err = tool.extract_json("<html>", Some("/data")).unwrap_err();
+        assert!(err.to_string().contains("not valid JSON"));
+    }
+
+    #[tokio::test]
+    async fn cached_fetch_is_returned_within_ttl() {
+        let tool = test_tool(vec!["example.com"]).with_cache(4, 60);
+        tool.cache.as_ref().unwrap().lock().insert(
+            "https://example.com/\n\n500000".into(),
+            "cached page".into(),
+        );
+
+        let result = tool
+            .execute(json!({"url": "https://example.com/"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "cached page");
+
+        // SSRF/allowlist validation still runs before the cache lookup.
+        let blocked = tool
+            .execute(json!({"url": "https://other.example.org/"}))
+            .await
+            .unwrap();
+        assert!(!blocked.success);
+    }
+
+    #[test]
+    fn content_hash_footer_is_appended_and_unchanged_page_is_short() {
+        let tool = test_tool(vec!["example.com"]).with_content_hash(true);
+        let first = tool.fetched_result("Release notes v1", 1000);
+        let hash = content_hash("Release notes v1");
+        assert_eq!(
+            first.output,
+            format!("Release notes v1\n\n[content_hash: {hash}]")
+        );
+
+        let again =
+            tool.unless_unchanged(tool.fetched_result("Release notes v1", 1000), Some(&hash));
+        assert!(again.success);
+        assert!(again.output.starts_with("Page unchanged"));
+        assert!(!again.output.contains("Release notes"));
+        assert!(again.output.contains(&hash));
+    }
+
+    #[test]
+    fn changed_page_returns_full_content_with_new_hash() {
+        let tool = test_tool(vec!["example.com"]).with_content_hash(true);
+        let old_hash = content_hash("Release notes v1");
+
+        let result = tool.unless_unchanged(
+            tool.fetched_result("Release notes v2", 1000),
+            Some(&old_hash),
+        );
+        assert!(result.success);
+        assert!(result.output.starts_with("Release notes v2"));
+        assert!(result
+            .output
+            .ends_with(&content_hash_footer(&content_hash("Release notes v2"))));
+
+        // Without the option the hash argument is ignored.
+        let plain = test_tool(vec!["example.com"]);
+        let result = plain.unless_unchanged(
+            plain.fetched_result("Release notes v1", 1000),
+            Some(&old_hash),
+        );
+        assert_eq!(result.output, "Release notes v1");
+    }
+
+    #[tokio::test]
+    async fn cached_page_with_matching_hash_is_reported_unchanged() {
+        let tool = test_tool(vec!["example.com"])
+            .with_content_hash(true)
+            .with_cache(4, 60);
+        let hash = content_hash("cached page");
+        tool.cache.as_ref().unwrap().lock().insert(
+            "https://example.com/\n\n500000".into(),
+            format!("cached page\n\n{}", content_hash_footer(&hash)),
+        );
+
+        let result = tool
+            .execute(json!({"url": "https://example.com/", "if_changed_since_hash": hash}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("Page unchanged"));
+        assert!(tool.parameters_schema()["properties"]["if_changed_since_hash"].is_object());
+    }
+
+    #[test]
+    fn cache_misses_after_ttl_and_evicts_least_recent() {
+        let mut cache = FetchCache::new(2, Duration::from_millis(30));
+        cache.insert("a".into(), "A".into());
+        assert_eq!(cache.get("a").as_deref(), Some("A"));
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(cache.get("a"), None);
+
+        let mut cache = FetchCache::new(2, Duration::from_secs(60));
+        cache.insert("a".into(), "A".into());
+        cache.insert("b".into(), "B".into());
+        assert!(cache.get("a").is_some());
+        cache.insert("c".into(), "C".into());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some() && cache.get("c").is_some());
+    }
+
     #[test]
     fn name_is_web_fetch() {
         let tool = test_tool(vec!["example.com"]);
@@ -573,6 +1500,39 @@ mod tests {
         assert!(!text.contains("Copyright 2025"));
     }
 
+    #[test]
+    fn empty_conversion_falls_back_to_alternate_converter() {
+        // Everything readable sits in elements the noise filter drops.
+        let html = "<html><body>\
+            <script>render()</script>\
+            <noscript><p>Article body for non-JS readers</p></noscript>\
+            <header><h1>Headline</h1></header>\
+            </body></html>";
+
+        for provider in ["nanohtml2text", "fast_html2md"] {
+            let tool = test_tool_with_provider(vec!["example.com"], vec![], provider, None, None);
+            let text = tool.convert_html_to_output(html).unwrap();
+            assert!(text.contains("Article body"), "{provider}: {text:?}");
+            assert!(text.contains("Headline"), "{provider}: {text:?}");
+            assert!(!text.contains("render()"), "{provider}: {text:?}");
+        }
+
+        let tool = test_tool(vec!["example.com"]).with_html_fallback(false);
+        assert!(tool.convert_html_to_output(html).unwrap().trim().is_empty());
+    }
+
+    #[test]
+    fn script_only_page_reports_no_extractable_text() {
+        let tool = test_tool(vec!["example.com"]);
+        let html = "<html><head><style>body{}</style></head>\
+            <body><div id=\"app\"></div><script>mount('#app')</script></body></html>";
+        let err = tool.convert_html_to_output(html).unwrap_err().to_string();
+        assert!(
+            err.contains("page produced no extractable text (may require JavaScript)"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn validate_accepts_exact_domain() {
         let tool = test_tool(vec!["example.com"]);
@@ -747,7 +1707,9 @@ mod tests {
     fn truncate_within_limit() {
         let tool = test_tool(vec!["example.com"]);
         let text = "hello world";
-        assert_eq!(tool.truncate_response(text), "hello world");
+        assert_eq!(tool.truncate_response(text, 100), "hello world");
+        // The budget is in characters, so multi-byte text at the limit is kept whole.
+        assert_eq!(tool.truncate_response("héllo", 5), "héllo");
     }
 
     #[test]
@@ -765,10 +1727,33 @@ mod tests {
             "test".to_string(),
         );
         let text = "hello world this is long";
-        let truncated = tool.truncate_response(text);
+        let truncated = tool.truncate_response(text, tool.max_response_size);
         assert!(truncated.contains("[Response truncated"));
     }
 
+    #[test]
+    fn max_chars_argument_is_clamped_to_max_response_size() {
+        let tool = test_tool(vec!["example.com"]);
+        let ceiling = tool.max_response_size;
+        assert_eq!(tool.parse_max_chars(&json!({})).unwrap(), ceiling);
+        assert_eq!(
+            tool.parse_max_chars(&json!({"max_chars": 200})).unwrap(),
+            200
+        );
+        assert_eq!(
+            tool.parse_max_chars(&json!({"max_chars": u64::MAX}))
+                .unwrap(),
+            ceiling
+        );
+        assert!(tool.parse_max_chars(&json!({"max_chars": 0})).is_err());
+        assert!(tool.parse_max_chars(&json!({"max_chars": "10"})).is_err());
+
+        let result = tool.content_result("a fairly long page of readable text", 8);
+        assert!(result.success);
+        assert!(result.output.starts_with("a fairly\n\n"));
+        assert!(result.output.contains("[Response truncated"));
+    }
+
     #[test]
     fn normalize_domain_strips_scheme_and_case() {
         let got = normalize_domain("  HTTPS://Docs.Example.com/path ").unwrap();
@@ -821,6 +1806,37 @@ mod tests {
         assert!(tool.validate_url("https://example.com").is_ok());
     }
 
+    #[test]
+    fn explain_blocked_trail_records_rejection_reason() {
+        let tool =
+            test_tool_with_blocklist(vec!["docs.rs"], vec!["evil.com"]).with_explain_blocked(true);
+        let mut trail = DecisionTrail::new();
+        let err = tool
+            .validate_url_traced("https://example.com/page", &mut trail)
+            .unwrap_err()
+            .to_string();
+
+        // The model-facing error stays terse.
+        assert_eq!(
+            err,
+            "Host 'example.com' is not in web_fetch.allowed_domains"
+        );
+        let log = trail.to_string();
+        assert!(log.contains("host 'example.com'"), "{log}");
+        assert!(
+            log.contains(r#"web_fetch.blocked_domains ["evil.com"]: no match"#),
+            "{log}"
+        );
+        assert!(
+            log.contains(r#"web_fetch.allowed_domains ["docs.rs"]: no match"#),
+            "{log}"
+        );
+        assert!(
+            log.ends_with("rejected: Host 'example.com' is not in web_fetch.allowed_domains"),
+            "{log}"
+        );
+    }
+
     #[tokio::test]
     async fn firecrawl_provider_requires_api_key() {
         let tool = test_tool_with_provider(vec!["*"], vec![], "firecrawl", None, None);
@@ -837,6 +1853,59 @@ mod tests {
         }
     }
 
+    #[cfg(not(feature = "web-fetch-readability"))]
+    #[tokio::test]
+    async fn readability_provider_requires_its_feature() {
+        let tool = test_tool_with_provider(vec!["*"], vec![], "readability", None, None);
+        let result = tool
+            .execute(json!({"url": "https://example.com"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap_or_default()
+            .contains("requires Cargo feature 'web-fetch-readability'"));
+    }
+
+    #[cfg(feature = "web-fetch-readability")]
+    #[test]
+    fn readability_keeps_the_main_article() {
+        let tool = test_tool_with_provider(vec!["*"], vec![], "readability", None, None);
+        let body = "The quick brown fox jumps over the lazy dog, again and again, \
+                    until the dog finally gets up and leaves.";
+        let html = format!(
+            "<html><body>\
+             <nav><a>Home</a></nav>\
+             <div class=\"promo\"><p>Subscribe now!</p><p>Sign up for our newsletter</p></div>\
+             <aside><p>Related: ten other stories you might like to read today</p></aside>\
+             <article><h1>Fox news</h1><p>{body}</p><p>{body}</p></article>\
+             <footer>Copyright 2025</footer>\
+             </body></html>"
+        );
+        let text = tool.convert_html_to_output(&html).unwrap();
+        assert!(text.contains("Fox news"), "{text}");
+        assert!(text.contains("quick brown fox"), "{text}");
+        for boilerplate in ["Home", "Subscribe", "newsletter", "Related", "Copyright"] {
+            assert!(!text.contains(boilerplate), "{boilerplate}: {text}");
+        }
+    }
+
+    #[cfg(feature = "web-fetch-readability")]
+    #[test]
+    fn readability_without_article_keeps_dense_paragraphs() {
+        let long = "This paragraph is long enough to count as body text because it \
+                    carries well over eighty characters of prose.";
+        let html =
+            format!("<div><p>Share</p><p>Tweet</p></div><div><p>{long}</p></div><p>Login</p>");
+        let main = WebFetchTool::extract_main_content(&html).unwrap();
+        assert!(main.contains("well over eighty"), "{main}");
+        assert!(!main.contains("Share") && !main.contains("Login"), "{main}");
+
+        let bare = "<div>No paragraphs here</div>";
+        assert_eq!(WebFetchTool::extract_main_content(bare).unwrap(), bare);
+    }
+
     #[tokio::test]
     async fn tavily_provider_requires_api_key() {
         let tool = test_tool_with_provider(vec!["*"], vec![], "tavily", None, None);
@@ -849,6 +1918,107 @@ mod tests {
         assert!(error.contains("requires [web_fetch].api_key"));
     }
 
+    #[cfg(feature = "firecrawl")]
+    #[tokio::test]
+    async fn firecrawl_follows_same_host_api_redirects_within_cap() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/scrape"))
+            .respond_with(
+                ResponseTemplate::new(308).insert_header("Location", "/firecrawl/v1/scrape"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/firecrawl/v1/scrape"))
+            .and(header("authorization", "Bearer fc-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "data": {"markdown": "# Scraped"}
+            })))
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_provider(
+            vec!["*"],
+            vec![],
+            "firecrawl",
+            Some("fc-key"),
+            Some(&server.uri()),
+        );
+        let output = tool
+            .fetch_with_firecrawl("https://example.com/page")
+            .await
+            .unwrap();
+        assert_eq!(output, "# Scraped");
+
+        let err = tool
+            .with_api_max_redirects(0)
+            .fetch_with_firecrawl("https://example.com/page")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("redirected more than 0 time(s)"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn provider_api_redirect_off_the_api_host_is_refused() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/extract"))
+            .respond_with(
+                ResponseTemplate::new(307)
+                    .insert_header("Location", "https://collector.example.net/extract"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_provider(
+            vec!["*"],
+            vec![],
+            "tavily",
+            Some("tv-key"),
+            Some(&server.uri()),
+        );
+        let err = tool
+            .fetch_with_tavily("https://example.com/page")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("redirected off '127.0.0.1'"), "{err}");
+    }
+
+    #[test]
+    fn http_client_uses_configured_timeouts_and_rejects_inconsistent_pair() {
+        let mut tool = test_tool(vec!["example.com"]);
+        tool.connect_timeout_secs = 5;
+        tool.timeout_secs = 45;
+        assert_eq!(
+            tool.http_timeouts().unwrap(),
+            (Duration::from_secs(5), Duration::from_secs(45))
+        );
+        assert!(tool.build_http_client().is_ok());
+
+        tool.connect_timeout_secs = 0;
+        tool.timeout_secs = 0;
+        assert_eq!(
+            tool.http_timeouts().unwrap(),
+            (Duration::from_secs(10), Duration::from_secs(30))
+        );
+
+        tool.connect_timeout_secs = 60;
+        tool.timeout_secs = 20;
+        let err = tool.build_http_client().unwrap_err().to_string();
+        assert!(err.contains("connect_timeout_secs (60)"), "{err}");
+    }
+
     #[test]
     fn parses_multiple_api_keys() {
         let tool =
@@ -863,4 +2033,121 @@ mod tests {
         assert_eq!(tool.get_next_api_key().as_deref(), Some("k2"));
         assert_eq!(tool.get_next_api_key().as_deref(), Some("k1"));
     }
+
+    fn robots_tool() -> WebFetchTool {
+        WebFetchTool::new(
+            Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::Supervised,
+                ..SecurityPolicy::default()
+            }),
+            "nanohtml2text".to_string(),
+            None,
+            None,
+            vec!["*".to_string()],
+            vec![],
+            UrlAccessConfig {
+                respect_robots: true,
+                ..UrlAccessConfig::default()
+            },
+            500_000,
+            30,
+            "ZeroClaw/1.0".to_string(),
+        )
+    }
+
+    #[test]
+    fn robots_rules_prefer_our_group_and_longest_match() {
+        let body = "\
+User-agent: *
+Disallow: /
+
+# our group
+User-agent: OtherBot
+User-agent: zeroclaw
+Disallow: /private
+Allow: /private/public
+Disallow: /*.pdf$
+";
+        let rules = RobotsRules::parse(body, "ZeroClaw/1.0");
+        assert!(rules.is_allowed("/docs"));
+        assert!(!rules.is_allowed("/private/notes"));
+        assert!(rules.is_allowed("/private/public/page"));
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf?download=1"));
+
+        let rules = RobotsRules::parse(body, "SomeoneElse/2.0");
+        assert!(!rules.is_allowed("/docs"));
+        assert!(RobotsRules::parse("", "ZeroClaw/1.0").is_allowed("/anything"));
+    }
+
+    #[tokio::test]
+    async fn robots_disallowed_path_is_refused_from_cache() {
+        let tool = robots_tool();
+        tool.robots_cache.lock().insert(
+            "https://example.com".to_string(),
+            RobotsRules::parse("User-agent: *\nDisallow: /admin\n", "ZeroClaw/1.0"),
+        );
+
+        let result = tool
+            .execute(json!({"url": "https://example.com/admin/users"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.contains("robots.txt") && error.contains("/admin/users"),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn robots_fetch_goes_through_url_validation() {
+        let tool = robots_tool();
+        let err = tool
+            .fetch_robots("http://127.0.0.1:8080")
+            .await
+            .unwrap_err();
+        assert!(!err.to_string().contains("Could not fetch"), "{err}");
+        assert!(tool.robots_cache.lock().is_empty());
+
+        // Disabled by default: no robots lookup at all.
+        let plain = test_tool(vec!["*"]);
+        plain.check_robots("http://127.0.0.1/").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn robots_read_stops_at_size_cap() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let origin = format!("http://{}", listener.local_addr().unwrap());
+        // Promises far more than the cap and never finishes the body, so an
+        // uncapped read would hang until the request timeout.
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let mut body = "User-agent: *\nDisallow: /private\n".to_string();
+            while body.len() <= ROBOTS_MAX_BYTES {
+                body.push_str("# padding\n");
+            }
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n",
+                body.len() * 4
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(body.as_bytes()).await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let mut tool = robots_tool();
+        tool.url_access.allow_loopback = true;
+        let rules = tokio::time::timeout(Duration::from_secs(10), tool.fetch_robots(&origin))
+            .await
+            .expect("robots.txt read should stop at the cap")
+            .unwrap();
+        assert!(!rules.is_allowed("/private/page"));
+        assert!(rules.is_allowed("/public"));
+        server.abort();
+    }
 }